@@ -0,0 +1,85 @@
+#![allow(missing_docs)] // FIXME: use expect
+#![no_std]
+#![cfg_attr(target_os = "cuda", feature(abi_ptx))]
+#![cfg_attr(target_os = "cuda", feature(alloc_error_handler))]
+#![cfg_attr(target_os = "cuda", feature(asm_experimental_arch))]
+#![feature(cfg_version)]
+#![feature(type_alias_impl_trait)]
+#![feature(decl_macro)]
+
+extern crate alloc;
+
+#[rust_cuda::kernel::kernel(pub use link! for impl)]
+#[kernel(allow(ptx::local_memory_use))]
+pub fn kernel(
+    increment: rust_cuda::kernel::param::PerThreadShallowCopy<u32>,
+    counter: &rust_cuda::kernel::param::ShallowInteriorMutable<core::sync::atomic::AtomicU32>,
+    threads: &rust_cuda::kernel::param::ShallowInteriorMutable<core::sync::atomic::AtomicU64>,
+) {
+    counter.fetch_add(increment, core::sync::atomic::Ordering::Relaxed);
+
+    // Every thread marks its index, which must be unique in the grid
+    let index = rust_cuda::device::thread::Thread::this().index();
+    threads.fetch_or(1 << index, core::sync::atomic::Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+    use rust_cuda::host::driver::{
+        function::{BlockSize, GridSize},
+        stream::{Stream, StreamFlags},
+    };
+
+    // Link the non-generic CUDA kernel
+    struct KernelPtx;
+    crate::link! { impl kernel for KernelPtx }
+
+    #[test]
+    fn emulated_launch() -> Result<(), rust_cuda::Error> {
+        // The mock driver's stream queues the work of the emulated kernel
+        let mut stream = Stream::new(StreamFlags::NON_BLOCKING, None)?;
+
+        let mut kernel = rust_cuda::kernel::TypedPtxKernel::<super::kernel>::new::<KernelPtx>(
+            rust_cuda::kernel::KernelAttributes::default(),
+        );
+        let config = rust_cuda::kernel::LaunchConfig {
+            grid: GridSize::x(4),
+            block: BlockSize::x(16),
+            ptx_jit: false,
+            ptx_jit_block_size: false,
+            cooperative: false,
+        };
+
+        let mut counter = AtomicU32::new(0);
+        let mut threads = AtomicU64::new(0);
+
+        rust_cuda::host::Stream::with(&mut stream, |stream| {
+            kernel.launch3(stream, &config, 2, &mut counter, &mut threads)
+        })?;
+
+        assert_eq!(counter.load(Ordering::Relaxed), 4 * 16 * 2);
+        assert_eq!(threads.load(Ordering::Relaxed), u64::MAX);
+
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "cuda")]
+mod cuda_prelude {
+    use rust_cuda::device::alloc::PTXAllocator;
+
+    #[global_allocator]
+    static _GLOBAL_ALLOCATOR: PTXAllocator = PTXAllocator;
+
+    #[panic_handler]
+    fn panic(_: &::core::panic::PanicInfo) -> ! {
+        rust_cuda::device::utils::abort()
+    }
+
+    #[alloc_error_handler]
+    fn alloc_error_handler(_: core::alloc::Layout) -> ! {
+        rust_cuda::device::utils::abort()
+    }
+}
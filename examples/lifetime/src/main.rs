@@ -2,7 +2,7 @@
 
 use lifetime::{kernel, link};
 
-fn main() -> rust_cuda::deps::rustacuda::error::CudaResult<()> {
+fn main() -> Result<(), rust_cuda::Error> {
     // Link the lifetime-only-generic CUDA kernel
     struct KernelPtx<'a, 'b>(core::marker::PhantomData<(&'a (), &'b ())>);
     link! { impl kernel<'a, 'b> for KernelPtx }
@@ -32,11 +32,15 @@ fn main() -> rust_cuda::deps::rustacuda::error::CudaResult<()> {
     let mut shared = core::sync::atomic::AtomicU32::new(0);
 
     // Create a new instance of the CUDA kernel and prepare the launch config
-    let mut kernel = rust_cuda::kernel::TypedPtxKernel::<kernel>::new::<KernelPtx>(None);
+    let mut kernel = rust_cuda::kernel::TypedPtxKernel::<kernel>::new::<KernelPtx>(
+        rust_cuda::kernel::KernelAttributes::default(),
+    );
     let config = rust_cuda::kernel::LaunchConfig {
         grid: rust_cuda::deps::rustacuda::function::GridSize::x(1),
         block: rust_cuda::deps::rustacuda::function::BlockSize::x(4),
         ptx_jit: false,
+        ptx_jit_block_size: false,
+        cooperative: false,
     };
 
     println!("shared(before)={shared:?}");
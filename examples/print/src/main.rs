@@ -2,7 +2,7 @@
 
 use print::{kernel, link, Action};
 
-fn main() -> rust_cuda::deps::rustacuda::error::CudaResult<()> {
+fn main() -> Result<(), rust_cuda::Error> {
     // Link the non-generic CUDA kernel
     struct KernelPtx;
     link! { impl kernel for KernelPtx }
@@ -30,11 +30,15 @@ fn main() -> rust_cuda::deps::rustacuda::error::CudaResult<()> {
         )?);
 
     // Create a new instance of the CUDA kernel and prepare the launch config
-    let mut kernel = rust_cuda::kernel::TypedPtxKernel::<kernel>::new::<KernelPtx>(None);
+    let mut kernel = rust_cuda::kernel::TypedPtxKernel::<kernel>::new::<KernelPtx>(
+        rust_cuda::kernel::KernelAttributes::default(),
+    );
     let config = rust_cuda::kernel::LaunchConfig {
         grid: rust_cuda::deps::rustacuda::function::GridSize::x(1),
         block: rust_cuda::deps::rustacuda::function::BlockSize::x(4),
         ptx_jit: false,
+        ptx_jit_block_size: false,
+        cooperative: false,
     };
 
     // Launch the CUDA kernel on the stream and synchronise to its completion
@@ -20,11 +20,16 @@
 //! [Gitpod Ready-to-Code]: https://img.shields.io/badge/Gitpod-ready-blue?logo=gitpod
 //! [gitpod]: https://gitpod.io/#https://github.com/juntyr/rust-cuda
 //!
-//! `rust-cuda-kernel` links the `libnvptxcompiler_static` CUDA library to check
-//! PTX code at compile time.
+//! With the `nvptxcompiler` feature, `rust-cuda-kernel` links the
+//! `libnvptxcompiler_static` CUDA library to check PTX code at compile time.
+//! Without it, a built-in PTX checker is used instead, so that kernels can be
+//! compiled without a CUDA toolkit installation.
 
 fn main() {
-    find_cuda_helper::include_cuda();
+    #[cfg(feature = "nvptxcompiler")]
+    {
+        find_cuda_helper::include_cuda();
 
-    println!("cargo:rustc-link-lib=nvptxcompiler_static");
+        println!("cargo:rustc-link-lib=nvptxcompiler_static");
+    }
 }
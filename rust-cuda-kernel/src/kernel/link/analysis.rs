@@ -0,0 +1,583 @@
+//! Static analysis of the PTX source code of a kernel, which checks the PTX
+//! lints that do not require assembling the PTX code.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as FmtWrite,
+};
+
+use crate::kernel::lints::{LintLevel, PtxLint};
+
+use super::source_map::PtxSourceMap;
+
+/// Maximum number of 32-bit registers that a single thread can use.
+const MAX_REGISTERS_PER_THREAD: u32 = 255;
+/// Maximum number of occurrences that are reported per lint.
+const MAX_REPORTED_OCCURRENCES: usize = 16;
+
+/// A line of the PTX source code that triggers a lint.
+struct PtxLintOccurrence<'a> {
+    /// The one-based line number in the PTX source code
+    line: usize,
+    /// The (mangled) symbol of the function that contains the line
+    function: Option<&'a str>,
+    source: String,
+}
+
+/// Statically checks the `kernel_ptx` for the double precision, local
+/// memory, `malloc`, `printf`, trap, and atomic use lints, and, if the PTX
+/// compiler is not available, estimates the register pressure for the
+/// register spills lint.
+///
+/// Every occurrence is reported with its PTX line, the demangled name of the
+/// enclosing Rust function, and, if known, the Rust source location that it
+/// was compiled from.
+pub fn check_kernel_ptx_lints(kernel_ptx: &str, ptx_lint_levels: &HashMap<PtxLint, LintLevel>) {
+    let occurrences = find_ptx_lint_occurrences(kernel_ptx, |lint| {
+        // The PTX compiler reports the actual register spills, so the
+        //  estimate is only used without it
+        if lint == PtxLint::RegisterSpills && cfg!(feature = "nvptxcompiler") {
+            return false;
+        }
+
+        ptx_lint_levels
+            .get(&lint)
+            .map_or(false, |level| *level > LintLevel::Allow)
+    });
+
+    report_occurrences(occurrences, &PtxSourceMap::new(kernel_ptx), ptx_lint_levels);
+}
+
+/// Finds all occurrences of the enabled lints in the `kernel_ptx`.
+///
+/// The register pressure of every function is estimated from its `.reg`
+/// declarations as the number of 32-bit registers that they declare, where
+/// 64-bit registers count twice, 128-bit registers four times, and predicate
+/// registers not at all. Since LLVM declares a new virtual register for
+/// almost every value, the estimate is a loose upper bound on the registers
+/// that the PTX compiler allocates after register allocation. Therefore, the
+/// `ptx::register_spills` lint is only triggered, at the function's header,
+/// when the estimate exceeds the [`MAX_REGISTERS_PER_THREAD`], i.e. when the
+/// function may spill registers to local memory.
+#[expect(clippy::too_many_lines)]
+fn find_ptx_lint_occurrences(
+    kernel_ptx: &str,
+    is_enabled: impl Fn(PtxLint) -> bool,
+) -> BTreeMap<PtxLint, Vec<PtxLintOccurrence>> {
+    let mut occurrences: BTreeMap<PtxLint, Vec<PtxLintOccurrence>> = BTreeMap::new();
+    let mut report = |lint, line, function, source| {
+        if is_enabled(lint) {
+            occurrences
+                .entry(lint)
+                .or_default()
+                .push(PtxLintOccurrence {
+                    line,
+                    function,
+                    source,
+                });
+        }
+    };
+
+    let mut depth = 0_usize;
+    let mut in_block_comment = false;
+    let mut header = None;
+    let mut function = None;
+    let mut registers = 0;
+    let mut pending_call = None;
+
+    for (index, line) in kernel_ptx.lines().enumerate() {
+        let line_number = index + 1;
+        let code = strip_comments(line, &mut in_block_comment);
+
+        if code.is_empty() {
+            continue;
+        }
+
+        if depth == 0 {
+            if let Some(name) = function_header_name(code) {
+                header = Some((name, line_number));
+            }
+        }
+
+        let name = function.map(|(name, _)| name);
+
+        if let Some((call_line, call)) = pending_call.take() {
+            // LLVM emits the call target on the line after the `call`
+            match call_target(code) {
+                Some(target) => {
+                    if let Some(lint) = call_lint(target) {
+                        report(lint, call_line, name, format!("{call} {target}"));
+                    }
+                },
+                None => pending_call = Some((call_line, call)),
+            }
+        } else if depth > 0 || code.starts_with('.') {
+            let instruction = code
+                .strip_prefix('@')
+                .and_then(|guarded| guarded.split_once(char::is_whitespace))
+                .map_or(code, |(_guard, instruction)| instruction.trim_start());
+            let (opcode, operands) = instruction
+                .split_once(char::is_whitespace)
+                .unwrap_or((instruction, ""));
+            let opcode = opcode.trim_end_matches(';');
+            let base = opcode.split('.').next().unwrap_or_default();
+            let has_modifier = |modifier| opcode.split('.').skip(1).any(|m| m == modifier);
+
+            let source = || String::from(code);
+
+            match (base, opcode) {
+                ("", ".reg") => registers += register_declaration_size(operands),
+                ("", ".local") => report(PtxLint::LocalMemoryUse, line_number, name, source()),
+                ("atom" | "red", _) => report(PtxLint::AtomicUse, line_number, name, source()),
+                ("trap", _) => report(PtxLint::TrapUse, line_number, name, source()),
+                ("call", _) => match call_target(operands) {
+                    Some(target) => {
+                        if let Some(lint) = call_lint(target) {
+                            report(lint, line_number, name, source());
+                        }
+                    },
+                    None => pending_call = Some((line_number, code)),
+                },
+                _ => (),
+            }
+
+            if !base.is_empty() && has_modifier("local") {
+                report(PtxLint::LocalMemoryUse, line_number, name, source());
+            }
+            if !base.is_empty() && has_modifier("f64") {
+                report(PtxLint::DoublePrecisionUse, line_number, name, source());
+            }
+        }
+
+        for c in code.chars() {
+            match c {
+                '{' => {
+                    if depth == 0 {
+                        function = header.take();
+                        registers = 0;
+                    }
+                    depth += 1;
+                },
+                '}' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        if let Some((name, header_line)) = function.take() {
+                            if registers > MAX_REGISTERS_PER_THREAD {
+                                report(
+                                    PtxLint::RegisterSpills,
+                                    header_line,
+                                    Some(name),
+                                    format!(
+                                        "declares up to {registers} 32-bit registers, more than \
+                                         the {MAX_REGISTERS_PER_THREAD} available per thread"
+                                    ),
+                                );
+                            }
+                        }
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        // A function declaration without a body ends with a semicolon
+        if depth == 0 && code.ends_with(';') {
+            header = None;
+        }
+    }
+
+    occurrences
+}
+
+/// Reports the `occurrences` of every lint as a warning or error, depending
+/// on its level in the `ptx_lint_levels`.
+fn report_occurrences(
+    occurrences: BTreeMap<PtxLint, Vec<PtxLintOccurrence>>,
+    source_map: &PtxSourceMap,
+    ptx_lint_levels: &HashMap<PtxLint, LintLevel>,
+) {
+    for (lint, occurrences) in occurrences {
+        let mut message = format!(
+            "[rust-cuda]: ptx::{lint}: the kernel {}:",
+            lint_description(lint)
+        );
+
+        for occurrence in occurrences.iter().take(MAX_REPORTED_OCCURRENCES) {
+            let _ = write!(message, "\n  line {}", occurrence.line);
+            if let Some(function) = occurrence.function {
+                let _ = write!(message, " in `{}`", demangle(function));
+            }
+            let _ = write!(message, ": {}", occurrence.source);
+            if let Some(location) = source_map.line_location(occurrence.line) {
+                let _ = write!(message, "\n    --> {location}");
+            }
+        }
+
+        let more = occurrences.len().saturating_sub(MAX_REPORTED_OCCURRENCES);
+        if more > 0 {
+            let _ = write!(message, "\n  ... and {more} more");
+        }
+
+        if ptx_lint_levels
+            .get(&lint)
+            .map_or(false, |level| *level > LintLevel::Warn)
+        {
+            emit_call_site_error!("{}", message);
+        } else {
+            emit_call_site_warning!("{}", message);
+        }
+    }
+}
+
+const fn lint_description(lint: PtxLint) -> &'static str {
+    match lint {
+        PtxLint::DoublePrecisionUse => "uses double precision floating point operations",
+        PtxLint::LocalMemoryUse => "uses local memory",
+        PtxLint::RegisterSpills => "may spill registers to local memory",
+        PtxLint::MallocUse => "allocates memory on the device heap with `malloc` or `free`",
+        PtxLint::PrintfUse => "prints with `vprintf`",
+        PtxLint::TrapUse => "may trap, e.g. when panicking",
+        PtxLint::AtomicUse => "uses atomic operations",
+        PtxLint::Verbose
+        | PtxLint::DumpAssembly
+        | PtxLint::DynamicStackSize
+        | PtxLint::RegisterBudget => "triggers the lint",
+    }
+}
+
+/// Returns the lint that is triggered by calling the `target` function.
+fn call_lint(target: &str) -> Option<PtxLint> {
+    match target {
+        "malloc" | "free" => Some(PtxLint::MallocUse),
+        "vprintf" => Some(PtxLint::PrintfUse),
+        _ => None,
+    }
+}
+
+/// Demangles the Rust function `symbol`, or returns it unchanged if it is not
+/// a mangled Rust symbol, e.g. for the kernel entry point.
+fn demangle(symbol: &str) -> String {
+    rustc_demangle::try_demangle(symbol)
+        .map_or_else(|_| String::from(symbol), |symbol| format!("{symbol:#}"))
+}
+
+/// Removes all `//` and `/* */` comments from the `line` and trims it,
+/// tracking whether a block comment continues onto the next line.
+pub(super) fn strip_comments<'a>(mut line: &'a str, in_block_comment: &mut bool) -> &'a str {
+    if *in_block_comment {
+        match line.split_once("*/") {
+            Some((_, rest)) => {
+                *in_block_comment = false;
+                line = rest;
+            },
+            None => return "",
+        }
+    }
+
+    if let Some((code, _comment)) = line.split_once("//") {
+        line = code;
+    }
+
+    // Block comments in the middle of an instruction are not expected in the
+    //  PTX that is generated by LLVM, so only the code before them is kept
+    if let Some((code, comment)) = line.split_once("/*") {
+        *in_block_comment = !comment.contains("*/");
+        line = code;
+    }
+
+    line.trim()
+}
+
+/// Returns the symbol of the `.entry` or `.func` that is declared in the
+/// `code` line, if any.
+pub(super) fn function_header_name(code: &str) -> Option<&str> {
+    let (_, rest) = code
+        .split_once(".entry")
+        .or_else(|| code.split_once(".func"))?;
+    let mut rest = rest.trim_start();
+
+    // Skip the optional return parameter list of a `.func`
+    if let Some(params) = rest.strip_prefix('(') {
+        rest = params.split_once(')')?.1.trim_start();
+    }
+
+    let name = rest
+        .split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '.')))
+        .next()?;
+
+    (!name.is_empty()).then_some(name)
+}
+
+/// Estimates the number of 32-bit registers that are declared by the
+/// `operands` of a `.reg` directive, e.g. `.b64 %rd<12>;`.
+fn register_declaration_size(operands: &str) -> u32 {
+    let operands = operands.trim_end_matches(';');
+    let Some((ty, names)) = operands.split_once(char::is_whitespace) else {
+        return 0;
+    };
+
+    let width = match ty {
+        ".pred" => 0,
+        ".b64" | ".u64" | ".s64" | ".f64" => 2,
+        ".b128" => 4,
+        _ => 1,
+    };
+
+    let count = names
+        .split(',')
+        .map(|name| {
+            name.split_once('<')
+                .and_then(|(_, count)| count.trim().trim_end_matches('>').parse().ok())
+                .unwrap_or(1)
+        })
+        .sum::<u32>();
+
+    width * count
+}
+
+/// Returns the function that is called by a `call` with the `operands`,
+/// e.g. `vprintf` in `(retval0), vprintf, (param0, param1);`.
+fn call_target(operands: &str) -> Option<&str> {
+    let mut depth = 0_usize;
+
+    operands
+        .split(|c: char| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                _ => (),
+            }
+            depth > 0 || matches!(c, ',' | ')' | ';')
+        })
+        .map(str::trim)
+        .find(|target| !target.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kernel::lints::PtxLint;
+
+    use super::{
+        call_target, find_ptx_lint_occurrences, register_declaration_size, strip_comments,
+    };
+
+    const KERNEL_PTX: &str = "\
+.version 7.0
+.target sm_35
+.address_size 64
+
+.extern .func  (.param .b32 func_retval0) vprintf
+(
+	.param .b64 vprintf_param_0,
+	.param .b64 vprintf_param_1
+)
+;
+
+.visible .entry kernel(
+	.param .u64 kernel_param_0
+)
+{
+	.local .align 8 .b8 __local_depot0[8];
+	.reg .pred %p<2>;
+	.reg .f64 %fd<3>;
+
+	add.rn.f64 %fd2, %fd1, %fd0;
+	ld.local.u32 %r1, [%rd1];
+	st.local.u32 [%rd1], %r1;
+	atom.global.add.u32 %r2, [%rd2], 1;
+	red.global.add.u32 [%rd2], 1;
+	@%p1 trap;
+	// trap;
+	/* atom.global.add.u32 %r2, [%rd2], 1;
+	   red.global.add.u32 [%rd2], 1; */
+	call.uni (retval0),
+	vprintf,
+	(
+	param0,
+	param1
+	);
+	ret;
+}
+";
+
+    fn occurrences(kernel_ptx: &str) -> Vec<(PtxLint, usize, Option<&str>, String)> {
+        find_ptx_lint_occurrences(kernel_ptx, |_| true)
+            .into_iter()
+            .flat_map(|(lint, occurrences)| {
+                occurrences.into_iter().map(move |occurrence| {
+                    (
+                        lint,
+                        occurrence.line,
+                        occurrence.function,
+                        occurrence.source,
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn kernel_lint_occurrences() {
+        let function = Some("kernel");
+
+        assert_eq!(
+            occurrences(KERNEL_PTX),
+            [
+                (
+                    PtxLint::DoublePrecisionUse,
+                    20,
+                    function,
+                    String::from("add.rn.f64 %fd2, %fd1, %fd0;")
+                ),
+                (
+                    PtxLint::LocalMemoryUse,
+                    16,
+                    function,
+                    String::from(".local .align 8 .b8 __local_depot0[8];")
+                ),
+                (
+                    PtxLint::LocalMemoryUse,
+                    21,
+                    function,
+                    String::from("ld.local.u32 %r1, [%rd1];")
+                ),
+                (
+                    PtxLint::LocalMemoryUse,
+                    22,
+                    function,
+                    String::from("st.local.u32 [%rd1], %r1;")
+                ),
+                (
+                    PtxLint::PrintfUse,
+                    29,
+                    function,
+                    String::from("call.uni (retval0), vprintf")
+                ),
+                (PtxLint::TrapUse, 25, function, String::from("@%p1 trap;")),
+                (
+                    PtxLint::AtomicUse,
+                    23,
+                    function,
+                    String::from("atom.global.add.u32 %r2, [%rd2], 1;")
+                ),
+                (
+                    PtxLint::AtomicUse,
+                    24,
+                    function,
+                    String::from("red.global.add.u32 [%rd2], 1;")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn disabled_lints_are_not_reported() {
+        let occurrences = find_ptx_lint_occurrences(KERNEL_PTX, |lint| lint == PtxLint::TrapUse);
+
+        assert_eq!(
+            occurrences.keys().copied().collect::<Vec<_>>(),
+            [PtxLint::TrapUse]
+        );
+        assert_eq!(occurrences.values().map(Vec::len).sum::<usize>(), 1);
+    }
+
+    #[test]
+    fn malloc_and_free_calls() {
+        let ptx = "\
+.func helper()
+{
+	call.uni (retval0), malloc, (param0);
+	call.uni free, (param0);
+	ret;
+}
+";
+
+        assert_eq!(
+            occurrences(ptx)
+                .into_iter()
+                .map(|(lint, line, function, _)| (lint, line, function))
+                .collect::<Vec<_>>(),
+            [
+                (PtxLint::MallocUse, 3, Some("helper")),
+                (PtxLint::MallocUse, 4, Some("helper")),
+            ]
+        );
+    }
+
+    #[test]
+    fn register_pressure_estimate() {
+        let ptx = "\
+.visible .entry small()
+{
+	.reg .b32 %r<200>;
+	ret;
+}
+
+.visible .entry large()
+{
+	.reg .pred %p<500>;
+	.reg .b32 %r<100>;
+	.reg .b64 %rd<78>;
+	ret;
+}
+";
+
+        assert_eq!(
+            occurrences(ptx)
+                .into_iter()
+                .map(|(lint, line, function, _)| (lint, line, function))
+                .collect::<Vec<_>>(),
+            [(PtxLint::RegisterSpills, 7, Some("large"))]
+        );
+    }
+
+    #[test]
+    fn register_declaration_sizes() {
+        assert_eq!(register_declaration_size(".pred %p<12>;"), 0);
+        assert_eq!(register_declaration_size(".b32 %r<12>;"), 12);
+        assert_eq!(register_declaration_size(".f64 %fd<12>;"), 24);
+        assert_eq!(register_declaration_size(".b128 %rq<2>;"), 8);
+        assert_eq!(register_declaration_size(".u32 %a, %b, %c<4>;"), 6);
+        assert_eq!(register_declaration_size(";"), 0);
+    }
+
+    #[test]
+    fn call_targets() {
+        assert_eq!(
+            call_target("(retval0), vprintf, (param0, param1);"),
+            Some("vprintf")
+        );
+        assert_eq!(call_target("free, (param0);"), Some("free"));
+        assert_eq!(call_target("helper;"), Some("helper"));
+        assert_eq!(call_target("(retval0),"), None);
+        assert_eq!(call_target(""), None);
+    }
+
+    #[test]
+    fn comments_are_stripped() {
+        let mut in_block_comment = false;
+
+        assert_eq!(
+            strip_comments("\tadd.f64 %fd1, %fd1, %fd1; // f64", &mut in_block_comment),
+            "add.f64 %fd1, %fd1, %fd1;"
+        );
+        assert_eq!(strip_comments("// trap;", &mut in_block_comment), "");
+        assert!(!in_block_comment);
+
+        assert_eq!(
+            strip_comments("ret; /* trap;", &mut in_block_comment),
+            "ret;"
+        );
+        assert!(in_block_comment);
+        assert_eq!(strip_comments("trap;", &mut in_block_comment), "");
+        assert!(in_block_comment);
+        assert_eq!(strip_comments("*/ exit;", &mut in_block_comment), "exit;");
+        assert!(!in_block_comment);
+
+        assert_eq!(
+            strip_comments("/* trap; */ ret;", &mut in_block_comment),
+            ""
+        );
+        assert!(!in_block_comment);
+    }
+}
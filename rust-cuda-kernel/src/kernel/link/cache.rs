@@ -0,0 +1,777 @@
+//! Fingerprinted cache of the compiled PTX of kernel specialisations.
+//!
+//! Every specialisation is built into its own artifact dir, which also stores
+//! the build request and a fingerprint of the build. The fingerprint consists
+//! of a key that hashes the build configuration, including the environment
+//! variables that configure rustc, the hashes of all source files that cargo
+//! lists in the build's dep-info, including those of local path dependencies
+//! and the `rerun-if-changed` inputs of their build scripts, the hashes of
+//! the manifests, lockfile, and cargo config files, and the hashes of the
+//! `rerun-if-env-changed` inputs of all build scripts. A specialisation whose
+//! fingerprint still matches is not rebuilt.
+//!
+//! All stale specialisations that an expansion needs, e.g. one for each of
+//! its target architectures, are rebuilt in parallel, together with the other
+//! stale specialisations of the same crate that are known from earlier
+//! builds. Parallel builds with the same options use separate copies of the
+//! shared target dir, which are seeded from it so that they do not rebuild
+//! the dependencies from scratch. Every artifact dir is locked while it is
+//! being built so that concurrent expansions do not write to it at once.
+
+use std::{
+    fmt::Write as FmtWrite,
+    fs,
+    hash::{Hash, Hasher},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::kernel::{
+    target::KernelTarget,
+    utils::{cargo_config_paths, tracked_env_var},
+    CHECK_SPECIALISATION,
+};
+
+use super::{manifest::kernel_crate_manifest, TARGET_NAME};
+
+/// Name of the file in the artifact dir that stores the build request.
+const REQUEST_FILE: &str = "request.json";
+/// Name of the file in the artifact dir that stores the build fingerprint.
+const FINGERPRINT_FILE: &str = "fingerprint";
+/// Name of the file in the artifact dir that locks it while it is built.
+const LOCK_FILE: &str = "lock";
+/// Locks that have not been refreshed for this long were abandoned by their
+/// holder, e.g. because its build was killed, and can be broken.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(60);
+/// Interval in which held locks are refreshed while waiting, e.g. for builds.
+pub const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Environment variables that configure rustc in all cargo builds, which are
+/// inherited by the kernel builds.
+const RUSTC_ENV_VARS: [&str; 7] = [
+    "RUSTC",
+    "RUSTC_WRAPPER",
+    "RUSTC_WORKSPACE_WRAPPER",
+    "RUSTFLAGS",
+    "CARGO_ENCODED_RUSTFLAGS",
+    "CARGO_BUILD_RUSTFLAGS",
+    "CARGO_TARGET_NVPTX64_NVIDIA_CUDA_RUSTFLAGS",
+];
+/// Specialisations that have not been used for this long are no longer
+/// rebuilt alongside other specialisations.
+const MAX_UNUSED_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// Maximum number of specialisations that are built in parallel.
+const MAX_PARALLEL_BUILDS: usize = 4;
+
+/// A request to build one specialisation of a kernel for CUDA.
+pub struct KernelBuild {
+    /// The name of the kernel crate
+    pub crate_name: String,
+    /// The directory of the kernel crate, in which cargo is run
+    pub crate_path: PathBuf,
+    /// The manifest whose library target contains the kernel
+    pub manifest_path: PathBuf,
+    /// The lockfile of the kernel crate's workspace
+    pub lockfile_path: PathBuf,
+    /// Short name of the specialisation that is shown in the build output
+    pub suffix: String,
+    /// The environment variable that selects the specialisation
+    pub specialisation_var: String,
+    /// The value of the `specialisation_var`
+    pub specialisation_value: String,
+    /// The arguments that are passed to `cargo build`
+    pub cargo_args: Vec<String>,
+    /// The dir that the PTX and the cache files are stored in
+    pub artifact_dir: PathBuf,
+    /// The target dir that is shared by all builds with the same options
+    pub target_dir: PathBuf,
+    /// The dir inside the target dir that the build's profile builds into
+    pub profile_dir: String,
+    /// Hash of the build configuration, excluding the source files
+    pub key: u64,
+}
+
+impl KernelBuild {
+    #[expect(clippy::too_many_arguments)]
+    pub fn new(
+        kernel: &syn::Ident,
+        crate_name: &str,
+        crate_path: &Path,
+        bin: Option<&str>,
+        suffix: &str,
+        specialisation_var: &str,
+        specialisation_value: &str,
+        target: &KernelTarget,
+    ) -> Self {
+        let scratch_dir = scratch::path(concat!(
+            env!("CARGO_PKG_NAME"),
+            "-",
+            env!("CARGO_PKG_VERSION"),
+        ));
+
+        let check = specialisation_value == CHECK_SPECIALISATION;
+        let mut cargo_args = target.cargo_args(TARGET_NAME, check);
+
+        let (manifest_path, lockfile_path) =
+            kernel_crate_manifest(crate_path, crate_name, bin, &mut cargo_args, &scratch_dir);
+
+        let artifact_dir = scratch_dir
+            .join("artifacts")
+            .join(crate_name)
+            .join(kernel.to_string())
+            .join(suffix);
+        if let Err(err) = fs::create_dir_all(&artifact_dir) {
+            abort_call_site!(
+                "failed to create the artifact dir {:?}: {}",
+                artifact_dir,
+                err
+            );
+        }
+
+        // Builds with different targets or build options use separate target
+        //  dirs so that they do not invalidate each other's cached dependencies
+        let target_dir = target.cache_key().map_or_else(
+            || scratch_dir.join("target"),
+            |target_key| scratch_dir.join(format!("target-{target_key}")),
+        );
+
+        let mut hasher = seahash::SeaHasher::new();
+        env!("CARGO").hash(&mut hasher);
+        proc_macro::tracked_env::var("RUSTUP_TOOLCHAIN")
+            .ok()
+            .hash(&mut hasher);
+        crate_path.hash(&mut hasher);
+        bin.hash(&mut hasher);
+        specialisation_var.hash(&mut hasher);
+        specialisation_value.hash(&mut hasher);
+        cargo_args.hash(&mut hasher);
+        for var in RUSTC_ENV_VARS {
+            proc_macro::tracked_env::var(var).ok().hash(&mut hasher);
+        }
+
+        Self {
+            crate_name: String::from(crate_name),
+            crate_path: PathBuf::from(crate_path),
+            manifest_path,
+            lockfile_path,
+            suffix: String::from(suffix),
+            specialisation_var: String::from(specialisation_var),
+            specialisation_value: String::from(specialisation_value),
+            cargo_args,
+            artifact_dir,
+            target_dir,
+            profile_dir: String::from(target.profile_dir(check)),
+            key: hasher.finish(),
+        }
+    }
+
+    /// Returns the path of the PTX file that the build produces.
+    pub fn ptx_path(&self) -> PathBuf {
+        self.artifact_dir
+            .join(format!("{}.ptx", self.crate_name.replace('-', "_")))
+    }
+
+    /// Returns the target dir that the build uses in the parallel build
+    /// `slot`, since parallel cargo builds cannot share a target dir.
+    pub fn slot_target_dir(&self, slot: usize) -> PathBuf {
+        if slot == 0 {
+            return self.target_dir.clone();
+        }
+
+        let mut target_dir = self.target_dir.clone().into_os_string();
+        target_dir.push(format!("-slot{slot}"));
+        PathBuf::from(target_dir)
+    }
+
+    /// Seeds the target dir of the parallel build `slot` with a copy of the
+    /// shared target dir if it does not exist yet, so that the build can
+    /// reuse the dependencies that have already been built.
+    ///
+    /// The copies keep the modification times that cargo uses to check
+    /// whether the dependencies are fresh. If seeding fails, the build
+    /// starts from an empty target dir instead.
+    pub fn seed_slot_target_dir(&self, slot: usize) {
+        let target_dir = self.slot_target_dir(slot);
+
+        if slot == 0 || target_dir.exists() || !self.target_dir.is_dir() {
+            return;
+        }
+
+        // The copy is only moved into place once it is complete
+        let mut seed_dir = target_dir.clone().into_os_string();
+        seed_dir.push(format!(".seed-{}", std::process::id()));
+        let seed_dir = PathBuf::from(seed_dir);
+
+        if copy_dir(&self.target_dir, &seed_dir).is_err()
+            || fs::rename(&seed_dir, &target_dir).is_err()
+        {
+            let _ = fs::remove_dir_all(&seed_dir);
+        }
+    }
+
+    /// Returns `true` iff the PTX of an earlier build exists and neither the
+    /// build configuration nor any of its source files have changed since.
+    pub fn is_fresh(&self) -> bool {
+        let Ok(fingerprint) = fs::read_to_string(self.artifact_dir.join(FINGERPRINT_FILE)) else {
+            return false;
+        };
+
+        let mut lines = fingerprint.lines();
+
+        if lines.next() != Some(format!("key {:016x}", self.key).as_str()) {
+            return false;
+        }
+
+        let fresh = self.ptx_path().is_file()
+            && lines.all(|line| match line.split_once(' ') {
+                Some(("file", entry)) => entry
+                    .split_once(' ')
+                    .map_or(false, |(hash, path)| file_hash(Path::new(path)) == hash),
+                Some(("env", entry)) => entry
+                    .split_once(' ')
+                    .map_or(false, |(hash, var)| env_hash(var) == hash),
+                _ => false,
+            });
+
+        if fresh {
+            // Mark the build as recently used
+            let _ = fs::File::options()
+                .append(true)
+                .open(self.artifact_dir.join(REQUEST_FILE))
+                .and_then(|file| file.set_modified(SystemTime::now()));
+        }
+
+        fresh
+    }
+
+    /// Finishes a successful build in the parallel build `slot` by
+    /// annotating the PTX with its specialisation and recording the build's
+    /// request and fingerprint, which includes the `rerun-if-env-changed`
+    /// inputs from the `build_script_outputs` of the build.
+    ///
+    /// If the build's dep-info cannot be found, no fingerprint is recorded
+    /// and the specialisation is always rebuilt.
+    pub fn finish(&self, slot: usize, build_script_outputs: &[PathBuf]) -> io::Result<()> {
+        let fingerprint_path = self.artifact_dir.join(FINGERPRINT_FILE);
+
+        // An outdated fingerprint must not outlive a failed update
+        if fingerprint_path.exists() {
+            fs::remove_file(&fingerprint_path)?;
+        }
+
+        if self.specialisation_value != CHECK_SPECIALISATION {
+            let mut file = fs::OpenOptions::new().append(true).open(self.ptx_path())?;
+            writeln!(file, "\n// {}", self.specialisation_value)?;
+        }
+
+        fs::write(
+            self.artifact_dir.join(REQUEST_FILE),
+            self.to_json().to_string(),
+        )?;
+
+        let Some(mut paths) = self.read_dep_info(slot) else {
+            return Ok(());
+        };
+
+        paths.extend([
+            self.crate_path.join("Cargo.toml"),
+            self.manifest_path.clone(),
+            self.lockfile_path.clone(),
+        ]);
+        // Config files that do not exist yet are recorded as missing
+        paths.extend(cargo_config_paths(&self.crate_path));
+        paths.sort();
+        paths.dedup();
+
+        let mut env_vars = build_script_outputs
+            .iter()
+            .filter_map(|output| fs::read_to_string(output).ok())
+            .flat_map(|output| {
+                output
+                    .lines()
+                    .filter_map(|line| {
+                        line.strip_prefix("cargo::rerun-if-env-changed=")
+                            .or_else(|| line.strip_prefix("cargo:rerun-if-env-changed="))
+                    })
+                    .map(|var| String::from(var.trim()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        env_vars.sort();
+        env_vars.dedup();
+
+        let mut fingerprint = format!("key {:016x}\n", self.key);
+
+        for path in &paths {
+            // A source file without a valid UTF-8 path cannot be recorded
+            let Some(path_str) = path.to_str() else {
+                return Ok(());
+            };
+
+            let _ = writeln!(fingerprint, "file {} {path_str}", file_hash(path));
+        }
+
+        for var in &env_vars {
+            let _ = writeln!(fingerprint, "env {} {var}", env_hash(var));
+        }
+
+        fs::write(fingerprint_path, fingerprint)
+    }
+
+    /// Returns the stale earlier builds of other specialisations of the same
+    /// kernel crate that have been used recently and could be locked, at
+    /// most `max_siblings` of them, excluding the `builds` themselves.
+    ///
+    /// The siblings are only locked if they are not already locked by
+    /// another build, so that waiting for them can never cause a deadlock.
+    pub fn stale_siblings(builds: &[Self], max_siblings: usize) -> Vec<(Self, ArtifactLock)> {
+        let Some(build) = builds.first() else {
+            return Vec::new();
+        };
+
+        if max_siblings == 0 {
+            return Vec::new();
+        }
+
+        let Some(crate_artifacts_dir) = build.artifact_dir.parent().and_then(Path::parent) else {
+            return Vec::new();
+        };
+
+        let Ok(kernel_dirs) = fs::read_dir(crate_artifacts_dir) else {
+            return Vec::new();
+        };
+
+        let mut siblings = kernel_dirs
+            .filter_map(Result::ok)
+            .filter_map(|kernel_dir| fs::read_dir(kernel_dir.path()).ok())
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|artifact_dir| artifact_dir.path().join(REQUEST_FILE))
+            .filter(|request_path| {
+                fs::metadata(request_path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(|modified| modified.elapsed().ok())
+                    .map_or(false, |age| age < MAX_UNUSED_AGE)
+            })
+            .filter_map(|request_path| Self::from_json(&fs::read(request_path).ok()?))
+            .filter(|sibling| {
+                sibling.crate_path == build.crate_path
+                    && sibling.specialisation_value != CHECK_SPECIALISATION
+                    && builds
+                        .iter()
+                        .all(|build| build.artifact_dir != sibling.artifact_dir)
+                    && !sibling.is_fresh()
+            })
+            .collect::<Vec<_>>();
+
+        siblings.sort_by(|a, b| a.artifact_dir.cmp(&b.artifact_dir));
+
+        siblings
+            .into_iter()
+            .filter_map(|sibling| {
+                let lock = ArtifactLock::try_acquire(&sibling.artifact_dir).ok()??;
+
+                // The sibling may have been rebuilt while it was unlocked
+                (!sibling.is_fresh()).then_some((sibling, lock))
+            })
+            .take(max_siblings)
+            .collect()
+    }
+
+    /// Reads the paths of all source files that the build in the parallel
+    /// build `slot` depends on from the dep-info that cargo writes next to
+    /// the build's artifacts.
+    fn read_dep_info(&self, slot: usize) -> Option<Vec<PathBuf>> {
+        let profile_dir = self
+            .slot_target_dir(slot)
+            .join(TARGET_NAME)
+            .join(&self.profile_dir);
+        let crate_artifact_name = self.crate_name.replace('-', "_");
+
+        let dep_info = [
+            format!("{crate_artifact_name}.d"),
+            format!("lib{crate_artifact_name}.d"),
+        ]
+        .into_iter()
+        .find_map(|file| fs::read_to_string(profile_dir.join(file)).ok())?;
+
+        // The dep-info uses the Makefile syntax `<target>: <dep> <dep> ...`,
+        //  in which spaces inside paths are escaped with a backslash
+        let (_target, deps) = dep_info.lines().next()?.split_once(": ")?;
+
+        let mut paths = Vec::new();
+        let mut path = String::new();
+        let mut chars = deps.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => path.extend(chars.next()),
+                ' ' => {
+                    if !path.is_empty() {
+                        paths.push(PathBuf::from(std::mem::take(&mut path)));
+                    }
+                },
+                c => path.push(c),
+            }
+        }
+        if !path.is_empty() {
+            paths.push(PathBuf::from(path));
+        }
+
+        Some(paths)
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        // Paths that are not valid UTF-8 are stored as `null`, so that the
+        //  request cannot be read back
+        serde_json::json!({
+            "crate_name": self.crate_name,
+            "crate_path": self.crate_path.to_str(),
+            "manifest_path": self.manifest_path.to_str(),
+            "lockfile_path": self.lockfile_path.to_str(),
+            "suffix": self.suffix,
+            "specialisation_var": self.specialisation_var,
+            "specialisation_value": self.specialisation_value,
+            "cargo_args": self.cargo_args,
+            "artifact_dir": self.artifact_dir.to_str(),
+            "target_dir": self.target_dir.to_str(),
+            "profile_dir": self.profile_dir,
+            "key": format!("{:016x}", self.key),
+        })
+    }
+
+    fn from_json(json: &[u8]) -> Option<Self> {
+        let json: serde_json::Value = serde_json::from_slice(json).ok()?;
+
+        let string = |field: &str| json.get(field)?.as_str().map(String::from);
+
+        Some(Self {
+            crate_name: string("crate_name")?,
+            crate_path: PathBuf::from(string("crate_path")?),
+            manifest_path: PathBuf::from(string("manifest_path")?),
+            lockfile_path: PathBuf::from(string("lockfile_path")?),
+            suffix: string("suffix")?,
+            specialisation_var: string("specialisation_var")?,
+            specialisation_value: string("specialisation_value")?,
+            cargo_args: json
+                .get("cargo_args")?
+                .as_array()?
+                .iter()
+                .map(|arg| arg.as_str().map(String::from))
+                .collect::<Option<_>>()?,
+            artifact_dir: PathBuf::from(string("artifact_dir")?),
+            target_dir: PathBuf::from(string("target_dir")?),
+            profile_dir: string("profile_dir")?,
+            key: u64::from_str_radix(&string("key")?, 16).ok()?,
+        })
+    }
+}
+
+/// Exclusive lock on an artifact dir, which is held while the dir is being
+/// built and released when the lock is dropped.
+///
+/// The lock is a file that is created exclusively inside the artifact dir.
+/// Its holder must [`refresh`](Self::refresh) it regularly, since locks that
+/// have not been refreshed for a while are considered to be abandoned.
+pub struct ArtifactLock {
+    path: PathBuf,
+}
+
+impl ArtifactLock {
+    /// Tries to lock the `artifact_dir`, returning [`None`] if it is already
+    /// locked by another build.
+    pub fn try_acquire(artifact_dir: &Path) -> io::Result<Option<Self>> {
+        let path = artifact_dir.join(LOCK_FILE);
+
+        let stale = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .map_or(false, |age| age > STALE_LOCK_AGE);
+
+        if stale {
+            let _ = fs::remove_file(&path);
+        }
+
+        match fs::File::options().write(true).create_new(true).open(&path) {
+            Ok(_) => Ok(Some(Self { path })),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Locks the artifact dirs of all `builds` in order, waiting for other
+    /// builds to release them.
+    ///
+    /// Since every build locks the artifact dirs that it waits for in the
+    /// same order, and only tries to lock any further dirs, concurrent builds
+    /// cannot deadlock.
+    pub fn acquire_all(builds: &[KernelBuild]) -> io::Result<Vec<Self>> {
+        let mut builds = builds.iter().collect::<Vec<_>>();
+        builds.sort_by(|a, b| a.artifact_dir.cmp(&b.artifact_dir));
+
+        let mut locks = Vec::with_capacity(builds.len());
+
+        for build in builds {
+            loop {
+                if let Some(lock) = Self::try_acquire(&build.artifact_dir)? {
+                    locks.push(lock);
+                    break;
+                }
+
+                for lock in &locks {
+                    lock.refresh();
+                }
+
+                std::thread::sleep(LOCK_POLL_INTERVAL);
+            }
+        }
+
+        Ok(locks)
+    }
+
+    /// Marks the lock as still being held.
+    pub fn refresh(&self) {
+        let _ = fs::File::options()
+            .append(true)
+            .open(&self.path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+    }
+}
+
+impl Drop for ArtifactLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Returns the maximum number of kernel builds that run in parallel.
+pub fn max_parallel_builds() -> usize {
+    std::thread::available_parallelism()
+        .map_or(1, usize::from)
+        .min(MAX_PARALLEL_BUILDS)
+}
+
+/// Returns the hash of the contents of the file at `path`, or `missing` if it
+/// cannot be read.
+fn file_hash(path: &Path) -> String {
+    fs::read(path).map_or_else(
+        |_| String::from("missing"),
+        |contents| format!("{:016x}", seahash::hash(&contents)),
+    )
+}
+
+/// Returns the hash of the value of the environment variable `var`, or
+/// `unset` if it is not set.
+fn env_hash(var: &str) -> String {
+    tracked_env_var(var).map_or_else(
+        |_| String::from("unset"),
+        |value| format!("{:016x}", seahash::hash(value.as_bytes())),
+    )
+}
+
+/// Recursively copies the `from` dir to the `to` dir, keeping the
+/// modification times of all files but skipping incremental compilation
+/// caches.
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let to = to.join(entry.file_name());
+
+        if file_type.is_dir() {
+            if entry.file_name() != "incremental" {
+                copy_dir(&entry.path(), &to)?;
+            }
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), &to)?;
+
+            fs::File::options()
+                .append(true)
+                .open(&to)?
+                .set_modified(entry.metadata()?.modified()?)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        path::{Path, PathBuf},
+        time::{Duration, SystemTime},
+    };
+
+    use crate::kernel::link::TARGET_NAME;
+
+    use super::{ArtifactLock, KernelBuild, LOCK_FILE};
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "rust-cuda-kernel-cache-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn kernel_build(dir: &Path) -> KernelBuild {
+        let artifact_dir = dir
+            .join("artifacts")
+            .join("my-kernel")
+            .join("kernel")
+            .join("sm_35");
+        fs::create_dir_all(&artifact_dir).unwrap();
+
+        KernelBuild {
+            crate_name: String::from("my-kernel"),
+            crate_path: dir.join("my kernel"),
+            manifest_path: dir.join("my kernel").join("Cargo.toml"),
+            lockfile_path: dir.join("my kernel").join("Cargo.lock"),
+            suffix: String::from("sm_35"),
+            specialisation_var: String::from("RUST_CUDA_TEST_SPECIALISATION"),
+            specialisation_value: String::from("specialisation"),
+            cargo_args: vec![String::from("--release")],
+            artifact_dir,
+            target_dir: dir.join("target"),
+            profile_dir: String::from("release"),
+            key: 42,
+        }
+    }
+
+    /// Writes the dep-info of the `build` that depends on the `deps`.
+    fn write_dep_info(build: &KernelBuild, deps: &[&Path]) {
+        let profile_dir = build.target_dir.join(TARGET_NAME).join(&build.profile_dir);
+        fs::create_dir_all(&profile_dir).unwrap();
+
+        let deps = deps
+            .iter()
+            .map(|dep| dep.to_str().unwrap().replace(' ', "\\ "))
+            .collect::<Vec<_>>();
+
+        fs::write(
+            profile_dir.join("my_kernel.d"),
+            format!(
+                "{}: {}\n\n{}:\n",
+                build.ptx_path().to_str().unwrap().replace(' ', "\\ "),
+                deps.join(" "),
+                deps.join(":\n"),
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn read_dep_info_with_escaped_spaces() {
+        let dir = test_dir("dep-info");
+        let build = kernel_build(&dir);
+
+        let lib = build.crate_path.join("src").join("lib.rs");
+        let module = build.crate_path.join("src").join("my module.rs");
+        write_dep_info(&build, &[&lib, &module]);
+
+        assert_eq!(build.read_dep_info(0), Some(vec![lib, module]));
+        // other parallel build slots use their own target dirs
+        assert_eq!(build.read_dep_info(1), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn is_fresh_after_source_and_env_changes() {
+        let dir = test_dir("fresh");
+        let build = kernel_build(&dir);
+        let env_var = format!("RUST_CUDA_KERNEL_CACHE_TEST_{}", std::process::id());
+
+        let lib = build.crate_path.join("src").join("lib.rs");
+        fs::create_dir_all(lib.parent().unwrap()).unwrap();
+        fs::write(&lib, "pub fn kernel() {}").unwrap();
+        write_dep_info(&build, &[&lib]);
+
+        let build_script_output = dir.join("output");
+        fs::write(
+            &build_script_output,
+            format!("cargo::rerun-if-env-changed={env_var}\n"),
+        )
+        .unwrap();
+
+        assert!(!build.is_fresh());
+
+        fs::write(build.ptx_path(), ".version 7.0\n").unwrap();
+        build.finish(0, &[build_script_output]).unwrap();
+        assert!(build.is_fresh());
+
+        // the PTX is annotated with its specialisation
+        assert_eq!(
+            fs::read_to_string(build.ptx_path()).unwrap(),
+            ".version 7.0\n\n// specialisation\n"
+        );
+
+        fs::write(&lib, "pub fn kernel() { loop {} }").unwrap();
+        assert!(!build.is_fresh());
+        fs::write(&lib, "pub fn kernel() {}").unwrap();
+        assert!(build.is_fresh());
+
+        std::env::set_var(&env_var, "1");
+        assert!(!build.is_fresh());
+        std::env::remove_var(&env_var);
+        assert!(build.is_fresh());
+
+        // a different build configuration is never fresh
+        let other = KernelBuild {
+            key: 24,
+            ..kernel_build(&dir)
+        };
+        assert!(!other.is_fresh());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn artifact_lock_lifecycle() {
+        let dir = test_dir("lock");
+        let lock_path = dir.join(LOCK_FILE);
+
+        let lock = ArtifactLock::try_acquire(&dir).unwrap().unwrap();
+        assert!(lock_path.is_file());
+        assert!(ArtifactLock::try_acquire(&dir).unwrap().is_none());
+
+        // a refreshed lock is not stale
+        File::options()
+            .append(true)
+            .open(&lock_path)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(120))
+            .unwrap();
+        lock.refresh();
+        assert!(ArtifactLock::try_acquire(&dir).unwrap().is_none());
+
+        std::mem::drop(lock);
+        assert!(!lock_path.exists());
+
+        let lock = ArtifactLock::try_acquire(&dir).unwrap().unwrap();
+
+        // a lock that has not been refreshed for a while is taken over
+        File::options()
+            .append(true)
+            .open(&lock_path)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(120))
+            .unwrap();
+        let takeover = ArtifactLock::try_acquire(&dir).unwrap().unwrap();
+        assert!(ArtifactLock::try_acquire(&dir).unwrap().is_none());
+
+        std::mem::drop(takeover);
+        assert!(!lock_path.exists());
+        std::mem::drop(lock);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
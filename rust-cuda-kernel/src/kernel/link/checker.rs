@@ -0,0 +1,701 @@
+//! Built-in PTX checker that is used instead of the native PTX compiler when
+//! the `nvptxcompiler` feature is disabled.
+//!
+//! The checker cannot assemble the PTX code, so it only checks that the PTX
+//! is well-formed enough to be loaded: the module header is present, all
+//! delimiters are balanced, and the kernel entry point exists with valid
+//! `.param` declarations.
+
+use std::{collections::HashMap, fmt, fmt::Write as FmtWrite};
+
+use crate::kernel::{
+    lints::{LintLevel, PtxLint},
+    target::KernelTarget,
+};
+
+use super::{
+    kernel_entry_point, ptx_source_code, resources::KernelResources, source_map::PtxSourceMap,
+    Specialisation,
+};
+
+/// Lints that can only be checked by assembling the PTX code.
+const ASSEMBLER_LINTS: [PtxLint; 2] = [PtxLint::DynamicStackSize, PtxLint::DumpAssembly];
+
+/// Fundamental types that a kernel `.param` can be declared with.
+const PARAM_TYPES: [&str; 17] = [
+    ".b8", ".b16", ".b32", ".b64", ".u8", ".u16", ".u32", ".u64", ".s8", ".s16", ".s32", ".s64",
+    ".f16", ".f16x2", ".f32", ".f64", ".pred",
+];
+
+#[expect(clippy::unnecessary_wraps)] // same signature as with the PTX compiler
+pub fn check_kernel_ptx_and_report(
+    kernel_ptx: &str,
+    specialisation: Specialisation,
+    kernel_hash: &proc_macro2::Ident,
+    target: &KernelTarget,
+    ptx_lint_levels: &HashMap<PtxLint, LintLevel>,
+) -> Option<KernelResources> {
+    for lint in ASSEMBLER_LINTS {
+        if ptx_lint_levels
+            .get(&lint)
+            .map_or(false, |level| *level > LintLevel::Warn)
+        {
+            emit_call_site_warning!(
+                "The ptx::{} lint cannot be checked by the built-in PTX checker. Enable the \
+                 `nvptxcompiler` feature to check it with the PTX compiler.",
+                lint
+            );
+        }
+    }
+
+    let entry_point = kernel_entry_point(specialisation, kernel_hash);
+
+    let (params, errors) = check_ptx_module(kernel_ptx, &entry_point, target.arch.as_deref());
+
+    // The full PTX source code is only reported if requested, since all
+    //  errors are attributed to their Rust source location
+    let verbose = ptx_lint_levels
+        .get(&PtxLint::Verbose)
+        .map_or(false, |level| *level > LintLevel::Allow);
+
+    if verbose {
+        emit_call_site_warning!("PTX checker input:\n{}", ptx_source_code(kernel_ptx));
+    }
+
+    if !errors.is_empty() {
+        let source_map = PtxSourceMap::new(kernel_ptx);
+
+        for error in &errors {
+            for message in source_map.annotate_log(&error.display(kernel_ptx).to_string()) {
+                emit_call_site_error!("PTX checker error log:\n{}", message);
+            }
+        }
+
+        abort_call_site!(
+            "Failed to check the kernel with the PTX checker.{}",
+            if verbose {
+                ""
+            } else {
+                "\nSet `#[kernel(warn(ptx::verbose))]` to also report the full PTX source code."
+            }
+        );
+    }
+
+    if verbose {
+        let mut info_log = format!(
+            "info    : Found the entry point `{entry_point}` with {} parameter(s)\n",
+            params.len()
+        );
+        for param in &params {
+            let _ = writeln!(info_log, "info    :   {param}");
+        }
+
+        emit_call_site_warning!("PTX checker info log:\n{}", info_log);
+    }
+
+    // The checker cannot know the resource usage of the kernel, so the
+    //  conservative estimate is used without a warning on every kernel
+    Some(KernelResources::UNKNOWN)
+}
+
+/// Checks that the `kernel_ptx` module is well-formed, targets the `arch` if
+/// one is requested, and defines the `entry_point`, whose `.param`
+/// declarations are returned alongside all errors that were found.
+fn check_ptx_module<'a>(
+    kernel_ptx: &'a str,
+    entry_point: &str,
+    arch: Option<&str>,
+) -> (Vec<PtxParam<'a>>, Vec<CheckError>) {
+    let mut errors = Vec::new();
+    let tokens = tokenize(kernel_ptx, &mut errors);
+
+    check_delimiters(&tokens, &mut errors);
+    check_module_header(&tokens, arch, &mut errors);
+    let params = check_entry_point(&tokens, entry_point, &mut errors);
+
+    (params, errors)
+}
+
+/// A token of the PTX source code, with its byte `offset`. Comments and
+/// whitespace are skipped.
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+/// An error that the PTX checker found at a byte `offset` of the PTX source
+/// code, or in the whole module if the `offset` is [`None`].
+struct CheckError {
+    offset: Option<usize>,
+    message: String,
+}
+
+impl CheckError {
+    const fn at(offset: usize, message: String) -> Self {
+        Self {
+            offset: Some(offset),
+            message,
+        }
+    }
+
+    fn display<'a>(&'a self, kernel_ptx: &'a str) -> impl fmt::Display + 'a {
+        struct DisplayCheckError<'a> {
+            error: &'a CheckError,
+            kernel_ptx: &'a str,
+        }
+
+        impl fmt::Display for DisplayCheckError<'_> {
+            fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                match self
+                    .error
+                    .offset
+                    .and_then(|offset| self.kernel_ptx.get(..offset))
+                {
+                    Some(before) => write!(
+                        fmt,
+                        "error   : line {}; {}",
+                        before.matches('\n').count() + 1,
+                        self.error.message
+                    ),
+                    None => write!(fmt, "error   : {}", self.error.message),
+                }
+            }
+        }
+
+        DisplayCheckError {
+            error: self,
+            kernel_ptx,
+        }
+    }
+}
+
+/// A `.param` declaration of the kernel entry point.
+struct PtxParam<'a> {
+    name: &'a str,
+    ty: &'a str,
+    align: Option<u64>,
+    array_len: Option<u64>,
+}
+
+impl fmt::Display for PtxParam<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str(".param ")?;
+        if let Some(align) = self.align {
+            write!(fmt, ".align {align} ")?;
+        }
+        write!(fmt, "{} {}", self.ty, self.name)?;
+        if let Some(array_len) = self.array_len {
+            write!(fmt, "[{array_len}]")?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits the `kernel_ptx` into identifier, directive, number, and
+/// punctuation tokens, skipping whitespace, comments, and string literals.
+fn tokenize<'a>(kernel_ptx: &'a str, errors: &mut Vec<CheckError>) -> Vec<Token<'a>> {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '%' | '.');
+
+    let mut tokens = Vec::new();
+    let mut chars = kernel_ptx.char_indices().peekable();
+
+    while let Some((offset, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let rest = kernel_ptx.get(offset..).unwrap_or_default();
+
+        let skip_to = if rest.starts_with("//") {
+            Some(rest.find('\n').map_or(kernel_ptx.len(), |end| offset + end))
+        } else if rest.starts_with("/*") {
+            let end = rest.find("*/").map(|end| offset + end + 2);
+            if end.is_none() {
+                errors.push(CheckError::at(
+                    offset,
+                    String::from("unterminated block comment"),
+                ));
+            }
+            Some(end.unwrap_or(kernel_ptx.len()))
+        } else if c == '"' {
+            let end = rest
+                .get(1..)
+                .and_then(|string| string.find('"'))
+                .map(|end| offset + end + 2);
+            if end.is_none() {
+                errors.push(CheckError::at(offset, String::from("unterminated string")));
+            }
+            Some(end.unwrap_or(kernel_ptx.len()))
+        } else {
+            None
+        };
+
+        if let Some(skip_to) = skip_to {
+            while chars.next_if(|(offset, _)| *offset < skip_to).is_some() {}
+            continue;
+        }
+
+        let mut end = offset + c.len_utf8();
+        if is_word(c) {
+            while let Some((offset, c)) = chars.next_if(|(_, c)| is_word(*c)) {
+                end = offset + c.len_utf8();
+            }
+        }
+
+        tokens.push(Token {
+            text: kernel_ptx.get(offset..end).unwrap_or_default(),
+            offset,
+        });
+    }
+
+    tokens
+}
+
+/// Checks that all braces, parentheses, and brackets are balanced.
+fn check_delimiters(tokens: &[Token], errors: &mut Vec<CheckError>) {
+    let mut open = Vec::new();
+
+    for token in tokens {
+        let closes = match token.text {
+            "{" | "(" | "[" => {
+                open.push(*token);
+                continue;
+            },
+            "}" => "{",
+            ")" => "(",
+            "]" => "[",
+            _ => continue,
+        };
+
+        match open.pop() {
+            Some(opener) if opener.text == closes => (),
+            Some(opener) => {
+                errors.push(CheckError::at(
+                    token.offset,
+                    format!(
+                        "mismatched closing `{}` for an open `{}`",
+                        token.text, opener.text
+                    ),
+                ));
+                return;
+            },
+            None => {
+                errors.push(CheckError::at(
+                    token.offset,
+                    format!("unmatched closing `{}`", token.text),
+                ));
+                return;
+            },
+        }
+    }
+
+    for opener in open {
+        errors.push(CheckError::at(
+            opener.offset,
+            format!("unclosed `{}`", opener.text),
+        ));
+    }
+}
+
+/// Checks that the module starts with a `.version` directive and declares
+/// its `.target`, which must be the `arch` if one is requested.
+fn check_module_header(tokens: &[Token], arch: Option<&str>, errors: &mut Vec<CheckError>) {
+    match tokens {
+        [version, number, ..] if version.text == ".version" => {
+            if !is_version_number(number.text) {
+                errors.push(CheckError::at(
+                    number.offset,
+                    format!("invalid PTX ISA version `{}`", number.text),
+                ));
+            }
+        },
+        [first, ..] => errors.push(CheckError::at(
+            first.offset,
+            String::from("the PTX module must start with a `.version` directive"),
+        )),
+        [] => errors.push(CheckError {
+            offset: None,
+            message: String::from("the PTX module is empty"),
+        }),
+    }
+
+    match tokens.iter().position(|token| token.text == ".target") {
+        Some(target) => {
+            if let (Some(arch), Some(target)) = (arch, tokens.get(target + 1)) {
+                if target.text != arch {
+                    errors.push(CheckError::at(
+                        target.offset,
+                        format!(
+                            "the PTX module targets `{}` instead of `{arch}`",
+                            target.text
+                        ),
+                    ));
+                }
+            }
+        },
+        None => errors.push(CheckError {
+            offset: None,
+            message: String::from("the PTX module is missing a `.target` directive"),
+        }),
+    }
+}
+
+fn is_version_number(text: &str) -> bool {
+    text.split_once('.').map_or(false, |(major, minor)| {
+        !major.is_empty()
+            && !minor.is_empty()
+            && major.bytes().all(|b| b.is_ascii_digit())
+            && minor.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+/// Checks that the `entry_point` is defined exactly once and parses its
+/// `.param` declarations.
+fn check_entry_point<'a>(
+    tokens: &[Token<'a>],
+    entry_point: &str,
+    errors: &mut Vec<CheckError>,
+) -> Vec<PtxParam<'a>> {
+    let mut definitions = tokens
+        .windows(2)
+        .enumerate()
+        .filter(|(_, window)| {
+            matches!(window, [entry, name] if entry.text == ".entry" && name.text == entry_point)
+        })
+        .map(|(index, _)| index + 2);
+
+    let Some(start) = definitions.next() else {
+        errors.push(CheckError {
+            offset: None,
+            message: format!("the kernel entry point `{entry_point}` is missing"),
+        });
+        return Vec::new();
+    };
+
+    if let Some(duplicate) = definitions.next().and_then(|index| tokens.get(index - 1)) {
+        errors.push(CheckError::at(
+            duplicate.offset,
+            format!("duplicate definition of the kernel entry point `{entry_point}`"),
+        ));
+    }
+
+    let mut tokens = tokens.get(start..).unwrap_or_default().iter().peekable();
+    let mut params = Vec::new();
+
+    // Kernels without any parameters have an empty parameter list
+    if tokens.next_if(|token| token.text == "(").is_some()
+        && tokens.next_if(|token| token.text == ")").is_none()
+    {
+        loop {
+            match parse_param(&mut tokens) {
+                Ok(param) => {
+                    if params
+                        .iter()
+                        .any(|other: &PtxParam| other.name == param.name)
+                    {
+                        errors.push(CheckError {
+                            offset: None,
+                            message: format!("duplicate kernel parameter `{}`", param.name),
+                        });
+                    }
+                    params.push(param);
+                },
+                Err(error) => {
+                    errors.push(error);
+                    return params;
+                },
+            }
+
+            match tokens.next() {
+                Some(token) if token.text == "," => (),
+                Some(token) if token.text == ")" => break,
+                Some(token) => {
+                    errors.push(CheckError::at(
+                        token.offset,
+                        format!(
+                            "expected `,` or `)` after a kernel parameter, found `{}`",
+                            token.text
+                        ),
+                    ));
+                    return params;
+                },
+                None => return params,
+            }
+        }
+    }
+
+    // Performance-tuning directives may precede the body of the entry point
+    match tokens.find(|token| matches!(token.text, "{" | ";")) {
+        Some(token) if token.text == "{" => (),
+        Some(token) => errors.push(CheckError::at(
+            token.offset,
+            format!("the kernel entry point `{entry_point}` is declared but not defined"),
+        )),
+        None => errors.push(CheckError {
+            offset: None,
+            message: format!("the kernel entry point `{entry_point}` has no body"),
+        }),
+    }
+
+    params
+}
+
+/// Parses a `.param [.align N] .type name[[N]]` declaration, skipping any
+/// `.ptr` attributes.
+fn parse_param<'a>(
+    tokens: &mut std::iter::Peekable<std::slice::Iter<Token<'a>>>,
+) -> Result<PtxParam<'a>, CheckError> {
+    let unexpected = |token: Option<&Token>, expected: &str| CheckError {
+        offset: token.map(|token| token.offset),
+        message: token.map_or_else(
+            || format!("expected {expected} in a kernel parameter, found the end of input"),
+            |token| {
+                format!(
+                    "expected {expected} in a kernel parameter, found `{}`",
+                    token.text
+                )
+            },
+        ),
+    };
+
+    match tokens.next() {
+        Some(token) if token.text == ".param" => (),
+        token => return Err(unexpected(token, "`.param`")),
+    }
+
+    let mut align = None;
+    let mut ty = None;
+    // the alignment after `.ptr` is that of the pointee, not the parameter
+    let mut is_ptr = false;
+
+    let name = loop {
+        match tokens.next() {
+            Some(token) if token.text == ".align" => match tokens.next() {
+                Some(number) => match number.text.parse() {
+                    Ok(number) if u64::is_power_of_two(number) => {
+                        if !is_ptr {
+                            align = Some(number);
+                        }
+                    },
+                    _ => return Err(unexpected(Some(number), "a power-of-two alignment")),
+                },
+                None => return Err(unexpected(None, "an alignment")),
+            },
+            Some(token) if PARAM_TYPES.contains(&token.text) && ty.is_none() => {
+                ty = Some(token.text);
+            },
+            Some(token) if token.text == ".ptr" => is_ptr = true,
+            Some(token)
+                if is_ptr && matches!(token.text, ".const" | ".global" | ".local" | ".shared") => {
+            },
+            Some(token) if !token.text.starts_with('.') && is_identifier(token.text) => {
+                break token.text;
+            },
+            token => return Err(unexpected(token, "a parameter type or name")),
+        }
+    };
+
+    let Some(ty) = ty else {
+        return Err(CheckError {
+            offset: None,
+            message: format!("the kernel parameter `{name}` has no type"),
+        });
+    };
+
+    let mut array_len = None;
+    if tokens.next_if(|token| token.text == "[").is_some() {
+        match tokens.next() {
+            Some(number) => match number.text.parse() {
+                Ok(number) => array_len = Some(number),
+                Err(_) => return Err(unexpected(Some(number), "an array length")),
+            },
+            None => return Err(unexpected(None, "an array length")),
+        }
+
+        match tokens.next() {
+            Some(token) if token.text == "]" => (),
+            token => return Err(unexpected(token, "`]`")),
+        }
+    }
+
+    Ok(PtxParam {
+        name,
+        ty,
+        align,
+        array_len,
+    })
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+
+    chars.next().map_or(false, |c| {
+        c.is_ascii_alphabetic() || matches!(c, '_' | '$' | '%')
+    }) && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '$'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_ptx_module;
+
+    const HEADER: &str = ".version 7.0\n.target sm_35\n.address_size 64\n\n";
+
+    /// Checks the PTX `module` with the `kernel` entry point for `sm_35` and
+    /// returns its displayed parameters and errors.
+    fn check(module: &str) -> (Vec<String>, Vec<String>) {
+        let (params, errors) = check_ptx_module(module, "kernel", Some("sm_35"));
+
+        (
+            params.iter().map(ToString::to_string).collect(),
+            errors
+                .iter()
+                .map(|error| error.display(module).to_string())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn entry_point_with_params() {
+        let module = format!(
+            "{HEADER}.visible .entry kernel(\n\t.param .u64 kernel_param_0,\n\t.param .f32 \
+             kernel_param_1\n)\n{{\n\tret;\n}}\n"
+        );
+
+        assert_eq!(
+            check(&module),
+            (
+                vec![
+                    String::from(".param .u64 kernel_param_0"),
+                    String::from(".param .f32 kernel_param_1")
+                ],
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn entry_point_without_params() {
+        let module =
+            format!("{HEADER}.visible .entry kernel()\n.maxntid 32, 1, 1\n{{\n\tret;\n}}\n");
+
+        assert_eq!(check(&module), (vec![], vec![]));
+    }
+
+    #[test]
+    fn pointer_and_array_params() {
+        let module = format!(
+            "{HEADER}.visible .entry kernel(\n\t.param .u64 .ptr .global .align 16 \
+             kernel_param_0,\n\t.param .align 8 .b8 kernel_param_1[24]\n)\n{{\n\tret;\n}}\n"
+        );
+
+        // The alignment after `.ptr` is that of the pointee
+        assert_eq!(
+            check(&module),
+            (
+                vec![
+                    String::from(".param .u64 kernel_param_0"),
+                    String::from(".param .align 8 .b8 kernel_param_1[24]")
+                ],
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_params() {
+        let module = format!(
+            "{HEADER}.visible .entry kernel(\n\t.param .align 3 .b8 \
+             kernel_param_0[8]\n)\n{{\n\tret;\n}}\n"
+        );
+
+        assert_eq!(
+            check(&module).1,
+            [String::from(
+                "error   : line 6; expected a power-of-two alignment in a kernel parameter, found \
+                 `3`"
+            )]
+        );
+    }
+
+    #[test]
+    fn unbalanced_delimiters() {
+        let module = format!("{HEADER}.visible .entry kernel()\n{{\n\tret;\n");
+
+        assert_eq!(
+            check(&module).1,
+            [String::from("error   : line 6; unclosed `{`")]
+        );
+
+        let module = format!("{HEADER}.visible .entry kernel()\n{{\n\tld.u32 %r1, [%rd1);\n}}\n");
+
+        assert_eq!(
+            check(&module).1,
+            [String::from(
+                "error   : line 7; mismatched closing `)` for an open `[`"
+            )]
+        );
+
+        let module = format!("{HEADER}.visible .entry kernel()\n{{\n\tret;\n}}\n}}\n");
+
+        assert_eq!(
+            check(&module).1,
+            [String::from("error   : line 9; unmatched closing `}`")]
+        );
+    }
+
+    #[test]
+    fn missing_entry_point() {
+        let module = format!("{HEADER}.visible .entry other()\n{{\n\tret;\n}}\n");
+
+        assert_eq!(
+            check(&module).1,
+            [String::from(
+                "error   : the kernel entry point `kernel` is missing"
+            )]
+        );
+    }
+
+    #[test]
+    fn duplicate_entry_point() {
+        let module = format!(
+            "{HEADER}.visible .entry kernel()\n{{\n\tret;\n}}\n.visible .entry \
+             kernel()\n{{\n\tret;\n}}\n"
+        );
+
+        assert_eq!(
+            check(&module).1,
+            [String::from(
+                "error   : line 9; duplicate definition of the kernel entry point `kernel`"
+            )]
+        );
+    }
+
+    #[test]
+    fn mismatched_target() {
+        let module = ".version 7.0\n.target sm_80\n.address_size 64\n\n.visible .entry \
+                      kernel()\n{\n\tret;\n}\n";
+
+        assert_eq!(
+            check(module).1,
+            [String::from(
+                "error   : line 2; the PTX module targets `sm_80` instead of `sm_35`"
+            )]
+        );
+    }
+
+    #[test]
+    fn missing_module_header() {
+        let module = ".visible .entry kernel()\n{\n\tret;\n}\n";
+
+        assert_eq!(
+            check(module).1,
+            [
+                String::from(
+                    "error   : line 1; the PTX module must start with a `.version` directive"
+                ),
+                String::from("error   : the PTX module is missing a `.target` directive"),
+            ]
+        );
+    }
+}
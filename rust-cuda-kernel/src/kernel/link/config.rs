@@ -1,16 +1,24 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use quote::quote;
+use syn::spanned::Spanned;
 
-use crate::kernel::lints::{parse_ptx_lint_level, LintLevel, PtxLint};
+use crate::kernel::{
+    lints::{parse_ptx_lint_level, LintLevel, PtxLint},
+    target::KernelTarget,
+};
 
 pub(super) struct LinkKernelConfig {
     pub(super) kernel: syn::Ident,
     pub(super) kernel_hash: syn::Ident,
     pub(super) crate_name: String,
     pub(super) crate_path: PathBuf,
+    pub(super) bin: Option<String>,
     pub(super) specialisation: String,
     pub(super) ptx_lint_levels: HashMap<PtxLint, LintLevel>,
+    pub(super) register_budget: Option<u32>,
+    pub(super) target: KernelTarget,
+    pub(super) archs: Vec<String>,
 }
 
 impl syn::parse::Parse for LinkKernelConfig {
@@ -45,9 +53,37 @@ impl syn::parse::Parse for LinkKernelConfig {
         >::parse_separated_nonempty(input)?;
 
         let mut ptx_lint_levels = HashMap::new();
+        let mut register_budget = None;
+        let mut target = KernelTarget::default();
+        let mut archs = Vec::new();
+        let mut bin = None;
 
         for attr in attrs {
-            parse_ptx_lint_level(&attr, &mut ptx_lint_levels);
+            if attr.path.is_ident("bin") {
+                bin = Some(attr.parse_args::<syn::LitStr>()?.value());
+            } else if attr.path.is_ident("archs") {
+                archs = KernelTarget::parse_archs(&attr)?;
+            } else if attr.path.is_ident("register_budget") {
+                register_budget = Some(attr.parse_args::<syn::LitInt>()?.base10_parse()?);
+            } else if attr.path.is_ident("target") {
+                target.parse_target(&attr);
+            } else if attr.path.is_ident("opt_level") {
+                target.parse_opt_level(&attr.parse_args()?);
+            } else if attr.path.is_ident("debug") {
+                target.parse_debug(&attr.parse_args()?);
+            } else if attr.path.is_ident("crate_features")
+                || attr.path.is_ident("cargo_args")
+                || attr.path.is_ident("rustflags")
+            {
+                target.parse_args(
+                    &attr.path,
+                    attr.parse_args_with(
+                        syn::punctuated::Punctuated::<syn::LitStr, syn::token::Comma>::parse_terminated,
+                    )?,
+                );
+            } else {
+                parse_ptx_lint_level(&attr, &mut ptx_lint_levels);
+            }
         }
 
         proc_macro_error2::abort_if_dirty();
@@ -57,8 +93,12 @@ impl syn::parse::Parse for LinkKernelConfig {
             kernel_hash,
             crate_name: name.value(),
             crate_path: PathBuf::from(path.value()),
+            bin,
             specialisation,
             ptx_lint_levels,
+            register_budget,
+            target,
+            archs,
         })
     }
 }
@@ -68,6 +108,8 @@ pub(super) struct CheckKernelConfig {
     pub(super) kernel_hash: syn::Ident,
     pub(super) crate_name: String,
     pub(super) crate_path: PathBuf,
+    pub(super) bin: Option<String>,
+    pub(super) target: KernelTarget,
 }
 
 impl syn::parse::Parse for CheckKernelConfig {
@@ -77,11 +119,49 @@ impl syn::parse::Parse for CheckKernelConfig {
         let name: syn::LitStr = input.parse()?;
         let path: syn::LitStr = input.parse()?;
 
+        let mut target = KernelTarget::default();
+        let mut bin = None;
+
+        while input.parse::<Option<syn::token::Comma>>()?.is_some() {
+            let attr: syn::MetaList = input.parse()?;
+
+            if attr.path.is_ident("bin") {
+                bin = Some(attr.parse_args::<syn::LitStr>()?.value());
+            } else if attr.path.is_ident("target") {
+                target.parse_target(&attr);
+            } else if attr.path.is_ident("opt_level") {
+                target.parse_opt_level(&attr.parse_args()?);
+            } else if attr.path.is_ident("debug") {
+                target.parse_debug(&attr.parse_args()?);
+            } else if attr.path.is_ident("crate_features")
+                || attr.path.is_ident("cargo_args")
+                || attr.path.is_ident("rustflags")
+            {
+                target.parse_args(
+                    &attr.path,
+                    attr.parse_args_with(
+                        syn::punctuated::Punctuated::<syn::LitStr, syn::token::Comma>::parse_terminated,
+                    )?,
+                );
+            } else {
+                emit_error!(
+                    attr.path.span(),
+                    "[rust-cuda]: Expected `bin(...)`, `target(...)`, `opt_level(...)`, \
+                     `debug(...)`, `crate_features(...)`, `cargo_args(...)`, or `rustflags(...)` \
+                     kernel check option."
+                );
+            }
+        }
+
+        proc_macro_error2::abort_if_dirty();
+
         Ok(Self {
             kernel,
             kernel_hash,
             crate_name: name.value(),
             crate_path: PathBuf::from(path.value()),
+            bin,
+            target,
         })
     }
 }
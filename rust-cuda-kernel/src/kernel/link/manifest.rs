@@ -0,0 +1,588 @@
+//! Resolution of the library target that a kernel is compiled from for CUDA.
+//!
+//! Kernels that are defined in a library target are compiled from the crate's
+//! own manifest. Kernels that are defined in a binary target are compiled from
+//! a synthetic manifest, which declares the binary's root source file as its
+//! library target, depends on the crate's own library target, and otherwise
+//! mirrors the crate's package and its workspace's profiles.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as FmtWrite,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use cargo_metadata::{DependencyKind, Metadata, MetadataCommand, Package, Target, TargetKind};
+
+/// Returns the path to the manifest whose library target contains the
+/// kernels of the `crate_name` crate at `crate_path`, where `bin` is the name
+/// of the binary target if the kernels are defined in one, and the path to
+/// the lockfile of the crate's workspace.
+///
+/// The synthetic manifest of a binary target belongs to its own workspace,
+/// which can only reuse the locked dependency versions of the crate's
+/// workspace if cargo is allowed to update the copied lockfile. If the
+/// `cargo_args` require the lockfile to be up-to-date, i.e. contain
+/// `--locked` or `--frozen`, the synthetic crate instead resolves its own
+/// lockfile, and these arguments are relaxed to only keep cargo offline.
+pub fn kernel_crate_manifest(
+    crate_path: &Path,
+    crate_name: &str,
+    bin: Option<&str>,
+    cargo_args: &mut Vec<String>,
+    scratch_dir: &Path,
+) -> (PathBuf, PathBuf) {
+    let manifest_path = crate_path.join("Cargo.toml");
+
+    if !matches!(fs::metadata(&manifest_path), Ok(metadata) if metadata.is_file()) {
+        abort_call_site!("{:?} is not a valid crate manifest path", manifest_path);
+    }
+
+    let metadata = match MetadataCommand::new()
+        .manifest_path(&manifest_path)
+        .no_deps()
+        .exec()
+    {
+        Ok(metadata) => metadata,
+        Err(err) => abort_call_site!(
+            "Failed to read the cargo metadata of {}: {}",
+            crate_name,
+            err
+        ),
+    };
+
+    let Some(package) = find_package(&metadata, &manifest_path) else {
+        abort_call_site!("{:?} is not a package manifest", manifest_path);
+    };
+
+    let Some(bin) = bin else {
+        if !package
+            .targets
+            .iter()
+            .any(|target| is_library(target) && target.name.replace('-', "_") == crate_name)
+        {
+            abort_call_site!(
+                "{} has no library target named `{}`",
+                package.name,
+                crate_name
+            );
+        }
+
+        return (manifest_path, lockfile_path(&metadata));
+    };
+
+    let Some(bin_target) = package
+        .targets
+        .iter()
+        .find(|target| (target.is_bin() || target.is_example()) && target.name == bin)
+    else {
+        abort_call_site!(
+            "{} has no binary or example target named `{}`",
+            package.name,
+            bin
+        );
+    };
+
+    let synthetic_path = scratch_dir.join("crates").join(format!(
+        "{crate_name}-{:016x}",
+        seahash::hash(manifest_path.as_os_str().as_encoded_bytes())
+    ));
+    if let Err(err) = fs::create_dir_all(&synthetic_path) {
+        abort_call_site!(
+            "Failed to create the synthetic crate dir {:?}: {}",
+            synthetic_path,
+            err
+        );
+    }
+
+    let synthetic_manifest_path = synthetic_path.join("Cargo.toml");
+    let mut synthetic_manifest = synthetic_manifest(package, bin_target, crate_name);
+    synthetic_manifest.push_str(&workspace_profiles(&metadata));
+
+    // Only write changed files to avoid spurious rebuilds
+    write_if_changed(&synthetic_manifest_path, synthetic_manifest.as_bytes());
+
+    let synthetic_lockfile_path = synthetic_path.join("Cargo.lock");
+
+    if unlock_cargo_args(cargo_args) {
+        // The copied lockfile would have to be pruned to the synthetic crate
+        if let Err(err) = fs::remove_file(&synthetic_lockfile_path) {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                abort_call_site!("Failed to remove {:?}: {}", synthetic_lockfile_path, err);
+            }
+        }
+    } else if let Ok(lockfile) = fs::read(lockfile_path(&metadata)) {
+        // Reuse the locked dependency versions of the crate's workspace
+        write_if_changed(&synthetic_lockfile_path, &lockfile);
+    }
+
+    (synthetic_manifest_path, lockfile_path(&metadata))
+}
+
+/// Returns the package of the workspace `metadata` whose manifest is at the
+/// `manifest_path`.
+///
+/// Without the resolved dependency graph, cargo metadata does not know which
+/// package is the root, and the workspace root package may not be the kernel
+/// crate's package.
+fn find_package<'a>(metadata: &'a Metadata, manifest_path: &Path) -> Option<&'a Package> {
+    metadata
+        .packages
+        .iter()
+        .find(|package| package.manifest_path == manifest_path)
+}
+
+/// Relaxes the `--locked` and `--frozen` `cargo_args` to only keep cargo
+/// offline, and returns `true` iff any of them were present.
+fn unlock_cargo_args(cargo_args: &mut Vec<String>) -> bool {
+    let locked = cargo_args
+        .iter()
+        .any(|arg| arg == "--locked" || arg == "--frozen");
+
+    cargo_args.retain(|arg| arg != "--locked");
+    for arg in cargo_args.iter_mut() {
+        if arg == "--frozen" {
+            *arg = String::from("--offline");
+        }
+    }
+
+    locked
+}
+
+fn is_library(target: &Target) -> bool {
+    target.kind.iter().any(|kind| {
+        matches!(
+            kind,
+            TargetKind::Lib
+                | TargetKind::RLib
+                | TargetKind::DyLib
+                | TargetKind::CDyLib
+                | TargetKind::StaticLib
+        )
+    })
+}
+
+fn lockfile_path(metadata: &Metadata) -> PathBuf {
+    metadata
+        .workspace_root
+        .join("Cargo.lock")
+        .into_std_path_buf()
+}
+
+fn write_if_changed(path: &Path, contents: &[u8]) {
+    if fs::read(path).map_or(false, |existing| existing == contents) {
+        return;
+    }
+
+    if let Err(err) = fs::write(path, contents) {
+        abort_call_site!("Failed to write {:?}: {}", path, err);
+    }
+}
+
+/// Returns the `[profile.*]` sections of the root manifest of the crate's
+/// workspace, since cargo only applies the profiles of the workspace root.
+///
+/// The sections are copied verbatim, which assumes that no line inside them
+/// starts with a `[`, e.g. as part of a multi-line array.
+fn workspace_profiles(metadata: &Metadata) -> String {
+    let root_manifest_path = metadata.workspace_root.join("Cargo.toml");
+
+    let root_manifest = match fs::read_to_string(&root_manifest_path) {
+        Ok(root_manifest) => root_manifest,
+        Err(err) => abort_call_site!("Failed to read {:?}: {}", root_manifest_path, err),
+    };
+
+    let mut profiles = String::new();
+    let mut in_profile = false;
+
+    for line in root_manifest.lines() {
+        if let Some(header) = line.trim_start().strip_prefix('[') {
+            in_profile = !header.starts_with('[')
+                && header
+                    .trim_start()
+                    .strip_prefix("profile")
+                    .map_or(false, |rest| rest.trim_start().starts_with(['.', ']']));
+
+            if in_profile {
+                profiles.push('\n');
+            }
+        }
+
+        if in_profile {
+            profiles.push_str(line);
+            profiles.push('\n');
+        }
+    }
+
+    profiles
+}
+
+/// Generates a standalone manifest for the `package` with a `cdylib` library
+/// target named `crate_name` whose root is that of the `bin_target`.
+///
+/// The synthetic package is renamed so that it can depend on the `package`'s
+/// own library target, if any, which the binary may use. All features of the
+/// `package` are forwarded to this library dependency.
+///
+/// All paths are absolute so that the manifest can be placed anywhere.
+/// Workspace-level `[patch]` and `[replace]` sections are not reflected in
+/// the package metadata and are thus not supported.
+fn synthetic_manifest(package: &Package, bin_target: &Target, crate_name: &str) -> String {
+    let mut manifest = format!(
+        "# Synthetic library target for the `{}` target of the `{}` package, which is generated \
+         by rust-cuda to compile its kernels for CUDA\n\n",
+        bin_target.name, package.name
+    );
+
+    let library = package.targets.iter().any(is_library).then(|| {
+        let package_dir = package
+            .manifest_path
+            .parent()
+            .map_or(package.manifest_path.as_str(), |dir| dir.as_str());
+
+        format!(
+            "{} = {{ path = {}, default-features = false }}",
+            toml_string(&package.name),
+            toml_string(package_dir)
+        )
+    });
+
+    let _ = writeln!(manifest, "[package]");
+    let _ = writeln!(
+        manifest,
+        "name = {}",
+        toml_string(&format!("{}-{}-kernels", package.name, bin_target.name))
+    );
+    let _ = writeln!(
+        manifest,
+        "version = {}",
+        toml_string(&package.version.to_string())
+    );
+    let _ = writeln!(
+        manifest,
+        "edition = {}",
+        toml_string(bin_target.edition.as_str())
+    );
+    if let Some(links) = &package.links {
+        let _ = writeln!(manifest, "links = {}", toml_string(links));
+    }
+    let _ = writeln!(
+        manifest,
+        "build = {}",
+        package
+            .targets
+            .iter()
+            .find(|target| target.is_custom_build())
+            .map_or_else(
+                || String::from("false"),
+                |build| toml_string(build.src_path.as_str())
+            )
+    );
+    let _ = writeln!(manifest, "autobins = false");
+    let _ = writeln!(manifest, "autoexamples = false");
+    let _ = writeln!(manifest, "autotests = false");
+    let _ = writeln!(manifest, "autobenches = false");
+
+    let _ = writeln!(manifest, "\n[lib]");
+    let _ = writeln!(manifest, "name = {}", toml_string(crate_name));
+    let _ = writeln!(
+        manifest,
+        "path = {}",
+        toml_string(bin_target.src_path.as_str())
+    );
+    let _ = writeln!(manifest, "crate-type = [\"cdylib\"]");
+
+    let _ = writeln!(manifest, "\n[features]");
+    for (feature, enables) in &package.features {
+        let _ = writeln!(
+            manifest,
+            "{} = [{}]",
+            toml_string(feature),
+            enables
+                .iter()
+                .map(String::as_str)
+                .map(toml_string)
+                .chain(
+                    library
+                        .is_some()
+                        .then(|| toml_string(&format!("{}/{feature}", package.name)))
+                )
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    let mut dependency_sections = dependency_sections(package);
+    if let Some(library) = library {
+        dependency_sections
+            .entry(String::from("dependencies"))
+            .or_default()
+            .push(library);
+    }
+
+    for (section, dependencies) in dependency_sections {
+        let _ = writeln!(manifest, "\n[{section}]");
+        for dependency in dependencies {
+            let _ = writeln!(manifest, "{dependency}");
+        }
+    }
+
+    // The synthetic crate is not part of any workspace
+    let _ = writeln!(manifest, "\n[workspace]");
+
+    manifest
+}
+
+/// Groups the normal and build dependencies of the `package` into manifest
+/// sections, e.g. `dependencies` or `target."cfg(unix)".build-dependencies`.
+fn dependency_sections(package: &Package) -> BTreeMap<String, Vec<String>> {
+    let mut sections: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for dependency in &package.dependencies {
+        let kind = match dependency.kind {
+            DependencyKind::Normal => "dependencies",
+            DependencyKind::Build => "build-dependencies",
+            _ => continue,
+        };
+
+        let section = dependency.target.as_ref().map_or_else(
+            || String::from(kind),
+            |platform| format!("target.{}.{kind}", toml_string(&platform.to_string())),
+        );
+
+        let mut fields = Vec::new();
+
+        if dependency.rename.is_some() {
+            fields.push(format!("package = {}", toml_string(&dependency.name)));
+        }
+        fields.push(format!(
+            "version = {}",
+            toml_string(&dependency.req.to_string())
+        ));
+        if let Some(path) = &dependency.path {
+            fields.push(format!("path = {}", toml_string(path.as_str())));
+        }
+        if let Some(git) = dependency
+            .source
+            .as_deref()
+            .and_then(|source| source.strip_prefix("git+"))
+        {
+            let git = git.split_once('#').map_or(git, |(git, _commit)| git);
+            let (url, query) = git.split_once('?').unwrap_or((git, ""));
+
+            fields.push(format!("git = {}", toml_string(url)));
+
+            for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+                if matches!(key, "rev" | "branch" | "tag") {
+                    fields.push(format!("{key} = {}", toml_string(value)));
+                }
+            }
+        }
+        if let Some(registry) = &dependency.registry {
+            fields.push(format!("registry-index = {}", toml_string(registry)));
+        }
+        if dependency.optional {
+            fields.push(String::from("optional = true"));
+        }
+        if !dependency.uses_default_features {
+            fields.push(String::from("default-features = false"));
+        }
+        if !dependency.features.is_empty() {
+            fields.push(format!(
+                "features = [{}]",
+                dependency
+                    .features
+                    .iter()
+                    .map(|feature| toml_string(feature))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        sections.entry(section).or_default().push(format!(
+            "{} = {{ {} }}",
+            toml_string(dependency.rename.as_ref().unwrap_or(&dependency.name)),
+            fields.join(", ")
+        ));
+    }
+
+    sections
+}
+
+/// Quotes the `string` as a TOML basic string, which supports all escape
+/// sequences that are used in JSON strings.
+fn toml_string(string: &str) -> String {
+    serde_json::Value::from(string).to_string()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use cargo_metadata::{Metadata, MetadataCommand};
+
+    use super::{find_package, synthetic_manifest, unlock_cargo_args, workspace_profiles};
+
+    /// Creates a workspace whose root package `root` has the member package
+    /// `member` with a library and a `my-bin` binary target, and returns
+    /// its metadata.
+    fn workspace(name: &str) -> (PathBuf, Metadata) {
+        let dir = std::env::temp_dir().join(format!(
+            "rust-cuda-kernel-manifest-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(dir.join("member").join("src").join("bin")).unwrap();
+
+        fs::write(
+            dir.join("Cargo.toml"),
+            r#"[package]
+name = "root"
+version = "0.1.0"
+edition = "2021"
+
+[workspace]
+members = ["member"]
+
+[profile.release]
+opt-level = 3
+
+[profile.release.package."*"]
+debug = false
+
+[workspace.lints.rust]
+unsafe_code = "deny"
+"#,
+        )
+        .unwrap();
+        fs::write(dir.join("src").join("lib.rs"), "").unwrap();
+
+        fs::write(
+            dir.join("member").join("Cargo.toml"),
+            r#"[package]
+name = "member"
+version = "0.2.0"
+edition = "2021"
+
+[features]
+default = ["fast"]
+fast = []
+
+[dependencies]
+root = { path = "..", default-features = false }
+
+[dev-dependencies]
+root = { path = ".." }
+"#,
+        )
+        .unwrap();
+        fs::write(dir.join("member").join("src").join("lib.rs"), "").unwrap();
+        fs::write(
+            dir.join("member").join("src").join("bin").join("my-bin.rs"),
+            "fn main() {}",
+        )
+        .unwrap();
+
+        let metadata = MetadataCommand::new()
+            .manifest_path(dir.join("member").join("Cargo.toml"))
+            .no_deps()
+            .exec()
+            .unwrap();
+
+        (dir, metadata)
+    }
+
+    #[test]
+    fn find_workspace_member_package() {
+        let (dir, metadata) = workspace("member");
+
+        let member = find_package(&metadata, &dir.join("member").join("Cargo.toml")).unwrap();
+        assert_eq!(member.name, "member");
+
+        let root = find_package(&metadata, &dir.join("Cargo.toml")).unwrap();
+        assert_eq!(root.name, "root");
+
+        assert!(find_package(&metadata, &dir.join("other").join("Cargo.toml")).is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn synthetic_library_manifest() {
+        let (dir, metadata) = workspace("synthetic");
+
+        let member = find_package(&metadata, &dir.join("member").join("Cargo.toml")).unwrap();
+        let bin = member
+            .targets
+            .iter()
+            .find(|target| target.name == "my-bin")
+            .unwrap();
+
+        let manifest = synthetic_manifest(member, bin, "my_bin");
+        let member_dir = serde_json::Value::from(dir.join("member").to_str().unwrap());
+        let bin_path = serde_json::Value::from(bin.src_path.as_str());
+
+        for line in [
+            String::from("name = \"member-my-bin-kernels\""),
+            String::from("version = \"0.2.0\""),
+            String::from("edition = \"2021\""),
+            String::from("build = false"),
+            String::from("name = \"my_bin\""),
+            format!("path = {bin_path}"),
+            String::from("crate-type = [\"cdylib\"]"),
+            String::from("\"default\" = [\"fast\", \"member/default\"]"),
+            String::from("\"fast\" = [\"member/fast\"]"),
+            format!("\"member\" = {{ path = {member_dir}, default-features = false }}"),
+        ] {
+            assert!(
+                manifest.lines().any(|manifest_line| manifest_line == line),
+                "{line} is missing from:\n{manifest}"
+            );
+        }
+
+        // Only normal and build dependencies are needed for the library
+        let dependencies = manifest
+            .lines()
+            .filter(|line| line.starts_with("\"root\" = "))
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            dependencies.as_slice(),
+            [dependency] if dependency.contains("default-features = false")
+        ));
+        assert!(!manifest.contains("[dev-dependencies]"));
+        assert!(manifest.trim_end().ends_with("[workspace]"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn copied_workspace_profiles() {
+        let (dir, metadata) = workspace("profiles");
+
+        assert_eq!(
+            workspace_profiles(&metadata),
+            "\n[profile.release]\nopt-level = 3\n\n\n[profile.release.package.\"*\"]\ndebug = \
+             false\n\n"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unlocked_cargo_args() {
+        let mut cargo_args = vec![String::from("--release"), String::from("--offline")];
+        assert!(!unlock_cargo_args(&mut cargo_args));
+        assert_eq!(cargo_args, ["--release", "--offline"]);
+
+        let mut cargo_args = vec![String::from("--locked"), String::from("--release")];
+        assert!(unlock_cargo_args(&mut cargo_args));
+        assert_eq!(cargo_args, ["--release"]);
+
+        let mut cargo_args = vec![String::from("--frozen"), String::from("--locked")];
+        assert!(unlock_cargo_args(&mut cargo_args));
+        assert_eq!(cargo_args, ["--offline"]);
+    }
+}
@@ -1,33 +1,52 @@
 use std::{
-    cell::Cell,
+    cell::{Cell, RefCell},
     collections::HashMap,
     ffi::CString,
-    fmt::Write as FmtWrite,
     fs,
-    io::{Read, Write},
-    os::raw::c_int,
+    io::Read,
     path::{Path, PathBuf},
-    ptr::addr_of_mut,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, PoisonError,
+    },
 };
+#[cfg(feature = "nvptxcompiler")]
+use std::{fmt::Write as FmtWrite, os::raw::c_int, ptr::addr_of_mut};
 
-use cargo_util::{ProcessBuilder, ProcessError};
+use cargo_util::ProcessBuilder;
 use colored::Colorize;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
 
+#[cfg(feature = "nvptxcompiler")]
+use crate::kernel::lints::{LintLevel, PtxLint};
 use crate::kernel::{
-    lints::{LintLevel, PtxLint},
-    utils::skip_kernel_compilation,
-    CHECK_SPECIALISATION, KERNEL_TYPE_LAYOUT_HASH_SEED_IDENT, KERNEL_TYPE_LAYOUT_IDENT,
-    KERNEL_TYPE_USE_END_CANARY, KERNEL_TYPE_USE_START_CANARY, PTX_CSTR_IDENT,
+    target::KernelTarget, utils::skip_kernel_compilation, CHECK_SPECIALISATION,
+    KERNEL_RESOURCES_IDENT, KERNEL_TYPE_LAYOUT_HASH_SEED_IDENT, KERNEL_TYPE_LAYOUT_IDENT,
+    KERNEL_TYPE_USE_END_CANARY, KERNEL_TYPE_USE_START_CANARY, PTX_CSTR_IDENT, PTX_VARIANTS_IDENT,
 };
 
+mod analysis;
+mod cache;
+#[cfg(not(feature = "nvptxcompiler"))]
+mod checker;
 mod config;
+mod manifest;
+#[cfg(feature = "nvptxcompiler")]
 mod ptx_compiler_sys;
+mod resources;
+mod source_map;
 
+use cache::{max_parallel_builds, ArtifactLock, KernelBuild, LOCK_POLL_INTERVAL};
+#[cfg(not(feature = "nvptxcompiler"))]
+use checker::check_kernel_ptx_and_report;
 use config::{CheckKernelConfig, LinkKernelConfig};
+#[cfg(feature = "nvptxcompiler")]
 use ptx_compiler_sys::NvptxError;
+use resources::KernelResources;
+#[cfg(feature = "nvptxcompiler")]
+use source_map::PtxSourceMap;
 
 const TARGET_NAME: &str = "nvptx64-nvidia-cuda";
 
@@ -41,38 +60,66 @@ pub fn check_kernel(tokens: TokenStream) -> TokenStream {
         kernel_hash,
         crate_name,
         crate_path,
+        bin,
+        mut target,
     } = match syn::parse(tokens) {
         Ok(config) => config,
         Err(err) => {
             abort_call_site!(
-                "check_kernel!(KERNEL HASH NAME PATH) expects KERNEL and HASH identifiers, annd \
-                 NAME and PATH string literals: {:?}",
+                "check_kernel!(KERNEL HASH NAME PATH TARGET,*) expects KERNEL and HASH \
+                 identifiers, NAME and PATH string literals, and optional TARGET tokens: {:?}",
                 err
             )
         },
     };
 
-    let kernel_ptx = compile_kernel_ptx(&kernel, &crate_name, &crate_path, Specialisation::Check);
+    target.apply_env_overrides();
 
-    check_kernel_ptx_and_report(
-        &kernel_ptx,
+    for kernel_ptx in compile_kernel_ptx(
+        &kernel,
+        &crate_name,
+        &crate_path,
+        bin.as_deref(),
         Specialisation::Check,
-        &kernel_hash,
-        &HashMap::new(),
-    );
+        std::slice::from_ref(&target),
+    ) {
+        let _ = check_kernel_ptx_and_report(
+            &kernel_ptx,
+            Specialisation::Check,
+            &kernel_hash,
+            &target,
+            &HashMap::new(),
+        );
+    }
 
     quote!().into()
 }
 
+#[expect(clippy::too_many_lines)]
 pub fn compile_kernel(tokens: TokenStream) -> TokenStream {
     let ptx_cstr_ident = syn::Ident::new(PTX_CSTR_IDENT, Span::call_site());
     let ffi_signature_ident = syn::Ident::new(KERNEL_TYPE_LAYOUT_IDENT, Span::call_site());
     let ffi_signature_hash_seed_ident =
         syn::Ident::new(KERNEL_TYPE_LAYOUT_HASH_SEED_IDENT, Span::call_site());
+    let resources_ident = syn::Ident::new(KERNEL_RESOURCES_IDENT, Span::call_site());
+
+    let ptx_variants_ident = syn::Ident::new(PTX_VARIANTS_IDENT, Span::call_site());
+
+    let unknown_resources = KernelResources::UNKNOWN.quote(&resources_ident);
+    let unknown_variants = quote! {
+        const #ptx_variants_ident: &'static [KernelPtxVariant] = &[KernelPtxVariant {
+            arch: ::core::option::Option::None,
+            ptx: #ptx_cstr_ident,
+            resources: #resources_ident,
+        }];
+    };
 
     proc_macro_error2::set_dummy(quote! {
         const #ptx_cstr_ident: &'static ::core::ffi::CStr = c"ERROR in this PTX compilation";
 
+        #unknown_resources
+        #unknown_variants
+
         const fn #ffi_signature_ident(_hashes: &[u64]) -> HostAndDeviceKernelSignatureTypeLayout {
             HostAndDeviceKernelSignatureTypeLayout::Match
         }
@@ -89,15 +136,19 @@ pub fn compile_kernel(tokens: TokenStream) -> TokenStream {
         kernel_hash,
         crate_name,
         crate_path,
+        bin,
         specialisation,
         ptx_lint_levels,
+        register_budget,
+        mut target,
+        archs,
     } = match syn::parse(tokens) {
         Ok(config) => config,
         Err(err) => {
             abort_call_site!(
-                "compile_kernel!(KERNEL HASH NAME PATH SPECIALISATION LINTS,*) expects KERNEL and \
-                 HASH identifiers, NAME and PATH string literals, and SPECIALISATION and LINTS \
-                 tokens: {:?}",
+                "compile_kernel!(KERNEL HASH NAME PATH SPECIALISATION LINTS,* TARGET,* ARCHS?) \
+                 expects KERNEL and HASH identifiers, NAME and PATH string literals, and \
+                 SPECIALISATION, LINTS, and optional TARGET and ARCHS tokens: {:?}",
                 err
             )
         },
@@ -106,27 +157,122 @@ pub fn compile_kernel(tokens: TokenStream) -> TokenStream {
     if skip_kernel_compilation() {
         return quote! {
             const #ptx_cstr_ident: &'static ::core::ffi::CStr = c"CLIPPY skips specialised PTX compilation";
+
+            #unknown_resources
+            #unknown_variants
         }
         .into();
     }
 
-    let mut kernel_ptx = compile_kernel_ptx(
+    target.apply_env_overrides();
+
+    // Kernels that are linked for several architectures override the
+    //  architecture of the kernel's target with each one of them
+    if let Some(arch) = &target.arch
+        && !archs.is_empty()
+    {
+        emit_call_site_warning!(
+            "The link! target architectures override the #[kernel(target(arch = \"{}\"))] \
+             attribute.",
+            arch,
+        );
+    }
+    let targets = if archs.is_empty() {
+        vec![target]
+    } else {
+        archs
+            .into_iter()
+            .map(|arch| KernelTarget {
+                arch: Some(arch),
+                ..target.clone()
+            })
+            .collect()
+    };
+
+    let mut type_layout = None;
+    let mut variants = Vec::with_capacity(targets.len());
+
+    // The stale variants for all target architectures are built in parallel
+    let kernel_ptxs = compile_kernel_ptx(
         &kernel,
         &crate_name,
         &crate_path,
+        bin.as_deref(),
         Specialisation::Link(&specialisation),
+        &targets,
     );
 
-    let type_layout = extract_ptx_kernel_layout(&mut kernel_ptx);
-    remove_kernel_type_use_from_ptx(&mut kernel_ptx);
+    for (target, mut kernel_ptx) in targets.iter().zip(kernel_ptxs) {
+        // All variants are compiled from the same kernel signature
+        let variant_type_layout = extract_ptx_kernel_layout(&mut kernel_ptx);
+        type_layout.get_or_insert(variant_type_layout);
+        remove_kernel_type_use_from_ptx(&mut kernel_ptx);
 
-    check_kernel_ptx_and_report(
-        &kernel_ptx,
-        Specialisation::Link(&specialisation),
-        &kernel_hash,
-        &ptx_lint_levels,
-    );
+        analysis::check_kernel_ptx_lints(&kernel_ptx, &ptx_lint_levels);
+
+        let resources = check_kernel_ptx_and_report(
+            &kernel_ptx,
+            Specialisation::Link(&specialisation),
+            &kernel_hash,
+            target,
+            &ptx_lint_levels,
+        )
+        .unwrap_or_else(|| {
+            emit_call_site_warning!(
+                "The PTX compiler did not report the resource usage of the kernel, assuming the \
+                 maximum register usage instead."
+            );
+            KernelResources::UNKNOWN
+        });
+
+        if let Some(register_budget) = register_budget {
+            resources.check_register_budget(register_budget, &ptx_lint_levels);
+        }
+
+        variants.push((target.arch.clone(), quote_kernel_ptx(kernel_ptx), resources));
+    }
+
+    let mut variants = variants.into_iter();
+    let (Some((lowest_arch, lowest_ptx, lowest_resources)), Some(type_layout)) =
+        (variants.next(), type_layout)
+    else {
+        abort_call_site!("Kernel compilation requires at least one target architecture")
+    };
+
+    let lowest_arch = quote_arch(lowest_arch.as_deref());
+    let lowest_resources = lowest_resources.quote(&resources_ident);
+
+    let variants = variants.map(|(arch, ptx, resources)| {
+        let arch = quote_arch(arch.as_deref());
+        let resources = resources.quote_expr();
+
+        quote! {
+            KernelPtxVariant {
+                arch: #arch,
+                ptx: #ptx,
+                resources: #resources,
+            }
+        }
+    });
 
+    (quote! {
+        const #ptx_cstr_ident: &'static ::core::ffi::CStr = #lowest_ptx;
+        #lowest_resources
+        const #ptx_variants_ident: &'static [KernelPtxVariant] = &[
+            KernelPtxVariant {
+                arch: #lowest_arch,
+                ptx: #ptx_cstr_ident,
+                resources: #resources_ident,
+            },
+            #(#variants),*
+        ];
+        #type_layout
+    })
+    .into()
+}
+
+/// Quotes the `kernel_ptx` as a C string literal.
+fn quote_kernel_ptx(kernel_ptx: String) -> proc_macro2::TokenStream {
     let kernel_ptx = match CString::new(kernel_ptx) {
         Ok(kernel_ptx) => kernel_ptx,
         Err(err) => abort_call_site!(
@@ -136,12 +282,16 @@ pub fn compile_kernel(tokens: TokenStream) -> TokenStream {
     };
 
     let kernel_ptx = proc_macro::Literal::c_string(&kernel_ptx);
-    let kernel_ptx = proc_macro2::TokenStream::from(proc_macro::TokenStream::from(
+    proc_macro2::TokenStream::from(proc_macro::TokenStream::from(
         proc_macro::TokenTree::Literal(kernel_ptx),
-    ));
+    ))
+}
 
-    (quote! { const #ptx_cstr_ident: &'static ::core::ffi::CStr = #kernel_ptx; #type_layout })
-        .into()
+fn quote_arch(arch: Option<&str>) -> proc_macro2::TokenStream {
+    arch.map_or_else(
+        || quote!(::core::option::Option::None),
+        |arch| quote!(::core::option::Option::Some(#arch)),
+    )
 }
 
 fn extract_ptx_kernel_layout(kernel_ptx: &mut String) -> proc_macro2::TokenStream {
@@ -270,15 +420,22 @@ fn remove_kernel_type_use_from_ptx(kernel_ptx: &mut String) {
     }
 }
 
+#[cfg(feature = "nvptxcompiler")]
 #[expect(clippy::too_many_lines)]
 fn check_kernel_ptx_and_report(
     kernel_ptx: &str,
     specialisation: Specialisation,
     kernel_hash: &proc_macro2::Ident,
+    target: &KernelTarget,
     ptx_lint_levels: &HashMap<PtxLint, LintLevel>,
-) {
-    let (result, error_log, info_log, binary, version, drop) =
-        check_kernel_ptx(kernel_ptx, specialisation, kernel_hash, ptx_lint_levels);
+) -> Option<KernelResources> {
+    let (result, error_log, info_log, binary, version, drop) = check_kernel_ptx(
+        kernel_ptx,
+        specialisation,
+        kernel_hash,
+        target,
+        ptx_lint_levels,
+    );
 
     let ptx_compiler = match &version {
         Ok((major, minor)) => format!("PTX compiler v{major}.{minor}"),
@@ -297,24 +454,18 @@ fn check_kernel_ptx_and_report(
         ));
     }
 
-    let ptx_source_code = {
-        let mut max_lines = kernel_ptx.chars().filter(|c| *c == '\n').count() + 1;
-        let mut indent = 0;
-        while max_lines > 0 {
-            max_lines /= 10;
-            indent += 1;
-        }
+    let source_map = PtxSourceMap::new(kernel_ptx);
+    let ptx_source_code = ptx_source_code(kernel_ptx);
 
-        format!(
-            "PTX source code:\n{}",
-            kernel_ptx
-                .lines()
-                .enumerate()
-                .map(|(i, l)| format!("{:indent$}| {l}", i + 1))
-                .collect::<Vec<_>>()
-                .join("\n")
-        )
-    };
+    // The full PTX source code is only reported if requested, since all log
+    //  messages are attributed to their Rust source location
+    let verbose = ptx_lint_levels
+        .get(&PtxLint::Verbose)
+        .map_or(false, |level| *level > LintLevel::Allow);
+
+    if verbose {
+        emit_call_site_warning!("{} input:\n{}", ptx_compiler, ptx_source_code);
+    }
 
     match binary {
         Ok(None) => (),
@@ -361,31 +512,52 @@ fn check_kernel_ptx_and_report(
         },
     }
 
-    match info_log {
-        Ok(None) => (),
-        Ok(Some(info_log)) => emit_call_site_warning!(
-            "{} info log:\n{}\n{}",
-            ptx_compiler,
-            info_log,
-            ptx_source_code
-        ),
+    let resources = match info_log {
+        Ok(None) => None,
+        Ok(Some(info_log)) => {
+            let resources = KernelResources::from_info_log(
+                &info_log,
+                &kernel_entry_point(specialisation, kernel_hash),
+            );
+
+            // The info log is always verbose so that the resource usage can
+            // be extracted, but it is only reported if requested
+            let info_log = if verbose {
+                info_log
+            } else {
+                resources::strip_verbose_info(&info_log)
+            };
+
+            for message in source_map.annotate_log(&info_log) {
+                emit_call_site_warning!("{} info log:\n{}", ptx_compiler, message);
+            }
+
+            resources
+        },
         Err(err) => {
             let _ = errors.write_fmt(format_args!(
                 "Error fetching the info log of the {ptx_compiler}: {err}\n"
             ));
+            None
         },
     };
 
-    let error_log = match error_log {
-        Ok(None) => String::new(),
+    let has_error_log = match error_log {
+        Ok(None) => false,
         Ok(Some(error_log)) => {
-            format!("{ptx_compiler} error log:\n{error_log}\n{ptx_source_code}")
+            let messages = source_map.annotate_log(&error_log);
+
+            for message in &messages {
+                emit_call_site_error!("{} error log:\n{}", ptx_compiler, message);
+            }
+
+            !messages.is_empty()
         },
         Err(err) => {
             let _ = errors.write_fmt(format_args!(
                 "Error fetching the error log of the {ptx_compiler}: {err}\n"
             ));
-            String::new()
+            false
         },
     };
 
@@ -393,24 +565,67 @@ fn check_kernel_ptx_and_report(
         let _ = errors.write_fmt(format_args!("Error compiling the PTX source code: {err}\n"));
     }
 
-    if !error_log.is_empty() || !errors.is_empty() {
+    if has_error_log || !errors.is_empty() {
         abort_call_site!(
-            "{error_log}{}{errors}",
-            if !error_log.is_empty() && !errors.is_empty() {
-                "\n\n"
+            "Failed to compile the kernel with the {}.{}{}",
+            ptx_compiler,
+            if errors.is_empty() {
+                String::new()
             } else {
+                format!("\n{}", errors.trim_end())
+            },
+            if verbose {
                 ""
+            } else {
+                "\nSet `#[kernel(warn(ptx::verbose))]` to also report the full PTX source code."
             }
         );
     }
+
+    resources
+}
+
+/// Formats the `kernel_ptx` with line numbers for inclusion in diagnostics.
+fn ptx_source_code(kernel_ptx: &str) -> String {
+    let mut max_lines = kernel_ptx.chars().filter(|c| *c == '\n').count() + 1;
+    let mut indent = 0;
+    while max_lines > 0 {
+        max_lines /= 10;
+        indent += 1;
+    }
+
+    format!(
+        "PTX source code:\n{}",
+        kernel_ptx
+            .lines()
+            .enumerate()
+            .map(|(i, l)| format!("{:indent$}| {l}", i + 1))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )
+}
+
+/// Returns the name of the kernel entry point in the PTX that is compiled
+/// for the `specialisation` of the kernel with the `kernel_hash`.
+fn kernel_entry_point(specialisation: Specialisation, kernel_hash: &proc_macro2::Ident) -> String {
+    match specialisation {
+        Specialisation::Check => format!("{kernel_hash}_{CHECK_SPECIALISATION}"),
+        Specialisation::Link("") => format!("{kernel_hash}_kernel"),
+        Specialisation::Link(specialisation) => format!(
+            "{kernel_hash}_kernel_{:016x}",
+            seahash::hash(specialisation.as_bytes())
+        ),
+    }
 }
 
+#[cfg(feature = "nvptxcompiler")]
 #[expect(clippy::type_complexity)]
 #[expect(clippy::too_many_lines)]
 fn check_kernel_ptx(
     kernel_ptx: &str,
     specialisation: Specialisation,
     kernel_hash: &proc_macro2::Ident,
+    target: &KernelTarget,
     ptx_lint_levels: &HashMap<PtxLint, LintLevel>,
 ) -> (
     Result<(), NvptxError>,
@@ -437,24 +652,40 @@ fn check_kernel_ptx(
     };
 
     let result = (|| {
-        let kernel_name = match specialisation {
-            Specialisation::Check => format!("{kernel_hash}_{CHECK_SPECIALISATION}"),
-            Specialisation::Link("") => format!("{kernel_hash}_kernel"),
-            Specialisation::Link(specialisation) => format!(
-                "{kernel_hash}_kernel_{:016x}",
-                seahash::hash(specialisation.as_bytes())
-            ),
-        };
+        let kernel_name = kernel_entry_point(specialisation, kernel_hash);
         let Ok(kernel_name) = CString::new(kernel_name.clone()) else {
             abort_call_site!("Failed to make a cstr from {:?}", kernel_name);
         };
 
+        let gpu_name = target.arch.as_ref().map(|arch| {
+            let gpu_name = format!("--gpu-name={arch}");
+            CString::new(gpu_name.clone())
+                .unwrap_or_else(|_| abort_call_site!("Failed to make a cstr from {:?}", gpu_name))
+        });
+
         let mut options = vec![c"--entry", kernel_name.as_c_str()];
 
-        if ptx_lint_levels
-            .values()
-            .any(|level| *level > LintLevel::Warn)
-        {
+        if let Some(gpu_name) = &gpu_name {
+            options.push(gpu_name.as_c_str());
+        }
+
+        match target.debug.as_deref() {
+            Some("full") => options.push(c"--device-debug"),
+            Some("lines") => options.push(c"--generate-line-info"),
+            _ => (),
+        }
+
+        // Only the compiler-backed lints turn the compiler's warnings into
+        //  errors, the other lints are checked by analysing the PTX itself
+        if ptx_lint_levels.iter().any(|(lint, level)| {
+            matches!(
+                lint,
+                PtxLint::Verbose
+                    | PtxLint::RegisterSpills
+                    | PtxLint::DynamicStackSize
+                    | PtxLint::DumpAssembly
+            ) && *level > LintLevel::Warn
+        }) {
             let mut options = options.clone();
 
             if ptx_lint_levels
@@ -463,18 +694,6 @@ fn check_kernel_ptx(
             {
                 options.push(c"--verbose");
             }
-            if ptx_lint_levels
-                .get(&PtxLint::DoublePrecisionUse)
-                .map_or(false, |level| *level > LintLevel::Warn)
-            {
-                options.push(c"--warn-on-double-precision-use");
-            }
-            if ptx_lint_levels
-                .get(&PtxLint::LocalMemoryUse)
-                .map_or(false, |level| *level > LintLevel::Warn)
-            {
-                options.push(c"--warn-on-local-memory-usage");
-            }
             if ptx_lint_levels
                 .get(&PtxLint::RegisterSpills)
                 .map_or(false, |level| *level > LintLevel::Warn)
@@ -503,24 +722,8 @@ fn check_kernel_ptx(
             })?;
         };
 
-        if ptx_lint_levels
-            .get(&PtxLint::Verbose)
-            .map_or(false, |level| *level > LintLevel::Allow)
-        {
-            options.push(c"--verbose");
-        }
-        if ptx_lint_levels
-            .get(&PtxLint::DoublePrecisionUse)
-            .map_or(false, |level| *level > LintLevel::Allow)
-        {
-            options.push(c"--warn-on-double-precision-use");
-        }
-        if ptx_lint_levels
-            .get(&PtxLint::LocalMemoryUse)
-            .map_or(false, |level| *level > LintLevel::Allow)
-        {
-            options.push(c"--warn-on-local-memory-usage");
-        }
+        // Always compile verbosely to report the kernel's resource usage
+        options.push(c"--verbose");
         if ptx_lint_levels
             .get(&PtxLint::RegisterSpills)
             .map_or(false, |level| *level > LintLevel::Allow)
@@ -659,8 +862,10 @@ fn compile_kernel_ptx(
     kernel: &syn::Ident,
     crate_name: &str,
     crate_path: &Path,
+    bin: Option<&str>,
     specialisation: Specialisation,
-) -> String {
+    targets: &[KernelTarget],
+) -> Vec<String> {
     let specialisation_var = format!(
         "RUST_CUDA_DERIVE_SPECIALISE_{}_{}",
         crate_name.to_uppercase(),
@@ -677,35 +882,55 @@ fn compile_kernel_ptx(
         },
     };
 
-    let kernel_path = cargo_build_kernel_ptx_with_prefixed_output(
-        crate_name,
-        crate_path,
-        &specialisation_suffix,
-        &specialisation_var,
-        specialisation_value,
-    );
-
-    if let Specialisation::Link(specialisation) = specialisation {
-        if let Err(err) = fs::OpenOptions::new()
-            .append(true)
-            .open(&kernel_path)
-            .and_then(|mut file| writeln!(file, "\n// {specialisation}"))
-        {
-            abort_call_site!(
-                "Failed to write specialisation to {:?}: {}",
-                kernel_path,
-                err
+    let builds = targets
+        .iter()
+        .map(|target| {
+            // Kernels that are compiled for different targets are cached separately
+            let specialisation_suffix = target.cache_key().map_or_else(
+                || specialisation_suffix.clone(),
+                |target_key| format!("{specialisation_suffix}_{target_key}"),
             );
-        }
-    }
 
-    let mut file = fs::File::open(&kernel_path)
-        .unwrap_or_else(|_| abort_call_site!("Failed to open kernel file at {:?}.", &kernel_path));
+            KernelBuild::new(
+                kernel,
+                crate_name,
+                crate_path,
+                bin,
+                &specialisation_suffix,
+                &specialisation_var,
+                specialisation_value,
+                target,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // The builds are locked before checking if they are fresh, since another
+    //  expansion may be rebuilding them at the same time
+    let locks = ArtifactLock::acquire_all(&builds)
+        .unwrap_or_else(|err| abort_call_site!("Failed to lock the kernel build: {}", err));
+
+    rebuild_stale_kernel_ptx(&builds, &locks, specialisation);
+
+    let kernel_ptxs = builds
+        .iter()
+        .map(|build| {
+            let kernel_path = build.ptx_path();
+
+            let mut file = fs::File::open(&kernel_path).unwrap_or_else(|_| {
+                abort_call_site!("Failed to open kernel file at {:?}.", &kernel_path)
+            });
+
+            let mut kernel_ptx = String::new();
 
-    let mut kernel_ptx = String::new();
+            file.read_to_string(&mut kernel_ptx).unwrap_or_else(|_| {
+                abort_call_site!("Failed to read kernel file at {:?}.", &kernel_path)
+            });
 
-    file.read_to_string(&mut kernel_ptx)
-        .unwrap_or_else(|_| abort_call_site!("Failed to read kernel file at {:?}.", &kernel_path));
+            kernel_ptx
+        })
+        .collect();
+
+    std::mem::drop(locks);
 
     colored::control::set_override(true);
     eprintln!(
@@ -715,50 +940,217 @@ fn compile_kernel_ptx(
     );
     colored::control::unset_override();
 
-    kernel_ptx
+    kernel_ptxs
 }
 
-fn cargo_build_kernel_ptx_with_prefixed_output(
-    crate_name: &str,
-    crate_path: &Path,
-    crate_suffix: &str,
-    specialisation_var: &str,
-    specialisation_value: &str,
-) -> PathBuf {
-    let any_output = Cell::new(false);
+/// Rebuilds the stale `builds`, which are all locked by the `locks`, in
+/// parallel, together with other stale specialisations of the same crate
+/// for a [`Specialisation::Link`].
+fn rebuild_stale_kernel_ptx(
+    builds: &[KernelBuild],
+    locks: &[ArtifactLock],
+    specialisation: Specialisation,
+) {
+    let stale_builds = builds
+        .iter()
+        .filter(|build| !build.is_fresh())
+        .collect::<Vec<_>>();
 
-    cargo_build_kernel_ptx(
-        crate_path,
-        crate_name,
-        crate_suffix,
-        specialisation_var,
-        specialisation_value,
-        |stdout_line, stdout| {
-            prefix_cargo_build_stdout_message(
-                crate_name,
-                crate_suffix,
-                stdout_line,
-                stdout,
-                &any_output,
+    if stale_builds.is_empty() {
+        return;
+    }
+
+    // Other stale specialisations are rebuilt in parallel so that their
+    //  own link! expansions can skip their builds
+    let siblings = match specialisation {
+        Specialisation::Check => Vec::new(),
+        Specialisation::Link(_) => KernelBuild::stale_siblings(
+            builds,
+            max_parallel_builds().saturating_sub(stale_builds.len()),
+        ),
+    };
+
+    let jobs = stale_builds
+        .iter()
+        .copied()
+        .chain(siblings.iter().map(|(sibling, _lock)| sibling))
+        .collect::<Vec<_>>();
+    let locks = locks
+        .iter()
+        .chain(siblings.iter().map(|(_sibling, lock)| lock))
+        .collect::<Vec<_>>();
+
+    let results = cargo_build_kernels_ptx(&jobs, &locks);
+    let (build_results, sibling_results) = results.split_at(stale_builds.len());
+
+    // Failed sibling builds are reported by their own link! expansions
+    for ((sibling, _lock), (slot, _output, result)) in siblings.iter().zip(sibling_results) {
+        if let Ok(build_script_outputs) = result {
+            let _ = sibling.finish(*slot, build_script_outputs);
+        }
+    }
+
+    for (build, (slot, output, result)) in stale_builds.iter().zip(build_results) {
+        report_cargo_build_output(build, output);
+
+        let build_script_outputs = result
+            .as_ref()
+            .unwrap_or_else(|err| abort_call_site!("Failed to build the CUDA kernel: {}", err));
+
+        if let Err(err) = build.finish(*slot, build_script_outputs) {
+            abort_call_site!(
+                "Failed to record the kernel build in {:?}: {}",
+                build.artifact_dir,
+                err
             );
-        },
-        |stderr_line, stderr| {
-            prefix_cargo_build_stderr_line(
-                crate_name,
-                crate_suffix,
-                stderr_line,
-                stderr,
+        }
+    }
+}
+
+/// A line of output of a cargo build.
+enum CargoOutputLine {
+    Stdout(String),
+    Stderr(String),
+}
+
+/// The parallel build slot, the output, and either the build script outputs
+/// or the error of a cargo build.
+type CargoBuildResult = (usize, Vec<CargoOutputLine>, Result<Vec<PathBuf>, String>);
+
+/// Builds all `builds` in parallel, with at most [`max_parallel_builds`]
+/// concurrent cargo builds, and returns their results in the same order.
+///
+/// Builds with the same target dir are assigned different parallel build
+/// slots. The `locks` of the builds are refreshed until all builds are done.
+fn cargo_build_kernels_ptx(
+    builds: &[&KernelBuild],
+    locks: &[&ArtifactLock],
+) -> Vec<CargoBuildResult> {
+    let next_build = AtomicUsize::new(0);
+    let busy_slots = Mutex::new(HashMap::<&Path, Vec<usize>>::new());
+
+    let mut results = std::thread::scope(|scope| {
+        let workers = (0..max_parallel_builds().min(builds.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+
+                    loop {
+                        let index = next_build.fetch_add(1, Ordering::Relaxed);
+                        let Some(build) = builds.get(index) else {
+                            break;
+                        };
+
+                        let slot = acquire_slot(&busy_slots, &build.target_dir);
+                        build.seed_slot_target_dir(slot);
+
+                        let output = RefCell::new(Vec::new());
+                        let result = cargo_build_kernel_ptx(
+                            build,
+                            slot,
+                            |line| {
+                                output
+                                    .borrow_mut()
+                                    .push(CargoOutputLine::Stdout(line.into()));
+                            },
+                            |line| {
+                                output
+                                    .borrow_mut()
+                                    .push(CargoOutputLine::Stderr(line.into()));
+                            },
+                        );
+
+                        release_slot(&busy_slots, &build.target_dir, slot);
+
+                        results.push((index, (slot, output.into_inner(), result)));
+                    }
+
+                    results
+                })
+            })
+            .collect::<Vec<_>>();
+
+        while !workers
+            .iter()
+            .all(std::thread::ScopedJoinHandle::is_finished)
+        {
+            for lock in locks {
+                lock.refresh();
+            }
+
+            std::thread::sleep(LOCK_POLL_INTERVAL);
+        }
+
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|_| abort_call_site!("A parallel CUDA kernel build panicked"))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    // Every build was taken by exactly one worker
+    results.sort_by_key(|(index, _result)| *index);
+
+    results.into_iter().map(|(_index, result)| result).collect()
+}
+
+/// Returns the lowest parallel build slot of the `target_dir` that is not
+/// used by another build, and marks it as used.
+fn acquire_slot<'a>(
+    busy_slots: &Mutex<HashMap<&'a Path, Vec<usize>>>,
+    target_dir: &'a Path,
+) -> usize {
+    let mut busy_slots = busy_slots.lock().unwrap_or_else(PoisonError::into_inner);
+    let target_dir_slots = busy_slots.entry(target_dir).or_default();
+
+    // At most all of the busy slots are taken by other builds
+    let slot = (0..=target_dir_slots.len())
+        .find(|slot| !target_dir_slots.contains(slot))
+        .unwrap_or_default();
+    target_dir_slots.push(slot);
+    std::mem::drop(busy_slots);
+
+    slot
+}
+
+/// Marks the parallel build `slot` of the `target_dir` as no longer used.
+fn release_slot(busy_slots: &Mutex<HashMap<&Path, Vec<usize>>>, target_dir: &Path, slot: usize) {
+    if let Some(busy_slots) = busy_slots
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_mut(target_dir)
+    {
+        busy_slots.retain(|busy_slot| *busy_slot != slot);
+    }
+}
+
+/// Reports the `output` of the cargo build of the `build`, prefixed with the
+/// kernel crate and specialisation.
+fn report_cargo_build_output(build: &KernelBuild, output: &[CargoOutputLine]) {
+    let any_output = Cell::new(false);
+
+    for line in output {
+        match line {
+            CargoOutputLine::Stdout(line) => prefix_cargo_build_stdout_message(
+                &build.crate_name,
+                &build.suffix,
+                line,
                 &any_output,
-            );
-        },
-    )
+            ),
+            CargoOutputLine::Stderr(line) => {
+                prefix_cargo_build_stderr_line(&build.crate_name, &build.suffix, line, &any_output);
+            },
+        }
+    }
 }
 
 fn prefix_cargo_build_stdout_message(
     crate_name: &str,
     crate_suffix: &str,
     stdout_line: &str,
-    stdout: &mut String,
     any_output: &Cell<bool>,
 ) {
     let Ok(cargo_metadata::Message::CompilerMessage(mut message)) =
@@ -779,8 +1171,6 @@ fn prefix_cargo_build_stdout_message(
     }
 
     if let Some(rendered) = &mut message.message.rendered {
-        stdout.push_str(rendered);
-
         colored::control::set_override(true);
         let prefix = "  | ".bright_black().bold().to_string();
         colored::control::unset_override();
@@ -811,7 +1201,6 @@ fn prefix_cargo_build_stderr_line(
     crate_name: &str,
     crate_suffix: &str,
     stderr_line: &str,
-    stderr: &mut String,
     any_output: &Cell<bool>,
 ) {
     if stderr_line.trim().is_empty()
@@ -824,9 +1213,6 @@ fn prefix_cargo_build_stderr_line(
         return;
     }
 
-    stderr.push_str(stderr_line);
-    stderr.push('\n');
-
     if !any_output.replace(true) {
         colored::control::set_override(true);
         eprintln!(
@@ -849,23 +1235,21 @@ fn prefix_cargo_build_stderr_line(
 
 // Adapted from Denys Zariaiev's MIT-licensed `ptx-builder` crate
 // https://github.com/denzp/rust-ptx-builder
-fn cargo_build_kernel_ptx<O: FnMut(&str, &mut String), E: FnMut(&str, &mut String)>(
-    crate_path: &Path,
-    crate_name: &str,
-    crate_suffix: &str,
-    specialisation_var: &str,
-    specialisation_value: &str,
+//
+// The build runs outside of the proc-macro context when it is in parallel
+//  with other builds, so it must report all errors as its result
+//
+// Returns the paths of the outputs of all build scripts that the build ran
+fn cargo_build_kernel_ptx<O: FnMut(&str), E: FnMut(&str)>(
+    build: &KernelBuild,
+    slot: usize,
     mut on_stdout_line: O,
     mut on_stderr_line: E,
-) -> PathBuf {
-    check_crate_is_library(crate_path, crate_name);
-
+) -> Result<Vec<PathBuf>, String> {
     let mut cargo = ProcessBuilder::new(env!("CARGO"));
     cargo.arg("build");
 
-    if specialisation_value != CHECK_SPECIALISATION {
-        cargo.arg("--release");
-    }
+    cargo.args(&build.cargo_args);
 
     cargo.arg("--color=always");
     cargo.arg("--message-format=json,json-diagnostic-rendered-ansi");
@@ -873,111 +1257,59 @@ fn cargo_build_kernel_ptx<O: FnMut(&str, &mut String), E: FnMut(&str, &mut Strin
     cargo.arg("--target");
     cargo.arg(TARGET_NAME);
 
+    cargo.arg("--manifest-path");
+    cargo.arg(&build.manifest_path);
+
     cargo.arg("--lib");
 
     cargo.arg("-v");
 
-    let scratch_dir = scratch::path(concat!(
-        env!("CARGO_PKG_NAME"),
-        "-",
-        env!("CARGO_PKG_VERSION"),
-    ));
-
-    let artifact_dir = scratch_dir
-        .join("artifacts")
-        .join(crate_name)
-        .join(crate_suffix);
-    if let Err(err) = fs::create_dir_all(&artifact_dir) {
-        abort_call_site!(
-            "failed to create the artifact dir {:?}: {}",
-            artifact_dir,
-            err
-        );
-    }
-
     cargo.arg("-Zunstable-options");
     cargo.arg("--artifact-dir");
-    cargo.arg(&artifact_dir);
+    cargo.arg(&build.artifact_dir);
 
-    let target_dir = scratch_dir.join("target");
+    let target_dir = build.slot_target_dir(slot);
     if let Err(err) = fs::create_dir_all(&target_dir) {
-        abort_call_site!("Failed to create the target dir {:?}: {}", target_dir, err);
+        return Err(format!(
+            "Failed to create the target dir {target_dir:?}: {err}"
+        ));
     }
 
     cargo
-        .cwd(crate_path)
+        .cwd(&build.crate_path)
         .env("CARGO_TARGET_DIR", target_dir.as_path())
-        .env(specialisation_var, specialisation_value);
+        .env(&build.specialisation_var, &build.specialisation_value);
 
-    let mut stdout = String::new();
-    let mut stderr = String::new();
+    let mut build_script_outputs = Vec::new();
 
-    if let Err(mut err) = cargo.exec_with_streaming(
-        &mut |s| {
-            on_stdout_line(s, &mut stdout);
-            Ok(())
-        },
-        &mut |s| {
-            on_stderr_line(s, &mut stderr);
-            Ok(())
-        },
-        false,
-    ) {
-        if let Some(err) = err.downcast_mut::<ProcessError>() {
-            let stdout = (!stdout.is_empty()).then(|| strip_ansi_escapes::strip(stdout));
-            let stderr = (!stderr.is_empty()).then(|| strip_ansi_escapes::strip(stderr));
-
-            // The error precomputes its string repr, so we need to recreate
-            //  it to replace the stdout and stderr
-            *err = ProcessError::new_raw(
-                &format!("process didn't exit successfully: {cargo}"),
-                err.code,
-                &err.code.map_or_else(
-                    || String::from("never executed"),
-                    |code| format!("code={code}"),
-                ),
-                stdout.as_deref(),
-                stderr.as_deref(),
-            );
-        }
+    cargo
+        .exec_with_streaming(
+            &mut |s| {
+                if let Ok(cargo_metadata::Message::BuildScriptExecuted(script)) =
+                    serde_json::from_str(s)
+                    && let Some(build_dir) = script.out_dir.parent()
+                {
+                    build_script_outputs.push(build_dir.join("output").into_std_path_buf());
+                }
 
-        abort_call_site!("Failed to build the CUDA kernel: {}", err);
-    }
+                on_stdout_line(s);
+                Ok(())
+            },
+            &mut |s| {
+                on_stderr_line(s);
+                Ok(())
+            },
+            false,
+        )
+        .map_err(|err| err.to_string())?;
 
-    let crate_artifact_name = crate_name.replace('-', "_");
-    let assembly_path = artifact_dir.join(format!("{crate_artifact_name}.ptx"));
+    let assembly_path = build.ptx_path();
 
     if !assembly_path.exists() {
-        abort_call_site!("Failed to open PTX file {:?}", assembly_path);
+        return Err(format!("Failed to open PTX file {assembly_path:?}"));
     }
 
-    assembly_path
-}
-
-fn check_crate_is_library(crate_path: &Path, crate_name: &str) {
-    if !matches!(fs::metadata(crate_path.join("Cargo.toml")), Ok(metadata) if metadata.is_file()) {
-        abort_call_site!(
-            "{:?} is not a valid crate manifest path",
-            crate_path.join("Cargo.toml")
-        );
-    }
-
-    let is_library = crate_path.join("src").join("lib.rs").exists();
-    let is_binary = crate_path.join("src").join("main.rs").exists();
-
-    if !is_library {
-        if is_binary {
-            abort_call_site!(
-                "{} is a binary-only crate, which is not supported",
-                crate_name
-            );
-        } else {
-            abort_call_site!(
-                "unable to find neither `lib.rs` nor `main.rs` for {}",
-                crate_name
-            );
-        }
-    }
+    Ok(build_script_outputs)
 }
 
 #[derive(Copy, Clone, Debug)]
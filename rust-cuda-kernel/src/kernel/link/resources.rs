@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::kernel::lints::{LintLevel, PtxLint};
+
+/// Resources that are used by a compiled kernel, as reported in the verbose
+/// info log of the PTX compiler.
+pub struct KernelResources {
+    pub registers_per_thread: u32,
+    pub static_shared_memory: u32,
+    pub local_memory: u32,
+    pub stack_frame: u32,
+    pub spill_stores: u32,
+    pub spill_loads: u32,
+    pub constant_banks: Vec<(u32, u32)>,
+    pub reported: bool,
+}
+
+impl KernelResources {
+    /// CUDA kernels can be launched with at most 1024 threads per block
+    /// unless their maximum block size is restricted.
+    const MAX_THREADS_PER_BLOCK: u32 = 1024;
+    /// Conservative estimate that is used if the PTX compiler does not report
+    /// the kernel's resource usage.
+    pub const UNKNOWN: Self = Self {
+        registers_per_thread: 255,
+        static_shared_memory: 0,
+        local_memory: 0,
+        stack_frame: 0,
+        spill_stores: 0,
+        spill_loads: 0,
+        constant_banks: Vec::new(),
+        reported: false,
+    };
+
+    /// Parses the resource usage of the compiled `entry_point` from the
+    /// `Used N registers, M bytes smem, ...` line and the `Function properties
+    /// for ...` section of the `info_log`.
+    pub fn from_info_log(info_log: &str, entry_point: &str) -> Option<Self> {
+        let usage = info_log
+            .lines()
+            .find_map(|line| info_message(line)?.strip_prefix("Used "))?;
+
+        let mut registers_per_thread = None;
+        let mut resources = Self {
+            reported: true,
+            ..Self::UNKNOWN
+        };
+
+        for (amount, kind) in resource_amounts(usage) {
+            match kind {
+                "registers" => registers_per_thread = Some(amount),
+                "bytes smem" => resources.static_shared_memory = amount,
+                "bytes lmem" => resources.local_memory = amount,
+                kind => {
+                    if let Some(bank) = kind
+                        .strip_prefix("bytes cmem[")
+                        .and_then(|bank| bank.strip_suffix(']'))
+                        .and_then(|bank| bank.parse().ok())
+                    {
+                        resources.constant_banks.push((bank, amount));
+                    }
+                },
+            }
+        }
+
+        resources.registers_per_thread = registers_per_thread?;
+
+        // The function properties are reported on the line after the header
+        let mut lines = info_log.lines();
+        if lines.any(|line| {
+            info_message(line)
+                .and_then(|message| message.strip_prefix("Function properties for "))
+                .map_or(false, |function| function.trim() == entry_point)
+        }) {
+            for (amount, kind) in resource_amounts(lines.next().unwrap_or_default()) {
+                match kind {
+                    "bytes stack frame" => resources.stack_frame = amount,
+                    "bytes spill stores" => resources.spill_stores = amount,
+                    "bytes spill loads" => resources.spill_loads = amount,
+                    _ => (),
+                }
+            }
+        }
+
+        Some(resources)
+    }
+
+    /// Checks the `ptx::register_budget` lint, i.e. whether the kernel uses
+    /// more than the `register_budget` registers per thread.
+    pub fn check_register_budget(
+        &self,
+        register_budget: u32,
+        ptx_lint_levels: &HashMap<PtxLint, LintLevel>,
+    ) {
+        let level = ptx_lint_levels
+            .get(&PtxLint::RegisterBudget)
+            .copied()
+            .unwrap_or(LintLevel::Allow);
+
+        if level == LintLevel::Allow {
+            return;
+        }
+
+        if !self.reported {
+            emit_call_site_warning!(
+                "[rust-cuda]: ptx::register_budget: the register usage of the kernel is unknown, \
+                 so the budget of {} registers per thread cannot be checked.",
+                register_budget
+            );
+            return;
+        }
+
+        if self.registers_per_thread <= register_budget {
+            return;
+        }
+
+        if level > LintLevel::Warn {
+            emit_call_site_error!(
+                "[rust-cuda]: ptx::register_budget: the kernel uses {} registers per thread, more \
+                 than the budget of {}.",
+                self.registers_per_thread,
+                register_budget
+            );
+        } else {
+            emit_call_site_warning!(
+                "[rust-cuda]: ptx::register_budget: the kernel uses {} registers per thread, more \
+                 than the budget of {}.",
+                self.registers_per_thread,
+                register_budget
+            );
+        }
+    }
+
+    pub fn quote(&self, const_ident: &syn::Ident) -> TokenStream {
+        let resources = self.quote_expr();
+
+        quote! {
+            const #const_ident: KernelResources = #resources;
+        }
+    }
+
+    /// Quotes the resources as a `KernelResources` expression.
+    pub fn quote_expr(&self) -> TokenStream {
+        let Self {
+            registers_per_thread,
+            static_shared_memory,
+            local_memory,
+            stack_frame,
+            spill_stores,
+            spill_loads,
+            constant_banks,
+            reported,
+        } = self;
+        let max_threads_per_block = Self::MAX_THREADS_PER_BLOCK;
+        let (banks, bank_bytes): (Vec<_>, Vec<_>) = constant_banks.iter().copied().unzip();
+
+        quote! {
+            KernelResources {
+                registers_per_thread: #registers_per_thread,
+                static_shared_memory: #static_shared_memory,
+                local_memory: #local_memory,
+                stack_frame: #stack_frame,
+                spill_stores: #spill_stores,
+                spill_loads: #spill_loads,
+                constant_banks: &[#((#banks, #bank_bytes)),*],
+                max_threads_per_block: #max_threads_per_block,
+                reported: #reported,
+            }
+        }
+    }
+}
+
+/// Parses the comma-separated `N kind` amounts of a resource usage line,
+/// e.g. `10 registers, 352 bytes cmem[0]`.
+fn resource_amounts(line: &str) -> impl Iterator<Item = (u32, &str)> {
+    line.split(',').filter_map(|resource| {
+        let (amount, kind) = resource.trim().split_once(' ')?;
+        Some((amount.parse().ok()?, kind.trim()))
+    })
+}
+
+/// Removes the informational messages, which are emitted by the PTX compiler
+/// in `--verbose` mode, from the `info_log`, but keeps all warnings.
+#[cfg(feature = "nvptxcompiler")]
+pub fn strip_verbose_info(info_log: &str) -> String {
+    let mut in_info = false;
+
+    info_log
+        .lines()
+        .filter(|line| {
+            if info_message(line).is_some() {
+                in_info = true;
+            } else if !line.starts_with(char::is_whitespace) {
+                in_info = false;
+            }
+
+            !in_info
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Extracts the trimmed message from an `[ptxas ]info    : message` line.
+fn info_message(line: &str) -> Option<&str> {
+    let (prefix, message) = line.split_once(':')?;
+
+    match prefix.trim_end().strip_suffix("info")?.trim_end() {
+        "" | "ptxas" => Some(message.trim()),
+        _ => None,
+    }
+}
@@ -0,0 +1,331 @@
+//! Mapping of the PTX source code of a kernel back to the Rust source code
+//! that it was compiled from, using the `.file` and `.loc` line info
+//! directives that the device build emits.
+
+use std::{collections::HashMap, fmt};
+
+use super::analysis::{function_header_name, strip_comments};
+
+/// A location in the Rust source code.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct RustLocation {
+    /// The index of the `.file` that contains the location
+    file: u32,
+    /// The one-based line number
+    line: u32,
+    /// The one-based column number, or zero if unknown
+    column: u32,
+}
+
+/// The Rust source locations of the lines and functions of a PTX module.
+pub struct PtxSourceMap<'a> {
+    files: HashMap<u32, String>,
+    /// The Rust source location of every line, by its zero-based index
+    lines: Vec<Option<RustLocation>>,
+    /// The first Rust source location in every function, by its symbol
+    functions: HashMap<&'a str, RustLocation>,
+}
+
+impl<'a> PtxSourceMap<'a> {
+    /// Parses the `.file` and `.loc` directives of the `kernel_ptx`. A `.loc`
+    /// applies to all following lines until the next `.loc` or the end of
+    /// the enclosing function.
+    pub fn new(kernel_ptx: &'a str) -> Self {
+        let mut files = HashMap::new();
+        let mut lines = Vec::new();
+        let mut functions = HashMap::new();
+
+        let mut in_block_comment = false;
+        let mut depth = 0_usize;
+        let mut function = None;
+        let mut location = None;
+
+        for line in kernel_ptx.lines() {
+            let code = strip_comments(line, &mut in_block_comment);
+
+            if let Some(file) = code.strip_prefix(".file") {
+                if let Some((index, path)) = parse_file_directive(file) {
+                    files.insert(index, path);
+                }
+            } else if let Some(loc) = code.strip_prefix(".loc") {
+                // Line zero marks code that has no source location
+                location = parse_loc_directive(loc).filter(|location| location.line > 0);
+
+                if let (Some(name), Some(location)) = (function, location) {
+                    functions.entry(name).or_insert(location);
+                }
+            } else if depth == 0 {
+                if let Some(name) = function_header_name(code) {
+                    function = Some(name);
+                }
+            }
+
+            lines.push(location);
+
+            for c in code.chars() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => {
+                        depth = depth.saturating_sub(1);
+                        if depth == 0 {
+                            function = None;
+                            location = None;
+                        }
+                    },
+                    _ => (),
+                }
+            }
+
+            // A function declaration without a body ends with a semicolon
+            if depth == 0 && code.ends_with(';') {
+                function = None;
+            }
+        }
+
+        Self {
+            files,
+            lines,
+            functions,
+        }
+    }
+
+    /// Returns the Rust source location of the one-based PTX `line`.
+    pub fn line_location(&self, line: usize) -> Option<impl fmt::Display + '_> {
+        let location = (*self.lines.get(line.checked_sub(1)?)?)?;
+
+        self.display(location)
+    }
+
+    /// Returns the first Rust source location in the function with the
+    /// (mangled) `symbol`.
+    pub fn function_location(&self, symbol: &str) -> Option<impl fmt::Display + '_> {
+        self.display(*self.functions.get(symbol)?)
+    }
+
+    /// Attributes every message of the `log` of the PTX compiler or checker
+    /// that refers to a PTX line or function to its Rust source location.
+    ///
+    /// Every unindented line starts a new message, which is continued by the
+    /// following indented lines.
+    pub fn annotate_log(&self, log: &str) -> Vec<String> {
+        let mut messages: Vec<String> = Vec::new();
+
+        for line in log.lines().filter(|line| !line.trim().is_empty()) {
+            match messages.last_mut() {
+                Some(message) if line.starts_with(char::is_whitespace) => {
+                    message.push('\n');
+                    message.push_str(line);
+                },
+                _ => messages.push(String::from(line)),
+            }
+        }
+
+        for message in &mut messages {
+            let header = message.lines().next().unwrap_or_default();
+
+            let location = log_line_number(header)
+                .and_then(|line| self.line_location(line))
+                .map(|location| location.to_string())
+                .or_else(|| {
+                    log_function_symbol(header)
+                        .and_then(|symbol| self.function_location(symbol))
+                        .map(|location| location.to_string())
+                });
+
+            if let Some(location) = location {
+                message.push_str("\n  --> ");
+                message.push_str(&location);
+            }
+        }
+
+        messages
+    }
+
+    fn display(&self, location: RustLocation) -> Option<impl fmt::Display + '_> {
+        struct DisplayRustLocation<'a> {
+            file: &'a str,
+            location: RustLocation,
+        }
+
+        impl fmt::Display for DisplayRustLocation<'_> {
+            fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                write!(fmt, "{}:{}", self.file, self.location.line)?;
+                if self.location.column > 0 {
+                    write!(fmt, ":{}", self.location.column)?;
+                }
+                Ok(())
+            }
+        }
+
+        Some(DisplayRustLocation {
+            file: self.files.get(&location.file)?,
+            location,
+        })
+    }
+}
+
+/// Parses the `<index> "<path>"` or `<index> "<directory>" "<file>"`
+/// operands of a `.file` directive, which may be followed by a timestamp and
+/// file size.
+fn parse_file_directive(operands: &str) -> Option<(u32, String)> {
+    let (index, rest) = operands.trim_start().split_once(char::is_whitespace)?;
+    let index = index.parse().ok()?;
+
+    let strings = rest.split('"').skip(1).step_by(2).collect::<Vec<_>>();
+    let path = match strings.as_slice() {
+        [directory, file, ..] => format!("{}/{file}", directory.trim_end_matches('/')),
+        [path] => String::from(*path),
+        [] => return None,
+    };
+
+    Some((index, path))
+}
+
+/// Parses the `<file> <line> <column>` operands of a `.loc` directive, which
+/// may be followed by further inlining information.
+fn parse_loc_directive(operands: &str) -> Option<RustLocation> {
+    let mut numbers = operands
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|operand| !operand.is_empty())
+        .map(str::parse);
+
+    Some(RustLocation {
+        file: numbers.next()?.ok()?,
+        line: numbers.next()?.ok()?,
+        column: numbers.next().and_then(Result::ok).unwrap_or_default(),
+    })
+}
+
+/// Returns the PTX line number that a log message refers to, e.g. `12` in
+/// `ptxas application ptx input, line 12; error   : ...`.
+fn log_line_number(message: &str) -> Option<usize> {
+    let (_, rest) = message.split_once("line ")?;
+    let (line, _) = rest.split_once(';')?;
+
+    line.trim().parse().ok()
+}
+
+/// Returns the (mangled) function symbol that a log message refers to, e.g.
+/// in `... in function '<symbol>'` or `Function properties for <symbol>`.
+fn log_function_symbol(message: &str) -> Option<&str> {
+    if let Some((_, rest)) = message.split_once('\'') {
+        return rest.split_once('\'').map(|(symbol, _)| symbol);
+    }
+
+    let (_, rest) = message.split_once("Function properties for ")?;
+
+    rest.split_whitespace().next()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::{parse_file_directive, parse_loc_directive, PtxSourceMap};
+
+    const KERNEL_PTX: &str = r#".version 7.0
+.target sm_35
+.address_size 64
+
+.visible .entry kernel(
+	.param .u64 kernel_param_0
+)
+{
+	.reg .b32 	%r<2>;
+	mov.u32 	%r1, 0;
+	.loc	2 5 9
+	add.s32 	%r1, %r1, 1;
+	.loc	1 12 0
+	ret;
+}
+	.file	1 "/src/my crate/src/lib.rs"
+	.file	2 "/src/my crate/" "src/kernel.rs", 1700000000, 1234
+"#;
+
+    #[test]
+    fn file_directives() {
+        assert_eq!(
+            parse_file_directive(r#" 1 "/src/my crate/src/lib.rs""#),
+            Some((1, String::from("/src/my crate/src/lib.rs")))
+        );
+        assert_eq!(
+            parse_file_directive(r#"	2 "/src/my crate/src/lib.rs", 1700000000, 1234"#),
+            Some((2, String::from("/src/my crate/src/lib.rs")))
+        );
+        assert_eq!(
+            parse_file_directive(r#" 3 "/src/my crate/" "src/lib.rs""#),
+            Some((3, String::from("/src/my crate/src/lib.rs")))
+        );
+        assert_eq!(
+            parse_file_directive(r#" 4 "/src/my crate" "src/lib.rs", 1700000000, 1234"#),
+            Some((4, String::from("/src/my crate/src/lib.rs")))
+        );
+        assert_eq!(parse_file_directive(" 5"), None);
+        assert_eq!(parse_file_directive(" five \"src/lib.rs\""), None);
+    }
+
+    #[test]
+    fn loc_directives() {
+        let location = parse_loc_directive("\t1 12 9").unwrap();
+        assert_eq!((location.file, location.line, location.column), (1, 12, 9));
+
+        let location =
+            parse_loc_directive(" 2 7 0, function_name $L__info_string0, inlined_at 1 3 5")
+                .unwrap();
+        assert_eq!((location.file, location.line, location.column), (2, 7, 0));
+
+        let location = parse_loc_directive(" 3 4").unwrap();
+        assert_eq!((location.file, location.line, location.column), (3, 4, 0));
+
+        assert!(parse_loc_directive(" 3").is_none());
+    }
+
+    #[test]
+    fn line_locations() {
+        let source_map = PtxSourceMap::new(KERNEL_PTX);
+
+        // Lines before the first `.loc` have no source location
+        assert!(source_map.line_location(10).is_none());
+        assert_eq!(
+            source_map.line_location(12).unwrap().to_string(),
+            "/src/my crate/src/kernel.rs:5:9"
+        );
+        assert_eq!(
+            source_map.line_location(14).unwrap().to_string(),
+            "/src/my crate/src/lib.rs:12"
+        );
+        // The location ends with the enclosing function
+        assert!(source_map.line_location(16).is_none());
+        assert!(source_map.line_location(0).is_none());
+        assert!(source_map.line_location(100).is_none());
+
+        assert_eq!(
+            source_map.function_location("kernel").unwrap().to_string(),
+            "/src/my crate/src/kernel.rs:5:9"
+        );
+        assert!(source_map.function_location("other").is_none());
+    }
+
+    #[test]
+    fn annotated_log() {
+        let source_map = PtxSourceMap::new(KERNEL_PTX);
+
+        assert_eq!(
+            source_map.annotate_log(
+                "ptxas application ptx input, line 12; error   : Unknown symbol\nptxas info    : \
+                 Function properties for kernel\n    0 bytes stack frame\nptxas application ptx \
+                 input, line 9; warning : Unused register"
+            ),
+            vec![
+                String::from(
+                    "ptxas application ptx input, line 12; error   : Unknown symbol\n  --> \
+                     /src/my crate/src/kernel.rs:5:9"
+                ),
+                String::from(
+                    "ptxas info    : Function properties for kernel\n    0 bytes stack frame\n  \
+                     --> /src/my crate/src/kernel.rs:5:9"
+                ),
+                String::from("ptxas application ptx input, line 9; warning : Unused register"),
+            ]
+        );
+    }
+}
@@ -83,6 +83,11 @@ pub fn parse_ptx_lint_level(
                 l if l == "register_spills" => PtxLint::RegisterSpills,
                 l if l == "dump_assembly" => PtxLint::DumpAssembly,
                 l if l == "dynamic_stack_size" => PtxLint::DynamicStackSize,
+                l if l == "malloc_use" => PtxLint::MallocUse,
+                l if l == "printf_use" => PtxLint::PrintfUse,
+                l if l == "trap_use" => PtxLint::TrapUse,
+                l if l == "atomic_use" => PtxLint::AtomicUse,
+                l if l == "register_budget" => PtxLint::RegisterBudget,
                 _ => {
                     emit_error!(
                         meta.path.span(),
@@ -156,6 +161,11 @@ pub enum PtxLint {
     RegisterSpills,
     DumpAssembly,
     DynamicStackSize,
+    MallocUse,
+    PrintfUse,
+    TrapUse,
+    AtomicUse,
+    RegisterBudget,
 }
 
 impl fmt::Display for PtxLint {
@@ -167,6 +177,11 @@ impl fmt::Display for PtxLint {
             Self::RegisterSpills => fmt.write_str("register_spills"),
             Self::DumpAssembly => fmt.write_str("dump_assembly"),
             Self::DynamicStackSize => fmt.write_str("dynamic_stack_size"),
+            Self::MallocUse => fmt.write_str("malloc_use"),
+            Self::PrintfUse => fmt.write_str("printf_use"),
+            Self::TrapUse => fmt.write_str("trap_use"),
+            Self::AtomicUse => fmt.write_str("atomic_use"),
+            Self::RegisterBudget => fmt.write_str("register_budget"),
         }
     }
 }
@@ -3,6 +3,7 @@ pub mod specialise;
 pub mod wrapper;
 
 mod lints;
+mod target;
 mod utils;
 
 const KERNEL_TYPE_USE_START_CANARY: &str = "// <rust-cuda-kernel-param-type-use-start> //";
@@ -10,4 +11,6 @@ const KERNEL_TYPE_USE_END_CANARY: &str = "// <rust-cuda-kernel-param-type-use-en
 const KERNEL_TYPE_LAYOUT_IDENT: &str = "KERNEL_SIGNATURE_LAYOUT";
 const KERNEL_TYPE_LAYOUT_HASH_SEED_IDENT: &str = "KERNEL_SIGNATURE_LAYOUT_HASH_SEED";
 const PTX_CSTR_IDENT: &str = "PTX_CSTR";
+const PTX_VARIANTS_IDENT: &str = "PTX_VARIANTS";
+const KERNEL_RESOURCES_IDENT: &str = "KERNEL_RESOURCES";
 const CHECK_SPECIALISATION: &str = "chECK";
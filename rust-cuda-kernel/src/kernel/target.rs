@@ -0,0 +1,579 @@
+use std::{
+    fs,
+    hash::{Hash, Hasher},
+};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+
+use crate::kernel::{lints::NestedMetaParser, utils::cargo_config_paths};
+
+/// The target architecture, codegen, and build options that a kernel is
+/// compiled with, where [`None`] options use the defaults of the kernel crate.
+#[derive(Clone, Default, PartialEq, Eq, Hash, Debug)]
+pub struct KernelTarget {
+    /// The `-C target-cpu`, e.g. `sm_80`, which is also passed to the PTX
+    /// compiler as its `--gpu-name`
+    pub arch: Option<String>,
+    /// The `-C target-feature`s, e.g. `+ptx75`
+    pub features: Option<String>,
+    /// The `opt-level` of the profile that the kernel is built with
+    pub opt_level: Option<String>,
+    /// The `none`, `lines`, or `full` debug information that the kernel is
+    /// built with
+    pub debug: Option<String>,
+    /// The features of the kernel crate that are enabled in the host build,
+    /// which are enabled instead of the default features
+    pub crate_features: Option<Vec<String>>,
+    /// The cargo profile that the kernel is built with instead of `dev` for
+    /// checking and `release` for linking
+    pub profile: Option<String>,
+    /// Extra arguments that are passed to `cargo build`, e.g. `--offline`
+    pub cargo_args: Vec<String>,
+    /// Extra flags that are appended to the `rustflags` of the kernel crate
+    pub rustflags: Vec<String>,
+}
+
+impl KernelTarget {
+    /// Parses the `target(arch = "<arch>", features = "<features>")` `meta`.
+    pub fn parse_target(&mut self, meta: &impl NestedMetaParser) {
+        if meta
+            .parse_nested_meta(|meta| {
+                let option = if meta.path.is_ident("arch") {
+                    &mut self.arch
+                } else if meta.path.is_ident("features") {
+                    &mut self.features
+                } else {
+                    emit_error!(
+                        meta.path.span(),
+                        "[rust-cuda]: Unknown #[kernel(target(<option>))] option, must be one of \
+                         `arch` or `features`.",
+                    );
+                    return Ok(());
+                };
+
+                let value = meta.value()?.parse::<syn::LitStr>()?;
+
+                if option.is_some() {
+                    emit_error!(
+                        meta.path.span(),
+                        "[rust-cuda]: Duplicate #[kernel(target(<option>))] option.",
+                    );
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("arch") && !is_valid_arch(&value.value()) {
+                    emit_error!(
+                        value.span(),
+                        "[rust-cuda]: Invalid #[kernel(target(arch = \"<arch>\"))] option: must \
+                         be of the form `sm_<version>`, e.g. `sm_80`.",
+                    );
+                    return Ok(());
+                }
+
+                *option = Some(value.value());
+
+                Ok(())
+            })
+            .is_err()
+        {
+            emit_error!(
+                meta.path().span(),
+                "[rust-cuda]: Invalid #[kernel(target(arch = \"<arch>\", features = \
+                 \"<features>\"))] attribute.",
+            );
+        }
+    }
+
+    /// Parses the `opt_level` literal, which must be one of `0`, `1`, `2`,
+    /// `3`, `"s"`, or `"z"`.
+    pub fn parse_opt_level(&mut self, opt_level: &syn::Lit) {
+        let value = match opt_level {
+            syn::Lit::Int(level) => level.base10_digits().to_owned(),
+            syn::Lit::Str(level) => level.value(),
+            _ => String::new(),
+        };
+
+        if !matches!(value.as_str(), "0" | "1" | "2" | "3" | "s" | "z") {
+            emit_error!(
+                opt_level.span(),
+                "[rust-cuda]: Invalid #[kernel(opt_level = <level>)] attribute: must be one of \
+                 `0`, `1`, `2`, `3`, `\"s\"`, or `\"z\"`.",
+            );
+            return;
+        }
+
+        if self.opt_level.is_some() {
+            emit_error!(
+                opt_level.span(),
+                "[rust-cuda]: Duplicate #[kernel(opt_level)] attribute.",
+            );
+            return;
+        }
+
+        self.opt_level = Some(value);
+    }
+
+    /// Parses the `debug` literal, which must be one of `"none"`, `"lines"`,
+    /// or `"full"`.
+    pub fn parse_debug(&mut self, debug: &syn::LitStr) {
+        if !is_valid_debug(&debug.value()) {
+            emit_error!(
+                debug.span(),
+                "[rust-cuda]: Invalid #[kernel(debug = \"<debug>\")] attribute: must be one of \
+                 `\"none\"`, `\"lines\"`, or `\"full\"`.",
+            );
+            return;
+        }
+
+        if self.debug.is_some() {
+            emit_error!(
+                debug.span(),
+                "[rust-cuda]: Duplicate #[kernel(debug)] attribute.",
+            );
+            return;
+        }
+
+        self.debug = Some(debug.value());
+    }
+
+    /// Parses the `crate_features("<feature>", ...)`,
+    /// `cargo_args("<arg>", ...)`, or `rustflags("<flag>", ...)` list of
+    /// `args` for the option at `path`.
+    pub fn parse_args(&mut self, path: &syn::Path, args: impl IntoIterator<Item = syn::LitStr>) {
+        if path.is_ident("crate_features") {
+            self.crate_features = Some(args.into_iter().map(|arg| arg.value()).collect());
+        } else if path.is_ident("cargo_args") {
+            for arg in args {
+                if is_reserved_cargo_arg(&arg.value()) {
+                    emit_error!(
+                        arg.span(),
+                        "[rust-cuda]: Invalid #[kernel(cargo_args(\"<arg>\", ...))] argument: \
+                         `{}` is set by the kernel compilation.",
+                        arg.value(),
+                    );
+                } else {
+                    self.cargo_args.push(arg.value());
+                }
+            }
+        } else if path.is_ident("rustflags") {
+            self.rustflags
+                .extend(args.into_iter().map(|arg| arg.value()));
+        } else {
+            emit_error!(
+                path.span(),
+                "[rust-cuda]: Unknown kernel build option, must be one of `crate_features`, \
+                 `cargo_args`, or `rustflags`.",
+            );
+        }
+    }
+
+    /// Applies the `RUST_CUDA_DEVICE_PROFILE`, `RUST_CUDA_DEVICE_DEBUG`,
+    /// `RUST_CUDA_DEVICE_CARGO_ARGS`, and `RUST_CUDA_DEVICE_RUSTFLAGS`
+    /// environment variables, which configure the builds of all kernels, to
+    /// the options. The debug information overrides that of the kernel,
+    /// while the arguments and flags are whitespace-separated and appended
+    /// after those of the kernel.
+    ///
+    /// If the host build is offline, as configured by the `CARGO_NET_OFFLINE`
+    /// environment variable or the `net.offline` cargo config, the kernels
+    /// are built with `--offline` as well.
+    pub fn apply_env_overrides(&mut self) {
+        if let Ok(profile) = proc_macro::tracked_env::var("RUST_CUDA_DEVICE_PROFILE")
+            && !profile.trim().is_empty()
+        {
+            self.profile = Some(String::from(profile.trim()));
+        }
+
+        if let Ok(debug) = proc_macro::tracked_env::var("RUST_CUDA_DEVICE_DEBUG")
+            && !debug.trim().is_empty()
+        {
+            if is_valid_debug(debug.trim()) {
+                self.debug = Some(String::from(debug.trim()));
+            } else {
+                emit_call_site_error!(
+                    "[rust-cuda]: Invalid RUST_CUDA_DEVICE_DEBUG value: must be one of `none`, \
+                     `lines`, or `full`."
+                );
+            }
+        }
+
+        if let Ok(cargo_args) = proc_macro::tracked_env::var("RUST_CUDA_DEVICE_CARGO_ARGS") {
+            for arg in cargo_args.split_whitespace() {
+                if is_reserved_cargo_arg(arg) {
+                    emit_call_site_error!(
+                        "[rust-cuda]: Invalid RUST_CUDA_DEVICE_CARGO_ARGS argument: `{}` is set \
+                         by the kernel compilation.",
+                        arg,
+                    );
+                } else {
+                    self.cargo_args.push(String::from(arg));
+                }
+            }
+        }
+
+        if let Ok(rustflags) = proc_macro::tracked_env::var("RUST_CUDA_DEVICE_RUSTFLAGS") {
+            self.rustflags
+                .extend(rustflags.split_whitespace().map(String::from));
+        }
+
+        if is_offline() && !self.cargo_args.iter().any(|arg| arg == "--offline") {
+            self.cargo_args.push(String::from("--offline"));
+        }
+    }
+
+    /// Parses the `archs("<arch>", ...)` list of target architectures that a
+    /// kernel is linked for, which is returned ordered by their compute
+    /// capability and without duplicates.
+    pub fn parse_archs(attr: &syn::MetaList) -> syn::Result<Vec<String>> {
+        let archs = attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::LitStr, syn::token::Comma>::parse_terminated,
+        )?;
+
+        let mut valid_archs = Vec::with_capacity(archs.len());
+
+        for arch in archs {
+            if is_valid_arch(&arch.value()) {
+                valid_archs.push(arch.value());
+            } else {
+                emit_error!(
+                    arch.span(),
+                    "[rust-cuda]: Invalid link! target architecture: must be of the form \
+                     `sm_<version>`, e.g. `sm_80`.",
+                );
+            }
+        }
+
+        valid_archs.sort_by_cached_key(|arch| (arch_version(arch), arch.clone()));
+        valid_archs.dedup();
+
+        Ok(valid_archs)
+    }
+
+    /// Returns whether all options use the defaults of the kernel crate.
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Returns a hash of the options that distinguishes the build cache of
+    /// kernels that are compiled for different targets, or [`None`] if all
+    /// options use the defaults.
+    pub fn cache_key(&self) -> Option<String> {
+        if self.is_default() {
+            return None;
+        }
+
+        let mut hasher = seahash::SeaHasher::new();
+        self.hash(&mut hasher);
+
+        Some(format!("{:016x}", hasher.finish()))
+    }
+
+    /// Returns the name of the directory inside the target dir that the
+    /// kernel's profile builds into, e.g. `debug` for the `dev` profile.
+    pub fn profile_dir(&self, check: bool) -> &str {
+        match self.profile.as_deref() {
+            Some("dev" | "test") => "debug",
+            Some("bench") => "release",
+            Some(profile) => profile,
+            None if check => "debug",
+            None => "release",
+        }
+    }
+
+    /// Returns the `cargo build` arguments that build the kernel with the
+    /// options, which use the `dev` profile if `check` and the `release`
+    /// profile otherwise, unless another profile is set.
+    ///
+    /// The `rustflags` are passed with `--config` so that they are appended
+    /// to those in any cargo config file, unlike `RUSTFLAGS`.
+    pub fn cargo_args(&self, target_name: &str, check: bool) -> Vec<String> {
+        let mut args = Vec::new();
+
+        let profile = match &self.profile {
+            Some(profile) => {
+                args.push(format!("--profile={profile}"));
+                profile.as_str()
+            },
+            None if check => "dev",
+            None => {
+                args.push(String::from("--release"));
+                "release"
+            },
+        };
+
+        if let Some(crate_features) = &self.crate_features {
+            args.push(String::from("--no-default-features"));
+
+            if !crate_features.is_empty() {
+                args.push(format!("--features={}", crate_features.join(",")));
+            }
+        }
+
+        // The `.loc` line info directives are always emitted, even without
+        //  debug sections, to attribute PTX diagnostics to their Rust source
+        let debuginfo = match self.debug.as_deref() {
+            Some("full") => "full",
+            Some("lines") => "line-tables-only",
+            _ => "line-directives-only",
+        };
+
+        let rustflags = std::iter::once(format!("-Cdebuginfo={debuginfo}"))
+            .chain(self.arch.iter().map(|arch| format!("-Ctarget-cpu={arch}")))
+            .chain(
+                self.features
+                    .iter()
+                    .map(|features| format!("-Ctarget-feature={features}")),
+            )
+            .chain(self.rustflags.iter().cloned())
+            .map(|flag| serde_json::Value::from(flag).to_string())
+            .collect::<Vec<_>>();
+
+        args.push(String::from("--config"));
+        args.push(format!(
+            "target.{target_name}.rustflags=[{}]",
+            rustflags.join(",")
+        ));
+
+        if let Some(opt_level) = &self.opt_level {
+            let opt_level = match opt_level.as_str() {
+                "s" | "z" => format!("\"{opt_level}\""),
+                opt_level => String::from(opt_level),
+            };
+
+            args.push(String::from("--config"));
+            args.push(format!("profile.{profile}.opt-level={opt_level}"));
+        }
+
+        args.extend(self.cargo_args.iter().cloned());
+
+        args
+    }
+
+    /// Quotes the options as a comma-prefixed list of
+    /// `target(arch = "<arch>", features = "<features>")`,
+    /// `opt_level("<level>")`, `debug("<debug>")`, `crate_features(...)`,
+    /// `cargo_args(...)`, and `rustflags(...)` attributes, which are parsed
+    /// by [`Self::parse_target`], [`Self::parse_opt_level`],
+    /// [`Self::parse_debug`], and [`Self::parse_args`].
+    pub fn quote(&self) -> TokenStream {
+        let arch = self.arch.as_ref().map(|arch| quote!(arch = #arch));
+        let features = self
+            .features
+            .as_ref()
+            .map(|features| quote!(features = #features));
+        let target = (arch.is_some() || features.is_some()).then(|| {
+            let options = arch.into_iter().chain(features);
+            quote!(, target(#(#options),*))
+        });
+
+        let opt_level = self
+            .opt_level
+            .as_ref()
+            .map(|opt_level| quote!(, opt_level(#opt_level)));
+
+        let debug = self.debug.as_ref().map(|debug| quote!(, debug(#debug)));
+
+        let crate_features = self
+            .crate_features
+            .as_ref()
+            .map(|crate_features| quote!(, crate_features(#(#crate_features),*)));
+        let cargo_args = (!self.cargo_args.is_empty()).then(|| {
+            let cargo_args = &self.cargo_args;
+            quote!(, cargo_args(#(#cargo_args),*))
+        });
+        let rustflags = (!self.rustflags.is_empty()).then(|| {
+            let rustflags = &self.rustflags;
+            quote!(, rustflags(#(#rustflags),*))
+        });
+
+        quote!(#target #opt_level #debug #crate_features #cargo_args #rustflags)
+    }
+}
+
+/// Returns the numeric version of a valid `arch`, e.g. `80` for `sm_80`.
+fn arch_version(arch: &str) -> u32 {
+    arch.trim_start_matches("sm_")
+        .trim_end_matches('a')
+        .parse()
+        .unwrap_or_default()
+}
+
+/// Returns `true` iff the cargo `arg` is set by the kernel compilation and
+/// must thus not be passed by the user.
+fn is_reserved_cargo_arg(arg: &str) -> bool {
+    let option = arg.split_once('=').map_or(arg, |(option, _value)| option);
+
+    matches!(
+        option,
+        "--target"
+            | "--target-dir"
+            | "--artifact-dir"
+            | "--manifest-path"
+            | "--message-format"
+            | "--release"
+            | "--profile"
+    )
+}
+
+/// Returns `true` iff cargo is configured to be offline, either by the
+/// `CARGO_NET_OFFLINE` environment variable or by the `net.offline` key of
+/// the closest cargo config file that sets it.
+///
+/// Cargo config files are searched for in the ancestors of the current
+/// directory, which cargo sets to the workspace root, and in `CARGO_HOME`.
+/// The `--offline` command line flag of the host build cannot be observed.
+fn is_offline() -> bool {
+    if let Ok(offline) = proc_macro::tracked_env::var("CARGO_NET_OFFLINE") {
+        return offline.trim() == "true";
+    }
+
+    let Ok(current_dir) = std::env::current_dir() else {
+        return false;
+    };
+
+    cargo_config_paths(&current_dir)
+        .into_iter()
+        .find_map(|path| config_net_offline(&fs::read_to_string(path).ok()?))
+        .unwrap_or(false)
+}
+
+/// Returns the value of the `net.offline` key in the cargo `config`, if it
+/// is set as `offline` in a `[net]` table, as a top-level dotted key, or in a
+/// top-level `net = { offline = ... }` inline table.
+///
+/// Only single-line keys and values are supported, which covers all forms in
+/// which a boolean key can be written.
+fn config_net_offline(config: &str) -> Option<bool> {
+    let mut table = None;
+
+    for line in config.lines() {
+        let line = strip_toml_comment(line).trim();
+
+        if let Some(header) = line.strip_prefix('[') {
+            table = header.strip_suffix(']').map(toml_key);
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        let (key, value) = (toml_key(key), value.trim());
+
+        let offline = match (table.as_deref(), key.as_slice()) {
+            (None, ["net", "offline"]) | (Some(["net"]), ["offline"]) => value,
+            (None, ["net"]) => value
+                .strip_prefix('{')
+                .and_then(|value| value.strip_suffix('}'))
+                .and_then(|table| {
+                    table.split(',').find_map(|entry| {
+                        let (key, value) = entry.split_once('=')?;
+                        (toml_key(key) == ["offline"]).then_some(value.trim())
+                    })
+                })
+                .unwrap_or_default(),
+            _ => continue,
+        };
+
+        return offline.parse().ok();
+    }
+
+    None
+}
+
+/// Returns the `line` of a TOML document without its trailing comment,
+/// respecting `#` characters inside of basic and literal strings.
+fn strip_toml_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            },
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return line.split_at(i).0,
+            _ => (),
+        }
+
+        escaped = false;
+    }
+
+    line
+}
+
+/// Splits a dotted TOML `key` into its trimmed and unquoted parts.
+fn toml_key(key: &str) -> Vec<&str> {
+    key.split('.')
+        .map(|part| part.trim().trim_matches(|c| c == '"' || c == '\''))
+        .collect()
+}
+
+fn is_valid_debug(debug: &str) -> bool {
+    matches!(debug, "none" | "lines" | "full")
+}
+
+fn is_valid_arch(arch: &str) -> bool {
+    arch.strip_prefix("sm_").map_or(false, |version| {
+        let version = version.strip_suffix('a').unwrap_or(version);
+        !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::config_net_offline;
+
+    #[test]
+    fn net_offline_table() {
+        assert_eq!(config_net_offline("[net]\noffline = true\n"), Some(true));
+        assert_eq!(
+            config_net_offline("[ net ] # network\nretry = 3\noffline = false # online\n"),
+            Some(false)
+        );
+        assert_eq!(
+            config_net_offline("[net]\nretry = 3\n[build]\noffline = true\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn net_offline_dotted_key() {
+        assert_eq!(config_net_offline("net.offline = true"), Some(true));
+        assert_eq!(config_net_offline("\"net\" . 'offline' = true"), Some(true));
+        assert_eq!(config_net_offline("[build]\nnet.offline = true"), None);
+    }
+
+    #[test]
+    fn net_offline_inline_table() {
+        assert_eq!(config_net_offline("net = { offline = true }"), Some(true));
+        assert_eq!(
+            config_net_offline("net = { retry = 3, offline = false } # inline"),
+            Some(false)
+        );
+        assert_eq!(config_net_offline("net = { retry = 3 }"), None);
+    }
+
+    #[test]
+    fn net_offline_comments_and_strings() {
+        assert_eq!(config_net_offline("# net.offline = true\n"), None);
+        assert_eq!(
+            config_net_offline(
+                r##"[build]
+rustflags = "-Cmetadata=#1 \"#\""
+target-dir = '#target'
+[net]
+offline = true # set by "CI"
+"##
+            ),
+            Some(true)
+        );
+        assert_eq!(config_net_offline("[net]\noffline = \"true\""), None);
+        assert_eq!(config_net_offline(""), None);
+    }
+}
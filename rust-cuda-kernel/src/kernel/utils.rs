@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 pub fn skip_kernel_compilation() -> bool {
     let mut skip_compilation = false;
 
@@ -11,3 +13,121 @@ pub fn skip_kernel_compilation() -> bool {
 
     skip_compilation
 }
+
+/// Returns the value of the environment variable `var`, which is tracked so
+/// that the crate that is being compiled is rebuilt when it changes.
+///
+/// Outside of a proc macro expansion, e.g. in unit tests, the variable is
+/// read from the process environment instead.
+pub fn tracked_env_var(var: &str) -> Result<String, std::env::VarError> {
+    if proc_macro::is_available() {
+        proc_macro::tracked_env::var(var)
+    } else {
+        std::env::var(var)
+    }
+}
+
+/// Returns the features of the crate that is being compiled, which cargo
+/// only passes to rustc as `--cfg feature="<feature>"` arguments, or [`None`]
+/// if the proc macro is not running inside a rustc invocation.
+pub fn active_crate_features() -> Option<Vec<String>> {
+    let mut args = Vec::new();
+
+    for arg in std::env::args() {
+        // cargo may pass long argument lists in an `@<path>` argfile
+        match arg
+            .strip_prefix('@')
+            .and_then(|path| std::fs::read_to_string(path).ok())
+        {
+            Some(argfile) => args.extend(argfile.lines().map(String::from)),
+            None => args.push(arg),
+        }
+    }
+
+    crate_features_from_args(&args)
+}
+
+/// Returns the features that are enabled by the `--cfg feature="<feature>"`
+/// or `--cfg=feature="<feature>"` arguments of a rustc invocation, or
+/// [`None`] if the `args` do not belong to a rustc invocation.
+fn crate_features_from_args(args: &[String]) -> Option<Vec<String>> {
+    if !args.iter().any(|arg| arg == "--crate-name") {
+        return None;
+    }
+
+    let mut features = args
+        .iter()
+        .enumerate()
+        .filter_map(|(i, option)| {
+            option.strip_prefix("--cfg=").or_else(|| {
+                args.get(i + 1)
+                    .filter(|_| option == "--cfg")
+                    .map(String::as_str)
+            })
+        })
+        .filter_map(|cfg| cfg.strip_prefix("feature=\"")?.strip_suffix('"'))
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    features.sort();
+    features.dedup();
+
+    Some(features)
+}
+
+/// Returns the paths of all cargo config files that apply to a cargo command
+/// which is run in the `dir`, ordered from the highest to the lowest
+/// precedence, whether they exist or not.
+pub fn cargo_config_paths(dir: &Path) -> Vec<PathBuf> {
+    let cargo_home = tracked_env_var("CARGO_HOME")
+        .map(PathBuf::from)
+        .ok()
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")));
+
+    dir.ancestors()
+        .map(|dir| dir.join(".cargo"))
+        .chain(cargo_home)
+        .flat_map(|dir| [dir.join("config.toml"), dir.join("config")])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::crate_features_from_args;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().copied().map(String::from).collect()
+    }
+
+    #[test]
+    fn crate_features() {
+        assert_eq!(
+            crate_features_from_args(&args(&[
+                "rustc",
+                "--crate-name",
+                "my_crate",
+                "--cfg",
+                "feature=\"std\"",
+                "--cfg",
+                "debug_assertions",
+                "--cfg",
+                "feature=\"alloc\"",
+                "--cfg=feature=\"std\"",
+                "--cfg=feature=\"last\"",
+            ])),
+            Some(args(&["alloc", "last", "std"]))
+        );
+        assert_eq!(
+            crate_features_from_args(&args(&["rustc", "--crate-name", "my_crate", "--cfg"])),
+            Some(Vec::new())
+        );
+    }
+
+    #[test]
+    fn not_a_rustc_invocation() {
+        assert_eq!(
+            crate_features_from_args(&args(&["rust-analyzer", "--cfg", "feature=\"std\""])),
+            None
+        );
+    }
+}
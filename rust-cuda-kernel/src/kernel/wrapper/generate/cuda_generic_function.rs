@@ -84,8 +84,15 @@ pub(in super::super) fn quote_cuda_generic_function(
     let generic_start_token = generic_start_token.unwrap_or_default();
     let generic_close_token = generic_close_token.unwrap_or_default();
 
+    // the emulated kernel launch calls the generic kernel function on the host
+    let func_cfg = if cfg!(feature = "emulate") {
+        quote! { #[cfg_attr(not(target_os = "cuda"), allow(dead_code))] }
+    } else {
+        quote! { #[cfg(target_os = "cuda")] }
+    };
+
     quote! {
-        #[cfg(target_os = "cuda")]
+        #func_cfg
         #(#func_attrs)*
         fn #func_ident #generic_start_token #generic_params #generic_close_token (
             #(#kernel_func_inputs),*
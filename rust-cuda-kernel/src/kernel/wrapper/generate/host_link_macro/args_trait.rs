@@ -1,7 +1,8 @@
 use proc_macro2::TokenStream;
 use quote::quote;
+use syn::spanned::Spanned;
 
-use crate::kernel::wrapper::{FunctionInputs, ImplGenerics};
+use crate::kernel::wrapper::{DeclGenerics, FunctionInputs, ImplGenerics};
 
 pub(in super::super) fn quote_args_trait(
     args: &syn::Ident,
@@ -48,3 +49,44 @@ pub(in super::super) fn quote_args_trait(
         }
     }
 }
+
+pub(super) fn quote_specialised_types(
+    args: &syn::Ident,
+    DeclGenerics {
+        generic_start_token,
+        generic_close_token,
+        ..
+    }: &DeclGenerics,
+    FunctionInputs { func_inputs }: &FunctionInputs,
+    macro_type_ids: &[syn::Ident],
+) -> Vec<TokenStream> {
+    func_inputs
+        .iter()
+        .enumerate()
+        .map(|(i, syn::PatType { ty, .. })| {
+            let type_ident = quote::format_ident!("__T_{}", i);
+
+            let specialised_ty = quote::quote_spanned! { ty.span()=>
+                <() as #args #generic_start_token
+                    #($#macro_type_ids),*
+                #generic_close_token>::#type_ident
+            };
+
+            // the args trait has to unbox outer lifetimes, so we need to add them back in
+            // here
+            if let syn::Type::Reference(syn::TypeReference {
+                and_token,
+                lifetime,
+                mutability,
+                ..
+            }) = &**ty
+            {
+                let lifetime = quote::quote_spanned! { lifetime.span()=> 'static };
+
+                quote! { #and_token #lifetime #mutability #specialised_ty }
+            } else {
+                specialised_ty
+            }
+        })
+        .collect()
+}
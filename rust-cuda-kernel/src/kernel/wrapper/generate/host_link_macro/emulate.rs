@@ -0,0 +1,77 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+
+use crate::kernel::wrapper::{DeclGenerics, FuncIdent, FunctionInputs, ImplGenerics};
+
+pub(super) fn quote_emulate_thread(
+    crate_path: &syn::Path,
+    FuncIdent { func_ident, .. }: &FuncIdent,
+    generics: &DeclGenerics,
+    impl_generics: &ImplGenerics,
+    inputs @ FunctionInputs { func_inputs }: &FunctionInputs,
+    func_params: &[syn::Ident],
+    macro_type_ids: &[syn::Ident],
+) -> TokenStream {
+    if !cfg!(feature = "emulate") {
+        return quote!();
+    }
+
+    let args = syn::Ident::new("KernelArgs", proc_macro::Span::def_site().into());
+    let args_trait = super::args_trait::quote_args_trait(&args, impl_generics, inputs);
+
+    let specialised_types =
+        super::args_trait::quote_specialised_types(&args, generics, inputs, macro_type_ids);
+
+    let private_func_params = func_params
+        .iter()
+        .map(|param| {
+            let mut private = syn::Ident::clone(param);
+            private.set_span(proc_macro::Span::def_site().into());
+            private
+        })
+        .collect::<Vec<_>>();
+
+    let emulated_param_wrap = func_inputs
+        .iter()
+        .zip(specialised_types.iter())
+        .zip(private_func_params.iter())
+        .enumerate()
+        .rev()
+        .fold(
+            quote! {
+                #func_ident::<#($#macro_type_ids),*>(#(#private_func_params),*)
+            },
+            |inner, (i, ((syn::PatType { ty, .. }, specialised_ty), param))| {
+                // Borrow the device param from its shared emulated representation
+                // To allow some parameters to allocate per-block state,
+                //  we pass them the param index i
+                quote::quote_spanned! { ty.span()=>
+                    <
+                        #specialised_ty as #crate_path::kernel::CudaKernelParameter
+                    >::with_emulated_as_device::<_, #i>(
+                        #param, |#param: <
+                            #specialised_ty as #crate_path::kernel::CudaKernelParameter
+                        >::DeviceType::<'_>| { #inner }
+                    )
+                }
+            },
+        );
+
+    quote! {
+        unsafe fn emulate_thread(params: ::core::ptr::NonNull<()>) {
+            #args_trait
+
+            // Safety: the params are the emulated host parameters of this kernel
+            let (#(#private_func_params,)*) = unsafe {
+                params.cast::<(#(
+                    <#specialised_types as #crate_path::kernel::CudaKernelParameter>::EmulatedHostType,
+                )*)>().as_ref()
+            };
+
+            #[allow(unused_unsafe)]
+            // Safety: every param is only given the index of its own position
+            unsafe { #emulated_param_wrap }
+        }
+    }
+}
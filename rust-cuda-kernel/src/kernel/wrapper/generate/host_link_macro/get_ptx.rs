@@ -5,7 +5,8 @@ use syn::spanned::Spanned;
 use crate::kernel::{
     utils::skip_kernel_compilation,
     wrapper::{DeclGenerics, FuncIdent, FunctionInputs, ImplGenerics},
-    KERNEL_TYPE_LAYOUT_HASH_SEED_IDENT, KERNEL_TYPE_LAYOUT_IDENT, PTX_CSTR_IDENT,
+    KERNEL_RESOURCES_IDENT, KERNEL_TYPE_LAYOUT_HASH_SEED_IDENT, KERNEL_TYPE_LAYOUT_IDENT,
+    PTX_CSTR_IDENT, PTX_VARIANTS_IDENT,
 };
 
 #[expect(clippy::too_many_arguments)]
@@ -40,6 +41,8 @@ pub(super) fn quote_get_ptx(
         generate_lifetime_erased_types(crate_path, &args, generics, inputs, macro_type_ids);
 
     let ptx_cstr_ident = syn::Ident::new(PTX_CSTR_IDENT, func_ident.span());
+    let resources_ident = syn::Ident::new(KERNEL_RESOURCES_IDENT, func_ident.span());
+    let ptx_variants_ident = syn::Ident::new(PTX_VARIANTS_IDENT, func_ident.span());
 
     let matching_kernel_assert = if skip_kernel_compilation() {
         quote!()
@@ -48,8 +51,8 @@ pub(super) fn quote_get_ptx(
             const _: #crate_path::safety::ptx_entry_point::Assert<{
                 #crate_path::safety::ptx_entry_point::HostAndDeviceKernelEntryPoint::Match
             }> = #crate_path::safety::ptx_entry_point::Assert::<{
-                #crate_path::safety::ptx_entry_point::check(
-                    #ptx_cstr_ident.to_bytes(),
+                #crate_path::safety::ptx_entry_point::check_variants(
+                    #ptx_variants_ident,
                     #crate_path::kernel::specialise_kernel_entry_point!(
                         #func_ident_hash #generic_start_token
                             #($#macro_type_ids),*
@@ -90,11 +93,19 @@ pub(super) fn quote_get_ptx(
         .collect::<Vec<_>>();
 
     quote! {
-        fn get_ptx() -> &'static ::core::ffi::CStr {
+        #[doc(hidden)]
+        const COMPILED_PTX: (
+            &'static ::core::ffi::CStr,
+            #crate_path::kernel::KernelResources,
+            &'static [#crate_path::kernel::KernelPtxVariant],
+        ) = {
             // FIXME: don't use imports here
             #[allow(unused_imports)]
-            use #crate_path::safety::ptx_kernel_signature::{
-                check as check_ptx_kernel_signature, HostAndDeviceKernelSignatureTypeLayout,
+            use #crate_path::{
+                kernel::{KernelPtxVariant, KernelResources},
+                safety::ptx_kernel_signature::{
+                    check as check_ptx_kernel_signature, HostAndDeviceKernelSignatureTypeLayout,
+                },
             };
 
             #args_trait
@@ -108,14 +119,24 @@ pub(super) fn quote_get_ptx(
             #crate_path::kernel::compile_kernel!{
                 #func_ident #func_ident_hash #crate_name #crate_manifest_dir #generic_start_token
                     #($#macro_type_ids),*
-                #generic_close_token #ptx_lint_levels
+                #generic_close_token #ptx_lint_levels $(, archs($($arch),+))?
             }
 
             #matching_kernel_assert
 
             #signature_layout_assert
 
-            #ptx_cstr_ident
+            (#ptx_cstr_ident, #resources_ident, #ptx_variants_ident)
+        };
+
+        const RESOURCES: #crate_path::kernel::KernelResources = Self::COMPILED_PTX.1;
+
+        fn get_ptx() -> &'static ::core::ffi::CStr {
+            Self::COMPILED_PTX.0
+        }
+
+        fn get_ptx_variants() -> &'static [#crate_path::kernel::KernelPtxVariant] {
+            Self::COMPILED_PTX.2
         }
     }
 }
@@ -123,32 +144,14 @@ pub(super) fn quote_get_ptx(
 fn generate_lifetime_erased_types(
     crate_path: &syn::Path,
     args: &syn::Ident,
-    DeclGenerics {
-        generic_start_token,
-        generic_close_token,
-        ..
-    }: &DeclGenerics,
-    FunctionInputs { func_inputs }: &FunctionInputs,
+    generics: &DeclGenerics,
+    inputs @ FunctionInputs { func_inputs }: &FunctionInputs,
     macro_type_ids: &[syn::Ident],
 ) -> Vec<proc_macro2::TokenStream> {
-    func_inputs
-        .iter()
-        .enumerate()
-        .map(|(i, syn::PatType { ty, .. })| {
-            let type_ident = quote::format_ident!("__T_{}", i);
-
-            let mut specialised_ty = quote::quote_spanned! { ty.span()=>
-                <() as #args #generic_start_token
-                    #($#macro_type_ids),*
-                #generic_close_token>::#type_ident
-            };
-            // the args trait has to unbox outer lifetimes, so we need to add them back in here
-            if let syn::Type::Reference(syn::TypeReference { and_token, lifetime, mutability, .. }) = &**ty {
-                let lifetime = quote::quote_spanned! { lifetime.span()=> 'static };
-
-                specialised_ty = quote! { #and_token #lifetime #mutability #specialised_ty };
-            }
-
+    super::args_trait::quote_specialised_types(args, generics, inputs, macro_type_ids)
+        .into_iter()
+        .zip(func_inputs.iter())
+        .map(|(specialised_ty, syn::PatType { ty, .. })| {
             quote::quote_spanned! { ty.span()=>
                 <#specialised_ty as #crate_path::kernel::CudaKernelParameter>::FfiType<'static, 'static>
             }
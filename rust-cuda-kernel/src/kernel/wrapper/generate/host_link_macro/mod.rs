@@ -4,8 +4,10 @@ use quote::quote;
 use crate::kernel::wrapper::{DeclGenerics, FuncIdent, FunctionInputs, ImplGenerics, KernelConfig};
 
 mod args_trait;
+mod emulate;
 mod get_ptx;
 
+use emulate::quote_emulate_thread;
 use get_ptx::quote_get_ptx;
 
 #[expect(clippy::too_many_arguments)] // FIXME
@@ -85,12 +87,22 @@ pub(in super::super) fn quote_host_link_macro(
         ptx_lint_levels,
     );
 
+    let emulate_thread = quote_emulate_thread(
+        crate_path,
+        func_ident,
+        decl_generics,
+        impl_generics,
+        func_inputs,
+        func_params,
+        &macro_non_lt_generic_ids,
+    );
+
     quote! {
         #[cfg(not(target_os = "cuda"))]
         #visibility macro #link(
             impl #func_ident_name #generic_start_token
                 #(#macro_generics),* $(,)?
-            #generic_close_token for $ptx:ident
+            #generic_close_token for $ptx:ident $(in [$($arch:literal),+ $(,)?])?
         ) {
             unsafe impl<#($#macro_only_lt_generic_ids),*> #crate_path::kernel::CompiledKernelPtx<
                 #func_ident_name #generic_start_token #($#macro_generic_ids),* #generic_close_token
@@ -98,6 +110,8 @@ pub(in super::super) fn quote_host_link_macro(
             {
                 #get_ptx
 
+                #emulate_thread
+
                 fn get_entry_point() -> &'static ::core::ffi::CStr {
                     #crate_path::kernel::specialise_kernel_entry_point!(
                         #func_ident_hash #generic_start_token
@@ -9,7 +9,11 @@ mod config;
 mod generate;
 mod parse;
 
-use crate::kernel::lints::{parse_ptx_lint_level, LintLevel, PtxLint};
+use crate::kernel::{
+    lints::{parse_ptx_lint_level, LintLevel, PtxLint},
+    target::KernelTarget,
+    utils::active_crate_features,
+};
 
 use config::KernelConfig;
 use generate::{
@@ -43,6 +47,8 @@ pub fn kernel(attr: TokenStream, func: TokenStream) -> TokenStream {
     let mut func = parse_kernel_fn(func);
 
     let mut crate_path = None;
+    let mut register_budget = None;
+    let mut target = KernelTarget::default();
     let mut ptx_lint_levels = HashMap::new();
 
     func.attrs.retain(|attr| {
@@ -76,6 +82,99 @@ pub fn kernel(attr: TokenStream, func: TokenStream) -> TokenStream {
                         return Ok(());
                     }
 
+                    if meta.path.is_ident("register_budget") {
+                        match meta
+                            .value()
+                            .and_then(<syn::LitInt as syn::parse::Parse>::parse)
+                            .and_then(|budget| budget.base10_parse::<u32>())
+                        {
+                            Ok(budget) => {
+                                if register_budget.is_none() {
+                                    register_budget = Some(budget);
+                                } else {
+                                    emit_error!(
+                                        meta.path.span(),
+                                        "[rust-cuda]: Duplicate #[kernel(register_budget)] \
+                                         attribute.",
+                                    );
+                                }
+                            },
+                            Err(err) => emit_error!(
+                                meta.path.span(),
+                                "[rust-cuda]: Invalid #[kernel(register_budget = <registers>)] \
+                                 attribute: {}.",
+                                err
+                            ),
+                        }
+
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("target") {
+                        target.parse_target(&meta);
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("opt_level") {
+                        match meta
+                            .value()
+                            .and_then(<syn::Lit as syn::parse::Parse>::parse)
+                        {
+                            Ok(opt_level) => target.parse_opt_level(&opt_level),
+                            Err(err) => emit_error!(
+                                meta.path.span(),
+                                "[rust-cuda]: Invalid #[kernel(opt_level = <level>)] attribute: \
+                                 {}.",
+                                err
+                            ),
+                        }
+
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("debug") {
+                        match meta
+                            .value()
+                            .and_then(<syn::LitStr as syn::parse::Parse>::parse)
+                        {
+                            Ok(debug) => target.parse_debug(&debug),
+                            Err(err) => emit_error!(
+                                meta.path.span(),
+                                "[rust-cuda]: Invalid #[kernel(debug = \"<debug>\")] attribute: \
+                                 {}.",
+                                err
+                            ),
+                        }
+
+                        return Ok(());
+                    }
+
+                    if meta.path.is_ident("cargo_args") || meta.path.is_ident("rustflags") {
+                        let args = (|| -> syn::Result<_> {
+                            let args;
+                            syn::parenthesized!(args in meta.input);
+                            args.parse_terminated(
+                                <syn::LitStr as syn::parse::Parse>::parse,
+                                syn::token::Comma,
+                            )
+                        })();
+
+                        match args {
+                            Ok(args) => target.parse_args(&meta.path, args),
+                            Err(err) => emit_error!(
+                                meta.path.span(),
+                                "[rust-cuda]: Invalid #[kernel({}(\"<arg>\", ...))] attribute: {}.",
+                                meta.path
+                                    .get_ident()
+                                    .map(ToString::to_string)
+                                    .unwrap_or_default(),
+                                err
+                            ),
+                        }
+
+                        return Ok(());
+                    }
+
                     if meta.path.is_ident("allow")
                         || meta.path.is_ident("warn")
                         || meta.path.is_ident("deny")
@@ -87,8 +186,12 @@ pub fn kernel(attr: TokenStream, func: TokenStream) -> TokenStream {
 
                     emit_error!(
                         meta.path.span(),
-                        "[rust-cuda]: Expected #[kernel(crate = \"<crate-path>\")] or \
-                         #[kernel(allow/warn/deny/forbid(<lint>))] function attribute."
+                        "[rust-cuda]: Expected #[kernel(crate = \"<crate-path>\")], \
+                         #[kernel(register_budget = <registers>)], #[kernel(target(arch = \
+                         \"<arch>\", features = \"<features>\"))], #[kernel(opt_level = \
+                         <level>)], #[kernel(debug = \"<debug>\")], \
+                         #[kernel(cargo_args(\"<arg>\", ...))], #[kernel(rustflags(\"<flag>\", \
+                         ...))], or #[kernel(allow/warn/deny/forbid(<lint>))] function attribute."
                     );
 
                     Ok(())
@@ -116,6 +219,23 @@ pub fn kernel(attr: TokenStream, func: TokenStream) -> TokenStream {
     let _ = ptx_lint_levels.try_insert(PtxLint::RegisterSpills, LintLevel::Warn);
     let _ = ptx_lint_levels.try_insert(PtxLint::DumpAssembly, LintLevel::Allow);
     let _ = ptx_lint_levels.try_insert(PtxLint::DynamicStackSize, LintLevel::Warn);
+    let _ = ptx_lint_levels.try_insert(PtxLint::MallocUse, LintLevel::Allow);
+    let _ = ptx_lint_levels.try_insert(PtxLint::PrintfUse, LintLevel::Allow);
+    let _ = ptx_lint_levels.try_insert(PtxLint::TrapUse, LintLevel::Allow);
+    let _ = ptx_lint_levels.try_insert(PtxLint::AtomicUse, LintLevel::Allow);
+    // The register budget lint is only denied by default if a budget is set
+    if register_budget.is_some() {
+        let _ = ptx_lint_levels.try_insert(PtxLint::RegisterBudget, LintLevel::Deny);
+    }
+
+    // Kernels in binary targets are compiled from a synthetic library target
+    let bin = proc_macro::tracked_env::var("CARGO_BIN_NAME")
+        .ok()
+        .map(|bin| quote!(, bin(#bin)));
+    // The device build enables the same crate features as the host build
+    target.crate_features = active_crate_features();
+    let target = target.quote();
+    let target = quote!(#bin #target);
 
     let ptx_lint_levels = {
         let (lints, levels): (Vec<Ident>, Vec<Ident>) = ptx_lint_levels
@@ -128,8 +248,10 @@ pub fn kernel(attr: TokenStream, func: TokenStream) -> TokenStream {
             })
             .unzip();
 
+        let register_budget = register_budget.map(|budget| quote!(, register_budget(#budget)));
+
         quote! {
-            #(#levels(ptx::#lints)),*
+            #(#levels(ptx::#lints)),* #register_budget #target
         }
     };
 
@@ -225,7 +347,7 @@ pub fn kernel(attr: TokenStream, func: TokenStream) -> TokenStream {
         &func_params,
         &func.attrs,
     );
-    let host_generic_kernel_check = quote_generic_check(&crate_path, &func_ident);
+    let host_generic_kernel_check = quote_generic_check(&crate_path, &func_ident, &target);
     let host_link_macro = quote_host_link_macro(
         &crate_path,
         &config,
@@ -332,6 +454,7 @@ fn quote_generic_check(
         func_ident_hash,
         ..
     }: &FuncIdent,
+    target: &proc_macro2::TokenStream,
 ) -> proc_macro2::TokenStream {
     let crate_name = proc_macro::tracked_env::var("CARGO_CRATE_NAME")
         .unwrap_or_else(|err| abort_call_site!("Failed to read crate name: {:?}.", err));
@@ -342,7 +465,7 @@ fn quote_generic_check(
     quote::quote_spanned! { func_ident_hash.span()=>
         #[cfg(not(target_os = "cuda"))]
         #crate_path::kernel::check_kernel! {
-            #func_ident #func_ident_hash #crate_name #crate_manifest_dir
+            #func_ident #func_ident_hash #crate_name #crate_manifest_dir #target
         }
     }
 }
@@ -47,10 +47,10 @@ pub(super) fn parse_kernel_fn(tokens: TokenStream) -> syn::ItemFn {
         }
     }
 
-    if func.sig.inputs.len() > 12 {
+    if func.sig.inputs.len() > 32 {
         emit_warning!(
             func.sig.inputs.span(),
-            "Kernel function has too many arguments, {} were found but at most 12 are supported.",
+            "Kernel function has too many arguments, {} were found but at most 32 are supported.",
             func.sig.inputs.len()
         );
     }
@@ -56,7 +56,10 @@ mod kernel;
 ///
 /// While the [`#[kernel]`](macro@kernel) attribute supports functions with any
 /// number of arguments, [`rust_cuda::kernel::TypedPtxKernel`] only supports
-/// launching kernels with up to 12 parameters at the moment.
+/// launching kernels with up to 32 parameters at the moment. Kernels are
+/// launched with a tuple of their parameters, e.g. using
+/// `kernel.launch(stream, &config, (a, b, c))`. The numbered
+/// `launch0`-`launch12` methods remain available as shorthands.
 ///
 /// The [`#[kernel]`](macro@kernel) attribute uses the following syntax:
 ///
@@ -75,6 +78,47 @@ mod kernel;
 /// Note that all kernel parameters must implement the sealed
 /// [`rust_cuda::kernel::CudaKernelParameter`] trait.
 ///
+/// The kernel is compiled for CUDA from the library target of its crate,
+/// which is resolved with `cargo metadata` and may use a custom `[lib] path`.
+/// Kernels that are defined in a binary or example target are compiled from a
+/// synthetic library target instead, whose root is the binary's root source
+/// file, which depends on the crate's own library target, if any, and which
+/// otherwise mirrors the crate's package, features, dependencies, and its
+/// workspace's profiles. Workspace-level `[patch]` sections are not applied
+/// to the synthetic library target. The synthetic library target reuses the
+/// workspace's lockfile unless `--locked` or `--frozen` is passed to its
+/// build, in which case it resolves its own lockfile.
+///
+/// The kernel is compiled with the same crate features that are enabled in
+/// the host build, and is built with `--offline` if the host build is offline
+/// by the `CARGO_NET_OFFLINE` environment variable or the `net.offline` cargo
+/// config. The host build's command line flags, e.g. `--offline`, `--locked`,
+/// or `--frozen`, cannot be observed by the kernel compilation. The device
+/// build is further configured with the following environment variables,
+/// which apply to all kernels:
+///
+/// - `RUST_CUDA_DEVICE_PROFILE` builds the kernels with the named cargo
+///   profile, which by default is `dev` for checking and `release` for linking.
+/// - `RUST_CUDA_DEVICE_DEBUG` builds the kernels with `none`, `lines`, or
+///   `full` debug information, overriding any `#[kernel(debug = "<debug>")]`.
+/// - `RUST_CUDA_DEVICE_CARGO_ARGS` passes whitespace-separated extra arguments
+///   to `cargo build`, e.g. `--offline --locked` for offline builds.
+/// - `RUST_CUDA_DEVICE_RUSTFLAGS` appends whitespace-separated extra flags to
+///   the kernel crate's `rustflags`.
+///
+/// Kernels that are built with different features, profiles, debug
+/// information, arguments, or flags are cached separately.
+///
+/// Every kernel specialisation is only rebuilt if its build configuration,
+/// any of the source files, manifests, or cargo config files that it was
+/// built from, the rustc flags in the environment, or the environment
+/// variables that its build scripts depend on have changed. The stale
+/// variants of a specialisation for all of its `link!` target architectures
+/// are rebuilt in parallel, alongside other recently used stale
+/// specialisations of the same crate. Since the specialisations of a crate
+/// are only discovered as their `link!` macros are expanded, the first build
+/// of every specialisation cannot be batched with the others.
+///
 /// To use a specific monomorphised instantiation of the kernel, the generated
 /// `link!` macro must be invoked with the following syntax:
 ///
@@ -110,40 +154,119 @@ mod kernel;
 /// the kernel-defining crate to construct the requested
 /// [`rust_cuda::kernel::TypedPtxKernel`].
 ///
+/// The `link!` macro also records the registers, shared, local, and constant
+/// memory, and register spills that the PTX compiler reports for each linked
+/// kernel instantiation in the `RESOURCES` associated constant of its
+/// [`rust_cuda::kernel::CompiledKernelPtx`] implementation. Its `usage()`
+/// subset can be fed into the device-independent `rust_cuda::kernel::occupancy`
+/// model.
+///
+/// A kernel instantiation can also be linked for several target
+/// architectures, in which case one PTX variant is embedded per architecture:
+/// ```rust,ignore
+/// link! { impl my_kernel for KernelPtx in ["sm_70", "sm_80", "sm_90"] }
+/// ```
+/// The variants are available from `get_ptx_variants` of the
+/// [`rust_cuda::kernel::CompiledKernelPtx`] implementation, while `get_ptx`
+/// and `RESOURCES` refer to the lowest architecture.
+/// [`rust_cuda::kernel::TypedPtxKernel`] selects the variant with the newest
+/// architecture that the current device supports, and otherwise falls back to
+/// the lowest one.
+///
 /// Inside the scope of the [`#[kernel]`](macro@kernel) attribute, a helper
 /// `#[kernel(...)]` attribute can be applied to the kernel function:
 ///
 /// - `#[kernel(crate = "<crate-path>")]` changes the path to the [`rust-cuda`]
 ///   crate that the kernel compilation uses, which by default is `rust_cuda`.
+/// - `#[kernel(register_budget = <registers>)]` sets the maximum number of
+///   registers per thread that each kernel instantiation may use, which is
+///   checked by the `ptx::register_budget` lint.
+/// - `#[kernel(target(arch = "<arch>", features = "<features>"))]` compiles the
+///   kernel for the `sm_<version>` architecture, e.g. `sm_80`, and with the
+///   comma-separated target features, e.g. `+ptx75`. Both options are optional
+///   and are appended to the `rustflags` of the kernel crate's cargo config as
+///   `-C target-cpu` and `-C target-feature`. The architecture is also passed
+///   to the PTX compiler, so that the lints and resources are checked for it.
+/// - `#[kernel(opt_level = <level>)]` compiles the kernel with the `0`, `1`,
+///   `2`, `3`, `"s"`, or `"z"` optimisation level instead of the one from the
+///   kernel crate's profile. Kernels with different targets or optimisation
+///   levels are built and cached separately.
+/// - `#[kernel(debug = "<debug>")]` compiles the kernel with `"none"`,
+///   `"lines"`, or `"full"` debug information, i.e. without debug sections,
+///   with line tables, or with full debug sections, respectively. The PTX
+///   compiler is passed the matching `--generate-line-info` or `--device-debug`
+///   option. By default, `"none"` is used.
+/// - `#[kernel(cargo_args("<arg>", ...))]` passes extra arguments, e.g.
+///   `"--offline"` or `"-Zbuild-std=core"`, to the `cargo build` that compiles
+///   the kernel.
+/// - `#[kernel(rustflags("<flag>", ...))]` appends extra flags to the
+///   `rustflags` of the kernel crate's cargo config, like `target(...)`.
 /// - `#[kernel(allow/warn/deny/forbid(<lint>))]` checks the specified
 ///   CUDA-specific lint for each kernel compilation, using default Rust
 ///   semantics for allowing, warning on, denying, or forbidding a lint. The
 ///   following lints are supported:
 ///   - `ptx::double_precision_use`: check for any uses of [`f64`] operations
-///     inside the compiled PTX binary, as they are often significantly less
-///     performant on NVIDIA GPUs than [`f32`] operations. By default,
+///     inside the PTX code, as they are often significantly less performant on
+///     NVIDIA GPUs than [`f32`] operations. By default,
 ///     `#[kernel(warn(ptx::double_precision_use))]` is set.
-///   - `ptx::local_memory_use`: check for any usage of local memory, which may
-///     slow down kernel execution. By default,
+///   - `ptx::local_memory_use`: check for any `.local` declarations or accesses
+///     to local memory, which may slow down kernel execution. By default,
 ///     `#[kernel(warn(ptx::local_memory_use))]` is set.
 ///   - `ptx::register_spills`: check for any spills of registers to local
 ///     memory. While using less registers can allow more kernels to be run in
 ///     parallel, register spills may also point to missed optimisations. By
 ///     default, `#[kernel(warn(ptx::register_spills))]` is set.
+///   - `ptx::malloc_use`: check for any calls to `malloc` or `free`, which
+///     allocate memory on the limited device heap. By default,
+///     `#[kernel(allow(ptx::malloc_use))]` is set.
+///   - `ptx::printf_use`: check for any calls to `vprintf`, which is used to
+///     print from the device. By default, `#[kernel(allow(ptx::printf_use))]`
+///     is set.
+///   - `ptx::trap_use`: check for any `trap` instructions, which abort the
+///     kernel, e.g. when it panics. By default,
+///     `#[kernel(allow(ptx::trap_use))]` is set.
+///   - `ptx::atomic_use`: check for any atomic (`atom` or `red`) operations. By
+///     default, `#[kernel(allow(ptx::atomic_use))]` is set.
+///   - `ptx::register_budget`: check if the kernel uses more registers per
+///     thread than the `#[kernel(register_budget = <registers>)]`, if set. By
+///     default, `#[kernel(deny(ptx::register_budget))]` is set iff a register
+///     budget is set.
 ///   - `ptx::dynamic_stack_size`: check if the PTX compiler is unable to
 ///     statically determine the size of the required kernel function stack.
 ///     When the static stack size is known, the compiler may be able to keep it
 ///     entirely within the fast register file. However, when the stack size is
 ///     dynamic, more costly memory load and store operations are needed. By
 ///     default, `#[kernel(warn(ptx::dynamic_stack_size))]` is set.
-///   - `ptx::verbose`: utility lint to output verbose PTX compiler messages as
-///     warnings (`warn`) or errors (`deny` or `forbid`) or to not output them
-///     (`allow`). By default, `#[kernel(allow(ptx::verbose))]` is set.
+///   - `ptx::verbose`: utility lint to output verbose PTX compiler messages and
+///     the full PTX source code as warnings (`warn`) or errors (`deny` or
+///     `forbid`) or to not output them (`allow`). By default,
+///     `#[kernel(allow(ptx::verbose))]` is set.
 ///   - `ptx::dump_assembly`: utility lint to output the compiled PTX assembly
 ///     code as a warning (`warn`) or an error (`deny` or `forbid`) or to not
 ///     output it (`allow`). By default, `#[kernel(allow(ptx::dump_assembly))]`
 ///     is set.
 ///
+/// The `double_precision_use`, `local_memory_use`, `malloc_use`,
+/// `printf_use`, `trap_use`, and `atomic_use` lints are checked by statically
+/// analysing the PTX code, and every occurrence is reported with its PTX line
+/// and the enclosing Rust function. The remaining lints are checked by the
+/// native PTX compiler, which is linked with the default `nvptxcompiler`
+/// feature. Build machines without a CUDA toolkit installation can opt out of
+/// this feature, in which case a built-in PTX checker only verifies that the
+/// kernel's PTX is well-formed and that its entry point exists with valid
+/// `.param` declarations. The kernel's PTX is still embedded, but its resource
+/// usage is unknown. Instead, `ptx::register_spills` is estimated from the
+/// registers that every function declares, which is a loose upper bound that
+/// only reports functions that may need more than the 255 registers per
+/// thread, and `ptx::dynamic_stack_size`, `ptx::dump_assembly`, and
+/// `ptx::register_budget` are not checked, which is reported as a warning if
+/// they are set to `deny` or `forbid` or if a register budget is set.
+///
+/// The kernel is compiled with `.loc` line info directives, with which every
+/// lint occurrence and every error or warning of the PTX compiler or checker
+/// is attributed to the Rust source file, line, and column that the PTX code
+/// was compiled from.
+///
 /// [`rust_cuda::kernel::TypedPtxKernel`]: https://juntyr.github.io/rust-cuda/rust_cuda/kernel/struct.TypedPtxKernel.html
 /// [`rust_cuda::kernel::CudaKernelParameter`]: https://juntyr.github.io/rust-cuda/rust_cuda/kernel/trait.CudaKernelParameter.html
 /// [`rust_cuda::kernel::CompiledKernelPtx`]: https://juntyr.github.io/rust-cuda/rust_cuda/kernel/trait.CompiledKernelPtx.html
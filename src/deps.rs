@@ -6,7 +6,7 @@ pub extern crate const_type_layout;
 #[cfg(feature = "host")]
 pub extern crate owning_ref;
 
-#[cfg(feature = "host")]
+#[cfg(all(feature = "host", not(feature = "mock")))]
 pub extern crate rustacuda;
 
 pub extern crate rustacuda_core;
@@ -0,0 +1,711 @@
+use std::{
+    alloc::Layout,
+    any::TypeId,
+    cell::Cell,
+    collections::HashMap,
+    panic::{AssertUnwindSafe, Location},
+    ptr::NonNull,
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{
+    device::thread::{Dim3, Idx3},
+    host::driver::error::CudaError,
+    kernel::LaunchConfig,
+    Error,
+};
+
+thread_local! {
+    static EMULATED_THREAD: Cell<Option<EmulatedThread>> = const { Cell::new(None) };
+}
+
+#[derive(Clone, Copy)]
+struct EmulatedThread {
+    idx: Idx3,
+    block: NonNull<EmulatedBlock>,
+}
+
+struct EmulatedBlock {
+    idx: Idx3,
+    dim: Dim3,
+    grid: Dim3,
+    barrier: EmulatedBarrier,
+    grid_barrier: Option<NonNull<EmulatedBarrier>>,
+    shared: Mutex<HashMap<SharedSite, SharedAllocation>>,
+}
+
+// Safety: the raw shared memory allocations are only handed out as raw
+//         pointers, the grid barrier outlives the block and is synchronised,
+//         and all other state is synchronised
+unsafe impl Sync for EmulatedBlock {}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum SharedSite {
+    Static(&'static Location<'static>, TypeId),
+    Param(usize),
+}
+
+struct SharedAllocation {
+    data: NonNull<u8>,
+    layout: Layout,
+}
+
+impl EmulatedBlock {
+    fn with_grid_barrier(&self, inner: impl FnOnce(&EmulatedBarrier)) {
+        if let Some(grid_barrier) = self.grid_barrier {
+            // Safety: the grid barrier outlives all of its emulated blocks
+            inner(unsafe { grid_barrier.as_ref() });
+        }
+    }
+}
+
+impl SharedAllocation {
+    fn new(layout: Layout) -> Result<Self, Error> {
+        // zero-sized shared memory still gets its own unique address
+        let layout = Layout::from_size_align(layout.size().max(1), layout.align())
+            .map_err(|_| CudaError::InvalidValue)?;
+
+        // Safety: layout has a non-zero size
+        let data = unsafe { std::alloc::alloc(layout) };
+
+        NonNull::new(data)
+            .map(|data| Self { data, layout })
+            .ok_or(Error::Driver(CudaError::LaunchOutOfResources))
+    }
+}
+
+impl Drop for SharedAllocation {
+    fn drop(&mut self) {
+        // Safety: data was allocated with layout in Self::new
+        unsafe { std::alloc::dealloc(self.data.as_ptr(), self.layout) };
+    }
+}
+
+/// Panic payload that is used to unwind an emulated thread which calls
+/// [`crate::device::utils::exit`].
+struct EmulatedThreadExit;
+
+/// Panic payload that is used to unwind an emulated thread which is waiting
+/// on a barrier that can no longer be reached since another thread in its
+/// block has failed.
+struct EmulatedBlockPoisoned;
+
+/// Thread-block barrier that, like `bar.sync`, only waits for the threads
+/// that have not yet exited and that is poisoned if any thread in the block
+/// fails.
+struct EmulatedBarrier {
+    state: Mutex<EmulatedBarrierState>,
+    condvar: Condvar,
+}
+
+struct EmulatedBarrierState {
+    active: usize,
+    waiting: usize,
+    generation: usize,
+    poisoned: bool,
+}
+
+impl EmulatedBarrier {
+    const fn new(active: usize) -> Self {
+        Self {
+            state: Mutex::new(EmulatedBarrierState {
+                active,
+                waiting: 0,
+                generation: 0,
+                poisoned: false,
+            }),
+            condvar: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<EmulatedBarrierState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[expect(clippy::significant_drop_tightening)] // the guard is moved into the condvar
+    fn wait(&self) {
+        let mut state = self.lock();
+
+        if state.poisoned {
+            std::mem::drop(state);
+            std::panic::resume_unwind(Box::new(EmulatedBlockPoisoned));
+        }
+
+        state.waiting += 1;
+
+        if state.waiting >= state.active {
+            state.waiting = 0;
+            state.generation = state.generation.wrapping_add(1);
+            std::mem::drop(state);
+
+            self.condvar.notify_all();
+            return;
+        }
+
+        let generation = state.generation;
+
+        let state = self
+            .condvar
+            .wait_while(state, |state| {
+                state.generation == generation && !state.poisoned
+            })
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if state.generation == generation {
+            std::mem::drop(state);
+            std::panic::resume_unwind(Box::new(EmulatedBlockPoisoned));
+        }
+    }
+
+    fn exit(&self) {
+        let mut state = self.lock();
+
+        state.active = state.active.saturating_sub(1);
+
+        // the exiting thread may have been the last one the waiters were
+        //  waiting for, so the barrier must be released on its behalf
+        if state.waiting > 0 && state.waiting >= state.active {
+            state.waiting = 0;
+            state.generation = state.generation.wrapping_add(1);
+            std::mem::drop(state);
+
+            self.condvar.notify_all();
+        }
+    }
+
+    fn poison(&self) {
+        let mut state = self.lock();
+
+        state.poisoned = true;
+        state.active = state.active.saturating_sub(1);
+        std::mem::drop(state);
+
+        self.condvar.notify_all();
+    }
+}
+
+/// Maximum number of threads per emulated thread block, which matches the
+/// limit of all CUDA devices.
+const MAX_EMULATED_BLOCK_THREADS: usize = 1024;
+
+/// Maximum number of threads in an emulated cooperative grid, all of which
+/// must be spawned as concurrent host threads.
+const MAX_EMULATED_COOPERATIVE_THREADS: usize = 4096;
+
+/// Executes the `thread` function on every thread of the emulated grid that
+/// is described by the `config`.
+///
+/// The thread blocks are executed one after the other. All threads inside a
+/// thread block are executed concurrently on their own host thread such that
+/// [`crate::device::thread::ThreadBlock::synchronize`] can act as a real
+/// barrier. Therefore, at most [`MAX_EMULATED_BLOCK_THREADS`] host threads are
+/// spawned at once. For a [`LaunchConfig::cooperative`] launch, all thread
+/// blocks are executed concurrently instead, such that
+/// [`crate::device::thread::ThreadBlockGrid::synchronize`] can act as a real
+/// grid-wide barrier, which limits cooperative grids to
+/// [`MAX_EMULATED_COOPERATIVE_THREADS`] threads in total.
+///
+/// # Errors
+///
+/// Returns an [`Error::InvalidLaunchDimensions`] iff the `config` describes an
+/// empty grid or block, an [`Error::LaunchLimitExceeded`] iff the block has
+/// more than [`MAX_EMULATED_BLOCK_THREADS`] threads, an
+/// [`Error::CooperativeLaunchTooLarge`] iff a cooperative grid has more than
+/// [`MAX_EMULATED_COOPERATIVE_THREADS`] threads, an [`Error::Driver`] with
+/// [`CudaError::LaunchOutOfResources`] iff the threads of a block could not be
+/// spawned or their shared memory could not be allocated, an
+/// [`Error::LayoutMismatch`] iff shared memory was requested with different
+/// layouts, and an [`Error::Driver`] with [`CudaError::LaunchFailed`] iff any
+/// thread panicked or aborted.
+pub fn launch_grid(config: &LaunchConfig, thread: &(dyn Fn() + Sync)) -> Result<(), Error> {
+    let grid = Dim3 {
+        x: config.grid.x,
+        y: config.grid.y,
+        z: config.grid.z,
+    };
+    let dim = Dim3 {
+        x: config.block.x,
+        y: config.block.y,
+        z: config.block.z,
+    };
+
+    if grid.size() == 0 || dim.size() == 0 {
+        return Err(Error::InvalidLaunchDimensions {
+            grid: config.grid.clone(),
+            block: config.block.clone(),
+        });
+    }
+
+    if dim.size() > MAX_EMULATED_BLOCK_THREADS {
+        return Err(Error::LaunchLimitExceeded {
+            field: "block size",
+            value: dim.size() as u64,
+            limit: MAX_EMULATED_BLOCK_THREADS as u64,
+        });
+    }
+
+    if config.cooperative {
+        if grid.size().saturating_mul(dim.size()) > MAX_EMULATED_COOPERATIVE_THREADS {
+            return Err(Error::CooperativeLaunchTooLarge {
+                blocks: grid.size() as u64,
+                max_co_resident_blocks: (MAX_EMULATED_COOPERATIVE_THREADS / dim.size()) as u64,
+            });
+        }
+
+        return launch_cooperative_grid(dim, grid, thread);
+    }
+
+    for z in 0..grid.z {
+        for y in 0..grid.y {
+            for x in 0..grid.x {
+                launch_block(Idx3 { x, y, z }, dim, grid, None, thread)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn launch_cooperative_grid(dim: Dim3, grid: Dim3, thread: &(dyn Fn() + Sync)) -> Result<(), Error> {
+    let grid_barrier = EmulatedBarrier::new(grid.size() * dim.size());
+    let grid_barrier_ref = &grid_barrier;
+
+    let mut result = Ok(());
+
+    std::thread::scope(|scope| {
+        let mut handles = Vec::with_capacity(grid.size());
+
+        'spawn: for z in 0..grid.z {
+            for y in 0..grid.y {
+                for x in 0..grid.x {
+                    let handle = std::thread::Builder::new()
+                        .name(format!("rust-cuda-emulated-({x},{y},{z})"))
+                        .spawn_scoped(scope, move || {
+                            launch_block(
+                                Idx3 { x, y, z },
+                                dim,
+                                grid,
+                                Some(grid_barrier_ref),
+                                thread,
+                            )
+                        });
+
+                    if let Ok(handle) = handle {
+                        handles.push(handle);
+                    } else {
+                        // the blocks that were already spawned can never
+                        //  complete a grid barrier, so they must be released
+                        grid_barrier_ref.poison();
+                        result = Err(Error::Driver(CudaError::LaunchOutOfResources));
+                        break 'spawn;
+                    }
+                }
+            }
+        }
+
+        for handle in handles {
+            let block_result = handle
+                .join()
+                .unwrap_or(Err(Error::Driver(CudaError::LaunchFailed)));
+
+            if result.is_ok() {
+                result = block_result;
+            }
+        }
+    });
+
+    result
+}
+
+fn launch_block(
+    idx: Idx3,
+    dim: Dim3,
+    grid: Dim3,
+    grid_barrier: Option<&EmulatedBarrier>,
+    thread: &(dyn Fn() + Sync),
+) -> Result<(), Error> {
+    let block = EmulatedBlock {
+        idx,
+        dim,
+        grid,
+        barrier: EmulatedBarrier::new(dim.size()),
+        grid_barrier: grid_barrier.map(NonNull::from),
+        shared: Mutex::new(HashMap::new()),
+    };
+    let block_ref = &block;
+
+    let mut result = Ok(());
+
+    std::thread::scope(|scope| {
+        let mut handles = Vec::with_capacity(dim.size());
+
+        'spawn: for z in 0..dim.z {
+            for y in 0..dim.y {
+                for x in 0..dim.x {
+                    let handle = std::thread::Builder::new()
+                        .name(format!(
+                            "rust-cuda-emulated-({},{},{})-({x},{y},{z})",
+                            idx.x, idx.y, idx.z
+                        ))
+                        .spawn_scoped(scope, move || {
+                            run_thread(Idx3 { x, y, z }, block_ref, thread)
+                        });
+
+                    if let Ok(handle) = handle {
+                        handles.push(handle);
+                    } else {
+                        // the threads that were already spawned can never
+                        //  complete a barrier, so they must be released
+                        block_ref.barrier.poison();
+                        block_ref.with_grid_barrier(EmulatedBarrier::poison);
+                        result = Err(Error::Driver(CudaError::LaunchOutOfResources));
+                        break 'spawn;
+                    }
+                }
+            }
+        }
+
+        for handle in handles {
+            let thread_result = handle
+                .join()
+                .unwrap_or(Err(Error::Driver(CudaError::LaunchFailed)));
+
+            if result.is_ok() {
+                result = thread_result;
+            }
+        }
+    });
+
+    result
+}
+
+fn run_thread(idx: Idx3, block: &EmulatedBlock, thread: &(dyn Fn() + Sync)) -> Result<(), Error> {
+    let emulated = EmulatedThread {
+        idx,
+        block: NonNull::from(block),
+    };
+
+    EMULATED_THREAD.with(|cell| cell.set(Some(emulated)));
+
+    let result = std::panic::catch_unwind(AssertUnwindSafe(thread));
+
+    EMULATED_THREAD.with(|cell| cell.set(None));
+
+    match result {
+        Ok(()) => {
+            block.barrier.exit();
+            block.with_grid_barrier(EmulatedBarrier::exit);
+            Ok(())
+        },
+        Err(payload) if payload.is::<EmulatedThreadExit>() => {
+            block.barrier.exit();
+            block.with_grid_barrier(EmulatedBarrier::exit);
+            Ok(())
+        },
+        Err(payload) => {
+            block.barrier.poison();
+            block.with_grid_barrier(EmulatedBarrier::poison);
+
+            if let Ok(shared_alloc_error) = payload.downcast::<Error>() {
+                return Err(*shared_alloc_error);
+            }
+
+            Err(Error::Driver(CudaError::LaunchFailed))
+        },
+    }
+}
+
+fn with_emulated_thread<Q>(inner: impl FnOnce(Idx3, &EmulatedBlock) -> Q) -> Q {
+    let Some(EmulatedThread { idx, block }) = EMULATED_THREAD.with(Cell::get) else {
+        #[expect(clippy::panic)]
+        {
+            panic!(
+                "[rust-cuda]: device functions can only be called from inside an emulated kernel \
+                 launch"
+            )
+        }
+    };
+
+    // Safety: the emulated block outlives all of its emulated threads
+    inner(idx, unsafe { block.as_ref() })
+}
+
+#[must_use]
+pub fn is_emulated_thread() -> bool {
+    EMULATED_THREAD.with(Cell::get).is_some()
+}
+
+#[must_use]
+pub fn thread_idx() -> Idx3 {
+    with_emulated_thread(|idx, _| idx)
+}
+
+#[must_use]
+pub fn block_idx() -> Idx3 {
+    with_emulated_thread(|_, block| block.idx)
+}
+
+#[must_use]
+pub fn block_dim() -> Dim3 {
+    with_emulated_thread(|_, block| block.dim)
+}
+
+#[must_use]
+pub fn grid_dim() -> Dim3 {
+    with_emulated_thread(|_, block| block.grid)
+}
+
+pub fn synchronize_block() {
+    with_emulated_thread(|_, block| block.barrier.wait());
+}
+
+#[must_use]
+pub fn is_cooperative_grid() -> bool {
+    with_emulated_thread(|_, block| block.grid_barrier.is_some())
+}
+
+pub fn synchronize_grid() {
+    with_emulated_thread(|_, block| {
+        assert!(
+            block.grid_barrier.is_some(),
+            "[rust-cuda]: the grid can only be synchronised in a cooperative kernel launch"
+        );
+
+        block.with_grid_barrier(EmulatedBarrier::wait);
+    });
+}
+
+pub fn exit_thread() -> ! {
+    std::panic::resume_unwind(Box::new(EmulatedThreadExit))
+}
+
+pub fn abort_thread() -> ! {
+    #[expect(clippy::panic)]
+    {
+        panic!("[rust-cuda]: emulated CUDA kernel aborted")
+    }
+}
+
+/// Returns the per-block shared memory for the static
+/// [`crate::utils::shared::ThreadBlockShared`] that is declared at the
+/// `location`.
+pub fn static_shared<T: 'static>(location: &'static Location<'static>) -> *mut T {
+    block_shared(
+        SharedSite::Static(location, TypeId::of::<T>()),
+        Layout::new::<T>(),
+    )
+    .cast()
+}
+
+/// Returns the per-block shared memory for the `PARAM`th kernel parameter.
+pub fn param_shared(param: usize, layout: Layout) -> *mut u8 {
+    block_shared(SharedSite::Param(param), layout)
+}
+
+fn block_shared(site: SharedSite, layout: Layout) -> *mut u8 {
+    with_emulated_thread(|_, block| {
+        let mut shared = block.shared.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(allocation) = shared.get(&site) {
+            // zero-sized shared memory is allocated with a size of one byte
+            if allocation.layout.align() != layout.align()
+                || allocation.layout.size() != layout.size().max(1)
+            {
+                let error = Error::LayoutMismatch {
+                    requested: layout,
+                    found: allocation.layout,
+                };
+                std::mem::drop(shared);
+                std::panic::resume_unwind(Box::new(error))
+            }
+
+            return allocation.data.as_ptr();
+        }
+
+        match SharedAllocation::new(layout) {
+            Ok(allocation) => shared.entry(site).or_insert(allocation).data.as_ptr(),
+            Err(err) => {
+                std::mem::drop(shared);
+                std::panic::resume_unwind(Box::new(err))
+            },
+        }
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic, clippy::cast_possible_truncation)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    use crate::{
+        device::thread::Thread,
+        host::driver::function::{BlockSize, GridSize},
+        kernel::LaunchConfig,
+        utils::shared::{ThreadBlockShared, ThreadBlockSharedSlice},
+        Error,
+    };
+
+    const fn config(grid: u32, block: u32, cooperative: bool) -> LaunchConfig {
+        LaunchConfig {
+            grid: GridSize {
+                x: grid,
+                y: 1,
+                z: 1,
+            },
+            block: BlockSize {
+                x: block,
+                y: 1,
+                z: 1,
+            },
+            ptx_jit: false,
+            ptx_jit_block_size: false,
+            cooperative,
+        }
+    }
+
+    #[test]
+    fn block_synchronize_orders_shared_accesses() {
+        const BLOCK: usize = 32;
+
+        let output = (0..(4 * BLOCK))
+            .map(|_| AtomicU32::new(u32::MAX))
+            .collect::<Vec<_>>();
+
+        let result = super::launch_grid(&config(4, BLOCK as u32, false), &|| {
+            let thread = Thread::this();
+            let block = thread.block();
+
+            let shared = ThreadBlockShared::<[u32; BLOCK]>::new_uninit();
+            let idx = thread.idx().x as usize;
+
+            // Safety: idx < BLOCK and every thread writes its own element
+            unsafe { *shared.index_mut_unchecked(idx) = thread.index() as u32 };
+
+            block.synchronize();
+
+            // Safety: the neighbour's element was written before the barrier
+            let neighbour = unsafe { *shared.index_mut_unchecked((idx + 1) % BLOCK) };
+
+            output[thread.index()].store(neighbour, Ordering::Relaxed);
+        });
+
+        assert_eq!(result, Ok(()));
+
+        for (i, value) in output.iter().enumerate() {
+            let block_start = i - (i % BLOCK);
+            let neighbour = block_start + ((i + 1) % BLOCK);
+
+            assert_eq!(value.load(Ordering::Relaxed), neighbour as u32);
+        }
+    }
+
+    #[test]
+    fn shared_memory_is_per_block() {
+        const BLOCK: usize = 16;
+
+        let sums = Mutex::new(Vec::new());
+
+        let result = super::launch_grid(&config(3, BLOCK as u32, false), &|| {
+            let thread = Thread::this();
+            let block = thread.block();
+            let block_idx = block.idx().x;
+
+            let counter = ThreadBlockShared::<u32>::new_uninit();
+
+            ThreadBlockSharedSlice::<u32>::with_uninit_for_param::<0, _, _>(BLOCK, |slice| {
+                let idx = thread.idx().x as usize;
+
+                // Safety: idx < BLOCK and every thread writes its own element
+                unsafe { *slice.index_mut_unchecked(idx) = block_idx + 1 };
+
+                if idx == 0 {
+                    // Safety: only the first thread writes before the barrier
+                    unsafe { *counter.as_mut_ptr() = 0 };
+                }
+
+                block.synchronize();
+
+                if idx == 0 {
+                    // Safety: all threads have written their element before the barrier
+                    let sum = unsafe { &*slice.as_mut_slice_ptr() }.iter().sum::<u32>();
+                    // Safety: only the first thread accesses the counter
+                    let counter = unsafe { &mut *counter.as_mut_ptr() };
+                    *counter += sum;
+
+                    sums.lock().unwrap().push((block_idx, *counter));
+                }
+            });
+        });
+
+        assert_eq!(result, Ok(()));
+
+        let mut sums = sums.into_inner().unwrap();
+        sums.sort_unstable();
+
+        assert_eq!(
+            sums,
+            [
+                (0, BLOCK as u32),
+                (1, 2 * BLOCK as u32),
+                (2, 3 * BLOCK as u32)
+            ]
+        );
+    }
+
+    #[test]
+    fn cooperative_grid_synchronize() {
+        let arrived = AtomicUsize::new(0);
+        let observed = Mutex::new(Vec::new());
+
+        let result = super::launch_grid(&config(4, 8, true), &|| {
+            let grid = Thread::this().block().grid();
+
+            assert!(grid.is_cooperative());
+
+            arrived.fetch_add(1, Ordering::AcqRel);
+            grid.synchronize();
+            observed
+                .lock()
+                .unwrap()
+                .push(arrived.load(Ordering::Acquire));
+        });
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(observed.into_inner().unwrap(), [32; 32]);
+    }
+
+    #[test]
+    fn panicking_thread_fails_launch_without_deadlock() {
+        let result = super::launch_grid(&config(1, 8, false), &|| {
+            let thread = Thread::this();
+
+            assert_ne!(thread.idx().x, 3, "emulated thread failure");
+
+            thread.block().synchronize();
+        });
+
+        assert!(matches!(result, Err(Error::Driver(_))));
+    }
+
+    #[test]
+    fn launch_limits() {
+        assert!(matches!(
+            super::launch_grid(&config(0, 1, false), &|| {}),
+            Err(Error::InvalidLaunchDimensions { .. })
+        ));
+        assert!(matches!(
+            super::launch_grid(&config(1, 2048, false), &|| {}),
+            Err(Error::LaunchLimitExceeded { limit: 1024, .. })
+        ));
+        assert!(matches!(
+            super::launch_grid(&config(64, 128, true), &|| {}),
+            Err(Error::CooperativeLaunchTooLarge {
+                blocks: 64,
+                max_co_resident_blocks: 32,
+            })
+        ));
+    }
+}
@@ -2,6 +2,10 @@
 #[cfg(feature = "kernel")]
 pub use rust_cuda_kernel::{specialise_kernel_function, specialise_kernel_param_type};
 
+#[cfg(feature = "device")]
 pub mod alloc;
 pub mod thread;
 pub mod utils;
+
+#[cfg(feature = "emulate")]
+pub(crate) mod emulate;
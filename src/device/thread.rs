@@ -40,11 +40,19 @@ impl Thread {
     #[expect(clippy::inline_always)]
     #[inline(always)]
     pub fn idx(&self) -> Idx3 {
+        #[cfg(feature = "device")]
         #[expect(clippy::cast_sign_loss)]
-        Idx3 {
-            x: unsafe { nvptx::_thread_idx_x() } as u32,
-            y: unsafe { nvptx::_thread_idx_y() } as u32,
-            z: unsafe { nvptx::_thread_idx_z() } as u32,
+        {
+            Idx3 {
+                x: unsafe { nvptx::_thread_idx_x() } as u32,
+                y: unsafe { nvptx::_thread_idx_y() } as u32,
+                z: unsafe { nvptx::_thread_idx_z() } as u32,
+            }
+        }
+
+        #[cfg(feature = "emulate")]
+        {
+            crate::device::emulate::thread_idx()
         }
     }
 
@@ -61,11 +69,19 @@ impl ThreadBlock {
     #[expect(clippy::inline_always)]
     #[inline(always)]
     pub fn dim(&self) -> Dim3 {
+        #[cfg(feature = "device")]
         #[expect(clippy::cast_sign_loss)]
-        Dim3 {
-            x: unsafe { nvptx::_block_dim_x() } as u32,
-            y: unsafe { nvptx::_block_dim_y() } as u32,
-            z: unsafe { nvptx::_block_dim_z() } as u32,
+        {
+            Dim3 {
+                x: unsafe { nvptx::_block_dim_x() } as u32,
+                y: unsafe { nvptx::_block_dim_y() } as u32,
+                z: unsafe { nvptx::_block_dim_z() } as u32,
+            }
+        }
+
+        #[cfg(feature = "emulate")]
+        {
+            crate::device::emulate::block_dim()
         }
     }
 
@@ -73,11 +89,19 @@ impl ThreadBlock {
     #[expect(clippy::inline_always)]
     #[inline(always)]
     pub fn idx(&self) -> Idx3 {
+        #[cfg(feature = "device")]
         #[expect(clippy::cast_sign_loss)]
-        Idx3 {
-            x: unsafe { nvptx::_block_idx_x() } as u32,
-            y: unsafe { nvptx::_block_idx_y() } as u32,
-            z: unsafe { nvptx::_block_idx_z() } as u32,
+        {
+            Idx3 {
+                x: unsafe { nvptx::_block_idx_x() } as u32,
+                y: unsafe { nvptx::_block_idx_y() } as u32,
+                z: unsafe { nvptx::_block_idx_z() } as u32,
+            }
+        }
+
+        #[cfg(feature = "emulate")]
+        {
+            crate::device::emulate::block_idx()
         }
     }
 
@@ -91,7 +115,13 @@ impl ThreadBlock {
     #[expect(clippy::inline_always)]
     #[inline(always)]
     pub fn synchronize(&self) {
-        unsafe { nvptx::_syncthreads() }
+        #[cfg(feature = "device")]
+        unsafe {
+            nvptx::_syncthreads();
+        }
+
+        #[cfg(feature = "emulate")]
+        crate::device::emulate::synchronize_block();
     }
 }
 
@@ -100,15 +130,178 @@ impl ThreadBlockGrid {
     #[expect(clippy::inline_always)]
     #[inline(always)]
     pub fn dim(&self) -> Dim3 {
+        #[cfg(feature = "device")]
         #[expect(clippy::cast_sign_loss)]
-        Dim3 {
-            x: unsafe { nvptx::_grid_dim_x() } as u32,
-            y: unsafe { nvptx::_grid_dim_y() } as u32,
-            z: unsafe { nvptx::_grid_dim_z() } as u32,
+        {
+            Dim3 {
+                x: unsafe { nvptx::_grid_dim_x() } as u32,
+                y: unsafe { nvptx::_grid_dim_y() } as u32,
+                z: unsafe { nvptx::_grid_dim_z() } as u32,
+            }
+        }
+
+        #[cfg(feature = "emulate")]
+        {
+            crate::device::emulate::grid_dim()
+        }
+    }
+
+    /// Returns `true` iff the kernel was launched
+    /// [`cooperative`](crate::kernel::LaunchConfig::cooperative)ly, such that
+    /// [`ThreadBlockGrid::synchronize`] can be used.
+    #[must_use]
+    #[expect(clippy::inline_always)]
+    #[inline(always)]
+    pub fn is_cooperative(&self) -> bool {
+        #[cfg(feature = "device")]
+        {
+            !grid_workspace().is_null()
+        }
+
+        #[cfg(feature = "emulate")]
+        {
+            crate::device::emulate::is_cooperative_grid()
+        }
+    }
+
+    /// Synchronises all threads in the grid, i.e. waits until all threads of
+    /// all thread blocks have reached this barrier.
+    ///
+    /// The grid barrier is only available if the kernel was launched
+    /// [`cooperative`](crate::kernel::LaunchConfig::cooperative)ly. Otherwise,
+    /// calling [`ThreadBlockGrid::synchronize`] aborts the kernel. Like
+    /// [`ThreadBlock::synchronize`], it must be called by all threads in the
+    /// grid.
+    #[expect(clippy::inline_always)]
+    #[inline(always)]
+    pub fn synchronize(&self) {
+        #[cfg(feature = "device")]
+        {
+            let workspace = grid_workspace();
+
+            if workspace.is_null() {
+                crate::device::utils::abort();
+            }
+
+            let thread = Thread::this();
+            let block = thread.block();
+
+            block.synchronize();
+
+            let thread_idx = thread.idx();
+            if (thread_idx.x | thread_idx.y | thread_idx.z) == 0 {
+                let block_idx = block.idx();
+
+                #[expect(clippy::cast_possible_truncation)]
+                let arrival = grid_barrier_arrival(
+                    (block_idx.x | block_idx.y | block_idx.z) == 0,
+                    self.dim().size() as u32,
+                );
+
+                // Safety: the driver provides a valid, initialised, and
+                //         suitably aligned grid workspace for cooperative
+                //         launches, whose barrier word is only ever accessed
+                //         atomically
+                let barrier = unsafe {
+                    core::sync::atomic::AtomicU32::from_ptr(core::ptr::addr_of_mut!(
+                        (*workspace).barrier
+                    ))
+                };
+
+                // Publish all prior writes of this block before arriving
+                core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+
+                let old = barrier.fetch_add(arrival, core::sync::atomic::Ordering::AcqRel);
+
+                while !grid_barrier_has_flipped(
+                    old,
+                    barrier.load(core::sync::atomic::Ordering::Acquire),
+                ) {
+                    core::hint::spin_loop();
+                }
+
+                // Observe all writes of the other blocks before leaving
+                core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+            }
+
+            block.synchronize();
         }
+
+        #[cfg(feature = "emulate")]
+        crate::device::emulate::synchronize_grid();
+    }
+}
+
+/// The grid workspace that the CUDA driver allocates for a cooperative
+/// launch.
+///
+/// The driver passes a pointer to the workspace in the `%envreg1` (low bits)
+/// and `%envreg2` (high bits) environment registers, which is null if the
+/// kernel was not launched cooperatively. The layout of the workspace is a
+/// part of the driver ABI and matches the `grid_workspace` struct in the
+/// `cooperative_groups/details/driver_abi.h` header of the CUDA toolkit,
+/// whose `grid_group::sync` implementation we mirror.
+#[cfg(any(feature = "device", test))]
+#[repr(C)]
+struct GridWorkspace {
+    size: u32,
+    barrier: u32,
+}
+
+/// Returns the pointer to the [`GridWorkspace`] that the driver provides for
+/// cooperative launches, or null if the kernel was not launched
+/// cooperatively.
+#[cfg(feature = "device")]
+#[expect(clippy::inline_always)]
+#[inline(always)]
+fn grid_workspace() -> *mut GridWorkspace {
+    let lo: u32;
+    let hi: u32;
+
+    // Safety: reading the environment registers has no side effects
+    unsafe {
+        core::arch::asm!(
+            "mov.u32 {lo}, %envreg1;",
+            "mov.u32 {hi}, %envreg2;",
+            lo = out(reg32) lo,
+            hi = out(reg32) hi,
+            options(pure, nomem, nostack),
+        );
+    }
+
+    #[expect(clippy::cast_possible_truncation)]
+    {
+        ((u64::from(hi) << 32) | u64::from(lo)) as usize as *mut GridWorkspace
     }
 }
 
+/// Returns the value that a thread block adds to the grid barrier word when
+/// it arrives at the barrier of a grid with `blocks` thread blocks.
+///
+/// The arrival of the first block is offset such that the arrivals of all
+/// blocks sum up to exactly `0x8000_0000`. Since the driver zero-initialises
+/// the barrier word, it is thus either `0` or `0x8000_0000` between barriers,
+/// and its most significant bit flips exactly when the last block arrives.
+#[cfg(any(feature = "device", test))]
+#[expect(clippy::inline_always)]
+#[inline(always)]
+const fn grid_barrier_arrival(is_first_block: bool, blocks: u32) -> u32 {
+    if is_first_block {
+        0x8000_0000_u32.wrapping_sub(blocks.wrapping_sub(1))
+    } else {
+        1
+    }
+}
+
+/// Returns `true` iff the grid barrier word has flipped since a block
+/// observed its `old` value when arriving, i.e. iff all blocks have arrived.
+#[cfg(any(feature = "device", test))]
+#[expect(clippy::inline_always)]
+#[inline(always)]
+const fn grid_barrier_has_flipped(old: u32, current: u32) -> bool {
+    ((old ^ current) & 0x8000_0000) != 0
+}
+
 /// Dimension specified in kernel launching
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Dim3 {
@@ -144,3 +337,57 @@ impl Idx3 {
             + (self.z as usize) * (dim.x as usize) * (dim.y as usize)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{grid_barrier_arrival, grid_barrier_has_flipped, GridWorkspace};
+
+    #[test]
+    fn grid_workspace_matches_driver_abi() {
+        assert_eq!(std::mem::size_of::<GridWorkspace>(), 8);
+        assert_eq!(std::mem::offset_of!(GridWorkspace, size), 0);
+        assert_eq!(std::mem::offset_of!(GridWorkspace, barrier), 4);
+    }
+
+    #[test]
+    fn grid_barrier_flips_on_last_arrival() {
+        for blocks in [1_u32, 2, 3, 80, 1024] {
+            for first_position in [0, blocks / 2, blocks - 1] {
+                // the barrier word alternates between these values
+                for initial in [0_u32, 0x8000_0000] {
+                    let mut barrier = initial;
+                    let mut observed = Vec::new();
+
+                    for position in 0..blocks {
+                        let old = barrier;
+                        barrier = barrier
+                            .wrapping_add(grid_barrier_arrival(position == first_position, blocks));
+                        observed.push(old);
+
+                        let all_arrived = position + 1 == blocks;
+                        for old in &observed {
+                            assert_eq!(grid_barrier_has_flipped(*old, barrier), all_arrived);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn grid_barrier_can_be_reused() {
+        let blocks = 4;
+        let mut barrier = 0_u32;
+
+        for _ in 0..3 {
+            let old = barrier;
+
+            for position in 0..blocks {
+                barrier = barrier.wrapping_add(grid_barrier_arrival(position == 0, blocks));
+            }
+
+            assert!(grid_barrier_has_flipped(old, barrier));
+            assert_eq!(barrier, old ^ 0x8000_0000);
+        }
+    }
+}
@@ -1,12 +1,24 @@
+#[cfg(feature = "device")]
 use crate::deps::alloc::{fmt, format};
 
 /// Abort the CUDA kernel using the `trap` system call.
 ///
 /// [`abort`] poisons the CUDA context and no more work can be performed in it.
+///
+/// In an emulated kernel launch, [`abort`] panics, which makes the launch
+/// fail.
 #[expect(clippy::inline_always)]
 #[inline(always)]
 pub fn abort() -> ! {
-    unsafe { ::core::arch::nvptx::trap() }
+    #[cfg(feature = "device")]
+    unsafe {
+        ::core::arch::nvptx::trap()
+    }
+
+    #[cfg(feature = "emulate")]
+    {
+        crate::device::emulate::abort_thread()
+    }
 }
 
 /// Exit the CUDA kernel using the `exit` instruction.
@@ -22,7 +34,15 @@ pub fn abort() -> ! {
 #[expect(clippy::inline_always)]
 #[inline(always)]
 pub unsafe fn exit() -> ! {
-    unsafe { ::core::arch::asm!("exit;", options(noreturn)) }
+    #[cfg(feature = "device")]
+    unsafe {
+        ::core::arch::asm!("exit;", options(noreturn))
+    }
+
+    #[cfg(feature = "emulate")]
+    {
+        crate::device::emulate::exit_thread()
+    }
 }
 
 /// Prints to the CUDA kernel's standard output using the `vprintf` system call.
@@ -53,6 +73,7 @@ pub macro println {
 /// The [`Arguments`](core::fmt::Arguments) instance can be created with the
 /// [`format_args!`](core::format_args) macro.
 #[inline(always)]
+#[cfg(feature = "device")]
 pub fn print(args: ::core::fmt::Arguments) {
     #[repr(C)]
     struct FormatArgs {
@@ -79,6 +100,14 @@ pub fn print(args: ::core::fmt::Arguments) {
     }
 }
 
+/// The [`print()`] function takes an [`Arguments`](core::fmt::Arguments) struct
+/// and formats and prints it to the host's standard output in an emulated
+/// kernel launch.
+#[cfg(all(feature = "emulate", not(feature = "device")))]
+pub fn print(args: ::core::fmt::Arguments) {
+    std::print!("{args}");
+}
+
 /// Helper function to efficiently pretty-print a [`core::panic::PanicInfo`]
 /// using the `vprintf` system call.
 ///
@@ -89,6 +118,7 @@ pub fn print(args: ::core::fmt::Arguments) {
 /// pull in a large amount of string formatting and dynamic allocation code.
 /// If unset, a default placeholder panic message is printed instead.
 #[inline(always)]
+#[cfg(feature = "device")]
 pub fn pretty_print_panic_info(info: &::core::panic::PanicInfo, allow_dynamic_message: bool) {
     #[repr(C)]
     struct FormatArgs {
@@ -148,6 +178,7 @@ pub fn pretty_print_panic_info(info: &::core::panic::PanicInfo, allow_dynamic_me
 /// allocation error handler) using the `vprintf` system call.
 #[track_caller]
 #[inline(always)]
+#[cfg(feature = "device")]
 pub fn pretty_print_alloc_error(layout: ::core::alloc::Layout) {
     #[repr(C)]
     struct FormatArgs {
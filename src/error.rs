@@ -0,0 +1,124 @@
+use std::{alloc::Layout, ffi::CStr};
+
+use crate::host::driver::{
+    error::CudaError,
+    function::{BlockSize, GridSize},
+};
+
+/// The error type of `rust-cuda`'s host APIs.
+///
+/// It wraps the [`CudaError`]s that are returned by the CUDA driver and adds
+/// more precise variants for the errors that `rust-cuda` detects itself.
+///
+/// Since [`Error`] implements [`From<CudaError>`], it can be used as the error
+/// type of all APIs that are generic over `E: From<CudaError>`. The lossy
+/// inverse conversion into the closest [`CudaError`] is also provided such
+/// that code which uses [`CudaError`] as its error type keeps working.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// An error that was returned by the CUDA driver.
+    #[error(transparent)]
+    Driver(#[from] CudaError),
+    /// A kernel requires more shared memory per block than is available.
+    #[error(
+        "the kernel requires {requested} bytes of shared memory per block, but only {available} \
+         bytes are available"
+    )]
+    SharedMemoryTooLarge {
+        /// Number of bytes of shared memory that were requested per block
+        requested: usize,
+        /// Number of bytes of shared memory that are available per block
+        available: usize,
+    },
+    /// A kernel was launched with an empty or too large grid or block.
+    #[error("invalid kernel launch dimensions with grid {grid:?} and block {block:?}")]
+    InvalidLaunchDimensions {
+        /// The grid of thread blocks of the launch
+        grid: GridSize,
+        /// The block of threads of the launch
+        block: BlockSize,
+    },
+    /// A field of a kernel's launch config exceeds the limit of the device.
+    #[error("the launch config's `{field}` of {value} exceeds the device limit of {limit}")]
+    LaunchLimitExceeded {
+        /// The name of the offending launch config field
+        field: &'static str,
+        /// The value of the offending launch config field
+        value: u64,
+        /// The limit of the device
+        limit: u64,
+    },
+    /// The current device does not support cooperative kernel launches.
+    #[error("the current device does not support cooperative kernel launches")]
+    CooperativeLaunchNotSupported,
+    /// The grid of a cooperative kernel launch is too large for all of its
+    /// blocks to be co-resident on the device.
+    #[error(
+        "the cooperative kernel launch with {blocks} blocks exceeds the {max_co_resident_blocks} \
+         blocks that can be co-resident on the device"
+    )]
+    CooperativeLaunchTooLarge {
+        /// Number of blocks in the grid of the launch
+        blocks: u64,
+        /// Maximum number of blocks that can be co-resident on the device
+        max_co_resident_blocks: u64,
+    },
+    /// The PTX of a kernel could not be JIT compiled, or it does not contain
+    /// the kernel's entry point.
+    #[error("failed to JIT compile the PTX of the kernel {entry_point:?}")]
+    PtxJit {
+        /// The entry point of the kernel
+        entry_point: Box<CStr>,
+        /// The error that was returned by the CUDA driver
+        #[source]
+        source: CudaError,
+    },
+    /// A [`KernelAttributes`](crate::kernel::KernelAttributes) field has an
+    /// invalid value.
+    #[error("invalid value {value} for the kernel attribute `{attribute}`")]
+    InvalidKernelAttribute {
+        /// The name of the attribute
+        attribute: &'static str,
+        /// The invalid value of the attribute
+        value: u32,
+    },
+    /// The completion signal of an asynchronous computation was lost, e.g.
+    /// because the stream callback that sends it was dropped, or the result
+    /// had already been taken.
+    #[error("the completion signal of an asynchronous computation was lost")]
+    CompletionLost,
+    /// An operation that is not permitted while a stream is being captured
+    /// into a [`Graph`](crate::host::graph::Graph) was attempted during the
+    /// capture, e.g. synchronising on the captured work, or freeing device
+    /// memory that the captured work may still refer to.
+    #[error("the operation is not permitted while the stream is being captured into a graph")]
+    StreamCaptureUnsupported,
+    /// Memory was requested with a different layout than it was allocated
+    /// with.
+    #[error("memory was requested with the layout {requested:?} but allocated with {found:?}")]
+    LayoutMismatch {
+        /// The layout that was requested
+        requested: Layout,
+        /// The layout that the memory was allocated with
+        found: Layout,
+    },
+}
+
+impl From<Error> for CudaError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Driver(error) | Error::PtxJit { source: error, .. } => error,
+            Error::SharedMemoryTooLarge { .. } | Error::CooperativeLaunchTooLarge { .. } => {
+                Self::LaunchOutOfResources
+            },
+            Error::InvalidLaunchDimensions { .. }
+            | Error::LaunchLimitExceeded { .. }
+            | Error::InvalidKernelAttribute { .. }
+            | Error::LayoutMismatch { .. } => Self::InvalidValue,
+            Error::CooperativeLaunchNotSupported => Self::NotSupported,
+            Error::StreamCaptureUnsupported => Self::NotPermitted,
+            Error::CompletionLost => Self::AlreadyAcquired,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use super::{device::Device, error::CudaResult};
+
+/// Mock of the preferred split between L1 cache and shared memory.
+///
+/// Since the mock driver does not simulate any caches, the configuration
+/// has no effect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CacheConfig {
+    PreferNone,
+    PreferShared,
+    PreferL1,
+    PreferEqual,
+}
+
+/// Mock of the shared-memory bank size configuration.
+///
+/// Since the mock driver does not simulate shared-memory banks, the
+/// configuration has no effect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SharedMemoryConfig {
+    DefaultBankSize,
+    FourByteBankSize,
+    EightByteBankSize,
+}
+
+/// Mock of the CUDA context that is current on the calling thread.
+///
+/// The mock driver always uses its single [`Device`].
+pub struct CurrentContext;
+
+impl CurrentContext {
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`CudaResult`] to
+    /// mirror the real driver API.
+    pub const fn get_device() -> CudaResult<Device> {
+        Device::get_device(0)
+    }
+}
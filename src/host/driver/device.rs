@@ -0,0 +1,103 @@
+use super::{
+    error::{CudaError, CudaResult},
+    ext::{DeviceAttributeExt, DeviceExt},
+};
+
+/// Mock of the CUDA device attributes.
+///
+/// Only the attributes which are queried by `rust-cuda` are modelled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeviceAttribute {
+    MaxThreadsPerBlock,
+    MaxBlockDimX,
+    MaxBlockDimY,
+    MaxBlockDimZ,
+    MaxGridDimX,
+    MaxGridDimY,
+    MaxGridDimZ,
+    MaxSharedMemoryPerBlock,
+    WarpSize,
+    MaxRegistersPerBlock,
+    MaxRegistersPerMultiprocessor,
+    MultiprocessorCount,
+    MaxThreadsPerMultiprocessor,
+    MaxSharedMemoryPerMultiprocessor,
+    ComputeCapabilityMajor,
+    ComputeCapabilityMinor,
+    CooperativeLaunch,
+}
+
+/// Mock of a CUDA device.
+///
+/// The mock driver provides a single [`Device`], which reports the
+/// [`DeviceAttribute`]s of a device with compute capability 7.0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Device {
+    ordinal: u32,
+}
+
+impl Device {
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`CudaResult`] to
+    /// mirror the real driver API.
+    pub const fn num_devices() -> CudaResult<u32> {
+        Ok(1)
+    }
+
+    /// # Errors
+    ///
+    /// Returns a [`CudaError::InvalidDevice`] iff the `ordinal` is not zero.
+    pub const fn get_device(ordinal: u32) -> CudaResult<Self> {
+        if ordinal == 0 {
+            Ok(Self { ordinal })
+        } else {
+            Err(CudaError::InvalidDevice)
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`CudaResult`] to
+    /// mirror the real driver API.
+    pub fn name(self) -> CudaResult<String> {
+        Ok(format!("rust-cuda mock device {}", self.ordinal))
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`CudaResult`] to
+    /// mirror the real driver API.
+    pub const fn get_attribute(self, attr: DeviceAttribute) -> CudaResult<i32> {
+        match attr {
+            DeviceAttribute::MaxThreadsPerBlock
+            | DeviceAttribute::MaxBlockDimX
+            | DeviceAttribute::MaxBlockDimY => Ok(1024),
+            DeviceAttribute::MaxBlockDimZ => Ok(64),
+            DeviceAttribute::MaxGridDimX => Ok(i32::MAX),
+            DeviceAttribute::MaxGridDimY | DeviceAttribute::MaxGridDimZ => Ok(65535),
+            DeviceAttribute::MaxSharedMemoryPerBlock => Ok(48 * 1024),
+            DeviceAttribute::WarpSize => Ok(32),
+            DeviceAttribute::MaxRegistersPerBlock
+            | DeviceAttribute::MaxRegistersPerMultiprocessor => Ok(64 * 1024),
+            DeviceAttribute::MultiprocessorCount => Ok(80),
+            DeviceAttribute::MaxThreadsPerMultiprocessor => Ok(2048),
+            DeviceAttribute::MaxSharedMemoryPerMultiprocessor => Ok(96 * 1024),
+            DeviceAttribute::ComputeCapabilityMajor => Ok(7),
+            DeviceAttribute::ComputeCapabilityMinor => Ok(0),
+            DeviceAttribute::CooperativeLaunch => Ok(1),
+        }
+    }
+}
+
+impl DeviceExt for Device {
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`CudaResult`] to
+    /// mirror the real driver API.
+    fn get_attribute_ext(self, attr: DeviceAttributeExt) -> CudaResult<i32> {
+        match attr {
+            DeviceAttributeExt::MaxSharedMemoryPerBlockOptin => Ok(96 * 1024),
+        }
+    }
+}
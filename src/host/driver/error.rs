@@ -0,0 +1,85 @@
+use std::fmt;
+
+/// Mock of the errors that can be returned by the CUDA driver API.
+///
+/// The variants and their discriminants mirror those of the real driver, but
+/// the mock driver itself only ever returns a small subset of them.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CudaError {
+    InvalidValue = 1,
+    OutOfMemory = 2,
+    NotInitialized = 3,
+    Deinitialized = 4,
+    ProfilerDisabled = 5,
+    ProfilerNotInitialized = 6,
+    ProfilerAlreadyStarted = 7,
+    ProfilerAlreadyStopped = 8,
+    NoDevice = 100,
+    InvalidDevice = 101,
+    InvalidImage = 200,
+    InvalidContext = 201,
+    ContextAlreadyCurrent = 202,
+    MapFailed = 205,
+    UnmapFailed = 206,
+    ArrayIsMapped = 207,
+    AlreadyMapped = 208,
+    NoBinaryForGpu = 209,
+    AlreadyAcquired = 210,
+    NotMapped = 211,
+    NotMappedAsArray = 212,
+    NotMappedAsPointer = 213,
+    EccUncorrectable = 214,
+    UnsupportedLimit = 215,
+    ContextAlreadyInUse = 216,
+    PeerAccessUnsupported = 217,
+    InvalidPtx = 218,
+    InvalidGraphicsContext = 219,
+    NvlinkUncorrectable = 220,
+    InvalidSouce = 300,
+    FileNotFound = 301,
+    SharedObjectSymbolNotFound = 302,
+    SharedObjectInitFailed = 303,
+    OperatingSystemError = 304,
+    InvalidHandle = 400,
+    NotFound = 500,
+    NotReady = 600,
+    IllegalAddress = 700,
+    LaunchOutOfResources = 701,
+    LaunchTimeout = 702,
+    LaunchIncompatibleTexturing = 703,
+    PeerAccessAlreadyEnabled = 704,
+    PeerAccessNotEnabled = 705,
+    PrimaryContextActive = 708,
+    ContextIsDestroyed = 709,
+    AssertError = 710,
+    TooManyPeers = 711,
+    HostMemoryAlreadyRegistered = 712,
+    HostMemoryNotRegistered = 713,
+    HardwareStackError = 714,
+    IllegalInstruction = 715,
+    MisalignedAddress = 716,
+    InvalidAddressSpace = 717,
+    InvalidProgramCounter = 718,
+    LaunchFailed = 719,
+    NotPermitted = 800,
+    NotSupported = 801,
+    UnknownError = 999,
+    InvalidMemoryAllocation = 100_100,
+}
+
+impl fmt::Display for CudaError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        // The mock driver has no access to the driver's error strings
+        write!(fmt, "mock CUDA error {self:?} ({})", *self as u32)
+    }
+}
+
+impl std::error::Error for CudaError {}
+
+/// Result type for most mock CUDA functions.
+pub type CudaResult<T> = Result<T, CudaError>;
+
+/// Result type for the `drop` functions, which return the un-dropped value
+/// together with the error.
+pub type DropResult<T> = Result<(), (CudaError, T)>;
@@ -0,0 +1,147 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
+};
+
+use super::{
+    error::{CudaError, CudaResult},
+    stream::{Stream, StreamQueue},
+};
+
+flags! {
+    /// Mock of the flags that an [`Event`] can be created with.
+    EventFlags {
+        DEFAULT = 0x0,
+        BLOCKING_SYNC = 0x1,
+        DISABLE_TIMING = 0x2,
+        INTERPROCESS = 0x4,
+    }
+}
+
+/// Mock of the completion status of an [`Event`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventStatus {
+    Ready,
+    NotReady,
+}
+
+#[derive(Default)]
+struct EventState {
+    pending_records: Cell<usize>,
+    recorded: Cell<bool>,
+    stream: RefCell<Weak<StreamQueue>>,
+}
+
+/// Mock of a CUDA event.
+///
+/// A mock [`Event`] completes once all work that was submitted to its
+/// [`Stream`] before it was recorded has completed, i.e. immediately unless
+/// the [`Stream`] is held.
+pub struct Event {
+    flags: EventFlags,
+    state: Rc<EventState>,
+}
+
+impl Event {
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`CudaResult`] to
+    /// mirror the real driver API.
+    pub fn new(flags: EventFlags) -> CudaResult<Self> {
+        Ok(Self {
+            flags,
+            state: Rc::new(EventState::default()),
+        })
+    }
+
+    /// Records the [`Event`] on the `stream`, i.e. after all work that has
+    /// already been submitted to the `stream`.
+    ///
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`CudaResult`] to
+    /// mirror the real driver API.
+    pub fn record(&self, stream: &Stream) -> CudaResult<()> {
+        self.state
+            .pending_records
+            .set(self.state.pending_records.get() + 1);
+        *self.state.stream.borrow_mut() = Rc::downgrade(stream.queue());
+
+        let state = self.state.clone();
+
+        stream.submit(move || {
+            state.pending_records.set(state.pending_records.get() - 1);
+            state.recorded.set(true);
+            Ok(())
+        })
+    }
+
+    /// Returns [`EventStatus::NotReady`] iff the [`Event`] has been recorded
+    /// on a held [`Stream`] and the work it waits on has not yet completed.
+    /// Like the real driver, an [`Event`] which has not yet been recorded is
+    /// reported as [`EventStatus::Ready`].
+    ///
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`CudaResult`] to
+    /// mirror the real driver API.
+    pub fn query(&self) -> CudaResult<EventStatus> {
+        if self.state.pending_records.get() > 0 {
+            Ok(EventStatus::NotReady)
+        } else {
+            Ok(EventStatus::Ready)
+        }
+    }
+
+    /// Blocks until the [`Event`] has completed, i.e. executes all queued
+    /// work on the [`Stream`] it was recorded on, even if that [`Stream`] is
+    /// held.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`CudaError`] that occurred in the queued work.
+    pub fn synchronize(&self) -> CudaResult<()> {
+        if self.state.pending_records.get() == 0 {
+            return Ok(());
+        }
+
+        let stream = self.state.stream.borrow().upgrade();
+
+        stream.map_or(Ok(()), |stream| stream.flush())
+    }
+
+    /// Since the mock driver does not simulate timings, the elapsed time
+    /// between two completed [`Event`]s is always zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError::InvalidHandle`] iff either [`Event`] was
+    /// created with [`EventFlags::DISABLE_TIMING`], or a
+    /// [`CudaError::NotReady`] iff either [`Event`] has not completed yet.
+    pub fn elapsed_time_f32(&self, start: &Self) -> CudaResult<f32> {
+        if self.flags.contains(EventFlags::DISABLE_TIMING)
+            || start.flags.contains(EventFlags::DISABLE_TIMING)
+        {
+            return Err(CudaError::InvalidHandle);
+        }
+
+        if [self, start]
+            .into_iter()
+            .any(|event| !event.state.recorded.get() || event.state.pending_records.get() > 0)
+        {
+            return Err(CudaError::NotReady);
+        }
+
+        Ok(0.0)
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`Result`] to mirror
+    /// the real driver API.
+    pub fn drop(event: Self) -> Result<(), (CudaError, Self)> {
+        std::mem::drop(event);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+//! CUDA graphs, which are captured from the work that is submitted to a
+//! [`Stream`] and can then be launched many times.
+
+use std::mem::ManuallyDrop;
+
+use cuda_driver_sys::{CUgraph, CUgraphExec, CUgraphExecUpdateResult, CUstreamCaptureMode};
+
+use super::raw;
+use crate::host::driver::{
+    error::{CudaError, CudaResult, DropResult},
+    stream::Stream,
+};
+
+/// The modes in which a [`Stream`] can be captured into a [`Graph`].
+///
+/// In the [`Global`](Self::Global) and [`ThreadLocal`](Self::ThreadLocal)
+/// modes, the driver refuses potentially unsafe API calls, e.g. allocating
+/// or freeing memory, while the capture is ongoing in any thread or in the
+/// capturing thread, respectively.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StreamCaptureMode {
+    Global,
+    ThreadLocal,
+    Relaxed,
+}
+
+impl From<StreamCaptureMode> for CUstreamCaptureMode {
+    fn from(mode: StreamCaptureMode) -> Self {
+        match mode {
+            StreamCaptureMode::Global => Self::CU_STREAM_CAPTURE_MODE_GLOBAL,
+            StreamCaptureMode::ThreadLocal => Self::CU_STREAM_CAPTURE_MODE_THREAD_LOCAL,
+            StreamCaptureMode::Relaxed => Self::CU_STREAM_CAPTURE_MODE_RELAXED,
+        }
+    }
+}
+
+/// A captured CUDA graph, which must be instantiated into a [`GraphExec`]
+/// before it can be launched.
+#[derive(Debug)]
+pub struct Graph {
+    inner: CUgraph,
+}
+
+impl Graph {
+    pub(super) const fn from_raw(inner: CUgraph) -> Self {
+        Self { inner }
+    }
+
+    /// Instantiates this [`Graph`] into an executable [`GraphExec`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the [`Graph`] cannot be instantiated.
+    pub fn instantiate(&self) -> CudaResult<GraphExec> {
+        let mut exec = std::ptr::null_mut();
+
+        // Safety: the graph handle is valid and no error log is requested
+        raw::check(unsafe {
+            cuda_driver_sys::cuGraphInstantiate(
+                &mut exec,
+                self.inner,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                0,
+            )
+        })?;
+
+        Ok(GraphExec { inner: exec })
+    }
+
+    /// Destroys the `graph`, returning it back together with the error if
+    /// destroying it fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the [`Graph`] cannot be destroyed.
+    pub fn drop(graph: Self) -> DropResult<Self> {
+        let graph = ManuallyDrop::new(graph);
+
+        // Safety: the graph handle is valid and not used again on success
+        match raw::check(unsafe { cuda_driver_sys::cuGraphDestroy(graph.inner) }) {
+            Ok(()) => Ok(()),
+            Err(err) => Err((err, ManuallyDrop::into_inner(graph))),
+        }
+    }
+}
+
+impl Drop for Graph {
+    fn drop(&mut self) {
+        // Safety: the graph handle is valid and never used again
+        let _ = unsafe { cuda_driver_sys::cuGraphDestroy(self.inner) };
+    }
+}
+
+/// An executable CUDA graph that has been instantiated from a [`Graph`].
+#[derive(Debug)]
+pub struct GraphExec {
+    inner: CUgraphExec,
+}
+
+impl GraphExec {
+    /// Updates the parameters of this [`GraphExec`] in place to those of the
+    /// `graph`, which must have the same topology as the [`Graph`] that this
+    /// [`GraphExec`] was instantiated from.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the update is not possible, in which case
+    /// the `graph` must be instantiated anew.
+    pub fn update(&mut self, graph: &Graph) -> CudaResult<()> {
+        let mut error_node = std::ptr::null_mut();
+        let mut update_result = CUgraphExecUpdateResult::CU_GRAPH_EXEC_UPDATE_SUCCESS;
+
+        // Safety: both handles are valid and the out-pointers are writable
+        raw::check(unsafe {
+            cuda_driver_sys::cuGraphExecUpdate(
+                self.inner,
+                graph.inner,
+                &mut error_node,
+                &mut update_result,
+            )
+        })?;
+
+        if update_result == CUgraphExecUpdateResult::CU_GRAPH_EXEC_UPDATE_SUCCESS {
+            Ok(())
+        } else {
+            Err(CudaError::InvalidValue)
+        }
+    }
+
+    /// Asynchronously launches this [`GraphExec`] on the `stream`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the launch fails.
+    pub fn launch(&self, stream: &Stream) -> CudaResult<()> {
+        // Safety: both handles are valid
+        raw::check(unsafe { cuda_driver_sys::cuGraphLaunch(self.inner, raw::stream(stream)) })
+    }
+
+    /// Destroys the `graph`, returning it back together with the error if
+    /// destroying it fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the [`GraphExec`] cannot be destroyed.
+    pub fn drop(graph: Self) -> DropResult<Self> {
+        let graph = ManuallyDrop::new(graph);
+
+        // Safety: the graph handle is valid and not used again on success
+        match raw::check(unsafe { cuda_driver_sys::cuGraphExecDestroy(graph.inner) }) {
+            Ok(()) => Ok(()),
+            Err(err) => Err((err, ManuallyDrop::into_inner(graph))),
+        }
+    }
+}
+
+impl Drop for GraphExec {
+    fn drop(&mut self) {
+        // Safety: the graph handle is valid and never used again
+        let _ = unsafe { cuda_driver_sys::cuGraphExecDestroy(self.inner) };
+    }
+}
@@ -0,0 +1,244 @@
+//! Extensions of the CUDA driver API which the pinned [`rustacuda`] fork does
+//! not provide.
+//!
+//! Without the `mock` feature, these extensions call the CUDA driver directly
+//! through the raw [`cuda_driver_sys`] bindings. The mock driver implements
+//! them in its own modules.
+
+use std::ffi::c_void;
+
+#[cfg(not(feature = "mock"))]
+use super::{device::Device, stream::Stream};
+use super::{
+    error::CudaResult,
+    function::{BlockSize, Function, GridSize},
+    graph::{Graph, StreamCaptureMode},
+};
+
+#[cfg(not(feature = "mock"))]
+pub mod graph;
+#[cfg(not(feature = "mock"))]
+mod raw;
+
+/// Device attributes which are missing from
+/// [`DeviceAttribute`](super::device::DeviceAttribute).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeviceAttributeExt {
+    /// Maximum shared memory per thread block, in bytes, that a kernel can
+    /// opt into with [`FunctionAttributeExt::MaxDynamicSharedSizeBytes`].
+    MaxSharedMemoryPerBlockOptin,
+}
+
+/// Extension of the driver [`Device`](super::device::Device).
+pub trait DeviceExt {
+    /// Returns the value of the `attr` of this device.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`](super::error::CudaError) iff the attribute
+    /// cannot be queried.
+    fn get_attribute_ext(self, attr: DeviceAttributeExt) -> CudaResult<i32>;
+}
+
+/// Function attributes which are missing from
+/// [`FunctionAttribute`](super::function::FunctionAttribute).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FunctionAttributeExt {
+    /// Maximum dynamic shared memory, in bytes, that a launch of the kernel
+    /// can request.
+    MaxDynamicSharedSizeBytes,
+    /// Preferred percentage of the unified L1 cache / shared memory that is
+    /// carved out as shared memory.
+    PreferredSharedMemoryCarveout,
+}
+
+/// Extension of the driver [`Function`].
+pub trait FunctionExt {
+    /// Returns the value of the `attr` of this function.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`](super::error::CudaError) iff the attribute
+    /// cannot be queried.
+    fn get_attribute_ext(&self, attr: FunctionAttributeExt) -> CudaResult<i32>;
+
+    /// Sets the `attr` of this function to the `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`](super::error::CudaError) iff the attribute
+    /// cannot be set, e.g. because the `value` is out of range.
+    fn set_attribute_ext(&mut self, attr: FunctionAttributeExt, value: i32) -> CudaResult<()>;
+}
+
+/// Extension of the driver [`Stream`](super::stream::Stream).
+pub trait StreamExt {
+    /// Launches the kernel `func` cooperatively on this stream, such that
+    /// all thread blocks of the grid are co-resident on the device and can
+    /// synchronise with each other.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as for
+    /// [`Stream::launch`](super::stream::Stream::launch) apply, i.e. the `args`
+    /// must match the kernel's parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`](super::error::CudaError) iff the launch fails,
+    /// e.g. because the device does not support cooperative launches or the
+    /// grid is too large to be co-resident.
+    unsafe fn launch_cooperative<G: Into<GridSize>, B: Into<BlockSize>>(
+        &self,
+        func: &Function,
+        grid_size: G,
+        block_size: B,
+        shared_mem_bytes: u32,
+        args: &[*mut c_void],
+    ) -> CudaResult<()>;
+
+    /// Begins capturing all work that is submitted to this stream into a
+    /// [`Graph`] instead of executing it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`](super::error::CudaError) iff the capture
+    /// cannot be started, e.g. because the stream is already being captured.
+    fn begin_capture(&self, mode: StreamCaptureMode) -> CudaResult<()>;
+
+    /// Ends the capture of this stream and returns the captured [`Graph`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`](super::error::CudaError) iff the stream is not
+    /// being captured, or the capture was invalidated, e.g. by a prohibited
+    /// API call during the capture.
+    fn end_capture(&self) -> CudaResult<Graph>;
+
+    /// Returns `true` iff this stream is currently being captured.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`](super::error::CudaError) iff the capture
+    /// status cannot be queried.
+    fn is_capturing(&self) -> CudaResult<bool>;
+}
+
+#[cfg(not(feature = "mock"))]
+impl From<DeviceAttributeExt> for cuda_driver_sys::CUdevice_attribute {
+    fn from(attr: DeviceAttributeExt) -> Self {
+        match attr {
+            DeviceAttributeExt::MaxSharedMemoryPerBlockOptin => {
+                Self::CU_DEVICE_ATTRIBUTE_MAX_SHARED_MEMORY_PER_BLOCK_OPTIN
+            },
+        }
+    }
+}
+
+#[cfg(not(feature = "mock"))]
+impl DeviceExt for Device {
+    fn get_attribute_ext(self, attr: DeviceAttributeExt) -> CudaResult<i32> {
+        let mut value = 0;
+
+        // Safety: the device handle is valid and the out-pointer is writable
+        raw::check(unsafe {
+            cuda_driver_sys::cuDeviceGetAttribute(&mut value, attr.into(), raw::device(self))
+        })?;
+
+        Ok(value)
+    }
+}
+
+#[cfg(not(feature = "mock"))]
+impl From<FunctionAttributeExt> for cuda_driver_sys::CUfunction_attribute {
+    fn from(attr: FunctionAttributeExt) -> Self {
+        match attr {
+            FunctionAttributeExt::MaxDynamicSharedSizeBytes => {
+                Self::CU_FUNC_ATTRIBUTE_MAX_DYNAMIC_SHARED_SIZE_BYTES
+            },
+            FunctionAttributeExt::PreferredSharedMemoryCarveout => {
+                Self::CU_FUNC_ATTRIBUTE_PREFERRED_SHARED_MEMORY_CARVEOUT
+            },
+        }
+    }
+}
+
+#[cfg(not(feature = "mock"))]
+impl FunctionExt for Function<'_> {
+    fn get_attribute_ext(&self, attr: FunctionAttributeExt) -> CudaResult<i32> {
+        let mut value = 0;
+
+        // Safety: the function handle is valid and the out-pointer is writable
+        raw::check(unsafe {
+            cuda_driver_sys::cuFuncGetAttribute(&mut value, attr.into(), raw::function(self))
+        })?;
+
+        Ok(value)
+    }
+
+    fn set_attribute_ext(&mut self, attr: FunctionAttributeExt, value: i32) -> CudaResult<()> {
+        // Safety: the function handle is valid
+        raw::check(unsafe {
+            cuda_driver_sys::cuFuncSetAttribute(raw::function(self), attr.into(), value)
+        })
+    }
+}
+
+#[cfg(not(feature = "mock"))]
+impl StreamExt for Stream {
+    unsafe fn launch_cooperative<G: Into<GridSize>, B: Into<BlockSize>>(
+        &self,
+        func: &Function,
+        grid_size: G,
+        block_size: B,
+        shared_mem_bytes: u32,
+        args: &[*mut c_void],
+    ) -> CudaResult<()> {
+        let grid_size: GridSize = grid_size.into();
+        let block_size: BlockSize = block_size.into();
+
+        // Safety: the handles are valid for the duration of the call and the
+        //         caller guarantees that the args match the kernel
+        raw::check(unsafe {
+            cuda_driver_sys::cuLaunchCooperativeKernel(
+                raw::function(func),
+                grid_size.x,
+                grid_size.y,
+                grid_size.z,
+                block_size.x,
+                block_size.y,
+                block_size.z,
+                shared_mem_bytes,
+                raw::stream(self),
+                args.as_ptr().cast_mut(),
+            )
+        })
+    }
+
+    fn begin_capture(&self, mode: StreamCaptureMode) -> CudaResult<()> {
+        // Safety: the stream handle is valid
+        raw::check(unsafe {
+            cuda_driver_sys::cuStreamBeginCapture_v2(raw::stream(self), mode.into())
+        })
+    }
+
+    fn end_capture(&self) -> CudaResult<Graph> {
+        let mut graph = std::ptr::null_mut();
+
+        // Safety: the stream handle is valid and the out-pointer is writable
+        raw::check(unsafe { cuda_driver_sys::cuStreamEndCapture(raw::stream(self), &mut graph) })?;
+
+        Ok(Graph::from_raw(graph))
+    }
+
+    fn is_capturing(&self) -> CudaResult<bool> {
+        let mut status = cuda_driver_sys::CUstreamCaptureStatus::CU_STREAM_CAPTURE_STATUS_NONE;
+
+        // Safety: the stream handle is valid and the out-pointer is writable
+        raw::check(unsafe {
+            cuda_driver_sys::cuStreamIsCapturing(raw::stream(self), &mut status)
+        })?;
+
+        Ok(status != cuda_driver_sys::CUstreamCaptureStatus::CU_STREAM_CAPTURE_STATUS_NONE)
+    }
+}
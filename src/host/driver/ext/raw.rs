@@ -0,0 +1,115 @@
+use cuda_driver_sys::{CUdevice, CUfunction, CUresult, CUstream};
+
+use crate::host::driver::{
+    device::Device,
+    error::{CudaError, CudaResult},
+    function::Function,
+    stream::Stream,
+};
+
+// The pinned rustacuda fork only exposes the raw driver handles inside its
+//  own crate. Its `Device`, `Stream` and `Function` are plain wrappers around
+//  a single raw handle (the `Function` also has a zero-sized lifetime
+//  marker), which is what the following assertions check as far as possible.
+const _: () = assert!(std::mem::size_of::<Device>() == std::mem::size_of::<CUdevice>());
+const _: () = assert!(std::mem::align_of::<Device>() == std::mem::align_of::<CUdevice>());
+const _: () = assert!(std::mem::size_of::<Stream>() == std::mem::size_of::<CUstream>());
+const _: () = assert!(std::mem::align_of::<Stream>() == std::mem::align_of::<CUstream>());
+const _: () = assert!(std::mem::size_of::<Function>() == std::mem::size_of::<CUfunction>());
+const _: () = assert!(std::mem::align_of::<Function>() == std::mem::align_of::<CUfunction>());
+
+/// Returns the raw driver handle of the `device`.
+pub(super) fn device(device: Device) -> CUdevice {
+    // Safety: a `Device` only consists of its `CUdevice` ordinal
+    unsafe { std::mem::transmute_copy::<Device, CUdevice>(&device) }
+}
+
+/// Returns the raw driver handle of the `stream`.
+pub(super) fn stream(stream: &Stream) -> CUstream {
+    // Safety: a `Stream` only consists of its `CUstream` handle, which is
+    //         copied out without taking ownership of it
+    unsafe { std::mem::transmute_copy::<Stream, CUstream>(stream) }
+}
+
+/// Returns the raw driver handle of the `function`.
+pub(super) fn function(function: &Function) -> CUfunction {
+    // Safety: a `Function` only consists of its `CUfunction` handle and a
+    //         zero-sized marker, the handle is copied out without taking
+    //         ownership of it
+    unsafe { std::mem::transmute_copy::<Function, CUfunction>(function) }
+}
+
+/// Converts the raw `result` of a driver call into a [`CudaResult`].
+///
+/// Error codes which are not known to [`CudaError`] are reported as
+/// [`CudaError::UnknownError`], like `rustacuda` does.
+pub(super) fn check(result: CUresult) -> CudaResult<()> {
+    const ERRORS: [CudaError; 57] = [
+        CudaError::InvalidValue,
+        CudaError::OutOfMemory,
+        CudaError::NotInitialized,
+        CudaError::Deinitialized,
+        CudaError::ProfilerDisabled,
+        CudaError::ProfilerNotInitialized,
+        CudaError::ProfilerAlreadyStarted,
+        CudaError::ProfilerAlreadyStopped,
+        CudaError::NoDevice,
+        CudaError::InvalidDevice,
+        CudaError::InvalidImage,
+        CudaError::InvalidContext,
+        CudaError::ContextAlreadyCurrent,
+        CudaError::MapFailed,
+        CudaError::UnmapFailed,
+        CudaError::ArrayIsMapped,
+        CudaError::AlreadyMapped,
+        CudaError::NoBinaryForGpu,
+        CudaError::AlreadyAcquired,
+        CudaError::NotMapped,
+        CudaError::NotMappedAsArray,
+        CudaError::NotMappedAsPointer,
+        CudaError::EccUncorrectable,
+        CudaError::UnsupportedLimit,
+        CudaError::ContextAlreadyInUse,
+        CudaError::PeerAccessUnsupported,
+        CudaError::InvalidPtx,
+        CudaError::InvalidGraphicsContext,
+        CudaError::NvlinkUncorrectable,
+        CudaError::InvalidSouce,
+        CudaError::FileNotFound,
+        CudaError::SharedObjectSymbolNotFound,
+        CudaError::SharedObjectInitFailed,
+        CudaError::OperatingSystemError,
+        CudaError::InvalidHandle,
+        CudaError::NotFound,
+        CudaError::NotReady,
+        CudaError::IllegalAddress,
+        CudaError::LaunchOutOfResources,
+        CudaError::LaunchTimeout,
+        CudaError::LaunchIncompatibleTexturing,
+        CudaError::PeerAccessAlreadyEnabled,
+        CudaError::PeerAccessNotEnabled,
+        CudaError::PrimaryContextActive,
+        CudaError::ContextIsDestroyed,
+        CudaError::AssertError,
+        CudaError::TooManyPeers,
+        CudaError::HostMemoryAlreadyRegistered,
+        CudaError::HostMemoryNotRegistered,
+        CudaError::HardwareStackError,
+        CudaError::IllegalInstruction,
+        CudaError::MisalignedAddress,
+        CudaError::InvalidAddressSpace,
+        CudaError::InvalidProgramCounter,
+        CudaError::LaunchFailed,
+        CudaError::NotPermitted,
+        CudaError::NotSupported,
+    ];
+
+    if result == CUresult::CUDA_SUCCESS {
+        return Ok(());
+    }
+
+    Err(ERRORS
+        .into_iter()
+        .find(|err| (*err as u32) == (result as u32))
+        .unwrap_or(CudaError::UnknownError))
+}
@@ -0,0 +1,138 @@
+use std::marker::PhantomData;
+
+use super::{
+    context::{CacheConfig, SharedMemoryConfig},
+    error::{CudaError, CudaResult},
+    ext::{FunctionAttributeExt, FunctionExt},
+    module::Module,
+};
+
+macro_rules! dim3 {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name {
+            pub x: u32,
+            pub y: u32,
+            pub z: u32,
+        }
+
+        impl $name {
+            #[must_use]
+            pub const fn x(x: u32) -> Self {
+                Self { x, y: 1, z: 1 }
+            }
+
+            #[must_use]
+            pub const fn xy(x: u32, y: u32) -> Self {
+                Self { x, y, z: 1 }
+            }
+
+            #[must_use]
+            pub const fn xyz(x: u32, y: u32, z: u32) -> Self {
+                Self { x, y, z }
+            }
+        }
+
+        impl From<u32> for $name {
+            fn from(x: u32) -> Self {
+                Self::x(x)
+            }
+        }
+
+        impl From<(u32, u32)> for $name {
+            fn from((x, y): (u32, u32)) -> Self {
+                Self::xy(x, y)
+            }
+        }
+
+        impl From<(u32, u32, u32)> for $name {
+            fn from((x, y, z): (u32, u32, u32)) -> Self {
+                Self::xyz(x, y, z)
+            }
+        }
+
+        impl From<&Self> for $name {
+            fn from(other: &Self) -> Self {
+                other.clone()
+            }
+        }
+    };
+}
+
+dim3! {
+    /// Mock of the dimensions of a grid, i.e. the number of thread blocks in
+    /// a kernel launch.
+    GridSize
+}
+
+dim3! {
+    /// Mock of the dimensions of a thread block, i.e. the number of threads
+    /// in a block.
+    BlockSize
+}
+
+/// Mock of the CUDA function attributes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FunctionAttribute {
+    MaxThreadsPerBlock,
+    SharedMemorySizeBytes,
+    ConstSizeBytes,
+    LocalSizeBytes,
+    NumRegisters,
+    PtxVersion,
+    BinaryVersion,
+    CacheModeCa,
+}
+
+/// Mock of a CUDA kernel function inside a [`Module`].
+///
+/// Since no [`Module`] can be created by the mock driver, no [`Function`]
+/// can ever be created either.
+pub struct Function<'a> {
+    _module: PhantomData<&'a Module>,
+}
+
+impl Function<'_> {
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since no [`Function`]
+    /// can be created.
+    pub const fn get_attribute(&self, _attr: FunctionAttribute) -> CudaResult<i32> {
+        Err(CudaError::NotSupported)
+    }
+
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since no [`Function`]
+    /// can be created.
+    pub fn set_cache_config(&mut self, _config: CacheConfig) -> CudaResult<()> {
+        Err(CudaError::NotSupported)
+    }
+
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since no [`Function`]
+    /// can be created.
+    pub fn set_shared_memory_config(&mut self, _config: SharedMemoryConfig) -> CudaResult<()> {
+        Err(CudaError::NotSupported)
+    }
+}
+
+impl FunctionExt for Function<'_> {
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since no [`Function`]
+    /// can be created.
+    fn get_attribute_ext(&self, _attr: FunctionAttributeExt) -> CudaResult<i32> {
+        Err(CudaError::NotSupported)
+    }
+
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since no [`Function`]
+    /// can be created.
+    fn set_attribute_ext(&mut self, _attr: FunctionAttributeExt, _value: i32) -> CudaResult<()> {
+        Err(CudaError::NotSupported)
+    }
+}
@@ -0,0 +1,88 @@
+use super::{
+    error::{CudaError, CudaResult},
+    stream::Stream,
+};
+
+/// Mock of the modes in which a [`Stream`] can be captured into a [`Graph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StreamCaptureMode {
+    Global,
+    ThreadLocal,
+    Relaxed,
+}
+
+/// Mock of a captured CUDA graph.
+///
+/// Since the mock driver cannot capture [`Stream`]s, no [`Graph`] can ever
+/// be created.
+pub struct Graph {
+    _private: (),
+}
+
+impl Graph {
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since no [`Graph`] can
+    /// be created.
+    pub const fn instantiate(&self) -> CudaResult<GraphExec> {
+        Err(CudaError::NotSupported)
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`Result`] to mirror
+    /// the real driver API.
+    pub fn drop(graph: Self) -> Result<(), (CudaError, Self)> {
+        std::mem::drop(graph);
+
+        Ok(())
+    }
+}
+
+/// Mock of an executable CUDA graph.
+///
+/// Since no [`Graph`] can be created by the mock driver, no [`GraphExec`]
+/// can ever be created either.
+pub struct GraphExec {
+    _private: (),
+}
+
+impl Drop for Graph {
+    fn drop(&mut self) {
+        // Mirror the real driver, which destroys the graph on drop
+    }
+}
+
+impl GraphExec {
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since no [`GraphExec`]
+    /// can be created.
+    pub fn update(&mut self, _graph: &Graph) -> CudaResult<()> {
+        Err(CudaError::NotSupported)
+    }
+
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since no [`GraphExec`]
+    /// can be created.
+    pub const fn launch(&self, _stream: &Stream) -> CudaResult<()> {
+        Err(CudaError::NotSupported)
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`Result`] to mirror
+    /// the real driver API.
+    pub fn drop(graph: Self) -> Result<(), (CudaError, Self)> {
+        std::mem::drop(graph);
+
+        Ok(())
+    }
+}
+
+impl Drop for GraphExec {
+    fn drop(&mut self) {
+        // Mirror the real driver, which destroys the executable graph on drop
+    }
+}
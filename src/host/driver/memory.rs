@@ -0,0 +1,696 @@
+use std::{
+    alloc::Layout,
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+};
+
+use super::error::{CudaError, CudaResult};
+
+pub use rustacuda_core::{DeviceCopy, DevicePointer};
+
+use super::stream::Stream;
+
+/// Allocates the host memory that simulates `len` elements of device or
+/// page-locked memory.
+fn allocate<T>(len: usize) -> CudaResult<NonNull<T>> {
+    let layout = Layout::array::<T>(len).map_err(|_| CudaError::InvalidValue)?;
+
+    if layout.size() == 0 {
+        return Ok(NonNull::dangling());
+    }
+
+    // Safety: layout has a non-zero size
+    let data = unsafe { std::alloc::alloc(layout) };
+
+    NonNull::new(data.cast()).ok_or(CudaError::OutOfMemory)
+}
+
+/// # Safety
+///
+/// `data` must have been allocated by [`allocate`] with the same `len`.
+unsafe fn deallocate<T>(data: NonNull<T>, len: usize) {
+    let Ok(layout) = Layout::array::<T>(len) else {
+        return;
+    };
+
+    if layout.size() > 0 {
+        // Safety: data was allocated with layout in allocate
+        unsafe { std::alloc::dealloc(data.as_ptr().cast(), layout) };
+    }
+}
+
+/// Copies `len` elements from `source` to `dest` in submission order on the
+/// `stream`, or immediately if there is no `stream`.
+///
+/// # Safety
+///
+/// `source` must be valid for `len` reads, `dest` must be valid for `len`
+/// writes, and the two must not overlap, until the copy has executed on the
+/// `stream`.
+unsafe fn copy<T>(
+    source: *const T,
+    dest: *mut T,
+    len: usize,
+    stream: Option<&Stream>,
+) -> CudaResult<()> {
+    // The copy is type-erased to bytes such that it can be queued on a held
+    //  stream independent of the lifetimes inside `T`
+    let source = source.cast::<u8>();
+    let dest = dest.cast::<u8>();
+    let size = std::mem::size_of::<T>()
+        .checked_mul(len)
+        .ok_or(CudaError::InvalidValue)?;
+
+    // Safety: ensured by the caller
+    let copy = move || {
+        unsafe { std::ptr::copy_nonoverlapping(source, dest, size) };
+        Ok(())
+    };
+
+    stream.map_or_else(copy, |stream| stream.submit(copy))
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Mock of synchronous copies between host and device memory.
+pub trait CopyDestination<O: ?Sized>: private::Sealed {
+    /// # Errors
+    ///
+    /// Returns a [`CudaError::InvalidValue`] iff the `source` and destination
+    /// have different lengths.
+    fn copy_from(&mut self, source: &O) -> CudaResult<()>;
+
+    /// # Errors
+    ///
+    /// Returns a [`CudaError::InvalidValue`] iff the source and `dest` have
+    /// different lengths.
+    fn copy_to(&self, dest: &mut O) -> CudaResult<()>;
+}
+
+/// Mock of asynchronous copies between host and device memory.
+pub trait AsyncCopyDestination<O: ?Sized>: private::Sealed {
+    /// # Safety
+    ///
+    /// The `source` must not be modified until the copy on the `stream` has
+    /// completed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError::InvalidValue`] iff the `source` and destination
+    /// have different lengths.
+    unsafe fn async_copy_from(&mut self, source: &O, stream: &Stream) -> CudaResult<()>;
+
+    /// # Safety
+    ///
+    /// The `dest` must not be accessed until the copy on the `stream` has
+    /// completed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError::InvalidValue`] iff the source and `dest` have
+    /// different lengths.
+    unsafe fn async_copy_to(&self, dest: &mut O, stream: &Stream) -> CudaResult<()>;
+}
+
+/// Mock of an owned single value in device memory.
+pub struct DeviceBox<T> {
+    ptr: DevicePointer<T>,
+}
+
+impl<T: DeviceCopy> DeviceBox<T> {
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the device memory cannot be allocated.
+    pub fn new(value: &T) -> CudaResult<Self> {
+        // Safety: the uninitialised memory is immediately initialised
+        let mut device_box = unsafe { Self::uninitialized() }?;
+        device_box.copy_from(value)?;
+        Ok(device_box)
+    }
+}
+
+impl<T> DeviceBox<T> {
+    /// # Safety
+    ///
+    /// The device memory is uninitialised.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the device memory cannot be allocated.
+    pub unsafe fn uninitialized() -> CudaResult<Self> {
+        let data = allocate::<T>(1)?;
+
+        Ok(Self {
+            // Safety: data points to simulated device memory
+            ptr: unsafe { DevicePointer::wrap(data.as_ptr()) },
+        })
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`DeviceBox::into_raw`].
+    pub unsafe fn from_raw(ptr: *mut T) -> Self {
+        Self {
+            // Safety: ensured by the caller
+            ptr: unsafe { DevicePointer::wrap(ptr) },
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`DeviceBox::into_device`].
+    #[must_use]
+    pub const unsafe fn from_device(ptr: DevicePointer<T>) -> Self {
+        Self { ptr }
+    }
+
+    #[must_use]
+    pub fn into_raw(device_box: Self) -> *mut T {
+        let mut ptr = Self::into_device(device_box);
+        ptr.as_raw_mut()
+    }
+
+    #[must_use]
+    pub fn into_device(device_box: Self) -> DevicePointer<T> {
+        let device_box = std::mem::ManuallyDrop::new(device_box);
+        device_box.ptr
+    }
+
+    #[must_use]
+    pub fn as_device_ptr(&mut self) -> DevicePointer<T> {
+        self.ptr
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`Result`] to mirror
+    /// the real driver API.
+    pub fn drop(device_box: Self) -> Result<(), (CudaError, Self)> {
+        std::mem::drop(device_box);
+
+        Ok(())
+    }
+}
+
+impl<T> Drop for DeviceBox<T> {
+    fn drop(&mut self) {
+        if let Some(data) = NonNull::new(self.ptr.as_raw_mut()) {
+            // Safety: self.ptr was allocated in DeviceBox::uninitialized
+            unsafe { deallocate(data, 1) };
+        }
+    }
+}
+
+impl<T> private::Sealed for DeviceBox<T> {}
+
+impl<T: DeviceCopy> CopyDestination<T> for DeviceBox<T> {
+    fn copy_from(&mut self, source: &T) -> CudaResult<()> {
+        // Safety: both point to one valid and non-overlapping T
+        unsafe { copy(source, self.ptr.as_raw_mut(), 1, None) }
+    }
+
+    fn copy_to(&self, dest: &mut T) -> CudaResult<()> {
+        // Safety: both point to one valid and non-overlapping T
+        unsafe { copy(self.ptr.as_raw(), dest, 1, None) }
+    }
+}
+
+impl<T: DeviceCopy> CopyDestination<Self> for DeviceBox<T> {
+    fn copy_from(&mut self, source: &Self) -> CudaResult<()> {
+        // Safety: both point to one valid and non-overlapping T
+        unsafe { copy(source.ptr.as_raw(), self.ptr.as_raw_mut(), 1, None) }
+    }
+
+    fn copy_to(&self, dest: &mut Self) -> CudaResult<()> {
+        // Safety: both point to one valid and non-overlapping T
+        unsafe { copy(self.ptr.as_raw(), dest.ptr.as_raw_mut(), 1, None) }
+    }
+}
+
+impl<T: DeviceCopy> AsyncCopyDestination<LockedBox<T>> for DeviceBox<T> {
+    unsafe fn async_copy_from(&mut self, source: &LockedBox<T>, stream: &Stream) -> CudaResult<()> {
+        // Safety: both point to one valid and non-overlapping T
+        unsafe { copy(source.as_ptr(), self.ptr.as_raw_mut(), 1, Some(stream)) }
+    }
+
+    unsafe fn async_copy_to(&self, dest: &mut LockedBox<T>, stream: &Stream) -> CudaResult<()> {
+        // Safety: both point to one valid and non-overlapping T
+        unsafe { copy(self.ptr.as_raw(), dest.as_mut_ptr(), 1, Some(stream)) }
+    }
+}
+
+impl<T: DeviceCopy> AsyncCopyDestination<Self> for DeviceBox<T> {
+    unsafe fn async_copy_from(&mut self, source: &Self, stream: &Stream) -> CudaResult<()> {
+        // Safety: both point to one valid and non-overlapping T
+        unsafe { copy(source.ptr.as_raw(), self.ptr.as_raw_mut(), 1, Some(stream)) }
+    }
+
+    unsafe fn async_copy_to(&self, dest: &mut Self, stream: &Stream) -> CudaResult<()> {
+        // Safety: both point to one valid and non-overlapping T
+        unsafe { copy(self.ptr.as_raw(), dest.ptr.as_raw_mut(), 1, Some(stream)) }
+    }
+}
+
+/// Mock of a borrowed slice of device memory.
+///
+/// Like a real device slice, the elements of a [`DeviceSlice`] cannot be
+/// accessed directly from the host.
+#[repr(transparent)]
+pub struct DeviceSlice<T>([T]);
+
+impl<T> DeviceSlice<T> {
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[must_use]
+    pub const fn as_ptr(&self) -> *const T {
+        self.0.as_ptr()
+    }
+
+    #[must_use]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.0.as_mut_ptr()
+    }
+
+    #[must_use]
+    pub fn as_device_ptr(&mut self) -> DevicePointer<T> {
+        // Safety: the slice points to simulated device memory
+        unsafe { DevicePointer::wrap(self.0.as_mut_ptr()) }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    #[must_use]
+    pub const fn split_at(&self, mid: usize) -> (&Self, &Self) {
+        let (left, right) = self.0.split_at(mid);
+
+        (Self::from_slice(left), Self::from_slice(right))
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    #[must_use]
+    pub fn split_at_mut(&mut self, mid: usize) -> (&mut Self, &mut Self) {
+        let (left, right) = self.0.split_at_mut(mid);
+
+        (Self::from_mut_slice(left), Self::from_mut_slice(right))
+    }
+
+    /// # Safety
+    ///
+    /// `data` must point to `len` elements of device memory that outlive `'a`.
+    #[must_use]
+    pub unsafe fn from_raw_parts<'a>(data: DevicePointer<T>, len: usize) -> &'a Self {
+        // Safety: ensured by the caller
+        Self::from_slice(unsafe { std::slice::from_raw_parts(data.as_raw(), len) })
+    }
+
+    /// # Safety
+    ///
+    /// `data` must point to `len` elements of device memory that outlive `'a`
+    /// and are not aliased.
+    #[must_use]
+    pub unsafe fn from_raw_parts_mut<'a>(mut data: DevicePointer<T>, len: usize) -> &'a mut Self {
+        // Safety: ensured by the caller
+        Self::from_mut_slice(unsafe { std::slice::from_raw_parts_mut(data.as_raw_mut(), len) })
+    }
+
+    const fn from_slice(slice: &[T]) -> &Self {
+        // Safety: DeviceSlice is a transparent wrapper around [T]
+        unsafe { &*(std::ptr::from_ref(slice) as *const Self) }
+    }
+
+    fn from_mut_slice(slice: &mut [T]) -> &mut Self {
+        // Safety: DeviceSlice is a transparent wrapper around [T]
+        unsafe { &mut *(std::ptr::from_mut(slice) as *mut Self) }
+    }
+}
+
+impl<T> private::Sealed for DeviceSlice<T> {}
+
+impl<T: DeviceCopy, I: AsRef<[T]> + AsMut<[T]> + ?Sized> CopyDestination<I> for DeviceSlice<T> {
+    fn copy_from(&mut self, source: &I) -> CudaResult<()> {
+        let source = source.as_ref();
+
+        if self.len() != source.len() {
+            return Err(CudaError::InvalidValue);
+        }
+
+        // Safety: both point to len valid and non-overlapping Ts
+        unsafe { copy(source.as_ptr(), self.as_mut_ptr(), self.len(), None) }
+    }
+
+    fn copy_to(&self, dest: &mut I) -> CudaResult<()> {
+        let dest = dest.as_mut();
+
+        if self.len() != dest.len() {
+            return Err(CudaError::InvalidValue);
+        }
+
+        // Safety: both point to len valid and non-overlapping Ts
+        unsafe { copy(self.as_ptr(), dest.as_mut_ptr(), self.len(), None) }
+    }
+}
+
+impl<T: DeviceCopy, I: AsRef<[T]> + AsMut<[T]> + ?Sized> AsyncCopyDestination<I>
+    for DeviceSlice<T>
+{
+    unsafe fn async_copy_from(&mut self, source: &I, stream: &Stream) -> CudaResult<()> {
+        let source = source.as_ref();
+
+        if self.len() != source.len() {
+            return Err(CudaError::InvalidValue);
+        }
+
+        // Safety: both point to len valid and non-overlapping Ts
+        unsafe { copy(source.as_ptr(), self.as_mut_ptr(), self.len(), Some(stream)) }
+    }
+
+    unsafe fn async_copy_to(&self, dest: &mut I, stream: &Stream) -> CudaResult<()> {
+        let dest = dest.as_mut();
+
+        if self.len() != dest.len() {
+            return Err(CudaError::InvalidValue);
+        }
+
+        // Safety: both point to len valid and non-overlapping Ts
+        unsafe { copy(self.as_ptr(), dest.as_mut_ptr(), self.len(), Some(stream)) }
+    }
+}
+
+/// Mock of an owned buffer of device memory.
+pub struct DeviceBuffer<T: DeviceCopy> {
+    buf: DevicePointer<T>,
+    capacity: usize,
+}
+
+impl<T: DeviceCopy> DeviceBuffer<T> {
+    /// # Safety
+    ///
+    /// The device memory is uninitialised.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the device memory cannot be allocated.
+    pub unsafe fn uninitialized(size: usize) -> CudaResult<Self> {
+        let data = allocate::<T>(size)?;
+
+        Ok(Self {
+            // Safety: data points to simulated device memory
+            buf: unsafe { DevicePointer::wrap(data.as_ptr()) },
+            capacity: size,
+        })
+    }
+
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the device memory cannot be allocated.
+    pub fn from_slice(slice: &[T]) -> CudaResult<Self> {
+        // Safety: the uninitialised memory is immediately initialised
+        let mut buffer = unsafe { Self::uninitialized(slice.len()) }?;
+        buffer.copy_from(slice)?;
+        Ok(buffer)
+    }
+
+    /// # Safety
+    ///
+    /// The `slice` must not be modified until the copy on the `stream` has
+    /// completed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the device memory cannot be allocated.
+    pub unsafe fn from_slice_async(slice: &[T], stream: &Stream) -> CudaResult<Self> {
+        // Safety: the uninitialised memory is immediately initialised
+        let mut buffer = unsafe { Self::uninitialized(slice.len()) }?;
+        // Safety: ensured by the caller
+        unsafe { buffer.async_copy_from(slice, stream) }?;
+        Ok(buffer)
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated as a [`DeviceBuffer`] with the same
+    /// `capacity`.
+    #[must_use]
+    pub const unsafe fn from_raw_parts(ptr: DevicePointer<T>, capacity: usize) -> Self {
+        Self { buf: ptr, capacity }
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`Result`] to mirror
+    /// the real driver API.
+    pub fn drop(buffer: Self) -> Result<(), (CudaError, Self)> {
+        std::mem::drop(buffer);
+
+        Ok(())
+    }
+}
+
+impl<T: DeviceCopy> Deref for DeviceBuffer<T> {
+    type Target = DeviceSlice<T>;
+
+    fn deref(&self) -> &Self::Target {
+        // Safety: the buffer owns capacity elements of simulated device memory
+        unsafe { DeviceSlice::from_raw_parts(self.buf, self.capacity) }
+    }
+}
+
+impl<T: DeviceCopy> DerefMut for DeviceBuffer<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // Safety: the buffer owns capacity elements of simulated device memory
+        unsafe { DeviceSlice::from_raw_parts_mut(self.buf, self.capacity) }
+    }
+}
+
+impl<T: DeviceCopy> Drop for DeviceBuffer<T> {
+    fn drop(&mut self) {
+        if let Some(data) = NonNull::new(self.buf.as_raw_mut()) {
+            // Safety: self.buf was allocated in DeviceBuffer::uninitialized
+            unsafe { deallocate(data, self.capacity) };
+        }
+    }
+}
+
+/// Mock of an owned single value in page-locked host memory.
+pub struct LockedBox<T> {
+    ptr: NonNull<T>,
+}
+
+impl<T: DeviceCopy> LockedBox<T> {
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the page-locked memory cannot be allocated.
+    pub fn new(value: &T) -> CudaResult<Self> {
+        // Safety: the uninitialised memory is immediately initialised
+        let mut locked_box = unsafe { Self::uninitialized() }?;
+        // Safety: locked_box has space for one T
+        unsafe { std::ptr::copy_nonoverlapping(value, locked_box.as_mut_ptr(), 1) };
+        Ok(locked_box)
+    }
+}
+
+impl<T> LockedBox<T> {
+    /// # Safety
+    ///
+    /// The page-locked memory is uninitialised.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the page-locked memory cannot be allocated.
+    pub unsafe fn uninitialized() -> CudaResult<Self> {
+        Ok(Self {
+            ptr: allocate::<T>(1)?,
+        })
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`LockedBox::into_raw`].
+    pub const unsafe fn from_raw(ptr: *mut T) -> Self {
+        Self {
+            // Safety: ensured by the caller
+            ptr: unsafe { NonNull::new_unchecked(ptr) },
+        }
+    }
+
+    #[must_use]
+    pub fn into_raw(locked_box: Self) -> *mut T {
+        let locked_box = std::mem::ManuallyDrop::new(locked_box);
+        locked_box.ptr.as_ptr()
+    }
+
+    #[must_use]
+    pub const fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    #[must_use]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`Result`] to mirror
+    /// the real driver API.
+    pub fn drop(locked_box: Self) -> Result<(), (CudaError, Self)> {
+        std::mem::drop(locked_box);
+
+        Ok(())
+    }
+}
+
+impl<T> Deref for LockedBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // Safety: the box owns one T
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T> DerefMut for LockedBox<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // Safety: the box owns one T
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T> Drop for LockedBox<T> {
+    fn drop(&mut self) {
+        // Safety: self.ptr was allocated in LockedBox::uninitialized
+        unsafe { deallocate(self.ptr, 1) };
+    }
+}
+
+/// Mock of an owned buffer of page-locked host memory.
+pub struct LockedBuffer<T: DeviceCopy> {
+    buf: NonNull<T>,
+    capacity: usize,
+}
+
+impl<T: DeviceCopy> LockedBuffer<T> {
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the page-locked memory cannot be allocated.
+    pub fn new(value: &T, size: usize) -> CudaResult<Self> {
+        // Safety: the uninitialised memory is immediately initialised
+        let buffer = unsafe { Self::uninitialized(size) }?;
+
+        for i in 0..size {
+            // Safety: the buffer has space for size elements
+            unsafe { std::ptr::copy_nonoverlapping(value, buffer.buf.as_ptr().add(i), 1) };
+        }
+
+        Ok(buffer)
+    }
+
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the page-locked memory cannot be allocated.
+    pub fn from_slice(slice: &[T]) -> CudaResult<Self> {
+        // Safety: the uninitialised memory is immediately initialised
+        let buffer = unsafe { Self::uninitialized(slice.len()) }?;
+
+        // Safety: the buffer has space for slice.len() elements
+        unsafe {
+            std::ptr::copy_nonoverlapping(slice.as_ptr(), buffer.buf.as_ptr(), slice.len());
+        }
+
+        Ok(buffer)
+    }
+
+    /// # Safety
+    ///
+    /// The page-locked memory is uninitialised.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff the page-locked memory cannot be allocated.
+    pub unsafe fn uninitialized(size: usize) -> CudaResult<Self> {
+        Ok(Self {
+            buf: allocate::<T>(size)?,
+            capacity: size,
+        })
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated as a [`LockedBuffer`] with the same
+    /// `size`.
+    pub const unsafe fn from_raw_parts(ptr: *mut T, size: usize) -> Self {
+        Self {
+            // Safety: ensured by the caller
+            buf: unsafe { NonNull::new_unchecked(ptr) },
+            capacity: size,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_slice(&self) -> &[T] {
+        // Safety: the buffer owns capacity elements
+        unsafe { std::slice::from_raw_parts(self.buf.as_ptr(), self.capacity) }
+    }
+
+    #[must_use]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // Safety: the buffer owns capacity elements
+        unsafe { std::slice::from_raw_parts_mut(self.buf.as_ptr(), self.capacity) }
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`Result`] to mirror
+    /// the real driver API.
+    pub fn drop(buffer: Self) -> Result<(), (CudaError, Self)> {
+        std::mem::drop(buffer);
+
+        Ok(())
+    }
+}
+
+impl<T: DeviceCopy> AsRef<[T]> for LockedBuffer<T> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: DeviceCopy> AsMut<[T]> for LockedBuffer<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: DeviceCopy> Deref for LockedBuffer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T: DeviceCopy> DerefMut for LockedBuffer<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: DeviceCopy> Drop for LockedBuffer<T> {
+    fn drop(&mut self) {
+        // Safety: self.buf was allocated in LockedBuffer::uninitialized
+        unsafe { deallocate(self.buf, self.capacity) };
+    }
+}
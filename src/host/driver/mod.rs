@@ -0,0 +1,84 @@
+//! The subset of the CUDA driver API that is used by `rust-cuda`.
+//!
+//! By default, this module re-exports the corresponding [`rustacuda`]
+//! modules. With the `mock` feature, it instead provides a pure-host
+//! in-memory implementation of the same API, which allows the host-side
+//! borrow, restore, copy, and stream logic to be tested without a GPU.
+//!
+//! The mock implementation is self-contained and does not link against the
+//! CUDA driver library. By default, it executes all asynchronous work
+//! eagerly and in submission order, which is one valid schedule of the CUDA
+//! stream semantics. A [`stream::Stream`] can be held to instead queue up its
+//! work until it is released, so that tests can observe work, and the
+//! [`event::Event`]s recorded after it, which have not yet completed. Device
+//! and page-locked memory are simulated by host allocations, and a single
+//! [`device::Device`] with compute capability 7.0 is reported. Since no PTX can
+//! be loaded, [`module::Module`]s and thus [`function::Function`]s cannot be
+//! created, but kernels can still be run with the `emulate` feature. Streams
+//! cannot be captured, so no [`graph::Graph`]s can be created either.
+//!
+//! The [`ext`] module extends both implementations with the parts of the
+//! driver API that `rustacuda` does not provide.
+
+#[cfg(not(feature = "mock"))]
+pub use rustacuda::{context, device, error, event, function, memory, module, stream};
+
+#[cfg(not(feature = "mock"))]
+pub use ext::graph;
+
+#[cfg(feature = "mock")]
+/// Defines a mock of a set of driver flags with the given bit values.
+macro_rules! flags {
+    ($(#[$meta:meta])* $name:ident { $($flag:ident = $bits:literal),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub struct $name {
+            bits: u32,
+        }
+
+        impl $name {
+            $(pub const $flag: Self = Self { bits: $bits };)*
+
+            #[must_use]
+            pub const fn bits(self) -> u32 {
+                self.bits
+            }
+
+            #[must_use]
+            pub const fn contains(self, other: Self) -> bool {
+                (self.bits & other.bits) == other.bits
+            }
+        }
+
+        impl std::ops::BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, other: Self) -> Self {
+                Self {
+                    bits: self.bits | other.bits,
+                }
+            }
+        }
+    };
+}
+
+pub mod ext;
+
+#[cfg(feature = "mock")]
+pub mod context;
+#[cfg(feature = "mock")]
+pub mod device;
+#[cfg(feature = "mock")]
+pub mod error;
+#[cfg(feature = "mock")]
+pub mod event;
+#[cfg(feature = "mock")]
+pub mod function;
+#[cfg(feature = "mock")]
+pub mod graph;
+#[cfg(feature = "mock")]
+pub mod memory;
+#[cfg(feature = "mock")]
+pub mod module;
+#[cfg(feature = "mock")]
+pub mod stream;
@@ -0,0 +1,46 @@
+use std::ffi::CStr;
+
+use super::error::{CudaError, CudaResult};
+
+use super::function::Function;
+
+/// Mock of a loaded CUDA module.
+///
+/// Since the mock driver cannot load PTX, no [`Module`] can ever be created.
+pub struct Module {
+    _private: (),
+}
+
+impl Module {
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since the mock driver
+    /// cannot load PTX.
+    pub const fn load_from_string(_image: &CStr) -> CudaResult<Self> {
+        Err(CudaError::NotSupported)
+    }
+
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since the mock driver
+    /// cannot load PTX.
+    pub const fn get_function<'a>(&'a self, _name: &CStr) -> CudaResult<Function<'a>> {
+        Err(CudaError::NotSupported)
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`Result`] to mirror
+    /// the real driver API.
+    pub fn drop(module: Self) -> Result<(), (CudaError, Self)> {
+        std::mem::drop(module);
+
+        Ok(())
+    }
+}
+
+impl Drop for Module {
+    fn drop(&mut self) {
+        // Mirror the real driver, which unloads the module on drop
+    }
+}
@@ -0,0 +1,372 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    ffi::c_void,
+    rc::Rc,
+};
+
+use super::{
+    error::{CudaError, CudaResult},
+    event::Event,
+    ext::StreamExt,
+    function::{BlockSize, Function, GridSize},
+    graph::{Graph, StreamCaptureMode},
+};
+
+flags! {
+    /// Mock of the flags that a [`Stream`] can be created with.
+    StreamFlags {
+        DEFAULT = 0x0,
+        NON_BLOCKING = 0x1,
+    }
+}
+
+flags! {
+    /// Mock of the flags for [`Stream::wait_event`].
+    StreamWaitEventFlags {
+        DEFAULT = 0x0,
+    }
+}
+
+type Work = Box<dyn FnOnce() -> CudaResult<()>>;
+
+/// The in-order queue of the work that has been submitted to a [`Stream`]
+/// but not yet executed.
+#[derive(Default)]
+pub(super) struct StreamQueue {
+    held: Cell<bool>,
+    pending: RefCell<VecDeque<Work>>,
+    completed: Cell<u64>,
+}
+
+impl StreamQueue {
+    fn submit(&self, work: Work) -> CudaResult<()> {
+        if self.held.get() || !self.pending.borrow().is_empty() {
+            self.pending.borrow_mut().push_back(work);
+            return Ok(());
+        }
+
+        self.execute(work)
+    }
+
+    /// Executes all pending work in submission order and returns the first
+    /// error that occurred, if any.
+    pub(super) fn flush(&self) -> CudaResult<()> {
+        let mut result = Ok(());
+
+        // The queue is not borrowed while the work executes since the work
+        //  may itself submit more work to this queue
+        while let Some(work) = self.pending.borrow_mut().pop_front() {
+            result = result.and(self.execute(work));
+        }
+
+        result
+    }
+
+    fn execute(&self, work: Work) -> CudaResult<()> {
+        work()?;
+
+        self.completed.set(self.completed.get() + 1);
+
+        Ok(())
+    }
+}
+
+/// Mock of a CUDA stream.
+///
+/// By default, all work that is submitted to a mock [`Stream`] is executed
+/// eagerly and in submission order, i.e. every asynchronous operation has
+/// already completed once the call that submitted it returns.
+///
+/// A [`Stream`] can be [held](Self::hold) to instead queue up all
+/// subsequently submitted work, which then only executes, still in
+/// submission order, once the [`Stream`] is [released](Self::release) or
+/// synchronised on. This allows tests to deterministically observe
+/// asynchronous operations that have not yet completed.
+pub struct Stream {
+    flags: StreamFlags,
+    priority: i32,
+    queue: Rc<StreamQueue>,
+}
+
+impl Stream {
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`CudaResult`] to
+    /// mirror the real driver API.
+    pub fn new(flags: StreamFlags, priority: Option<i32>) -> CudaResult<Self> {
+        Ok(Self {
+            flags,
+            priority: priority.unwrap_or(0),
+            queue: Rc::new(StreamQueue::default()),
+        })
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`CudaResult`] to
+    /// mirror the real driver API.
+    pub const fn get_flags(&self) -> CudaResult<StreamFlags> {
+        Ok(self.flags)
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`CudaResult`] to
+    /// mirror the real driver API.
+    pub const fn get_priority(&self) -> CudaResult<i32> {
+        Ok(self.priority)
+    }
+
+    /// Returns the number of operations that have completed on this
+    /// [`Stream`], which can be used to check the stream ordering in tests.
+    #[must_use]
+    pub fn completed_operations(&self) -> u64 {
+        self.queue.completed.get()
+    }
+
+    /// Returns the number of operations that have been submitted to this
+    /// [`Stream`] but have not yet completed.
+    #[must_use]
+    pub fn pending_operations(&self) -> usize {
+        self.queue.pending.borrow().len()
+    }
+
+    /// Holds this [`Stream`] such that all subsequently submitted work is
+    /// queued up instead of being executed eagerly.
+    ///
+    /// Another [`Stream`] which waits on an [`Event`] that has been recorded
+    /// on this [`Stream`] may still force its queued work to execute.
+    pub fn hold(&self) {
+        self.queue.held.set(true);
+    }
+
+    /// Releases this [`Stream`] such that all queued work is executed in
+    /// submission order, and all subsequently submitted work is again
+    /// executed eagerly.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`CudaError`] that occurred in the queued work.
+    pub fn release(&self) -> CudaResult<()> {
+        self.queue.held.set(false);
+        self.queue.flush()
+    }
+
+    /// Adds a callback to be called after all previously submitted work has
+    /// completed.
+    ///
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, but returns a [`CudaResult`] to
+    /// mirror the real driver API.
+    pub fn add_callback<T>(&self, callback: Box<T>) -> CudaResult<()>
+    where
+        T: FnOnce(CudaResult<()>) + Send + 'static,
+    {
+        self.submit(move || {
+            callback(Ok(()));
+            Ok(())
+        })
+    }
+
+    /// Blocks until all work that has been submitted to this [`Stream`] has
+    /// completed, i.e. executes all queued work, even if the [`Stream`] is
+    /// held.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`CudaError`] that occurred in the queued work.
+    pub fn synchronize(&self) -> CudaResult<()> {
+        self.queue.flush()
+    }
+
+    /// Makes all future work submitted to this [`Stream`] wait until the
+    /// `event` has completed. The mock driver forces the `event` to complete
+    /// immediately, even if the [`Stream`] it was recorded on is held.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`CudaError`] that occurred in the work that the
+    /// `event` waited on.
+    pub fn wait_event(&self, event: &Event, _flags: StreamWaitEventFlags) -> CudaResult<()> {
+        event.synchronize()?;
+
+        self.submit(|| Ok(()))
+    }
+
+    /// # Safety
+    ///
+    /// Since no [`Function`] can be created by the mock driver, this method
+    /// can never be called.
+    ///
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since no [`Function`]
+    /// can be created.
+    #[expect(clippy::needless_pass_by_value)] // mirrors the real driver API
+    pub unsafe fn launch<G: Into<GridSize>, B: Into<BlockSize>>(
+        &self,
+        _func: &Function,
+        _grid_size: G,
+        _block_size: B,
+        _shared_mem_bytes: u32,
+        _args: &[*mut c_void],
+    ) -> CudaResult<()> {
+        Err(CudaError::NotSupported)
+    }
+
+    /// Like the real driver, all queued work is still executed when the
+    /// [`Stream`] is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`CudaError`] that occurred in the queued work.
+    pub fn drop(stream: Self) -> Result<(), (CudaError, Self)> {
+        match stream.queue.flush() {
+            Ok(()) => Ok(()),
+            Err(err) => Err((err, stream)),
+        }
+    }
+
+    /// Executes the `work` in submission order on this [`Stream`].
+    pub(super) fn submit(&self, work: impl FnOnce() -> CudaResult<()> + 'static) -> CudaResult<()> {
+        self.queue.submit(Box::new(work))
+    }
+
+    pub(super) const fn queue(&self) -> &Rc<StreamQueue> {
+        &self.queue
+    }
+}
+
+impl StreamExt for Stream {
+    /// # Safety
+    ///
+    /// Since no [`Function`] can be created by the mock driver, this method
+    /// can never be called.
+    ///
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since no [`Function`]
+    /// can be created.
+    unsafe fn launch_cooperative<G: Into<GridSize>, B: Into<BlockSize>>(
+        &self,
+        _func: &Function,
+        _grid_size: G,
+        _block_size: B,
+        _shared_mem_bytes: u32,
+        _args: &[*mut c_void],
+    ) -> CudaResult<()> {
+        Err(CudaError::NotSupported)
+    }
+
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since the mock driver
+    /// cannot capture work into a [`Graph`].
+    fn begin_capture(&self, _mode: StreamCaptureMode) -> CudaResult<()> {
+        Err(CudaError::NotSupported)
+    }
+
+    /// # Errors
+    ///
+    /// Always returns a [`CudaError::NotSupported`] since the mock driver
+    /// cannot capture work into a [`Graph`].
+    fn end_capture(&self) -> CudaResult<Graph> {
+        Err(CudaError::NotSupported)
+    }
+
+    /// # Errors
+    ///
+    /// Does not fail in the mock driver, which never captures a [`Stream`].
+    fn is_capturing(&self) -> CudaResult<bool> {
+        Ok(false)
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        let _ = self.queue.flush();
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::{Stream, StreamFlags, StreamWaitEventFlags};
+    use crate::host::driver::event::{Event, EventFlags, EventStatus};
+
+    #[test]
+    fn held_stream_queues_work_in_order() {
+        let stream = Stream::new(StreamFlags::DEFAULT, None).unwrap();
+        let order = Rc::new(Cell::new(0_u32));
+
+        stream.hold();
+
+        for i in 0..3 {
+            let order = order.clone();
+            stream
+                .submit(move || {
+                    assert_eq!(order.get(), i);
+                    order.set(i + 1);
+                    Ok(())
+                })
+                .unwrap();
+        }
+
+        assert_eq!(stream.pending_operations(), 3);
+        assert_eq!(order.get(), 0);
+
+        stream.release().unwrap();
+
+        assert_eq!(stream.pending_operations(), 0);
+        assert_eq!(stream.completed_operations(), 3);
+        assert_eq!(order.get(), 3);
+
+        // Once released, the stream executes work eagerly again
+        stream.submit(|| Ok(())).unwrap();
+        assert_eq!(stream.completed_operations(), 4);
+    }
+
+    #[test]
+    fn event_on_held_stream_is_not_ready() {
+        let stream = Stream::new(StreamFlags::DEFAULT, None).unwrap();
+        let other = Stream::new(StreamFlags::DEFAULT, None).unwrap();
+        let event = Event::new(EventFlags::DEFAULT).unwrap();
+
+        assert_eq!(event.query(), Ok(EventStatus::Ready));
+
+        stream.hold();
+        event.record(&stream).unwrap();
+
+        assert_eq!(event.query(), Ok(EventStatus::NotReady));
+        assert!(event.elapsed_time_f32(&event).is_err());
+
+        other
+            .wait_event(&event, StreamWaitEventFlags::DEFAULT)
+            .unwrap();
+
+        assert_eq!(event.query(), Ok(EventStatus::Ready));
+        assert_eq!(event.elapsed_time_f32(&event), Ok(0.0));
+        assert_eq!(other.completed_operations(), 1);
+    }
+
+    #[test]
+    fn callbacks_are_deferred_on_held_stream() {
+        let stream = Stream::new(StreamFlags::DEFAULT, None).unwrap();
+        let (sender, receiver) = oneshot::channel();
+
+        stream.hold();
+        stream
+            .add_callback(Box::new(move |result| sender.send(result).unwrap()))
+            .unwrap();
+
+        assert_eq!(receiver.try_recv(), Err(oneshot::TryRecvError::Empty));
+
+        stream.synchronize().unwrap();
+
+        assert_eq!(receiver.try_recv(), Ok(Ok(())));
+    }
+}
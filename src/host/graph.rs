@@ -0,0 +1,382 @@
+//! Capture and replay of asynchronous work, e.g. kernel launches, as CUDA
+//! graphs.
+//!
+//! Launching the same sequence of kernels many times can be dominated by the
+//! launch overhead. A [`Graph`] records the work that is submitted to a
+//! [`Stream`] once, and can then replay all of it with a single call.
+
+use std::{cell::RefCell, marker::PhantomData, mem::ManuallyDrop};
+
+use crate::{
+    host::{
+        driver::{
+            error::CudaResult,
+            ext::StreamExt,
+            graph::{Graph as DriverGraph, GraphExec, StreamCaptureMode},
+            stream::Stream as DriverStream,
+        },
+        CudaDropWrapper, Stream,
+    },
+    utils::r#async::{Async, NoCompletion},
+    Error,
+};
+
+type CompletionSender = oneshot::Sender<CudaResult<()>>;
+
+thread_local! {
+    static CAPTURES: RefCell<Vec<Capture>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A [`Stream`] that is being captured on the current thread, together with
+/// the completion signals of the [`Async`]s that were created during the
+/// capture.
+struct Capture {
+    stream: *const DriverStream,
+    completions: Vec<CompletionSender>,
+    invalidated: bool,
+}
+
+/// An instantiated graph of asynchronous work that was recorded on the
+/// `'stream` and can be replayed on it.
+///
+/// All data that the recorded work uses must be lent to CUDA before the
+/// [`Graph`] is captured, and be borrowed by the `record` closure. The
+/// [`Graph`] then holds these borrows for its lifetime `'a`, i.e. the data
+/// can only be used again once the [`Graph`] has been dropped. Any [`Async`]
+/// that was used in the recorded work only completes after the [`Graph`]
+/// has been dropped and all of its replays have completed.
+pub struct Graph<'a, 'stream> {
+    stream: Stream<'stream>,
+    exec: CudaDropWrapper<GraphExec>,
+    completions: Vec<CompletionSender>,
+    _capture: PhantomData<&'a ()>,
+}
+
+impl<'a, 'stream> Graph<'a, 'stream> {
+    /// Records all asynchronous work, e.g. `launch_async` calls, that
+    /// `record` submits to the `stream` into a new [`Graph`], without
+    /// executing it.
+    ///
+    /// While the `stream` is being captured, any operation that would need
+    /// the recorded work to have completed, or that frees device memory that
+    /// the recorded work may refer to, fails the capture. In particular,
+    /// synchronous kernel launches, [`Async::synchronize`], and lending data
+    /// to CUDA inside `record` are not permitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::StreamCaptureUnsupported`] iff a prohibited
+    /// operation was attempted during the capture, an [`Error`] iff
+    /// capturing the `stream` or instantiating the captured graph fails, or
+    /// the error that is returned by `record`.
+    pub fn capture(
+        stream: Stream<'stream>,
+        record: impl FnOnce(Stream<'stream>) -> Result<(), Error> + 'a,
+    ) -> Result<Self, Error> {
+        let (graph, completions) = CaptureGuard::begin(stream)?.record(record)?;
+
+        Ok(Self {
+            stream,
+            exec: CudaDropWrapper::from(graph.instantiate()?),
+            completions,
+            _capture: PhantomData::<&'a ()>,
+        })
+    }
+
+    /// Re-records the asynchronous work of this [`Graph`], e.g. to change
+    /// the values of
+    /// [`PerThreadShallowCopy`](crate::kernel::param::PerThreadShallowCopy)
+    /// kernel parameters between replays.
+    ///
+    /// If the newly recorded work has the same structure as before, only the
+    /// parameters of the instantiated graph are updated in place. Otherwise,
+    /// the graph is instantiated anew. The [`Async`]s that were used in the
+    /// previously recorded work complete once all previous replays have
+    /// completed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::StreamCaptureUnsupported`] iff a prohibited
+    /// operation was attempted during the capture, an [`Error`] iff
+    /// capturing the `stream` or instantiating the captured graph fails, or
+    /// the error that is returned by `record`.
+    pub fn recapture(
+        &mut self,
+        record: impl FnOnce(Stream<'stream>) -> Result<(), Error> + 'a,
+    ) -> Result<(), Error> {
+        let (graph, completions) = CaptureGuard::begin(self.stream)?.record(record)?;
+
+        if self.exec.update(&graph).is_err() {
+            self.exec = CudaDropWrapper::from(graph.instantiate()?);
+        }
+
+        let previous = std::mem::replace(&mut self.completions, completions);
+        complete_after_replays(self.stream, previous)?;
+
+        Ok(())
+    }
+
+    /// Asynchronously replays all recorded work on the `stream`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] iff launching the graph fails.
+    pub fn launch(
+        &self,
+        stream: Stream<'stream>,
+    ) -> Result<Async<'static, 'stream, (), NoCompletion>, Error> {
+        self.exec.launch(&stream)?;
+
+        Async::pending((), stream, NoCompletion).map_err(Error::from)
+    }
+}
+
+impl Drop for Graph<'_, '_> {
+    fn drop(&mut self) {
+        let completions = std::mem::take(&mut self.completions);
+
+        let _ = complete_after_replays(self.stream, completions);
+    }
+}
+
+/// Sends the `completions` once all work that has been submitted to the
+/// `stream` so far, including all replays of a [`Graph`], has completed.
+fn complete_after_replays(stream: Stream, completions: Vec<CompletionSender>) -> CudaResult<()> {
+    if completions.is_empty() {
+        return Ok(());
+    }
+
+    stream.add_callback(Box::new(|result: CudaResult<()>| {
+        for completion in completions {
+            std::mem::drop(completion.send(result));
+        }
+    }))
+}
+
+/// Guard for an ongoing capture of a [`Stream`], which ends the capture even
+/// if recording the work panics.
+struct CaptureGuard<'stream> {
+    stream: Stream<'stream>,
+}
+
+impl<'stream> CaptureGuard<'stream> {
+    fn begin(stream: Stream<'stream>) -> Result<Self, Error> {
+        // The thread-local mode makes the driver refuse potentially unsafe
+        //  calls from this thread, e.g. freeing memory, during the capture
+        stream.begin_capture(StreamCaptureMode::ThreadLocal)?;
+
+        CAPTURES.with(|captures| {
+            captures.borrow_mut().push(Capture {
+                stream: std::ptr::from_ref::<DriverStream>(&*stream),
+                completions: Vec::new(),
+                invalidated: false,
+            });
+        });
+
+        Ok(Self { stream })
+    }
+
+    fn record(
+        self,
+        record: impl FnOnce(Stream<'stream>) -> Result<(), Error>,
+    ) -> Result<(CudaDropWrapper<DriverGraph>, Vec<CompletionSender>), Error> {
+        let result = record(self.stream);
+
+        let (capture, graph) = ManuallyDrop::new(self).end();
+
+        result?;
+
+        match capture {
+            Some(Capture {
+                completions,
+                invalidated: false,
+                ..
+            }) => Ok((CudaDropWrapper::from(graph?), completions)),
+            _ => Err(Error::StreamCaptureUnsupported),
+        }
+    }
+
+    fn end(&self) -> (Option<Capture>, CudaResult<DriverGraph>) {
+        let capture = CAPTURES.with(|captures| {
+            let mut captures = captures.borrow_mut();
+
+            let index = captures
+                .iter()
+                .rposition(|capture| std::ptr::eq(capture.stream, &*self.stream))?;
+
+            Some(captures.remove(index))
+        });
+
+        // the capture must always be ended, even if recording failed
+        (capture, self.stream.end_capture())
+    }
+}
+
+impl Drop for CaptureGuard<'_> {
+    fn drop(&mut self) {
+        // Only reached if recording panicked, the senders of the captured
+        //  work are dropped, and the partially captured graph is discarded
+        let _ = self.end();
+    }
+}
+
+/// Returns `true` iff the `stream` is currently being captured into a
+/// [`Graph`] on this thread.
+pub(crate) fn is_captured(stream: &DriverStream) -> bool {
+    CAPTURES.with(|captures| {
+        captures
+            .borrow()
+            .iter()
+            .any(|capture| std::ptr::eq(capture.stream, stream))
+    })
+}
+
+/// Invalidates all ongoing captures on this thread, e.g. since device memory
+/// that the captured work may refer to has been freed.
+pub(crate) fn invalidate_captures() {
+    CAPTURES.with(|captures| {
+        for capture in captures.borrow_mut().iter_mut() {
+            capture.invalidated = true;
+        }
+    });
+}
+
+/// Defers the `completion` signal if the `stream` is currently being captured
+/// on this thread, such that it is only sent once the captured [`Graph`] has
+/// been dropped and all of its replays have completed.
+///
+/// # Errors
+///
+/// Returns the `completion` back iff the `stream` is not being captured.
+pub(crate) fn defer_completion(
+    stream: &DriverStream,
+    completion: CompletionSender,
+) -> Result<(), CompletionSender> {
+    CAPTURES.with(|captures| {
+        match captures
+            .borrow_mut()
+            .iter_mut()
+            .rev()
+            .find(|capture| std::ptr::eq(capture.stream, stream))
+        {
+            Some(capture) => {
+                capture.completions.push(completion);
+                Ok(())
+            },
+            None => Err(completion),
+        }
+    })
+}
+
+#[cfg(all(test, feature = "mock"))]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::mem::ManuallyDrop;
+
+    use crate::{
+        host::{
+            driver::{
+                error::CudaError,
+                memory::DeviceBox,
+                stream::{Stream as DriverStream, StreamFlags},
+            },
+            CudaDropWrapper, Stream,
+        },
+        utils::r#async::{Async, NoCompletion},
+        Error,
+    };
+
+    use super::{defer_completion, is_captured, Capture, CaptureGuard, CAPTURES};
+
+    /// Registers a capture of the `stream` without beginning it in the mock
+    /// driver, which cannot capture streams.
+    fn begin_mock_capture(stream: Stream) -> CaptureGuard {
+        CAPTURES.with(|captures| {
+            captures.borrow_mut().push(Capture {
+                stream: std::ptr::from_ref::<DriverStream>(&*stream),
+                completions: Vec::new(),
+                invalidated: false,
+            });
+        });
+
+        CaptureGuard { stream }
+    }
+
+    #[test]
+    fn synchronize_is_refused_during_capture() {
+        let mut stream = DriverStream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+
+        Stream::with(&mut stream, |stream| {
+            let capture = begin_mock_capture(stream);
+            assert!(is_captured(&stream));
+
+            let pending = Async::pending((), stream, NoCompletion).unwrap();
+            assert_eq!(pending.synchronize(), Err(Error::StreamCaptureUnsupported));
+
+            // the completion of the pending work is deferred to the graph
+            let (capture, _graph) = ManuallyDrop::new(capture).end();
+            assert_eq!(capture.unwrap().completions.len(), 1);
+            assert!(!is_captured(&stream));
+        });
+    }
+
+    #[test]
+    fn freeing_memory_invalidates_capture() {
+        let mut stream = DriverStream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+
+        Stream::with(&mut stream, |stream| {
+            let result = begin_mock_capture(stream).record(|_stream| {
+                std::mem::drop(CudaDropWrapper::from(DeviceBox::new(&42_u32)?));
+                Ok(())
+            });
+
+            assert!(matches!(result, Err(Error::StreamCaptureUnsupported)));
+            assert!(!is_captured(&stream));
+
+            // without any prohibited operation, only the mock driver fails
+            let result = begin_mock_capture(stream).record(|_stream| Ok(()));
+
+            assert!(matches!(
+                result,
+                Err(Error::Driver(CudaError::NotSupported))
+            ));
+        });
+    }
+
+    #[test]
+    fn other_streams_are_not_captured() {
+        let mut stream = DriverStream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let mut other = DriverStream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+
+        Stream::with(&mut stream, |stream| {
+            Stream::with(&mut other, |other| {
+                let capture = begin_mock_capture(stream);
+
+                assert!(!is_captured(&other));
+                assert!(defer_completion(&other, oneshot::channel().0).is_err());
+                assert!(Async::pending((), other, NoCompletion)
+                    .unwrap()
+                    .synchronize()
+                    .is_ok());
+
+                std::mem::drop(capture);
+                assert!(!is_captured(&stream));
+            });
+        });
+    }
+
+    #[test]
+    #[allow(clippy::panic)]
+    fn panicking_record_ends_capture() {
+        let mut stream = DriverStream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+
+        Stream::with(&mut stream, |stream| {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                begin_mock_capture(stream).record(|_stream| panic!("recording failed"))
+            }));
+
+            assert!(result.is_err());
+            assert!(!is_captured(&stream));
+        });
+    }
+}
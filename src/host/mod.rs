@@ -5,15 +5,14 @@ use std::{
 };
 
 use const_type_layout::TypeGraphLayout;
-use rustacuda::{
-    context::Context,
-    error::CudaError,
-    event::Event,
-    memory::{CopyDestination, DeviceBox, DeviceBuffer, LockedBox, LockedBuffer},
-    module::Module,
-};
 
 use crate::{
+    host::driver::{
+        error::CudaError,
+        event::Event,
+        memory::{CopyDestination, DeviceBox, DeviceBuffer, LockedBox, LockedBuffer},
+        module::Module,
+    },
     safety::PortableBitSemantics,
     utils::{
         adapter::DeviceCopyWithPortableBitSemantics,
@@ -25,17 +24,20 @@ use crate::{
     },
 };
 
+pub mod driver;
+pub mod graph;
+
 type InvariantLifetime<'brand> = PhantomData<fn(&'brand ()) -> &'brand ()>;
 
 #[derive(Copy, Clone)]
 #[repr(transparent)]
 pub struct Stream<'stream> {
-    stream: &'stream rustacuda::stream::Stream,
+    stream: &'stream driver::stream::Stream,
     _brand: InvariantLifetime<'stream>,
 }
 
 impl<'stream> Deref for Stream<'stream> {
-    type Target = rustacuda::stream::Stream;
+    type Target = driver::stream::Stream;
 
     fn deref(&self) -> &Self::Target {
         self.stream
@@ -65,7 +67,7 @@ impl<'stream> Stream<'stream> {
     /// }
     /// ```
     pub fn with<O>(
-        stream: &mut rustacuda::stream::Stream,
+        stream: &mut driver::stream::Stream,
         inner: impl for<'new_stream> FnOnce(Stream<'new_stream>) -> O,
     ) -> O {
         inner(Stream {
@@ -77,7 +79,7 @@ impl<'stream> Stream<'stream> {
 
 pub trait CudaDroppable: Sized {
     #[expect(clippy::missing_errors_doc)]
-    fn drop(val: Self) -> Result<(), (rustacuda::error::CudaError, Self)>;
+    fn drop(val: Self) -> Result<(), (crate::host::driver::error::CudaError, Self)>;
 }
 
 #[repr(transparent)]
@@ -112,26 +114,32 @@ impl<C: CudaDroppable> DerefMut for CudaDropWrapper<C> {
     }
 }
 
+// Freeing memory invalidates any ongoing graph capture on this thread, since
+//  the captured work may still refer to the memory
 impl<T> CudaDroppable for DeviceBox<T> {
     fn drop(val: Self) -> Result<(), (CudaError, Self)> {
+        graph::invalidate_captures();
         Self::drop(val)
     }
 }
 
 impl<T: rustacuda_core::DeviceCopy> CudaDroppable for DeviceBuffer<T> {
     fn drop(val: Self) -> Result<(), (CudaError, Self)> {
+        graph::invalidate_captures();
         Self::drop(val)
     }
 }
 
 impl<T> CudaDroppable for LockedBox<T> {
     fn drop(val: Self) -> Result<(), (CudaError, Self)> {
+        graph::invalidate_captures();
         Self::drop(val)
     }
 }
 
 impl<T: rustacuda_core::DeviceCopy> CudaDroppable for LockedBuffer<T> {
     fn drop(val: Self) -> Result<(), (CudaError, Self)> {
+        graph::invalidate_captures();
         Self::drop(val)
     }
 }
@@ -147,9 +155,12 @@ macro_rules! impl_sealed_drop_value {
 }
 
 impl_sealed_drop_value!(Module);
-impl_sealed_drop_value!(rustacuda::stream::Stream);
-impl_sealed_drop_value!(Context);
+impl_sealed_drop_value!(driver::stream::Stream);
+#[cfg(not(feature = "mock"))]
+impl_sealed_drop_value!(rustacuda::context::Context);
 impl_sealed_drop_value!(Event);
+impl_sealed_drop_value!(driver::graph::Graph);
+impl_sealed_drop_value!(driver::graph::GraphExec);
 
 #[expect(clippy::module_name_repetitions)]
 pub struct HostAndDeviceMutRef<'a, T: PortableBitSemantics + TypeGraphLayout> {
@@ -409,3 +420,59 @@ impl<'a, T: PortableBitSemantics + TypeGraphLayout> HostAndDeviceOwned<'a, T> {
         Async::ready(self, stream)
     }
 }
+
+#[cfg(all(test, feature = "mock"))]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::{
+        host::driver::{error::CudaError, memory::CopyDestination},
+        utils::adapter::DeviceCopyWithPortableBitSemantics,
+    };
+
+    use super::HostAndDeviceMutRef;
+
+    fn read_device(r: &HostAndDeviceMutRef<u32>) -> u32 {
+        let mut value = 0;
+        r.device_box
+            .copy_to(DeviceCopyWithPortableBitSemantics::from_mut(&mut value))
+            .unwrap();
+        value
+    }
+
+    fn write_device(r: &mut HostAndDeviceMutRef<u32>, value: u32) {
+        r.device_box
+            .copy_from(DeviceCopyWithPortableBitSemantics::from_ref(&value))
+            .unwrap();
+    }
+
+    #[test]
+    fn mut_ref_with_new_copies_device_changes_back() {
+        let mut value = 42;
+
+        let result = HostAndDeviceMutRef::with_new(&mut value, |mut r| {
+            assert_eq!(read_device(&r), 42);
+
+            // Simulate a kernel that writes to the device copy
+            write_device(&mut r, 24);
+
+            Ok::<_, CudaError>("done")
+        });
+
+        assert_eq!(result, Ok("done"));
+        assert_eq!(value, 24);
+    }
+
+    #[test]
+    fn mut_ref_with_new_copies_back_on_inner_error() {
+        let mut value = 42;
+
+        let result = HostAndDeviceMutRef::with_new(&mut value, |mut r| {
+            write_device(&mut r, 24);
+
+            Err::<(), _>(CudaError::LaunchFailed)
+        });
+
+        assert_eq!(result, Err(CudaError::LaunchFailed));
+        assert_eq!(value, 24);
+    }
+}
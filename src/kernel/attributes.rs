@@ -0,0 +1,255 @@
+use std::ffi::{CStr, CString};
+
+use crate::{
+    host::driver::{
+        context::{CacheConfig, CurrentContext},
+        error::{CudaError, CudaResult},
+        ext::{DeviceAttributeExt, DeviceExt, FunctionAttributeExt, FunctionExt},
+        function::{Function, FunctionAttribute},
+    },
+    Error,
+};
+
+/// Declarative configuration of a [`TypedPtxKernel`](super::TypedPtxKernel),
+/// which is applied to the kernel every time that it is (re)compiled.
+///
+/// All attributes are optional and keep the driver's defaults if they are
+/// [`None`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KernelAttributes {
+    /// Maximum number of bytes of dynamic shared memory that the kernel can
+    /// be launched with. Allocating more than 48 KiB per block requires
+    /// opting in with this attribute.
+    pub max_dynamic_shared_memory: Option<u32>,
+    /// Preferred percentage (0-100) of the unified L1 cache / shared memory
+    /// that should be carved out as shared memory.
+    pub preferred_shared_memory_carveout: Option<u8>,
+    /// Preferred cache configuration of the kernel.
+    pub cache_config: Option<CacheConfig>,
+    /// Maximum number of registers that every thread of the kernel can use,
+    /// which is passed to the JIT compiler with a `.maxnreg` directive.
+    pub max_registers: Option<u32>,
+}
+
+/// The effective attributes of a compiled kernel [`Function`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EffectiveKernelAttributes {
+    /// Number of registers that are used by every thread
+    pub registers_per_thread: u32,
+    /// Bytes of local memory that are used by every thread
+    pub local_memory_per_thread: u32,
+    /// Bytes of constant memory that are used by the kernel
+    pub const_memory: u32,
+    /// Bytes of statically allocated shared memory per block
+    pub static_shared_memory: u32,
+    /// Maximum number of bytes of dynamic shared memory per block
+    pub max_dynamic_shared_memory: u32,
+    /// Maximum number of threads per block that the kernel can be launched
+    /// with
+    pub max_threads_per_block: u32,
+}
+
+impl KernelAttributes {
+    /// The largest number of registers per thread that can be requested.
+    pub const MAX_REGISTERS_PER_THREAD: u32 = 255;
+
+    /// Inserts a `.maxnreg` directive for the `entry_point` into the `ptx`
+    /// source iff [`Self::max_registers`] is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::InvalidKernelAttribute`] iff
+    /// [`Self::max_registers`] is zero or larger than
+    /// [`Self::MAX_REGISTERS_PER_THREAD`], and an [`Error::PtxJit`] iff the
+    /// `ptx` does not contain the `entry_point`.
+    pub(super) fn configure_ptx(
+        &self,
+        ptx: &CStr,
+        entry_point: &CStr,
+    ) -> Result<Option<CString>, Error> {
+        let Some(max_registers) = self.max_registers else {
+            return Ok(None);
+        };
+
+        if max_registers == 0 || max_registers > Self::MAX_REGISTERS_PER_THREAD {
+            return Err(Error::InvalidKernelAttribute {
+                attribute: "max_registers",
+                value: max_registers,
+            });
+        }
+
+        let invalid_ptx = || Error::PtxJit {
+            entry_point: entry_point.into(),
+            source: CudaError::InvalidPtx,
+        };
+
+        let ptx = ptx.to_bytes();
+
+        let mut entry = b".entry ".to_vec();
+        entry.extend_from_slice(entry_point.to_bytes());
+
+        // performance directives go between the parameter list and the body,
+        //  the entry point must not just be a prefix of another entry's name
+        let Some(body) = ptx
+            .windows(entry.len() + 1)
+            .position(|window| {
+                window.starts_with(&entry)
+                    && matches!(
+                        window[entry.len()],
+                        b'(' | b'{' | b' ' | b'\t' | b'\r' | b'\n'
+                    )
+            })
+            .and_then(|entry_start| {
+                ptx.iter()
+                    .skip(entry_start)
+                    .position(|c| *c == b'{')
+                    .map(|body| entry_start + body)
+            })
+        else {
+            return Err(invalid_ptx());
+        };
+
+        let (head, tail) = ptx.split_at(body);
+
+        let mut configured = Vec::with_capacity(ptx.len() + 32);
+        configured.extend_from_slice(head);
+        configured.extend_from_slice(format!(".maxnreg {max_registers}\n").as_bytes());
+        configured.extend_from_slice(tail);
+
+        CString::new(configured)
+            .map(Some)
+            .map_err(|_| invalid_ptx())
+    }
+
+    /// Validates these attributes against the limits of the current device
+    /// and applies them to the freshly compiled `function`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::SharedMemoryTooLarge`] iff the requested shared
+    /// memory exceeds the limit of the current device, an
+    /// [`Error::InvalidKernelAttribute`] iff any other attribute is out of
+    /// range, or an [`Error::Driver`] iff querying the device or configuring
+    /// the `function` fails.
+    pub(super) fn apply(&self, function: &mut Function) -> Result<(), Error> {
+        if let Some(max_dynamic_shared_memory) = self.max_dynamic_shared_memory {
+            let device = CurrentContext::get_device()?;
+            let max_shared_memory_per_block =
+                device.get_attribute_ext(DeviceAttributeExt::MaxSharedMemoryPerBlockOptin)?;
+            let static_shared_memory =
+                function.get_attribute(FunctionAttribute::SharedMemorySizeBytes)?;
+
+            let requested = usize::try_from(max_dynamic_shared_memory)
+                .ok()
+                .zip(usize::try_from(static_shared_memory).ok())
+                .and_then(|(dynamic, r#static)| dynamic.checked_add(r#static))
+                .unwrap_or(usize::MAX);
+            let available = usize::try_from(max_shared_memory_per_block).unwrap_or(0);
+
+            let too_large = Error::SharedMemoryTooLarge {
+                requested,
+                available,
+            };
+
+            if requested > available {
+                return Err(too_large);
+            }
+
+            function.set_attribute_ext(
+                FunctionAttributeExt::MaxDynamicSharedSizeBytes,
+                i32::try_from(max_dynamic_shared_memory).map_err(|_| too_large)?,
+            )?;
+        }
+
+        if let Some(carveout) = self.preferred_shared_memory_carveout {
+            if carveout > 100 {
+                return Err(Error::InvalidKernelAttribute {
+                    attribute: "preferred_shared_memory_carveout",
+                    value: u32::from(carveout),
+                });
+            }
+
+            function.set_attribute_ext(
+                FunctionAttributeExt::PreferredSharedMemoryCarveout,
+                i32::from(carveout),
+            )?;
+        }
+
+        if let Some(cache_config) = self.cache_config {
+            function.set_cache_config(cache_config)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl EffectiveKernelAttributes {
+    /// Queries the effective attributes of the compiled kernel `function`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff querying the [`FunctionAttribute`]s of the
+    /// `function` fails.
+    pub fn of_function(function: &Function) -> CudaResult<Self> {
+        let get = |attr| {
+            u32::try_from(function.get_attribute(attr)?).map_err(|_| CudaError::InvalidValue)
+        };
+        let max_dynamic_shared_memory =
+            function.get_attribute_ext(FunctionAttributeExt::MaxDynamicSharedSizeBytes)?;
+
+        Ok(Self {
+            registers_per_thread: get(FunctionAttribute::NumRegisters)?,
+            local_memory_per_thread: get(FunctionAttribute::LocalSizeBytes)?,
+            const_memory: get(FunctionAttribute::ConstSizeBytes)?,
+            static_shared_memory: get(FunctionAttribute::SharedMemorySizeBytes)?,
+            max_dynamic_shared_memory: u32::try_from(max_dynamic_shared_memory)
+                .map_err(|_| CudaError::InvalidValue)?,
+            max_threads_per_block: get(FunctionAttribute::MaxThreadsPerBlock)?,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::ffi::{CStr, CString};
+
+    use super::KernelAttributes;
+
+    const PTX: &CStr =
+        c".visible .entry kernel_long(\n)\n{\nret;\n}\n.visible .entry kernel (\n)\n{\nret;\n}\n";
+
+    fn configure(entry_point: &CStr) -> CString {
+        KernelAttributes {
+            max_registers: Some(32),
+            ..KernelAttributes::default()
+        }
+        .configure_ptx(PTX, entry_point)
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn maxnreg_is_inserted_for_the_full_entry_name() {
+        assert_eq!(
+            configure(c"kernel").to_str().unwrap(),
+            ".visible .entry kernel_long(\n)\n{\nret;\n}\n.visible .entry kernel (\n)\n.maxnreg \
+             32\n{\nret;\n}\n",
+        );
+        assert_eq!(
+            configure(c"kernel_long").to_str().unwrap(),
+            ".visible .entry kernel_long(\n)\n.maxnreg 32\n{\nret;\n}\n.visible .entry kernel \
+             (\n)\n{\nret;\n}\n",
+        );
+    }
+
+    #[test]
+    fn missing_entry_point_is_rejected() {
+        assert!(KernelAttributes {
+            max_registers: Some(32),
+            ..KernelAttributes::default()
+        }
+        .configure_ptx(PTX, c"kern")
+        .is_err());
+    }
+}
@@ -0,0 +1,336 @@
+use std::sync::{Mutex, PoisonError};
+
+use crate::{
+    host::driver::{
+        context::CurrentContext,
+        device::{Device, DeviceAttribute},
+        error::CudaResult,
+        ext::{DeviceAttributeExt, DeviceExt, FunctionAttributeExt, FunctionExt},
+        function::{Function, FunctionAttribute},
+    },
+    Error,
+};
+
+use super::LaunchConfig;
+
+/// The launch limits of every [`Device`] that has been used so far.
+///
+/// The limits only depend on the device, so they are shared by all contexts
+/// on the same device.
+static DEVICE_LIMITS: Mutex<Vec<(Device, DeviceLimits)>> = Mutex::new(Vec::new());
+
+/// The launch limits of a [`Device`], which are queried only once.
+#[derive(Clone, Copy)]
+#[expect(clippy::struct_field_names)]
+struct DeviceLimits {
+    max_block_dim: [u32; 3],
+    max_grid_dim: [u32; 3],
+    max_threads_per_block: u32,
+    max_shared_memory_per_block_optin: u32,
+}
+
+impl DeviceLimits {
+    /// Returns the cached launch limits of the device of the current context,
+    /// which are queried on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`](crate::host::driver::error::CudaError) iff
+    /// querying the current device or its [`DeviceAttribute`]s fails.
+    #[expect(clippy::significant_drop_tightening)] // each device is only queried once
+    fn of_current_device() -> CudaResult<Self> {
+        let device = CurrentContext::get_device()?;
+
+        let mut cache = DEVICE_LIMITS.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some((_, limits)) = cache.iter().find(|(cached, _)| *cached == device) {
+            return Ok(*limits);
+        }
+
+        let get = |attr| -> CudaResult<u32> {
+            Ok(u32::try_from(device.get_attribute(attr)?).unwrap_or(0))
+        };
+
+        let limits = Self {
+            max_block_dim: [
+                get(DeviceAttribute::MaxBlockDimX)?,
+                get(DeviceAttribute::MaxBlockDimY)?,
+                get(DeviceAttribute::MaxBlockDimZ)?,
+            ],
+            max_grid_dim: [
+                get(DeviceAttribute::MaxGridDimX)?,
+                get(DeviceAttribute::MaxGridDimY)?,
+                get(DeviceAttribute::MaxGridDimZ)?,
+            ],
+            max_threads_per_block: get(DeviceAttribute::MaxThreadsPerBlock)?,
+            max_shared_memory_per_block_optin: u32::try_from(
+                device.get_attribute_ext(DeviceAttributeExt::MaxSharedMemoryPerBlockOptin)?,
+            )
+            .unwrap_or(0),
+        };
+
+        cache.push((device, limits));
+
+        Ok(limits)
+    }
+}
+
+/// The launch limits of a compiled [`Function`].
+#[derive(Clone, Copy)]
+struct FunctionLimits {
+    max_threads_per_block: u32,
+    static_shared_memory: u32,
+    max_dynamic_shared_memory: u32,
+}
+
+impl FunctionLimits {
+    /// Returns the launch limits of the compiled `function`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`](crate::host::driver::error::CudaError) iff
+    /// querying the [`FunctionAttribute`]s of the `function` fails.
+    fn of(function: &Function) -> CudaResult<Self> {
+        let get = |attr| -> CudaResult<u32> {
+            Ok(u32::try_from(function.get_attribute(attr)?).unwrap_or(0))
+        };
+
+        Ok(Self {
+            max_threads_per_block: get(FunctionAttribute::MaxThreadsPerBlock)?,
+            static_shared_memory: get(FunctionAttribute::SharedMemorySizeBytes)?,
+            max_dynamic_shared_memory: u32::try_from(
+                function.get_attribute_ext(FunctionAttributeExt::MaxDynamicSharedSizeBytes)?,
+            )
+            .unwrap_or(0),
+        })
+    }
+}
+
+impl LaunchConfig {
+    /// Checks that this launch config of the compiled `function` with
+    /// `dynamic_shared_memory` bytes of dynamic shared memory per block is
+    /// within the limits of the current device, and returns the validated
+    /// `dynamic_shared_memory`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::InvalidLaunchDimensions`] iff the grid or block is
+    /// empty, an [`Error::LaunchLimitExceeded`] iff any grid or block
+    /// dimension or the number of threads per block exceeds its limit, an
+    /// [`Error::SharedMemoryTooLarge`] iff the static and dynamic shared
+    /// memory do not fit into a block, or an [`Error::Driver`] iff the
+    /// attributes of the current device or the `function` cannot be queried.
+    pub(super) fn validate(
+        &self,
+        function: &Function,
+        dynamic_shared_memory: usize,
+    ) -> Result<u32, Error> {
+        self.check_dimensions()?;
+
+        let device_limits = DeviceLimits::of_current_device()?;
+        let function_limits = FunctionLimits::of(function)?;
+
+        self.check_limits(&device_limits, &function_limits, dynamic_shared_memory)
+    }
+
+    /// Checks that neither the grid nor the block of this launch config is
+    /// empty.
+    fn check_dimensions(&self) -> Result<(), Error> {
+        let block = [self.block.x, self.block.y, self.block.z];
+        let grid = [self.grid.x, self.grid.y, self.grid.z];
+
+        if block.contains(&0) || grid.contains(&0) {
+            return Err(Error::InvalidLaunchDimensions {
+                grid: self.grid.clone(),
+                block: self.block.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks this launch config with `dynamic_shared_memory` bytes of
+    /// dynamic shared memory per block against the `device_limits` and
+    /// `function_limits`.
+    fn check_limits(
+        &self,
+        device_limits: &DeviceLimits,
+        function_limits: &FunctionLimits,
+        dynamic_shared_memory: usize,
+    ) -> Result<u32, Error> {
+        let block = [self.block.x, self.block.y, self.block.z];
+        let grid = [self.grid.x, self.grid.y, self.grid.z];
+
+        for (field, value, limit) in [
+            ("block.x", block[0], device_limits.max_block_dim[0]),
+            ("block.y", block[1], device_limits.max_block_dim[1]),
+            ("block.z", block[2], device_limits.max_block_dim[2]),
+            ("grid.x", grid[0], device_limits.max_grid_dim[0]),
+            ("grid.y", grid[1], device_limits.max_grid_dim[1]),
+            ("grid.z", grid[2], device_limits.max_grid_dim[2]),
+        ] {
+            if value > limit {
+                return Err(Error::LaunchLimitExceeded {
+                    field,
+                    value: u64::from(value),
+                    limit: u64::from(limit),
+                });
+            }
+        }
+
+        let threads_per_block = u64::from(block[0]) * u64::from(block[1]) * u64::from(block[2]);
+        let max_threads_per_block = device_limits
+            .max_threads_per_block
+            .min(function_limits.max_threads_per_block);
+
+        if threads_per_block > u64::from(max_threads_per_block) {
+            return Err(Error::LaunchLimitExceeded {
+                field: "block.x * block.y * block.z",
+                value: threads_per_block,
+                limit: u64::from(max_threads_per_block),
+            });
+        }
+
+        // the dynamic shared memory limit of the function already accounts
+        //  for its static shared memory
+        let static_shared_memory = u64::from(function_limits.static_shared_memory);
+
+        let requested = u64::try_from(dynamic_shared_memory)
+            .unwrap_or(u64::MAX)
+            .saturating_add(static_shared_memory);
+        let available = (static_shared_memory
+            + u64::from(function_limits.max_dynamic_shared_memory))
+        .min(u64::from(device_limits.max_shared_memory_per_block_optin));
+
+        match u32::try_from(dynamic_shared_memory) {
+            Ok(dynamic_shared_memory) if requested <= available => Ok(dynamic_shared_memory),
+            _ => Err(Error::SharedMemoryTooLarge {
+                requested: usize::try_from(requested).unwrap_or(usize::MAX),
+                available: usize::try_from(available).unwrap_or(usize::MAX),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        host::driver::function::{BlockSize, GridSize},
+        kernel::LaunchConfig,
+        Error,
+    };
+
+    use super::{DeviceLimits, FunctionLimits};
+
+    const DEVICE_LIMITS: DeviceLimits = DeviceLimits {
+        max_block_dim: [1024, 1024, 64],
+        max_grid_dim: [i32::MAX as u32, 65535, 65535],
+        max_threads_per_block: 1024,
+        max_shared_memory_per_block_optin: 96 * 1024,
+    };
+
+    const FUNCTION_LIMITS: FunctionLimits = FunctionLimits {
+        max_threads_per_block: 512,
+        static_shared_memory: 16 * 1024,
+        max_dynamic_shared_memory: 32 * 1024,
+    };
+
+    fn config(grid: impl Into<GridSize>, block: impl Into<BlockSize>) -> LaunchConfig {
+        LaunchConfig {
+            grid: grid.into(),
+            block: block.into(),
+            ptx_jit: false,
+            ptx_jit_block_size: false,
+            cooperative: false,
+        }
+    }
+
+    fn check(config: &LaunchConfig, dynamic_shared_memory: usize) -> Result<u32, Error> {
+        config.check_dimensions().and_then(|()| {
+            config.check_limits(&DEVICE_LIMITS, &FUNCTION_LIMITS, dynamic_shared_memory)
+        })
+    }
+
+    #[test]
+    fn valid_launch() {
+        assert!(matches!(check(&config(4096, (16, 16)), 1024), Ok(1024)));
+        assert!(matches!(
+            check(&config((65535, 2), (8, 8, 8)), 32 * 1024),
+            Ok(32768)
+        ));
+    }
+
+    #[test]
+    fn empty_launch_dimensions() {
+        assert!(matches!(
+            check(&config(0, 32), 0),
+            Err(Error::InvalidLaunchDimensions { .. })
+        ));
+        assert!(matches!(
+            check(&config(1, (32, 0)), 0),
+            Err(Error::InvalidLaunchDimensions { .. })
+        ));
+    }
+
+    #[test]
+    fn launch_dimension_limits() {
+        assert!(matches!(
+            check(&config(1, (1, 1, 128)), 0),
+            Err(Error::LaunchLimitExceeded {
+                field: "block.z",
+                value: 128,
+                limit: 64,
+            })
+        ));
+        assert!(matches!(
+            check(&config((1, 65536), 1), 0),
+            Err(Error::LaunchLimitExceeded {
+                field: "grid.y",
+                value: 65536,
+                limit: 65535,
+            })
+        ));
+    }
+
+    #[test]
+    fn threads_per_block_limit() {
+        // each dimension is within its limit, but the function is compiled
+        //  for at most 512 threads per block
+        assert!(matches!(
+            check(&config(1, (32, 32)), 0),
+            Err(Error::LaunchLimitExceeded {
+                field: "block.x * block.y * block.z",
+                value: 1024,
+                limit: 512,
+            })
+        ));
+    }
+
+    #[test]
+    fn shared_memory_limit() {
+        assert!(matches!(
+            check(&config(1, 32), 32 * 1024 + 1),
+            Err(Error::SharedMemoryTooLarge {
+                requested: 49153,
+                available: 49152,
+            })
+        ));
+
+        let function_limits = FunctionLimits {
+            max_dynamic_shared_memory: 128 * 1024,
+            ..FUNCTION_LIMITS
+        };
+        assert!(matches!(
+            config(1, 32).check_limits(&DEVICE_LIMITS, &function_limits, 96 * 1024),
+            Err(Error::SharedMemoryTooLarge {
+                requested: 114_688,
+                available: 98304,
+            })
+        ));
+
+        assert!(matches!(
+            check(&config(1, 32), usize::MAX),
+            Err(Error::SharedMemoryTooLarge { .. })
+        ));
+    }
+}
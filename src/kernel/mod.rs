@@ -3,16 +3,10 @@ use std::{
     ffi::{CStr, CString},
     marker::PhantomData,
     mem::ManuallyDrop,
+    num::NonZeroUsize,
     ptr::NonNull,
 };
 
-#[cfg(feature = "host")]
-use rustacuda::{
-    error::{CudaError, CudaResult},
-    function::Function,
-    module::Module,
-};
-
 #[cfg(feature = "kernel")]
 pub use rust_cuda_kernel::kernel;
 
@@ -21,16 +15,42 @@ pub use rust_cuda_kernel::kernel;
 #[allow(clippy::module_name_repetitions)] // FIXME: use expect
 pub use rust_cuda_kernel::{check_kernel, compile_kernel, specialise_kernel_entry_point};
 
+#[cfg(feature = "host")]
+mod attributes;
+#[cfg(feature = "host")]
+mod limits;
 #[cfg(feature = "host")]
 mod ptx_jit;
 #[cfg(feature = "host")]
-use ptx_jit::{PtxJITCompiler, PtxJITResult};
-
+pub use attributes::{EffectiveKernelAttributes, KernelAttributes};
+#[cfg(feature = "host")]
+use ptx_jit::{PtxJITCache, PtxJITCompiler};
 #[cfg(feature = "host")]
-use crate::host::Stream;
+pub use ptx_jit::{
+    PtxJITCacheLookup, PtxJITCacheStats, PtxJITConstLoad, PtxJITDiagnostic, PtxJITDiskCache,
+    PtxJITReport,
+};
+
 use crate::safety::PortableBitSemantics;
+#[cfg(feature = "host")]
+use crate::{
+    host::{
+        driver::{
+            context::CurrentContext, device::DeviceAttribute, error::CudaError, ext::StreamExt,
+            function::Function, module::Module,
+        },
+        Stream,
+    },
+    Error,
+};
 
+pub mod occupancy;
 pub mod param;
+mod resources;
+mod variant;
+
+pub use resources::KernelResources;
+pub use variant::KernelPtxVariant;
 
 mod sealed {
     #[doc(hidden)]
@@ -46,7 +66,7 @@ pub trait WithNewAsync<
     'stream,
     P: ?Sized + CudaKernelParameter,
     O,
-    E: From<rustacuda::error::CudaError>,
+    E: From<crate::host::driver::error::CudaError>,
 >
 {
     fn with<'b>(self, param: P::AsyncHostType<'stream, 'b>) -> Result<O, E>
@@ -59,7 +79,7 @@ impl<
         'stream,
         P: ?Sized + CudaKernelParameter,
         O,
-        E: From<rustacuda::error::CudaError>,
+        E: From<crate::host::driver::error::CudaError>,
         F: for<'b> FnOnce(P::AsyncHostType<'stream, 'b>) -> Result<O, E>,
     > WithNewAsync<'stream, P, O, E> for F
 {
@@ -71,7 +91,7 @@ impl<
     }
 }
 
-#[cfg(feature = "device")]
+#[cfg(any(feature = "device", feature = "emulate"))]
 #[doc(hidden)]
 pub trait WithFfiAsDevice<P: ?Sized + CudaKernelParameter, O> {
     fn with<'b>(self, param: P::DeviceType<'b>) -> O
@@ -79,7 +99,7 @@ pub trait WithFfiAsDevice<P: ?Sized + CudaKernelParameter, O> {
         P: 'b;
 }
 
-#[cfg(feature = "device")]
+#[cfg(any(feature = "device", feature = "emulate"))]
 impl<P: ?Sized + CudaKernelParameter, O, F: for<'b> FnOnce(P::DeviceType<'b>) -> O>
     WithFfiAsDevice<P, O> for F
 {
@@ -102,14 +122,17 @@ pub trait CudaKernelParameter: sealed::Sealed {
     type FfiType<'stream, 'b>: PortableBitSemantics
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
     where
         Self: 'b;
+    #[doc(hidden)]
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType: Sync;
 
     #[cfg(feature = "host")]
     #[expect(clippy::missing_errors_doc)] // FIXME
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl WithNewAsync<'stream, Self, O, E>,
@@ -139,7 +162,16 @@ pub trait CudaKernelParameter: sealed::Sealed {
 
     #[doc(hidden)]
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b;
+
+    #[doc(hidden)]
+    #[cfg(feature = "host")]
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         param: Self::AsyncHostType<'stream, 'b>,
         token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -154,6 +186,250 @@ pub trait CudaKernelParameter: sealed::Sealed {
     ) -> O
     where
         Self: 'short;
+
+    #[doc(hidden)]
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(param: Self::SyncHostType, token: sealed::Token) -> Self::EmulatedHostType;
+
+    #[doc(hidden)]
+    #[cfg(feature = "emulate")]
+    /// # Safety
+    ///
+    /// This method must only be called from inside an emulated kernel launch,
+    /// once per emulated thread, and with the `PARAM` index of this parameter
+    /// in the kernel's signature.
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        param: &'short Self::EmulatedHostType,
+        inner: impl WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short;
+}
+
+#[cfg(all(feature = "host", not(doc)))]
+#[doc(hidden)]
+pub trait WithNewAsyncParams<
+    'stream,
+    P: ?Sized + CudaKernelParameters,
+    O,
+    E: From<crate::host::driver::error::CudaError>,
+>
+{
+    fn with<'b>(self, params: P::AsyncHostTypes<'stream, 'b>) -> Result<O, E>
+    where
+        P: 'b;
+}
+
+#[cfg(all(feature = "host", not(doc)))]
+impl<
+        'stream,
+        P: ?Sized + CudaKernelParameters,
+        O,
+        E: From<crate::host::driver::error::CudaError>,
+        F: for<'b> FnOnce(P::AsyncHostTypes<'stream, 'b>) -> Result<O, E>,
+    > WithNewAsyncParams<'stream, P, O, E> for F
+{
+    fn with<'b>(self, params: P::AsyncHostTypes<'stream, 'b>) -> Result<O, E>
+    where
+        P: 'b,
+    {
+        (self)(params)
+    }
+}
+
+#[cfg(feature = "host")]
+/// A tuple of [`CudaKernelParameter`]s, which is passed to the generic
+/// [`TypedPtxKernel::launch`] and [`Launcher::launch`] methods.
+///
+/// This trait is implemented for tuples of up to 32 parameters.
+pub trait CudaKernelParameters: sealed::Sealed {
+    type SyncHostTypes;
+    type AsyncHostTypes<'stream, 'b>
+    where
+        Self: 'b;
+    #[doc(hidden)]
+    #[cfg(feature = "emulate")]
+    type EmulatedHostTypes: Sync;
+
+    #[expect(clippy::missing_errors_doc)] // FIXME
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
+        params: Self::SyncHostTypes,
+        stream: Stream<'stream>,
+        #[cfg(not(doc))] inner: impl WithNewAsyncParams<'stream, Self, O, E>,
+        #[cfg(doc)] inner: impl FnOnce(Self::AsyncHostTypes<'stream, 'b>) -> Result<O, E>,
+    ) -> Result<O, E>
+    where
+        Self: 'b;
+
+    #[doc(hidden)]
+    fn with_async_as_ptx_jit<'stream, 'b, O>(
+        params: &Self::AsyncHostTypes<'stream, 'b>,
+        token: sealed::Token,
+        inner: impl for<'p> FnOnce(&[Option<&'p NonNull<[u8]>>]) -> O,
+    ) -> O
+    where
+        Self: 'b;
+
+    #[doc(hidden)]
+    fn with_shared_layouts_for_async<'stream, 'b, O>(
+        params: &Self::AsyncHostTypes<'stream, 'b>,
+        token: sealed::Token,
+        inner: impl FnOnce(&[std::alloc::Layout]) -> O,
+    ) -> O
+    where
+        Self: 'b;
+
+    #[doc(hidden)]
+    fn with_async_as_ffi<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
+        params: Self::AsyncHostTypes<'stream, 'b>,
+        token: sealed::Token,
+        inner: impl FnOnce(&[*mut core::ffi::c_void]) -> Result<O, E>,
+    ) -> Result<O, E>
+    where
+        Self: 'b;
+
+    #[doc(hidden)]
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        params: Self::SyncHostTypes,
+        token: sealed::Token,
+    ) -> Self::EmulatedHostTypes;
+}
+
+#[cfg(feature = "host")]
+/// Links a `Kernel` type, which is generated by the [`kernel`] macro, to the
+/// tuple `P` of its [`CudaKernelParameter`]s.
+pub trait KernelSignature<'stream, 'kernel, P: CudaKernelParameters> {}
+
+#[cfg(feature = "host")]
+macro_rules! impl_kernel_parameters {
+    (impl tuple ($($T:ident $arg:ident),*)) => {
+        impl<$($T: CudaKernelParameter),*> sealed::Sealed for ($($T,)*) {}
+
+        #[allow(clippy::let_unit_value)] // the params are empty for zero args
+        #[allow(clippy::unused_unit)] // the params are empty for zero args
+        impl<$($T: CudaKernelParameter),*> CudaKernelParameters for ($($T,)*) {
+            type SyncHostTypes = ($($T::SyncHostType,)*);
+            type AsyncHostTypes<'stream, 'b>
+                = ($($T::AsyncHostType<'stream, 'b>,)*)
+            where
+                Self: 'b;
+            #[cfg(feature = "emulate")]
+            type EmulatedHostTypes = ($($T::EmulatedHostType,)*);
+
+            #[allow(unused_variables)] // the stream is unused for zero args
+            fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
+                params: Self::SyncHostTypes,
+                stream: Stream<'stream>,
+                #[cfg(not(doc))] inner: impl WithNewAsyncParams<'stream, Self, O, E>,
+                #[cfg(doc)] inner: impl FnOnce(Self::AsyncHostTypes<'stream, 'b>) -> Result<O, E>,
+            ) -> Result<O, E>
+            where
+                Self: 'b,
+            {
+                let ($($arg,)*) = params;
+
+                impl_kernel_parameters! { impl with_new_async ($($arg: $T),*) + (stream) {
+                    // all async params are shortened to the innermost scope,
+                    //  in which they are all alive
+                    inner.with(($($T::shorten_async($arg, sealed::Token),)*))
+                } }
+            }
+
+            fn with_async_as_ptx_jit<'stream, 'b, O>(
+                params: &Self::AsyncHostTypes<'stream, 'b>,
+                _token: sealed::Token,
+                inner: impl for<'p> FnOnce(&[Option<&'p NonNull<[u8]>>]) -> O,
+            ) -> O
+            where
+                Self: 'b,
+            {
+                let ($($arg,)*) = params;
+
+                impl_kernel_parameters! { impl with_async_as_ptx_jit ref ($($arg: $T),*) + (sealed::Token) {
+                    inner(&[$($arg),*])
+                } }
+            }
+
+            fn with_shared_layouts_for_async<'stream, 'b, O>(
+                params: &Self::AsyncHostTypes<'stream, 'b>,
+                _token: sealed::Token,
+                inner: impl FnOnce(&[std::alloc::Layout]) -> O,
+            ) -> O
+            where
+                Self: 'b,
+            {
+                let ($($arg,)*) = params;
+
+                inner(&[$($T::shared_layout_for_async($arg, sealed::Token)),*])
+            }
+
+            fn with_async_as_ffi<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
+                params: Self::AsyncHostTypes<'stream, 'b>,
+                _token: sealed::Token,
+                inner: impl FnOnce(&[*mut core::ffi::c_void]) -> Result<O, E>,
+            ) -> Result<O, E>
+            where
+                Self: 'b,
+            {
+                let ($($arg,)*) = params;
+
+                $(
+                    let mut $arg = $T::async_to_ffi::<E>($arg, sealed::Token)?;
+                )*
+
+                inner(&[$(core::ptr::from_mut(&mut $arg).cast::<core::ffi::c_void>()),*])
+            }
+
+            #[cfg(feature = "emulate")]
+            fn sync_to_emulated(
+                params: Self::SyncHostTypes,
+                _token: sealed::Token,
+            ) -> Self::EmulatedHostTypes {
+                let ($($arg,)*) = params;
+
+                ($($T::sync_to_emulated($arg, sealed::Token),)*)
+            }
+        }
+
+        impl<'stream, 'kernel, Kernel, $($T: CudaKernelParameter),*>
+            KernelSignature<'stream, 'kernel, ($($T,)*)> for Kernel
+        where
+            Kernel: FnOnce(&mut Launcher<'stream, 'kernel, Kernel>, $($T),*),
+        {
+        }
+    };
+    (impl $func:ident () + ($($other:expr),*) $inner:block) => {
+        $inner
+    };
+    (impl $func:ident ($arg0:ident : $T0:ident $(, $arg:ident : $T:ident)*) + ($($other:expr),*) $inner:block) => {
+        $T0::$func($arg0 $(, $other)*, |$arg0: <$T0 as CudaKernelParameter>::AsyncHostType<'stream, '_>| {
+            impl_kernel_parameters! { impl $func ($($arg: $T),*) + ($($other),*) $inner }
+        })
+    };
+    (impl $func:ident ref () + ($($other:expr),*) $inner:block) => {
+        $inner
+    };
+    (impl $func:ident ref ($arg0:ident : $T0:ident $(, $arg:ident : $T:ident)*) + ($($other:expr),*) $inner:block) => {
+        $T0::$func($arg0 $(, $other)*, |$arg0| {
+            impl_kernel_parameters! { impl $func ref ($($arg: $T),*) + ($($other),*) $inner }
+        })
+    };
+    () => {
+        impl_kernel_parameters! { impl tuple () }
+    };
+    ($T0:ident $arg0:ident $(, $T:ident $arg:ident)*) => {
+        impl_kernel_parameters! { $($T $arg),* }
+        impl_kernel_parameters! { impl tuple ($T0 $arg0 $(, $T $arg)*) }
+    };
+}
+
+#[cfg(feature = "host")]
+impl_kernel_parameters! {
+    T1 arg1, T2 arg2, T3 arg3, T4 arg4, T5 arg5, T6 arg6, T7 arg7, T8 arg8,
+    T9 arg9, T10 arg10, T11 arg11, T12 arg12, T13 arg13, T14 arg14, T15 arg15, T16 arg16,
+    T17 arg17, T18 arg18, T19 arg19, T20 arg20, T21 arg21, T22 arg22, T23 arg23, T24 arg24,
+    T25 arg25, T26 arg26, T27 arg27, T28 arg28, T29 arg29, T30 arg30, T31 arg31, T32 arg32
 }
 
 #[cfg(feature = "host")]
@@ -171,11 +447,11 @@ macro_rules! impl_launcher_launch {
         pub fn $launch<$($T: CudaKernelParameter),*>(
             &mut self,
             $($arg: $T::SyncHostType),*
-        ) -> CudaResult<()>
+        ) -> Result<(), Error>
         where
             Kernel: FnOnce(&mut Launcher<'stream, '_, Kernel>, $($T),*),
         {
-            self.kernel.$launch::<$($T),*>(self.stream, &self.config, $($arg),*)
+            self.launch::<($($T,)*)>(($($arg,)*))
         }
 
         #[expect(clippy::missing_errors_doc)]
@@ -196,10 +472,15 @@ macro_rules! impl_launcher_launch {
         where
             Kernel: FnOnce(&mut Launcher<'stream, '_, Kernel>, $($T),*),
         {
+            // emulated kernels only support synchronous launches
+            if cfg!(feature = "emulate") {
+                return Err(Err::from(CudaError::NotSupported));
+            }
+
             #[allow(unused_variables)]
             let stream = self.stream;
 
-            impl_launcher_launch! { impl with_new_async ($($arg: $T),*) + (stream) {
+            impl_kernel_parameters! { impl with_new_async ($($arg: $T),*) + (stream) {
                 inner(self, $($arg),*)
             } }
         }
@@ -209,23 +490,15 @@ macro_rules! impl_launcher_launch {
         pub fn $launch_async<$($T: CudaKernelParameter),*>(
             &mut self,
             $($arg: $T::AsyncHostType<'stream, '_>),*
-        ) -> CudaResult<crate::utils::r#async::Async<
+        ) -> Result<crate::utils::r#async::Async<
             'static, 'stream, (), crate::utils::r#async::NoCompletion,
-        >>
+        >, Error>
         where
             Kernel: FnOnce(&mut Launcher<'stream, '_, Kernel>, $($T),*),
         {
-            self.kernel.$launch_async::<$($T),*>(self.stream, &self.config, $($arg),*)
+            self.launch_async::<($($T,)*)>(($($T::shorten_async($arg, sealed::Token),)*))
         }
     };
-    (impl $func:ident () + ($($other:expr),*) $inner:block) => {
-        $inner
-    };
-    (impl $func:ident ($arg0:ident : $T0:ident $(, $arg:ident : $T:ident)*) + ($($other:expr),*) $inner:block) => {
-        $T0::$func($arg0 $(, $other)*, |$arg0: <$T0 as CudaKernelParameter>::AsyncHostType<'stream, '_>| {
-            impl_launcher_launch! { impl $func ($($arg: $T),*) + ($($other),*) $inner }
-        })
-    };
 }
 
 #[cfg(feature = "host")]
@@ -281,14 +554,89 @@ impl<'stream, 'kernel, Kernel> Launcher<'stream, 'kernel, Kernel> {
         arg1: A, arg2: B, arg3: C, arg4: D, arg5: E, arg6: F, arg7: G, arg8: H, arg9: I, arg10: J,
         arg11: K, arg12: L
     ) => with12_async => launch12_async }
+
+    /// Launches the kernel with the tuple of `params` and synchronises on
+    /// the launch stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] iff converting the `params` or launching the
+    /// kernel fails.
+    pub fn launch<P: CudaKernelParameters>(&mut self, params: P::SyncHostTypes) -> Result<(), Error>
+    where
+        Kernel: for<'k> KernelSignature<'stream, 'k, P>,
+    {
+        self.kernel.launch::<P>(self.stream, &self.config, params)
+    }
+
+    /// Converts the tuple of `params` into their asynchronous representation
+    /// on the launch stream, which is then passed to `inner`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff converting the `params` fails, or the
+    /// error that is returned by `inner`. With the `emulate` feature,
+    /// asynchronous launches are not supported and
+    /// [`CudaError::NotSupported`] is always returned.
+    pub fn with_async<'a, Ok, Err: From<CudaError>, P: CudaKernelParameters>(
+        &'a mut self,
+        params: P::SyncHostTypes,
+        inner: impl FnOnce(&'a mut Self, P::AsyncHostTypes<'stream, '_>) -> Result<Ok, Err>,
+    ) -> Result<Ok, Err>
+    where
+        Kernel: for<'k> KernelSignature<'stream, 'k, P>,
+    {
+        // emulated kernels only support synchronous launches
+        if cfg!(feature = "emulate") {
+            return Err(Err::from(CudaError::NotSupported));
+        }
+
+        let stream = self.stream;
+
+        P::with_new_async(params, stream, |params: P::AsyncHostTypes<'stream, '_>| {
+            inner(self, params)
+        })
+    }
+
+    /// Asynchronously launches the kernel with the tuple of asynchronous
+    /// `params` on the launch stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] iff converting the `params` or launching the
+    /// kernel fails.
+    pub fn launch_async<P: CudaKernelParameters>(
+        &mut self,
+        params: P::AsyncHostTypes<'stream, '_>,
+    ) -> Result<
+        crate::utils::r#async::Async<'static, 'stream, (), crate::utils::r#async::NoCompletion>,
+        Error,
+    >
+    where
+        Kernel: for<'k> KernelSignature<'stream, 'k, P>,
+    {
+        self.kernel
+            .launch_async::<P>(self.stream, &self.config, params)
+    }
 }
 
 #[cfg(feature = "host")]
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LaunchConfig {
-    pub grid: rustacuda::function::GridSize,
-    pub block: rustacuda::function::BlockSize,
+    pub grid: crate::host::driver::function::GridSize,
+    pub block: crate::host::driver::function::BlockSize,
     pub ptx_jit: bool,
+    /// Specialise the kernel for the [`block`](Self::block) size with the
+    /// PTX JIT, which requires the block size with a `.reqntid` directive
+    /// and replaces reads of the block dimensions, e.g. in
+    /// [`Thread::index`](crate::device::thread::Thread::index), with
+    /// constants. Every block size is compiled and cached separately.
+    pub ptx_jit_block_size: bool,
+    /// Launch the kernel cooperatively, which guarantees that all thread
+    /// blocks of the grid are co-resident on the device and allows the
+    /// kernel to use
+    /// [`ThreadBlockGrid::synchronize`](crate::device::thread::ThreadBlockGrid::synchronize).
+    pub cooperative: bool,
 }
 
 #[cfg(feature = "host")]
@@ -302,10 +650,15 @@ pub struct RawPtxKernel {
 impl RawPtxKernel {
     /// # Errors
     ///
-    /// Returns a [`CudaError`] if `ptx` is not a valid PTX source, or it does
-    ///  not contain an entry point named `entry_point`.
-    pub fn new(ptx: &CStr, entry_point: &CStr) -> CudaResult<Self> {
-        let module: Box<Module> = Box::new(Module::load_from_string(ptx)?);
+    /// Returns an [`Error::PtxJit`] if `ptx` is not a valid PTX source, or it
+    ///  does not contain an entry point named `entry_point`.
+    pub fn new(ptx: &CStr, entry_point: &CStr) -> Result<Self, Error> {
+        let ptx_jit_error = |source| Error::PtxJit {
+            entry_point: entry_point.into(),
+            source,
+        };
+
+        let module: Box<Module> = Box::new(Module::load_from_string(ptx).map_err(ptx_jit_error)?);
 
         let function = unsafe { &*std::ptr::from_ref(module.as_ref()) }.get_function(entry_point);
 
@@ -316,7 +669,7 @@ impl RawPtxKernel {
                     std::mem::forget(module);
                 }
 
-                return Err(err);
+                return Err(ptx_jit_error(err));
             },
         };
 
@@ -330,6 +683,10 @@ impl RawPtxKernel {
     pub fn get_function(&self) -> &Function {
         &self.function
     }
+
+    fn get_function_mut(&mut self) -> &mut Function<'static> {
+        &mut self.function
+    }
 }
 
 #[cfg(feature = "host")]
@@ -348,16 +705,25 @@ impl Drop for RawPtxKernel {
     }
 }
 
+/// A [`RawPtxKernel`] that was compiled from a PTX JIT specialisation,
+/// together with the PTX source that it was compiled from.
 #[cfg(feature = "host")]
-pub type PtxKernelConfigure = dyn FnMut(&Function) -> CudaResult<()>;
+struct PtxJITKernel {
+    kernel: RawPtxKernel,
+    ptx: CString,
+    const_loads: Box<[PtxJITConstLoad]>,
+}
 
 #[cfg(feature = "host")]
 #[expect(clippy::module_name_repetitions)]
 pub struct TypedPtxKernel<Kernel> {
     compiler: PtxJITCompiler,
-    ptx_kernel: Option<RawPtxKernel>,
+    ptx_kernels: PtxJITCache<PtxJITKernel>,
+    ptx_jit_disk_cache: Option<PtxJITDiskCache>,
     entry_point: Box<CStr>,
-    configure: Option<Box<PtxKernelConfigure>>,
+    attributes: KernelAttributes,
+    #[cfg(feature = "emulate")]
+    emulate_thread: unsafe fn(NonNull<()>),
     marker: PhantomData<Kernel>,
 }
 
@@ -371,21 +737,11 @@ macro_rules! impl_typed_kernel_launch {
             stream: Stream<'stream>,
             config: &LaunchConfig,
             $($arg: $T::SyncHostType),*
-        ) -> CudaResult<()>
+        ) -> Result<(), Error>
         where
             Kernel: FnOnce(&mut Launcher<'stream, 'kernel, Kernel>, $($T),*),
         {
-            self.$with_async::<(), CudaError, $($T),*>(
-                stream,
-                config,
-                $($arg,)*
-                |kernel, stream, config, $($arg),*| {
-                    let r#async = kernel.$launch_async::<$($T),*>(stream, config, $($arg),*)?;
-
-                    // important: always synchronise here, this function is sync!
-                    r#async.synchronize()
-                },
-            )
+            self.launch::<($($T,)*)>(stream, config, ($($arg,)*))
         }
 
         #[expect(clippy::missing_errors_doc)]
@@ -411,7 +767,12 @@ macro_rules! impl_typed_kernel_launch {
         where
             Kernel: FnOnce(&mut Launcher<'stream, 'kernel, Kernel>, $($T),*),
         {
-            impl_typed_kernel_launch! { impl with_new_async ($($arg: $T),*) + (stream) {
+            // emulated kernels only support synchronous launches
+            if cfg!(feature = "emulate") {
+                return Err(Err::from(CudaError::NotSupported));
+            }
+
+            impl_kernel_parameters! { impl with_new_async ($($arg: $T),*) + (stream) {
                 inner(self, stream, config, $($arg),*)
             } }
         }
@@ -424,97 +785,58 @@ macro_rules! impl_typed_kernel_launch {
             stream: Stream<'stream>,
             config: &LaunchConfig,
             $($arg: $T::AsyncHostType<'stream, '_>),*
-        ) -> CudaResult<crate::utils::r#async::Async<
+        ) -> Result<crate::utils::r#async::Async<
             'static, 'stream, (), crate::utils::r#async::NoCompletion,
-        >>
-        // launch_async does not need to capture its parameters until kernel completion:
-        //  - moved parameters are moved and cannot be used again, deallocation will sync
-        //  - immutably borrowed parameters can be shared across multiple kernel launches
-        //  - mutably borrowed parameters are more tricky:
-        //    - Rust's borrowing rules ensure that a single mutable reference cannot be
-        //      passed into multiple parameters of the kernel (no mutable aliasing)
-        //    - CUDA guarantees that kernels launched on the same stream are executed
-        //      sequentially, so even immediate resubmissions for the same mutable data
-        //      will not have temporally overlapping mutation on the same stream
-        //    - however, we have to guarantee that mutable data cannot be used on several
-        //      different streams at the same time
-        //      - Async::move_to_stream always adds a synchronisation barrier between the
-        //        old and the new stream to ensure that all uses on the old stream happen
-        //        strictly before all uses on the new stream
-        //      - async launches take AsyncProj<&mut HostAndDeviceMutRef<..>>, which either
-        //        captures an Async, which must be moved to a different stream explicitly,
-        //        or contains data that cannot async move to a different stream without
-        //      - any use of a mutable borrow in an async kernel launch adds a sync barrier
-        //        on the launch stream s.t. the borrow is only complete once the kernel has
-        //        completed
+        >, Error>
         where
             Kernel: FnOnce(&mut Launcher<'stream, 'kernel, Kernel>, $($T),*),
         {
-            let function = if config.ptx_jit {
-                impl_typed_kernel_launch! { impl with_async_as_ptx_jit ref ($($arg: $T),*) + (sealed::Token) {
-                    self.compile_with_ptx_jit_args(Some(&[$($arg),*]))
-                } }?
-            } else {
-                self.compile_with_ptx_jit_args(None)?
-            };
-
-            #[allow(unused_mut)]
-            let mut shared_memory_size = crate::utils::shared::SharedMemorySize::new();
-            $(
-                shared_memory_size.add($T::shared_layout_for_async(&$arg, sealed::Token));
-            )*
-            let Ok(shared_memory_size) = u32::try_from(shared_memory_size.total()) else {
-                // FIXME: this should really be InvalidConfiguration = 9
-                return Err(CudaError::LaunchOutOfResources)
-            };
-
-            unsafe { stream.launch(
-                function,
-                config.grid.clone(),
-                config.block.clone(),
-                shared_memory_size,
-                &[
-                    $(core::ptr::from_mut(
-                        &mut $T::async_to_ffi($arg, sealed::Token)?
-                    ).cast::<core::ffi::c_void>()),*
-                ],
-            ) }?;
-
-            crate::utils::r#async::Async::pending(
-                (), stream, crate::utils::r#async::NoCompletion,
+            self.launch_async::<($($T,)*)>(
+                stream,
+                config,
+                ($($T::shorten_async($arg, sealed::Token),)*),
             )
         }
     };
-    (impl $func:ident () + ($($other:expr),*) $inner:block) => {
-        $inner
-    };
-    (impl $func:ident ($arg0:ident : $T0:ident $(, $arg:ident : $T:ident)*) + ($($other:expr),*) $inner:block) => {
-        $T0::$func($arg0 $(, $other)*, |$arg0: <$T0 as CudaKernelParameter>::AsyncHostType<'stream, '_>| {
-            impl_typed_kernel_launch! { impl $func ($($arg: $T),*) + ($($other),*) $inner }
-        })
-    };
-    (impl $func:ident ref () + ($($other:expr),*) $inner:block) => {
-        $inner
-    };
-    (impl $func:ident ref ($arg0:ident : $T0:ident $(, $arg:ident : $T:ident)*) + ($($other:expr),*) $inner:block) => {
-        $T0::$func(&$arg0 $(, $other)*, |$arg0| {
-            impl_typed_kernel_launch! { impl $func ref ($($arg: $T),*) + ($($other),*) $inner }
-        })
-    };
 }
 
 #[cfg(feature = "host")]
 impl<Kernel> TypedPtxKernel<Kernel> {
+    /// The default number of PTX JIT specialisations of a kernel that are
+    /// kept loaded.
+    pub const DEFAULT_PTX_JIT_CACHE_CAPACITY: NonZeroUsize =
+        PtxJITCache::<PtxJITKernel>::DEFAULT_CAPACITY;
+
+    /// Creates a new kernel from its [`CompiledKernelPtx`], which is
+    /// configured with the `attributes` every time that it is (re)compiled.
+    ///
+    /// If the kernel was compiled for several target architectures, the
+    /// [`KernelPtxVariant`] that best matches the compute capability of the
+    /// current context's device is selected. Without a current context, or
+    /// if no variant matches, the PTX for the lowest architecture is used.
+    ///
+    /// Up to [`Self::DEFAULT_PTX_JIT_CACHE_CAPACITY`] PTX JIT specialisations
+    /// of the kernel are kept loaded, which can be changed with
+    /// [`Self::set_ptx_jit_cache_capacity`].
     #[must_use]
-    pub fn new<T: CompiledKernelPtx<Kernel>>(configure: Option<Box<PtxKernelConfigure>>) -> Self {
-        let compiler = PtxJITCompiler::new(T::get_ptx());
+    pub fn new<T: CompiledKernelPtx<Kernel>>(attributes: KernelAttributes) -> Self {
+        let ptx = current_compute_capability()
+            .and_then(|compute_capability| {
+                KernelPtxVariant::select(T::get_ptx_variants(), compute_capability)
+            })
+            .map_or_else(T::get_ptx, |variant| variant.ptx);
+
+        let compiler = PtxJITCompiler::new(ptx);
         let entry_point = CString::from(T::get_entry_point()).into_boxed_c_str();
 
         Self {
             compiler,
-            ptx_kernel: None,
+            ptx_kernels: PtxJITCache::new(Self::DEFAULT_PTX_JIT_CACHE_CAPACITY),
+            ptx_jit_disk_cache: None,
             entry_point,
-            configure,
+            attributes,
+            #[cfg(feature = "emulate")]
+            emulate_thread: T::emulate_thread,
             marker: PhantomData::<Kernel>,
         }
     }
@@ -574,39 +896,415 @@ impl<Kernel> TypedPtxKernel<Kernel> {
         arg11: K, arg12: L
     ) => with12_async => launch12_async }
 
+    /// Launches the kernel with the tuple of `params` on the `stream` and
+    /// synchronises on it.
+    ///
+    /// With the `emulate` feature, the kernel is instead run on the host by
+    /// emulating every thread of the launch grid. Only the kernel execution
+    /// is emulated: [`Self::new`] still queries the current context's device,
+    /// and the `stream` must still wrap a driver stream that is not being
+    /// captured. Without the `mock` feature, emulated launches thus still
+    /// require a CUDA device and context. With it, the mock driver provides
+    /// both, so that kernels can be emulated on machines without a GPU.
+    ///
     /// # Errors
     ///
-    /// Returns a [`CudaError`] if the [`CompiledKernelPtx`] provided to
-    /// [`Self::new`] is not a valid PTX source or does not contain the
-    /// entry point it declares.
-    fn compile_with_ptx_jit_args(
-        &mut self,
-        arguments: Option<&[Option<&NonNull<[u8]>>]>,
-    ) -> CudaResult<&Function> {
-        let ptx_jit = self.compiler.with_arguments(arguments);
+    /// Returns an [`Error`] iff converting the `params`, compiling the
+    /// kernel, or launching it fails.
+    pub fn launch<'kernel, 'stream, P: CudaKernelParameters>(
+        &'kernel mut self,
+        stream: Stream<'stream>,
+        config: &LaunchConfig,
+        params: P::SyncHostTypes,
+    ) -> Result<(), Error>
+    where
+        Kernel: KernelSignature<'stream, 'kernel, P>,
+    {
+        // A synchronous launch cannot wait for work that is being captured
+        if crate::host::graph::is_captured(&stream) {
+            return Err(Error::StreamCaptureUnsupported);
+        }
 
-        let kernel_jit = match (&mut self.ptx_kernel, ptx_jit) {
-            (Some(ptx_kernel), PtxJITResult::Cached(_)) => ptx_kernel.get_function(),
-            (ptx_kernel, PtxJITResult::Cached(ptx_cstr) | PtxJITResult::Recomputed(ptx_cstr)) => {
-                let recomputed_ptx_kernel = RawPtxKernel::new(ptx_cstr, &self.entry_point)?;
+        #[cfg(feature = "emulate")]
+        {
+            let _ = stream;
+
+            let params = P::sync_to_emulated(params, sealed::Token);
+            let emulate_thread = self.emulate_thread;
+
+            crate::device::emulate::launch_grid(config, &|| {
+                // Safety:
+                // - the KernelSignature bound ensures that the params match the kernel
+                //   signature that emulate_thread expects
+                // - launch_grid only calls this closure once per emulated thread
+                unsafe { emulate_thread(NonNull::from(&params).cast()) }
+            })
+        }
 
-                // Replace the existing compiled kernel, drop the old one
-                let ptx_kernel = ptx_kernel.insert(recomputed_ptx_kernel);
+        #[cfg(not(feature = "emulate"))]
+        {
+            self.with_async::<(), Error, P>(
+                stream,
+                config,
+                params,
+                |kernel, stream, config, params| {
+                    let r#async = kernel.launch_async::<P>(stream, config, params)?;
 
-                let function = ptx_kernel.get_function();
+                    // important: always synchronise here, this function is sync!
+                    r#async.synchronize()
+                },
+            )
+        }
+    }
 
-                if let Some(configure) = self.configure.as_mut() {
-                    configure(function)?;
+    /// Converts the tuple of `params` into their asynchronous representation
+    /// on the `stream`, which is then passed to `inner`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff converting the `params` fails, or the
+    /// error that is returned by `inner`. With the `emulate` feature,
+    /// asynchronous launches are not supported and
+    /// [`CudaError::NotSupported`] is always returned.
+    pub fn with_async<'kernel, 'stream, Ok, Err: From<CudaError>, P: CudaKernelParameters>(
+        &'kernel mut self,
+        stream: Stream<'stream>,
+        config: &LaunchConfig,
+        params: P::SyncHostTypes,
+        inner: impl FnOnce(
+            &'kernel mut Self,
+            Stream<'stream>,
+            &LaunchConfig,
+            P::AsyncHostTypes<'stream, '_>,
+        ) -> Result<Ok, Err>,
+    ) -> Result<Ok, Err>
+    where
+        Kernel: KernelSignature<'stream, 'kernel, P>,
+    {
+        // emulated kernels only support synchronous launches
+        if cfg!(feature = "emulate") {
+            return Err(Err::from(CudaError::NotSupported));
+        }
+
+        P::with_new_async(params, stream, |params: P::AsyncHostTypes<'stream, '_>| {
+            inner(self, stream, config, params)
+        })
+    }
+
+    /// Asynchronously launches the kernel with the tuple of asynchronous
+    /// `params` on the `stream`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] iff converting the `params`, compiling the
+    /// kernel, or launching it fails, or if the `config` exceeds the limits
+    /// of the current device. With the `emulate` feature, asynchronous
+    /// launches are not supported and [`CudaError::NotSupported`] is always
+    /// returned.
+    pub fn launch_async<'kernel, 'stream, P: CudaKernelParameters>(
+        &'kernel mut self,
+        stream: Stream<'stream>,
+        config: &LaunchConfig,
+        params: P::AsyncHostTypes<'stream, '_>,
+    ) -> Result<
+        crate::utils::r#async::Async<'static, 'stream, (), crate::utils::r#async::NoCompletion>,
+        Error,
+    >
+    // launch_async does not need to capture its parameters until kernel completion:
+    //  - moved parameters are moved and cannot be used again, deallocation will sync
+    //  - immutably borrowed parameters can be shared across multiple kernel launches
+    //  - mutably borrowed parameters are more tricky:
+    //    - Rust's borrowing rules ensure that a single mutable reference cannot be passed into
+    //      multiple parameters of the kernel (no mutable aliasing)
+    //    - CUDA guarantees that kernels launched on the same stream are executed sequentially, so
+    //      even immediate resubmissions for the same mutable data will not have temporally
+    //      overlapping mutation on the same stream
+    //    - however, we have to guarantee that mutable data cannot be used on several different
+    //      streams at the same time
+    //      - Async::move_to_stream always adds a synchronisation barrier between the old and the
+    //        new stream to ensure that all uses on the old stream happen strictly before all uses
+    //        on the new stream
+    //      - async launches take AsyncProj<&mut HostAndDeviceMutRef<..>>, which either captures an
+    //        Async, which must be moved to a different stream explicitly, or contains data that
+    //        cannot async move to a different stream without
+    //      - any use of a mutable borrow in an async kernel launch adds a sync barrier on the
+    //        launch stream s.t. the borrow is only complete once the kernel has completed
+    where
+        Kernel: KernelSignature<'stream, 'kernel, P>,
+    {
+        // emulated kernels only support synchronous launches
+        if cfg!(feature = "emulate") {
+            return Err(Error::Driver(CudaError::NotSupported));
+        }
+
+        let block_size =
+            config
+                .ptx_jit_block_size
+                .then_some([config.block.x, config.block.y, config.block.z]);
+
+        let function = if config.ptx_jit {
+            P::with_async_as_ptx_jit(&params, sealed::Token, |arguments| {
+                self.compile_with_ptx_jit_args(Some(arguments), block_size)
+            })?
+        } else {
+            self.compile_with_ptx_jit_args(None, block_size)?
+        };
+
+        let shared_memory_size =
+            P::with_shared_layouts_for_async(&params, sealed::Token, |layouts| {
+                let mut shared_memory_size = crate::utils::shared::SharedMemorySize::new();
+                for layout in layouts {
+                    shared_memory_size.add(*layout);
                 }
+                shared_memory_size.total()
+            });
+        let shared_memory_size = config.validate(function, shared_memory_size)?;
 
-                function
+        if config.cooperative {
+            config.check_cooperative_launch(function, shared_memory_size)?;
+        }
+
+        P::with_async_as_ffi(params, sealed::Token, |arguments| unsafe {
+            if config.cooperative {
+                stream.launch_cooperative(
+                    function,
+                    config.grid.clone(),
+                    config.block.clone(),
+                    shared_memory_size,
+                    arguments,
+                )
+            } else {
+                stream.launch(
+                    function,
+                    config.grid.clone(),
+                    config.block.clone(),
+                    shared_memory_size,
+                    arguments,
+                )
+            }
+        })?;
+
+        crate::utils::r#async::Async::pending((), stream, crate::utils::r#async::NoCompletion)
+            .map_err(Error::from)
+    }
+
+    /// Returns the [`KernelAttributes`] that this kernel is configured with.
+    #[must_use]
+    pub const fn attributes(&self) -> &KernelAttributes {
+        &self.attributes
+    }
+
+    /// Reconfigures this kernel with new `attributes`, which are applied
+    /// the next time that the kernel is compiled.
+    pub fn set_attributes(&mut self, attributes: KernelAttributes) {
+        if attributes != self.attributes {
+            // Force a recompilation with the new attributes
+            self.ptx_kernels.clear();
+        }
+
+        self.attributes = attributes;
+    }
+
+    /// Returns the maximum number of PTX JIT specialisations of this kernel
+    /// that are kept loaded.
+    #[must_use]
+    pub const fn ptx_jit_cache_capacity(&self) -> NonZeroUsize {
+        self.ptx_kernels.capacity()
+    }
+
+    /// Changes the maximum number of PTX JIT specialisations of this kernel
+    /// that are kept loaded, unloading the least recently used ones if more
+    /// are currently loaded.
+    pub fn set_ptx_jit_cache_capacity(&mut self, capacity: NonZeroUsize) {
+        self.ptx_kernels.set_capacity(capacity);
+    }
+
+    /// Returns the hit, miss, and eviction statistics of the cache of PTX
+    /// JIT specialisations of this kernel.
+    #[must_use]
+    pub const fn ptx_jit_cache_stats(&self) -> PtxJITCacheStats {
+        self.ptx_kernels.stats()
+    }
+
+    /// Returns the [`PtxJITDiskCache`] that PTX JIT specialisations of this
+    /// kernel are persisted in, if any.
+    #[must_use]
+    pub const fn ptx_jit_disk_cache(&self) -> Option<&PtxJITDiskCache> {
+        self.ptx_jit_disk_cache.as_ref()
+    }
+
+    /// Persists future PTX JIT specialisations of this kernel in the
+    /// `disk_cache`, or stops persisting them if it is [`None`].
+    pub fn set_ptx_jit_disk_cache(&mut self, disk_cache: Option<PtxJITDiskCache>) {
+        self.ptx_jit_disk_cache = disk_cache;
+    }
+
+    /// Returns the patterns in the PTX source of this kernel that the PTX
+    /// JIT does not recognise, and which prevent some loads from
+    /// [`PtxJit`](param::PtxJit) parameters from being specialised.
+    #[must_use]
+    pub const fn ptx_jit_diagnostics(&self) -> &[PtxJITDiagnostic] {
+        self.compiler.diagnostics()
+    }
+
+    /// Returns a [`PtxJITReport`] of the most recently used PTX JIT
+    /// specialisation of this kernel, which e.g. shows which loads from
+    /// [`PtxJit`](param::PtxJit) parameters were replaced with constants, or
+    /// [`None`] if the kernel has not been compiled yet.
+    #[must_use]
+    pub fn ptx_jit_report(&self) -> Option<PtxJITReport<'_>> {
+        let ptx_kernel = self.ptx_kernels.most_recent()?;
+
+        Some(PtxJITReport {
+            ptx: &ptx_kernel.ptx,
+            const_loads: &ptx_kernel.const_loads,
+            cache_lookup: self.ptx_kernels.last_lookup()?,
+        })
+    }
+
+    /// Queries the [`EffectiveKernelAttributes`] of this kernel, which is
+    /// compiled first if it has not yet been compiled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] iff compiling the kernel or querying its
+    /// attributes fails.
+    pub fn effective_attributes(&mut self) -> Result<EffectiveKernelAttributes, Error> {
+        EffectiveKernelAttributes::of_function(self.get_or_compile_function()?).map_err(Error::from)
+    }
+
+    /// Returns the most recently compiled [`Function`] of this kernel, or
+    /// compiles it without any PTX JIT arguments if it has not yet been
+    /// compiled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::PtxJit`] if the [`CompiledKernelPtx`] provided to
+    /// [`Self::new`] is not a valid PTX source or does not contain the
+    /// entry point it declares.
+    fn get_or_compile_function(&mut self) -> Result<&Function, Error> {
+        let Self {
+            ref compiler,
+            ref mut ptx_kernels,
+            ref ptx_jit_disk_cache,
+            ref entry_point,
+            ref attributes,
+            ..
+        } = *self;
+
+        let ptx_kernel = ptx_kernels.most_recent_or_try_insert_with(|arguments, block_size| {
+            specialise_ptx_kernel(
+                compiler,
+                ptx_jit_disk_cache.as_ref(),
+                attributes,
+                entry_point,
+                arguments,
+                block_size,
+            )
+        })?;
+
+        Ok(ptx_kernel.kernel.get_function())
+    }
+
+    /// Returns the [`Function`] of this kernel that is specialised for the
+    /// PTX JIT `arguments` and `block_size`, which is compiled if it is not
+    /// cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::PtxJit`] if the [`CompiledKernelPtx`] provided to
+    /// [`Self::new`] is not a valid PTX source or does not contain the
+    /// entry point it declares, or another [`Error`] if its
+    /// [`KernelAttributes`] are invalid.
+    fn compile_with_ptx_jit_args(
+        &mut self,
+        arguments: Option<&[Option<&NonNull<[u8]>>]>,
+        block_size: Option<[u32; 3]>,
+    ) -> Result<&Function, Error> {
+        let Self {
+            ref compiler,
+            ref mut ptx_kernels,
+            ref ptx_jit_disk_cache,
+            ref entry_point,
+            ref attributes,
+            ..
+        } = *self;
+
+        let ptx_kernel = ptx_kernels.get_or_try_insert_with(
+            arguments,
+            block_size,
+            |arguments, block_size| {
+                specialise_ptx_kernel(
+                    compiler,
+                    ptx_jit_disk_cache.as_ref(),
+                    attributes,
+                    entry_point,
+                    arguments,
+                    block_size,
+                )
             },
-        };
+        )?;
 
-        Ok(kernel_jit)
+        Ok(ptx_kernel.kernel.get_function())
     }
 }
 
+/// Returns the `(major, minor)` compute capability of the current context's
+/// device, if there is one.
+#[cfg(feature = "host")]
+fn current_compute_capability() -> Option<(u32, u32)> {
+    let device = CurrentContext::get_device().ok()?;
+
+    let get = |attr| {
+        device
+            .get_attribute(attr)
+            .ok()
+            .and_then(|value| u32::try_from(value).ok())
+    };
+
+    Some((
+        get(DeviceAttribute::ComputeCapabilityMajor)?,
+        get(DeviceAttribute::ComputeCapabilityMinor)?,
+    ))
+}
+
+/// Compiles the PTX of the kernel, specialised for the PTX JIT `arguments`
+/// and `block_size` and configured with its `attributes`, and keeps the
+/// compiled PTX source.
+///
+/// # Errors
+///
+/// Returns an [`Error::PtxJit`] if the PTX source is not valid or does not
+/// contain the `entry_point`, or another [`Error`] if the `attributes` are
+/// invalid.
+#[cfg(feature = "host")]
+fn specialise_ptx_kernel(
+    compiler: &PtxJITCompiler,
+    disk_cache: Option<&PtxJITDiskCache>,
+    attributes: &KernelAttributes,
+    entry_point: &CStr,
+    arguments: Option<&[Option<Box<[u8]>>]>,
+    block_size: Option<[u32; 3]>,
+) -> Result<PtxJITKernel, Error> {
+    let ptx = disk_cache.map_or_else(
+        || compiler.specialise(arguments, block_size),
+        |disk_cache| disk_cache.specialise(compiler, entry_point, arguments, block_size),
+    );
+
+    let ptx = attributes
+        .configure_ptx(&ptx, entry_point)?
+        .unwrap_or_else(|| ptx.into_owned());
+
+    let mut kernel = RawPtxKernel::new(&ptx, entry_point)?;
+    attributes.apply(kernel.get_function_mut())?;
+
+    Ok(PtxJITKernel {
+        kernel,
+        ptx,
+        const_loads: compiler.const_loads(arguments),
+    })
+}
+
 #[cfg(feature = "host")]
 /// # Safety
 ///
@@ -618,6 +1316,34 @@ impl<Kernel> TypedPtxKernel<Kernel> {
 /// This trait should not be implemented manually &ndash; use the
 /// [`kernel`] macro instead.
 pub unsafe trait CompiledKernelPtx<Kernel> {
+    #[doc(hidden)]
+    const COMPILED_PTX: (&'static CStr, KernelResources, &'static [KernelPtxVariant]);
+
+    /// The resources that are used by the compiled kernel, as reported by the
+    /// PTX compiler for its lowest target architecture, including its local
+    /// memory, register spills, and constant memory usage.
+    ///
+    /// Their [`KernelResources::usage`] can be used with the [`occupancy`]
+    /// model to size kernel launches without a CUDA device, e.g. at build
+    /// time or in tests.
+    const RESOURCES: KernelResources;
+
+    /// Returns the PTX source code for the lowest target architecture.
     fn get_ptx() -> &'static CStr;
+
+    /// Returns all PTX variants of the kernel, one per target architecture,
+    /// ordered by their compute capability.
+    fn get_ptx_variants() -> &'static [KernelPtxVariant];
+
     fn get_entry_point() -> &'static CStr;
+
+    #[doc(hidden)]
+    #[cfg(feature = "emulate")]
+    /// # Safety
+    ///
+    /// `params` must point to a tuple of the
+    /// [`CudaKernelParameter::EmulatedHostType`]s of all of the `Kernel`'s
+    /// parameters, and this method must only be called once per thread of an
+    /// emulated kernel launch.
+    unsafe fn emulate_thread(params: NonNull<()>);
 }
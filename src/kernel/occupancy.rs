@@ -0,0 +1,654 @@
+//! Pure-Rust model of the theoretical occupancy of CUDA kernels.
+//!
+//! The model follows the CUDA occupancy calculator. It only requires the
+//! [`KernelResourceUsage`] of a kernel, e.g. from the
+//! [`usage`](super::KernelResources::usage) of the
+//! [`CompiledKernelPtx::RESOURCES`](super::CompiledKernelPtx::RESOURCES),
+//! and the resource limits of an [`SmArchitecture`]. Therefore, it can be
+//! used at build time and in tests, where no CUDA device is available.
+
+#[cfg(feature = "host")]
+use crate::{
+    host::driver::{
+        context::CurrentContext,
+        device::{Device, DeviceAttribute},
+        error::{CudaError, CudaResult},
+        function::{BlockSize, Function, FunctionAttribute, GridSize},
+    },
+    Error,
+};
+
+#[cfg(feature = "host")]
+use super::{LaunchConfig, TypedPtxKernel};
+
+/// Resources that are used by a compiled kernel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KernelResourceUsage {
+    /// Number of registers that are used by every thread
+    pub registers_per_thread: u32,
+    /// Bytes of statically allocated shared memory per block
+    pub static_shared_memory: u32,
+    /// Maximum number of threads per block that the kernel can be launched
+    /// with
+    pub max_threads_per_block: u32,
+}
+
+/// Resource limits of a streaming multiprocessor (SM) architecture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SmArchitecture {
+    /// The `(major, minor)` compute capability of the architecture
+    pub compute_capability: (u32, u32),
+    /// Number of threads per warp
+    pub warp_size: u32,
+    /// Maximum number of threads per block
+    pub max_threads_per_block: u32,
+    /// Maximum number of resident threads per multiprocessor
+    pub max_threads_per_multiprocessor: u32,
+    /// Maximum number of resident blocks per multiprocessor
+    pub max_blocks_per_multiprocessor: u32,
+    /// Maximum number of registers per block
+    pub max_registers_per_block: u32,
+    /// Number of registers per multiprocessor
+    pub max_registers_per_multiprocessor: u32,
+    /// Granularity (per warp) in which registers are allocated
+    pub register_allocation_unit: u32,
+    /// Maximum bytes of shared memory per block
+    pub max_shared_memory_per_block: u32,
+    /// Bytes of shared memory per multiprocessor
+    pub max_shared_memory_per_multiprocessor: u32,
+    /// Bytes of shared memory that are reserved by the system per block
+    pub reserved_shared_memory_per_block: u32,
+    /// Granularity (in bytes) in which shared memory is allocated
+    pub shared_memory_allocation_unit: u32,
+}
+
+/// The theoretical occupancy of a kernel launch on an [`SmArchitecture`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Occupancy {
+    /// Number of threads per block
+    pub block_size: u32,
+    /// Number of blocks that can be resident on a multiprocessor at once
+    pub active_blocks_per_multiprocessor: u32,
+    /// Number of warps that can be resident on a multiprocessor at once
+    pub active_warps_per_multiprocessor: u32,
+    /// Maximum number of warps that can be resident on a multiprocessor
+    pub max_warps_per_multiprocessor: u32,
+}
+
+impl Occupancy {
+    /// Returns the ratio of active warps to the maximum number of warps per
+    /// multiprocessor, which is in `[0.0, 1.0]`.
+    #[must_use]
+    pub fn ratio(&self) -> f64 {
+        f64::from(self.active_warps_per_multiprocessor)
+            / f64::from(self.max_warps_per_multiprocessor)
+    }
+}
+
+macro_rules! sm_architecture {
+    (
+        ($major:literal, $minor:literal): threads =
+        $threads:literal,blocks =
+        $blocks:literal,registers =
+        ($block_registers:literal, $registers:literal),shared =
+        ($block_shared:literal, $shared:literal, $reserved:literal, $shared_unit:literal)
+    ) => {
+        SmArchitecture {
+            compute_capability: ($major, $minor),
+            warp_size: 32,
+            max_threads_per_block: 1024,
+            max_threads_per_multiprocessor: $threads,
+            max_blocks_per_multiprocessor: $blocks,
+            max_registers_per_block: $block_registers * 1024,
+            max_registers_per_multiprocessor: $registers * 1024,
+            register_allocation_unit: 256,
+            max_shared_memory_per_block: $block_shared * 1024,
+            max_shared_memory_per_multiprocessor: $shared * 1024,
+            reserved_shared_memory_per_block: $reserved,
+            shared_memory_allocation_unit: $shared_unit,
+        }
+    };
+}
+
+impl SmArchitecture {
+    /// The limits of all known SM architectures, ordered by their compute
+    /// capability.
+    pub const ALL: &'static [Self] = &[
+        sm_architecture!((5, 0): threads = 2048, blocks = 32,
+            registers = (64, 64), shared = (48, 64, 0, 256)),
+        sm_architecture!((5, 2): threads = 2048, blocks = 32,
+            registers = (64, 64), shared = (48, 96, 0, 256)),
+        sm_architecture!((5, 3): threads = 2048, blocks = 32,
+            registers = (32, 64), shared = (48, 64, 0, 256)),
+        sm_architecture!((6, 0): threads = 2048, blocks = 32,
+            registers = (64, 64), shared = (48, 64, 0, 256)),
+        sm_architecture!((6, 1): threads = 2048, blocks = 32,
+            registers = (64, 64), shared = (48, 96, 0, 256)),
+        sm_architecture!((6, 2): threads = 2048, blocks = 32,
+            registers = (64, 64), shared = (48, 64, 0, 256)),
+        sm_architecture!((7, 0): threads = 2048, blocks = 32,
+            registers = (64, 64), shared = (96, 96, 0, 256)),
+        sm_architecture!((7, 2): threads = 2048, blocks = 32,
+            registers = (64, 64), shared = (96, 96, 0, 256)),
+        sm_architecture!((7, 5): threads = 1024, blocks = 16,
+            registers = (64, 64), shared = (64, 64, 0, 256)),
+        sm_architecture!((8, 0): threads = 2048, blocks = 32,
+            registers = (64, 64), shared = (163, 164, 1024, 128)),
+        sm_architecture!((8, 6): threads = 1536, blocks = 16,
+            registers = (64, 64), shared = (99, 100, 1024, 128)),
+        sm_architecture!((8, 7): threads = 1536, blocks = 16,
+            registers = (64, 64), shared = (163, 164, 1024, 128)),
+        sm_architecture!((8, 9): threads = 1536, blocks = 24,
+            registers = (64, 64), shared = (99, 100, 1024, 128)),
+        sm_architecture!((9, 0): threads = 2048, blocks = 32,
+            registers = (64, 64), shared = (227, 228, 1024, 128)),
+    ];
+
+    /// Returns the limits of the SM architecture with the `major.minor`
+    /// compute capability, or [`None`] if the architecture is unknown.
+    #[must_use]
+    pub const fn from_compute_capability(major: u32, minor: u32) -> Option<Self> {
+        let mut i = 0;
+
+        while i < Self::ALL.len() {
+            #[expect(clippy::indexing_slicing)] // i < Self::ALL.len()
+            let arch = Self::ALL[i];
+
+            if arch.compute_capability.0 == major && arch.compute_capability.1 == minor {
+                return Some(arch);
+            }
+
+            i += 1;
+        }
+
+        None
+    }
+
+    /// Computes the theoretical [`Occupancy`] of a kernel with the resource
+    /// `usage` that is launched with `block_size` threads and
+    /// `dynamic_shared_memory` bytes of dynamic shared memory per block.
+    #[must_use]
+    pub const fn occupancy(
+        &self,
+        usage: &KernelResourceUsage,
+        dynamic_shared_memory: u32,
+        block_size: u32,
+    ) -> Occupancy {
+        let max_warps_per_multiprocessor = self.max_threads_per_multiprocessor / self.warp_size;
+
+        let active_blocks_per_multiprocessor =
+            self.active_blocks_per_multiprocessor(usage, dynamic_shared_memory, block_size);
+
+        Occupancy {
+            block_size,
+            active_blocks_per_multiprocessor,
+            active_warps_per_multiprocessor: active_blocks_per_multiprocessor
+                * block_size.div_ceil(self.warp_size),
+            max_warps_per_multiprocessor,
+        }
+    }
+
+    /// Returns the block size with the highest theoretical [`Occupancy`] for
+    /// a kernel with the resource `usage` that is launched with
+    /// `dynamic_shared_memory` bytes of dynamic shared memory per block.
+    ///
+    /// Among equally good block sizes, larger blocks are preferred. Only
+    /// multiples of the warp size are considered, unless the kernel cannot
+    /// be launched with a full warp per block. Returns [`None`] if no block
+    /// of the kernel fits onto a multiprocessor.
+    #[must_use]
+    pub const fn max_occupancy(
+        &self,
+        usage: &KernelResourceUsage,
+        dynamic_shared_memory: u32,
+    ) -> Option<Occupancy> {
+        let max_block_size = if self.max_threads_per_block < usage.max_threads_per_block {
+            self.max_threads_per_block
+        } else {
+            usage.max_threads_per_block
+        };
+
+        let mut best: Option<Occupancy> = None;
+
+        let mut block_size = max_block_size - (max_block_size % self.warp_size);
+
+        if block_size == 0 {
+            block_size = max_block_size;
+        }
+
+        while block_size > 0 {
+            let occupancy = self.occupancy(usage, dynamic_shared_memory, block_size);
+
+            let is_better = match best {
+                None => occupancy.active_blocks_per_multiprocessor > 0,
+                Some(best) => {
+                    occupancy.active_warps_per_multiprocessor > best.active_warps_per_multiprocessor
+                },
+            };
+
+            if is_better {
+                best = Some(occupancy);
+            }
+
+            block_size = block_size.saturating_sub(self.warp_size);
+        }
+
+        best
+    }
+
+    const fn active_blocks_per_multiprocessor(
+        &self,
+        usage: &KernelResourceUsage,
+        dynamic_shared_memory: u32,
+        block_size: u32,
+    ) -> u32 {
+        if block_size == 0
+            || block_size > self.max_threads_per_block
+            || block_size > usage.max_threads_per_block
+        {
+            return 0;
+        }
+
+        let warps_per_block = block_size.div_ceil(self.warp_size);
+
+        let limit_by_warps =
+            (self.max_threads_per_multiprocessor / self.warp_size) / warps_per_block;
+
+        let registers_per_warp = (usage.registers_per_thread * self.warp_size)
+            .next_multiple_of(self.register_allocation_unit);
+        let registers_per_block = registers_per_warp * warps_per_block;
+        let limit_by_registers = if registers_per_block > self.max_registers_per_block {
+            0
+        } else if registers_per_block == 0 {
+            u32::MAX
+        } else {
+            self.max_registers_per_multiprocessor / registers_per_block
+        };
+
+        let shared_memory_per_block = usage
+            .static_shared_memory
+            .saturating_add(dynamic_shared_memory);
+        let limit_by_shared_memory = if shared_memory_per_block > self.max_shared_memory_per_block {
+            0
+        } else {
+            let shared_memory_per_block = (shared_memory_per_block
+                + self.reserved_shared_memory_per_block)
+                .next_multiple_of(self.shared_memory_allocation_unit);
+
+            if shared_memory_per_block == 0 {
+                u32::MAX
+            } else {
+                self.max_shared_memory_per_multiprocessor / shared_memory_per_block
+            }
+        };
+
+        let mut limit = self.max_blocks_per_multiprocessor;
+        if limit_by_warps < limit {
+            limit = limit_by_warps;
+        }
+        if limit_by_registers < limit {
+            limit = limit_by_registers;
+        }
+        if limit_by_shared_memory < limit {
+            limit = limit_by_shared_memory;
+        }
+        limit
+    }
+}
+
+#[cfg(feature = "host")]
+impl LaunchConfig {
+    /// The default number of threads per block that is used by
+    /// [`LaunchConfig::for_elements`].
+    pub const DEFAULT_BLOCK_SIZE: u32 = 256;
+
+    /// Creates a one-dimensional [`LaunchConfig`] with
+    /// [`Self::DEFAULT_BLOCK_SIZE`] threads per block and just enough blocks
+    /// to launch one thread per element for `n` elements.
+    ///
+    /// Since a kernel cannot be launched with an empty grid, at least one
+    /// block is launched even if `n` is zero.
+    #[must_use]
+    pub const fn for_elements(n: u32) -> Self {
+        Self {
+            grid: GridSize {
+                x: blocks_for_elements(n, Self::DEFAULT_BLOCK_SIZE),
+                y: 1,
+                z: 1,
+            },
+            block: BlockSize {
+                x: Self::DEFAULT_BLOCK_SIZE,
+                y: 1,
+                z: 1,
+            },
+            ptx_jit: false,
+            ptx_jit_block_size: false,
+            cooperative: false,
+        }
+    }
+
+    /// Creates a one-dimensional [`LaunchConfig`] that maximises the
+    /// theoretical occupancy of the `kernel` on the current device.
+    ///
+    /// The block size is chosen based on the registers and static shared
+    /// memory that are used by the compiled `kernel`, together with the
+    /// `dynamic_shared_memory` (in bytes) that its
+    /// [`ThreadBlockSharedSlice`](crate::utils::shared::ThreadBlockSharedSlice)
+    /// parameters require per block. The grid size is chosen such that every
+    /// multiprocessor is filled with the maximum number of concurrently
+    /// active blocks, i.e. the kernel should use a grid-stride loop, but no
+    /// more blocks are launched than are needed for one thread per element
+    /// for `n` elements. Since all blocks can be co-resident, the returned
+    /// config can also be used for a
+    /// [`cooperative`](LaunchConfig::cooperative) launch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] iff the `kernel` cannot be compiled, the
+    /// resource usage of the `kernel` or the attributes of the current device
+    /// cannot be queried, or if not even a single block of the `kernel` fits
+    /// onto a multiprocessor of the current device.
+    pub fn max_occupancy<Kernel>(
+        kernel: &mut TypedPtxKernel<Kernel>,
+        n: u32,
+        dynamic_shared_memory: usize,
+    ) -> Result<Self, Error> {
+        let function = kernel.get_or_compile_function()?;
+        let usage = KernelResourceUsage::of_function(function)?;
+
+        let device = CurrentContext::get_device()?;
+        let arch = SmArchitecture::of_device(device)?;
+        let multiprocessors =
+            attribute(device.get_attribute(DeviceAttribute::MultiprocessorCount)?)?;
+
+        let Some(occupancy) = u32::try_from(dynamic_shared_memory)
+            .ok()
+            .and_then(|dynamic_shared_memory| arch.max_occupancy(&usage, dynamic_shared_memory))
+        else {
+            return Err(Error::Driver(CudaError::LaunchOutOfResources));
+        };
+
+        let max_co_resident_blocks = occupancy
+            .active_blocks_per_multiprocessor
+            .saturating_mul(multiprocessors);
+
+        Ok(Self {
+            grid: GridSize {
+                x: blocks_for_elements(n, occupancy.block_size).min(max_co_resident_blocks),
+                y: 1,
+                z: 1,
+            },
+            block: BlockSize {
+                x: occupancy.block_size,
+                y: 1,
+                z: 1,
+            },
+            ptx_jit: false,
+            ptx_jit_block_size: false,
+            cooperative: false,
+        })
+    }
+
+    /// Checks that all thread blocks of this cooperative launch of the
+    /// `function` with `dynamic_shared_memory` bytes of dynamic shared memory
+    /// per block can be co-resident on the current device.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::CooperativeLaunchNotSupported`] iff the current
+    /// device does not support cooperative launches, an
+    /// [`Error::InvalidLaunchDimensions`] iff the block is too large, an
+    /// [`Error::CooperativeLaunchTooLarge`] iff the grid is too large to be
+    /// co-resident, or an [`Error::Driver`] iff the resource usage of the
+    /// `function` or the attributes of the current device cannot be queried.
+    pub(super) fn check_cooperative_launch(
+        &self,
+        function: &Function,
+        dynamic_shared_memory: u32,
+    ) -> Result<(), Error> {
+        let device = CurrentContext::get_device()?;
+
+        if device.get_attribute(DeviceAttribute::CooperativeLaunch)? == 0 {
+            return Err(Error::CooperativeLaunchNotSupported);
+        }
+
+        let usage = KernelResourceUsage::of_function(function)?;
+        let arch = SmArchitecture::of_device(device)?;
+        let multiprocessors =
+            attribute(device.get_attribute(DeviceAttribute::MultiprocessorCount)?)?;
+
+        let Some(block_size) = self
+            .block
+            .x
+            .checked_mul(self.block.y)
+            .and_then(|xy| xy.checked_mul(self.block.z))
+        else {
+            return Err(Error::InvalidLaunchDimensions {
+                grid: self.grid.clone(),
+                block: self.block.clone(),
+            });
+        };
+
+        let max_co_resident_blocks = u64::from(
+            arch.occupancy(&usage, dynamic_shared_memory, block_size)
+                .active_blocks_per_multiprocessor,
+        ) * u64::from(multiprocessors);
+
+        let grid_size = u64::from(self.grid.x) * u64::from(self.grid.y) * u64::from(self.grid.z);
+
+        if grid_size > max_co_resident_blocks {
+            return Err(Error::CooperativeLaunchTooLarge {
+                blocks: grid_size,
+                max_co_resident_blocks,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "host")]
+impl KernelResourceUsage {
+    /// Queries the resources that are used by the compiled kernel
+    /// `function`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff querying the [`FunctionAttribute`]s of the
+    /// `function` fails.
+    pub fn of_function(function: &Function) -> CudaResult<Self> {
+        Ok(Self {
+            registers_per_thread: attribute(
+                function.get_attribute(FunctionAttribute::NumRegisters)?,
+            )?,
+            static_shared_memory: attribute(
+                function.get_attribute(FunctionAttribute::SharedMemorySizeBytes)?,
+            )?,
+            max_threads_per_block: attribute(
+                function.get_attribute(FunctionAttribute::MaxThreadsPerBlock)?,
+            )?,
+        })
+    }
+}
+
+#[cfg(feature = "host")]
+impl SmArchitecture {
+    /// Queries the limits of the SM architecture of the `device`.
+    ///
+    /// The allocation granularities, which are not exposed as
+    /// [`DeviceAttribute`]s, are taken from the known architecture with the
+    /// same compute capability, or the closest older one.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CudaError`] iff querying the [`DeviceAttribute`]s of the
+    /// `device` fails.
+    pub fn of_device(device: Device) -> CudaResult<Self> {
+        let get = |attr| attribute(device.get_attribute(attr)?);
+
+        let compute_capability = (
+            get(DeviceAttribute::ComputeCapabilityMajor)?,
+            get(DeviceAttribute::ComputeCapabilityMinor)?,
+        );
+
+        let known = Self::ALL
+            .iter()
+            .rev()
+            .find(|arch| arch.compute_capability <= compute_capability)
+            .or(Self::ALL.first())
+            .copied()
+            .ok_or(CudaError::UnknownError)?;
+
+        Ok(Self {
+            compute_capability,
+            warp_size: get(DeviceAttribute::WarpSize)?,
+            max_threads_per_block: get(DeviceAttribute::MaxThreadsPerBlock)?,
+            max_threads_per_multiprocessor: get(DeviceAttribute::MaxThreadsPerMultiprocessor)?,
+            max_registers_per_block: get(DeviceAttribute::MaxRegistersPerBlock)?,
+            max_registers_per_multiprocessor: get(DeviceAttribute::MaxRegistersPerMultiprocessor)?,
+            max_shared_memory_per_block: get(DeviceAttribute::MaxSharedMemoryPerBlock)?,
+            max_shared_memory_per_multiprocessor: get(
+                DeviceAttribute::MaxSharedMemoryPerMultiprocessor,
+            )?,
+            ..known
+        })
+    }
+}
+
+/// Returns the number of blocks of `block_size` threads that are needed to
+/// launch one thread per element for `n` elements, but at least one.
+#[cfg(feature = "host")]
+const fn blocks_for_elements(n: u32, block_size: u32) -> u32 {
+    if n == 0 {
+        1
+    } else {
+        n.div_ceil(block_size)
+    }
+}
+
+#[cfg(feature = "host")]
+fn attribute(value: i32) -> CudaResult<u32> {
+    u32::try_from(value).map_err(|_| CudaError::InvalidValue)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::{KernelResourceUsage, SmArchitecture};
+
+    const fn usage(registers_per_thread: u32, static_shared_memory: u32) -> KernelResourceUsage {
+        KernelResourceUsage {
+            registers_per_thread,
+            static_shared_memory,
+            max_threads_per_block: 1024,
+        }
+    }
+
+    fn arch(major: u32, minor: u32) -> SmArchitecture {
+        SmArchitecture::from_compute_capability(major, minor).unwrap()
+    }
+
+    #[test]
+    fn known_architectures() {
+        assert_eq!(arch(8, 6).max_threads_per_multiprocessor, 1536);
+        assert_eq!(SmArchitecture::from_compute_capability(1, 0), None);
+    }
+
+    #[test]
+    fn occupancy_limited_by_warps() {
+        let occupancy = arch(7, 0).occupancy(&usage(32, 0), 0, 256);
+
+        assert_eq!(occupancy.active_blocks_per_multiprocessor, 8);
+        assert_eq!(occupancy.active_warps_per_multiprocessor, 64);
+        assert_eq!(occupancy.max_warps_per_multiprocessor, 64);
+        assert!((occupancy.ratio() - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn occupancy_limited_by_registers() {
+        let occupancy = arch(7, 0).occupancy(&usage(64, 0), 0, 256);
+
+        assert_eq!(occupancy.active_blocks_per_multiprocessor, 4);
+        assert_eq!(occupancy.active_warps_per_multiprocessor, 32);
+        assert!((occupancy.ratio() - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn occupancy_limited_by_shared_memory() {
+        // (48 KiB static + 2 KiB dynamic + 1 KiB reserved) per block
+        let occupancy = arch(8, 0).occupancy(&usage(32, 48 * 1024), 2 * 1024, 128);
+
+        assert_eq!(occupancy.active_blocks_per_multiprocessor, 3);
+        assert_eq!(occupancy.active_warps_per_multiprocessor, 12);
+    }
+
+    #[test]
+    fn occupancy_of_invalid_launches() {
+        let arch = arch(7, 0);
+
+        assert_eq!(
+            arch.occupancy(&usage(32, 0), 0, 0)
+                .active_blocks_per_multiprocessor,
+            0
+        );
+        assert_eq!(
+            arch.occupancy(&usage(32, 0), 0, 2048)
+                .active_blocks_per_multiprocessor,
+            0
+        );
+        assert_eq!(
+            arch.occupancy(&usage(32, 0), 97 * 1024, 32)
+                .active_blocks_per_multiprocessor,
+            0
+        );
+        assert_eq!(
+            arch.occupancy(&usage(255, 0), 0, 1024)
+                .active_blocks_per_multiprocessor,
+            0
+        );
+    }
+
+    #[test]
+    fn max_occupancy_prefers_larger_blocks() {
+        let arch = arch(7, 0);
+
+        let occupancy = arch.max_occupancy(&usage(32, 0), 0).unwrap();
+        assert_eq!(occupancy.block_size, 1024);
+        assert_eq!(occupancy.active_warps_per_multiprocessor, 64);
+
+        // 3 blocks of 17 warps fit within the register limit, which beats
+        //  e.g. 2 blocks of 24 warps
+        let occupancy = arch.max_occupancy(&usage(40, 0), 0).unwrap();
+        assert_eq!(occupancy.block_size, 544);
+        assert_eq!(occupancy.active_warps_per_multiprocessor, 51);
+    }
+
+    #[test]
+    fn max_occupancy_of_tiny_blocks() {
+        let usage = KernelResourceUsage {
+            max_threads_per_block: 20,
+            ..usage(32, 0)
+        };
+
+        let occupancy = arch(7, 0).max_occupancy(&usage, 0).unwrap();
+        assert_eq!(occupancy.block_size, 20);
+        assert_eq!(occupancy.active_blocks_per_multiprocessor, 32);
+    }
+
+    #[test]
+    fn max_occupancy_without_fitting_block() {
+        assert_eq!(arch(7, 0).max_occupancy(&usage(32, 0), 97 * 1024), None);
+    }
+
+    #[cfg(feature = "host")]
+    #[test]
+    fn launch_config_for_elements() {
+        use crate::kernel::LaunchConfig;
+
+        assert_eq!(LaunchConfig::for_elements(0).grid.x, 1);
+        assert_eq!(LaunchConfig::for_elements(1).grid.x, 1);
+        assert_eq!(LaunchConfig::for_elements(256).grid.x, 1);
+        assert_eq!(LaunchConfig::for_elements(257).grid.x, 2);
+        assert_eq!(LaunchConfig::for_elements(257).block.x, 256);
+    }
+}
@@ -75,11 +75,13 @@ impl<
         = T
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
         = T
     where
         Self: 'b;
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType = std::sync::Mutex<T>;
     type FfiType<'stream, 'b>
         = crate::utils::adapter::RustToCudaWithPortableBitCopySemantics<T>
     where
@@ -88,7 +90,7 @@ impl<
     type SyncHostType = T;
 
     #[cfg(feature = "host")]
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         _stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl super::WithNewAsync<'stream, Self, O, E>,
@@ -124,7 +126,18 @@ impl<
     }
 
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        _token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b,
+    {
+        param
+    }
+
+    #[cfg(feature = "host")]
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         param: Self::AsyncHostType<'stream, 'b>,
         _token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -146,6 +159,29 @@ impl<
 
         inner.with(param)
     }
+
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        param: Self::SyncHostType,
+        _token: sealed::Token,
+    ) -> Self::EmulatedHostType {
+        std::sync::Mutex::new(param)
+    }
+
+    #[cfg(feature = "emulate")]
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        param: &'short Self::EmulatedHostType,
+        inner: impl super::WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short,
+    {
+        let param = *param
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        inner.with(param)
+    }
 }
 impl<
         T: Copy
@@ -167,11 +203,13 @@ impl<
         = crate::utils::r#async::AsyncProj<'b, 'stream, crate::host::HostAndDeviceConstRef<'b, T>>
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
         = &'b T
     where
         Self: 'b;
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType = &'a T;
     type FfiType<'stream, 'b>
         = DeviceConstRef<'b, T>
     where
@@ -180,7 +218,7 @@ impl<
     type SyncHostType = &'a T;
 
     #[cfg(feature = "host")]
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl super::WithNewAsync<'stream, Self, O, E>,
@@ -219,7 +257,18 @@ impl<
     }
 
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        _token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b,
+    {
+        param
+    }
+
+    #[cfg(feature = "host")]
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         param: Self::AsyncHostType<'stream, 'b>,
         _token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -242,6 +291,25 @@ impl<
 
         inner.with(param)
     }
+
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        param: Self::SyncHostType,
+        _token: sealed::Token,
+    ) -> Self::EmulatedHostType {
+        param
+    }
+
+    #[cfg(feature = "emulate")]
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        param: &'short Self::EmulatedHostType,
+        inner: impl super::WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short,
+    {
+        inner.with(*param)
+    }
 }
 impl<
         'a,
@@ -260,11 +328,13 @@ impl<
         = <&'a PerThreadShallowCopy<T> as CudaKernelParameter>::AsyncHostType<'stream, 'b>
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
         = <&'a PerThreadShallowCopy<T> as CudaKernelParameter>::DeviceType<'b>
     where
         Self: 'b;
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType = &'a T;
     type FfiType<'stream, 'b>
         = <&'a PerThreadShallowCopy<T> as CudaKernelParameter>::FfiType<'stream, 'b>
     where
@@ -273,7 +343,7 @@ impl<
     type SyncHostType = <&'a PerThreadShallowCopy<T> as CudaKernelParameter>::SyncHostType;
 
     #[cfg(feature = "host")]
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl super::WithNewAsync<'stream, Self, O, E>,
@@ -315,7 +385,18 @@ impl<
     }
 
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b,
+    {
+        <&'a PerThreadShallowCopy<T> as CudaKernelParameter>::shorten_async(param, token)
+    }
+
+    #[cfg(feature = "host")]
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         param: Self::AsyncHostType<'stream, 'b>,
         token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -340,6 +421,25 @@ impl<
 
         inner.with(param)
     }
+
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        param: Self::SyncHostType,
+        _token: sealed::Token,
+    ) -> Self::EmulatedHostType {
+        param
+    }
+
+    #[cfg(feature = "emulate")]
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        param: &'short Self::EmulatedHostType,
+        inner: impl super::WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short,
+    {
+        inner.with(*param)
+    }
 }
 impl<
         'a,
@@ -388,11 +488,13 @@ impl<
         = crate::utils::r#async::AsyncProj<'b, 'stream, crate::host::HostAndDeviceConstRef<'b, T>>
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
         = &'b T
     where
         Self: 'b;
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType = &'a T;
     type FfiType<'stream, 'b>
         = DeviceConstRef<'b, T>
     where
@@ -403,7 +505,7 @@ impl<
     type SyncHostType = &'a mut T;
 
     #[cfg(feature = "host")]
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl super::WithNewAsync<'stream, Self, O, E>,
@@ -442,7 +544,18 @@ impl<
     }
 
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        _token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b,
+    {
+        param
+    }
+
+    #[cfg(feature = "host")]
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         param: Self::AsyncHostType<'stream, 'b>,
         _token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -465,6 +578,25 @@ impl<
 
         inner.with(param)
     }
+
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        param: Self::SyncHostType,
+        _token: sealed::Token,
+    ) -> Self::EmulatedHostType {
+        param
+    }
+
+    #[cfg(feature = "emulate")]
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        param: &'short Self::EmulatedHostType,
+        inner: impl super::WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short,
+    {
+        inner.with(*param)
+    }
 }
 impl<
         'a,
@@ -536,11 +668,13 @@ impl<
     >
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
         = T
     where
         Self: 'b;
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType = std::sync::Mutex<T>;
     type FfiType<'stream, 'b>
         = DeviceOwnedRef<'b, DeviceAccessible<<T as RustToCuda>::CudaRepresentation>>
     where
@@ -549,7 +683,7 @@ impl<
     type SyncHostType = T;
 
     #[cfg(feature = "host")]
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl super::WithNewAsync<'stream, Self, O, E>,
@@ -585,7 +719,18 @@ impl<
     }
 
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        _token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b,
+    {
+        param
+    }
+
+    #[cfg(feature = "host")]
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         param: Self::AsyncHostType<'stream, 'b>,
         _token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -609,6 +754,30 @@ impl<
             crate::lend::BorrowFromRust::with_moved_from_rust(param, |param| inner.with(param))
         }
     }
+
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        param: Self::SyncHostType,
+        _token: sealed::Token,
+    ) -> Self::EmulatedHostType {
+        std::sync::Mutex::new(param)
+    }
+
+    #[cfg(feature = "emulate")]
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        param: &'short Self::EmulatedHostType,
+        inner: impl super::WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short,
+    {
+        let param = param
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone();
+
+        inner.with(param)
+    }
 }
 impl<
         T: Send
@@ -631,11 +800,13 @@ impl<'a, T: Sync + RustToCuda> CudaKernelParameter for &'a DeepPerThreadBorrow<T
     >
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
         = &'b T
     where
         Self: 'b;
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType = &'a T;
     type FfiType<'stream, 'b>
         = DeviceConstRef<'b, DeviceAccessible<<T as RustToCuda>::CudaRepresentation>>
     where
@@ -644,7 +815,7 @@ impl<'a, T: Sync + RustToCuda> CudaKernelParameter for &'a DeepPerThreadBorrow<T
     type SyncHostType = &'a T;
 
     #[cfg(feature = "host")]
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl super::WithNewAsync<'stream, Self, O, E>,
@@ -683,7 +854,18 @@ impl<'a, T: Sync + RustToCuda> CudaKernelParameter for &'a DeepPerThreadBorrow<T
     }
 
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        _token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b,
+    {
+        param
+    }
+
+    #[cfg(feature = "host")]
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         param: Self::AsyncHostType<'stream, 'b>,
         _token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -706,6 +888,25 @@ impl<'a, T: Sync + RustToCuda> CudaKernelParameter for &'a DeepPerThreadBorrow<T
             crate::lend::BorrowFromRust::with_borrow_from_rust(param, |param| inner.with(param))
         }
     }
+
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        param: Self::SyncHostType,
+        _token: sealed::Token,
+    ) -> Self::EmulatedHostType {
+        param
+    }
+
+    #[cfg(feature = "emulate")]
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        param: &'short Self::EmulatedHostType,
+        inner: impl super::WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short,
+    {
+        inner.with(*param)
+    }
 }
 impl<'a, T: Sync + RustToCuda> sealed::Sealed for &'a DeepPerThreadBorrow<T> {}
 
@@ -724,11 +925,13 @@ impl<'a, T: Sync + RustToCuda + SafeMutableAliasing> CudaKernelParameter
     >
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
         = &'b mut T
     where
         Self: 'b;
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType = private_emulate::EmulatedAliasingMut<'a, T>;
     type FfiType<'stream, 'b>
         = DeviceMutRef<'b, DeviceAccessible<<T as RustToCuda>::CudaRepresentation>>
     where
@@ -737,7 +940,7 @@ impl<'a, T: Sync + RustToCuda + SafeMutableAliasing> CudaKernelParameter
     type SyncHostType = &'a mut T;
 
     #[cfg(feature = "host")]
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl super::WithNewAsync<'stream, Self, O, E>,
@@ -781,7 +984,18 @@ impl<'a, T: Sync + RustToCuda + SafeMutableAliasing> CudaKernelParameter
     }
 
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        _token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b,
+    {
+        param
+    }
+
+    #[cfg(feature = "host")]
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         mut param: Self::AsyncHostType<'stream, 'b>,
         _token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -805,6 +1019,29 @@ impl<'a, T: Sync + RustToCuda + SafeMutableAliasing> CudaKernelParameter
             crate::lend::BorrowFromRust::with_borrow_from_rust_mut(param, |param| inner.with(param))
         }
     }
+
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        param: Self::SyncHostType,
+        _token: sealed::Token,
+    ) -> Self::EmulatedHostType {
+        private_emulate::EmulatedAliasingMut::new(param)
+    }
+
+    #[cfg(feature = "emulate")]
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        param: &'short Self::EmulatedHostType,
+        inner: impl super::WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short,
+    {
+        // `param` must never be dropped as each thread only gets a shallow
+        //  copy that does NOT own any of the heap memory it might reference
+        let mut param = core::mem::ManuallyDrop::new(unsafe { param.shallow_copy() });
+
+        inner.with(&mut *param)
+    }
 }
 impl<'a, T: Sync + RustToCuda + SafeMutableAliasing> sealed::Sealed
     for &'a mut DeepPerThreadBorrow<T>
@@ -822,11 +1059,13 @@ impl<
         = <DeepPerThreadBorrow<T> as CudaKernelParameter>::AsyncHostType<'stream, 'b>
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
         = <DeepPerThreadBorrow<T> as CudaKernelParameter>::DeviceType<'b>
     where
         Self: 'b;
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType = std::sync::Mutex<T>;
     type FfiType<'stream, 'b>
         = <DeepPerThreadBorrow<T> as CudaKernelParameter>::FfiType<'stream, 'b>
     where
@@ -835,7 +1074,7 @@ impl<
     type SyncHostType = <DeepPerThreadBorrow<T> as CudaKernelParameter>::SyncHostType;
 
     #[cfg(feature = "host")]
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl super::WithNewAsync<'stream, Self, O, E>,
@@ -862,7 +1101,7 @@ impl<
     }
 
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         param: Self::AsyncHostType<'stream, 'b>,
         token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -883,6 +1122,17 @@ impl<
         Layout::new::<()>()
     }
 
+    #[cfg(feature = "host")]
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b,
+    {
+        <DeepPerThreadBorrow<T> as CudaKernelParameter>::shorten_async(param, token)
+    }
+
     #[cfg(feature = "device")]
     unsafe fn with_ffi_as_device<'short, O, const PARAM: usize>(
         param: Self::FfiType<'static, 'short>,
@@ -898,6 +1148,30 @@ impl<
             crate::lend::BorrowFromRust::with_moved_from_rust(param, |param| inner.with(param))
         }
     }
+
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        param: Self::SyncHostType,
+        _token: sealed::Token,
+    ) -> Self::EmulatedHostType {
+        std::sync::Mutex::new(param)
+    }
+
+    #[cfg(feature = "emulate")]
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        param: &'short Self::EmulatedHostType,
+        inner: impl super::WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short,
+    {
+        let param = param
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone();
+
+        inner.with(param)
+    }
 }
 impl<
         T: Send
@@ -913,11 +1187,13 @@ impl<'a, T: Sync + RustToCuda> CudaKernelParameter for &'a PtxJit<DeepPerThreadB
         = <&'a DeepPerThreadBorrow<T> as CudaKernelParameter>::AsyncHostType<'stream, 'b>
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
         = <&'a DeepPerThreadBorrow<T> as CudaKernelParameter>::DeviceType<'b>
     where
         Self: 'b;
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType = &'a T;
     type FfiType<'stream, 'b>
         = <&'a DeepPerThreadBorrow<T> as CudaKernelParameter>::FfiType<'stream, 'b>
     where
@@ -926,7 +1202,7 @@ impl<'a, T: Sync + RustToCuda> CudaKernelParameter for &'a PtxJit<DeepPerThreadB
     type SyncHostType = <&'a DeepPerThreadBorrow<T> as CudaKernelParameter>::SyncHostType;
 
     #[cfg(feature = "host")]
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl super::WithNewAsync<'stream, Self, O, E>,
@@ -968,7 +1244,18 @@ impl<'a, T: Sync + RustToCuda> CudaKernelParameter for &'a PtxJit<DeepPerThreadB
     }
 
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b,
+    {
+        <&'a DeepPerThreadBorrow<T> as CudaKernelParameter>::shorten_async(param, token)
+    }
+
+    #[cfg(feature = "host")]
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         param: Self::AsyncHostType<'stream, 'b>,
         token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -993,6 +1280,25 @@ impl<'a, T: Sync + RustToCuda> CudaKernelParameter for &'a PtxJit<DeepPerThreadB
             crate::lend::BorrowFromRust::with_borrow_from_rust(param, |param| inner.with(param))
         }
     }
+
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        param: Self::SyncHostType,
+        _token: sealed::Token,
+    ) -> Self::EmulatedHostType {
+        param
+    }
+
+    #[cfg(feature = "emulate")]
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        param: &'short Self::EmulatedHostType,
+        inner: impl super::WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short,
+    {
+        inner.with(*param)
+    }
 }
 impl<'a, T: Sync + RustToCuda> sealed::Sealed for &'a PtxJit<DeepPerThreadBorrow<T>> {}
 
@@ -1004,11 +1310,13 @@ impl<'a, T: Sync + RustToCuda + SafeMutableAliasing> CudaKernelParameter
         = <&'a mut DeepPerThreadBorrow<T> as CudaKernelParameter>::AsyncHostType<'stream, 'b>
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
         = <&'a mut DeepPerThreadBorrow<T> as CudaKernelParameter>::DeviceType<'b>
     where
         Self: 'b;
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType = private_emulate::EmulatedAliasingMut<'a, T>;
     type FfiType<'stream, 'b>
         = <&'a mut DeepPerThreadBorrow<T> as CudaKernelParameter>::FfiType<'stream, 'b>
     where
@@ -1017,7 +1325,7 @@ impl<'a, T: Sync + RustToCuda + SafeMutableAliasing> CudaKernelParameter
     type SyncHostType = <&'a mut DeepPerThreadBorrow<T> as CudaKernelParameter>::SyncHostType;
 
     #[cfg(feature = "host")]
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl super::WithNewAsync<'stream, Self, O, E>,
@@ -1064,7 +1372,18 @@ impl<'a, T: Sync + RustToCuda + SafeMutableAliasing> CudaKernelParameter
     }
 
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b,
+    {
+        <&'a mut DeepPerThreadBorrow<T> as CudaKernelParameter>::shorten_async(param, token)
+    }
+
+    #[cfg(feature = "host")]
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         param: Self::AsyncHostType<'stream, 'b>,
         token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -1089,6 +1408,29 @@ impl<'a, T: Sync + RustToCuda + SafeMutableAliasing> CudaKernelParameter
             crate::lend::BorrowFromRust::with_borrow_from_rust_mut(param, |param| inner.with(param))
         }
     }
+
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        param: Self::SyncHostType,
+        _token: sealed::Token,
+    ) -> Self::EmulatedHostType {
+        private_emulate::EmulatedAliasingMut::new(param)
+    }
+
+    #[cfg(feature = "emulate")]
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        param: &'short Self::EmulatedHostType,
+        inner: impl super::WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short,
+    {
+        // `param` must never be dropped as each thread only gets a shallow
+        //  copy that does NOT own any of the heap memory it might reference
+        let mut param = core::mem::ManuallyDrop::new(unsafe { param.shallow_copy() });
+
+        inner.with(&mut *param)
+    }
 }
 impl<'a, T: Sync + RustToCuda + SafeMutableAliasing> sealed::Sealed
     for &'a mut PtxJit<DeepPerThreadBorrow<T>>
@@ -1111,6 +1453,41 @@ fn emit_param_ptx_jit_marker<T: ?Sized, const INDEX: usize>(param: &T) {
     }
 }
 
+#[cfg(feature = "emulate")]
+mod private_emulate {
+    use core::{marker::PhantomData, ptr::NonNull};
+
+    use crate::safety::SafeMutableAliasing;
+
+    #[doc(hidden)]
+    pub struct EmulatedAliasingMut<'a, T: Sync + SafeMutableAliasing> {
+        inner: NonNull<T>,
+        _marker: PhantomData<&'a mut T>,
+    }
+
+    // Safety: each emulated thread only ever gets its own shallow copy of the
+    //         value, which [`SafeMutableAliasing`] guarantees to be safe
+    unsafe impl<'a, T: Sync + SafeMutableAliasing> Sync for EmulatedAliasingMut<'a, T> {}
+
+    impl<'a, T: Sync + SafeMutableAliasing> EmulatedAliasingMut<'a, T> {
+        pub(super) fn new(inner: &'a mut T) -> Self {
+            Self {
+                inner: NonNull::from(inner),
+                _marker: PhantomData::<&'a mut T>,
+            }
+        }
+
+        /// # Safety
+        ///
+        /// The returned shallow copy must never be dropped.
+        pub(super) const unsafe fn shallow_copy(&self) -> T {
+            // Safety: the mutable borrow of the value is held for 'a, and
+            //         SafeMutableAliasing guarantees that the copy is safe
+            unsafe { self.inner.as_ptr().read() }
+        }
+    }
+}
+
 mod private_shared {
     use core::marker::PhantomData;
 
@@ -1141,11 +1518,13 @@ impl<'a, T: 'static> CudaKernelParameter for &'a mut crate::utils::shared::Threa
         = &'b mut crate::utils::shared::ThreadBlockShared<T>
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
         = &'b mut crate::utils::shared::ThreadBlockShared<T>
     where
         Self: 'b;
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType = ();
     type FfiType<'stream, 'b>
         = private_shared::ThreadBlockSharedFfi<T>
     where
@@ -1154,7 +1533,7 @@ impl<'a, T: 'static> CudaKernelParameter for &'a mut crate::utils::shared::Threa
     type SyncHostType = Self;
 
     #[cfg(feature = "host")]
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         _stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl super::WithNewAsync<'stream, Self, O, E>,
@@ -1190,7 +1569,18 @@ impl<'a, T: 'static> CudaKernelParameter for &'a mut crate::utils::shared::Threa
     }
 
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        _token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b,
+    {
+        param
+    }
+
+    #[cfg(feature = "host")]
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         _param: Self::AsyncHostType<'stream, 'b>,
         _token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -1217,6 +1607,27 @@ impl<'a, T: 'static> CudaKernelParameter for &'a mut crate::utils::shared::Threa
 
         inner.with(&mut param)
     }
+
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        param: Self::SyncHostType,
+        _token: sealed::Token,
+    ) -> Self::EmulatedHostType {
+        let _ = param;
+    }
+
+    #[cfg(feature = "emulate")]
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        _param: &'short Self::EmulatedHostType,
+        inner: impl super::WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short,
+    {
+        let mut param = crate::utils::shared::ThreadBlockShared::new_uninit_for_param::<PARAM>();
+
+        inner.with(&mut param)
+    }
 }
 impl<'a, T: 'static> sealed::Sealed for &'a mut crate::utils::shared::ThreadBlockShared<T> {}
 
@@ -1228,11 +1639,13 @@ impl<'a, T: 'static + PortableBitSemantics + TypeGraphLayout> CudaKernelParamete
         = &'b mut crate::utils::shared::ThreadBlockSharedSlice<T>
     where
         Self: 'b;
-    #[cfg(any(feature = "device", doc))]
+    #[cfg(any(feature = "device", feature = "emulate", doc))]
     type DeviceType<'b>
         = &'b mut crate::utils::shared::ThreadBlockSharedSlice<T>
     where
         Self: 'b;
+    #[cfg(feature = "emulate")]
+    type EmulatedHostType = usize;
     type FfiType<'stream, 'b>
         = private_shared::ThreadBlockSharedSliceFfi<T>
     where
@@ -1241,7 +1654,7 @@ impl<'a, T: 'static + PortableBitSemantics + TypeGraphLayout> CudaKernelParamete
     type SyncHostType = Self;
 
     #[cfg(feature = "host")]
-    fn with_new_async<'stream, 'b, O, E: From<rustacuda::error::CudaError>>(
+    fn with_new_async<'stream, 'b, O, E: From<crate::host::driver::error::CudaError>>(
         param: Self::SyncHostType,
         _stream: crate::host::Stream<'stream>,
         #[cfg(not(doc))] inner: impl super::WithNewAsync<'stream, Self, O, E>,
@@ -1277,7 +1690,18 @@ impl<'a, T: 'static + PortableBitSemantics + TypeGraphLayout> CudaKernelParamete
     }
 
     #[cfg(feature = "host")]
-    fn async_to_ffi<'stream, 'b, E: From<rustacuda::error::CudaError>>(
+    fn shorten_async<'stream, 'b: 'short, 'short>(
+        param: Self::AsyncHostType<'stream, 'b>,
+        _token: sealed::Token,
+    ) -> Self::AsyncHostType<'stream, 'short>
+    where
+        Self: 'b,
+    {
+        param
+    }
+
+    #[cfg(feature = "host")]
+    fn async_to_ffi<'stream, 'b, E: From<crate::host::driver::error::CudaError>>(
         param: Self::AsyncHostType<'stream, 'b>,
         _token: sealed::Token,
     ) -> Result<Self::FfiType<'stream, 'b>, E>
@@ -1306,6 +1730,28 @@ impl<'a, T: 'static + PortableBitSemantics + TypeGraphLayout> CudaKernelParamete
             })
         }
     }
+
+    #[cfg(feature = "emulate")]
+    fn sync_to_emulated(
+        param: Self::SyncHostType,
+        _token: sealed::Token,
+    ) -> Self::EmulatedHostType {
+        param.len()
+    }
+
+    #[cfg(feature = "emulate")]
+    unsafe fn with_emulated_as_device<'short, O, const PARAM: usize>(
+        param: &'short Self::EmulatedHostType,
+        inner: impl super::WithFfiAsDevice<Self, O>,
+    ) -> O
+    where
+        Self: 'short,
+    {
+        crate::utils::shared::ThreadBlockSharedSlice::with_uninit_for_param::<PARAM, _, _>(
+            *param,
+            |param| inner.with(param),
+        )
+    }
 }
 impl<'a, T: 'static + PortableBitSemantics + TypeGraphLayout> sealed::Sealed
     for &'a mut crate::utils::shared::ThreadBlockSharedSlice<T>
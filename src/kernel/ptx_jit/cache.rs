@@ -0,0 +1,352 @@
+use std::{collections::VecDeque, num::NonZeroUsize, ptr::NonNull};
+
+use super::PtxJITArguments;
+
+/// Statistics of a [`PtxJITCache`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PtxJITCacheStats {
+    /// Number of lookups that found an already specialised entry
+    pub hits: u64,
+    /// Number of lookups that required a new specialisation
+    pub misses: u64,
+    /// Number of entries that were evicted to make space for new ones
+    pub evictions: u64,
+}
+
+/// Whether a lookup in a [`PtxJITCache`] found an already specialised
+/// entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PtxJITCacheLookup {
+    /// The entry was already specialised and cached
+    Hit,
+    /// The entry had to be specialised and compiled
+    Miss,
+}
+
+/// Bounded least-recently-used cache of values, e.g. loaded kernels, that
+/// are specialised for the byte representations of the kernel arguments.
+///
+/// Since the cache is expected to be small, its entries are stored in
+/// recency order and searched linearly.
+pub struct PtxJITCache<V> {
+    capacity: NonZeroUsize,
+    most_recent: Option<PtxJITCacheEntry<V>>,
+    // the least recently used entry is at the front
+    older: VecDeque<PtxJITCacheEntry<V>>,
+    stats: PtxJITCacheStats,
+    last_lookup: Option<PtxJITCacheLookup>,
+}
+
+/// An entry of a [`PtxJITCache`], which is keyed by the arguments and the
+/// block size that its value was specialised for.
+type PtxJITCacheEntry<V> = ((Option<PtxJITArguments>, Option<[u32; 3]>), V);
+
+impl<V> PtxJITCache<V> {
+    /// The default number of specialisations that are kept.
+    pub const DEFAULT_CAPACITY: NonZeroUsize = match NonZeroUsize::new(8) {
+        Some(capacity) => capacity,
+        None => NonZeroUsize::MIN,
+    };
+
+    #[must_use]
+    pub const fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            capacity,
+            most_recent: None,
+            older: VecDeque::new(),
+            stats: PtxJITCacheStats {
+                hits: 0,
+                misses: 0,
+                evictions: 0,
+            },
+            last_lookup: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn capacity(&self) -> NonZeroUsize {
+        self.capacity
+    }
+
+    /// Changes the `capacity` of the cache, evicting the least recently used
+    /// entries if the cache holds too many.
+    pub fn set_capacity(&mut self, capacity: NonZeroUsize) {
+        self.capacity = capacity;
+
+        self.evict_older_to(capacity.get() - 1);
+    }
+
+    #[must_use]
+    pub const fn stats(&self) -> PtxJITCacheStats {
+        self.stats
+    }
+
+    /// Returns whether the most recent lookup was a hit or a miss, or [`None`]
+    /// if there has not been any lookup yet.
+    #[must_use]
+    pub const fn last_lookup(&self) -> Option<PtxJITCacheLookup> {
+        self.last_lookup
+    }
+
+    /// Returns the most recently used value, if any.
+    #[must_use]
+    pub fn most_recent(&self) -> Option<&V> {
+        self.most_recent.as_ref().map(|(_, value)| value)
+    }
+
+    /// Removes all entries without counting them as evictions.
+    pub fn clear(&mut self) {
+        self.most_recent = None;
+        self.older.clear();
+    }
+
+    /// Returns the most recently used value, or inserts the value that
+    /// `specialise` creates for no arguments and no block size if the cache
+    /// is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error that is returned by `specialise`.
+    pub fn most_recent_or_try_insert_with<E>(
+        &mut self,
+        specialise: impl FnOnce(Option<&[Option<Box<[u8]>>]>, Option<[u32; 3]>) -> Result<V, E>,
+    ) -> Result<&V, E> {
+        if let Some((_, ref value)) = self.most_recent {
+            self.stats.hits += 1;
+            self.last_lookup = Some(PtxJITCacheLookup::Hit);
+            return Ok(value);
+        }
+
+        self.stats.misses += 1;
+        self.last_lookup = Some(PtxJITCacheLookup::Miss);
+
+        let value = specialise(None, None)?;
+
+        Ok(self.insert((None, None), value))
+    }
+
+    /// Looks up the value that was specialised for the `arguments` and the
+    /// `block_size` and marks it as the most recently used one, or inserts
+    /// the value that `specialise` creates for them if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error that is returned by `specialise`.
+    pub fn get_or_try_insert_with<E>(
+        &mut self,
+        arguments: Option<&[Option<&NonNull<[u8]>>]>,
+        block_size: Option<[u32; 3]>,
+        specialise: impl FnOnce(Option<&[Option<Box<[u8]>>]>, Option<[u32; 3]>) -> Result<V, E>,
+    ) -> Result<&V, E> {
+        if self.promote(arguments, block_size) {
+            if let Some((_, ref value)) = self.most_recent {
+                self.stats.hits += 1;
+                self.last_lookup = Some(PtxJITCacheLookup::Hit);
+                return Ok(value);
+            }
+        }
+
+        self.stats.misses += 1;
+        self.last_lookup = Some(PtxJITCacheLookup::Miss);
+
+        let arguments = arguments_key(arguments);
+        let value = specialise(arguments.as_deref(), block_size)?;
+
+        Ok(self.insert((arguments, block_size), value))
+    }
+
+    /// Marks the entry that was specialised for the `arguments` and the
+    /// `block_size` as the most recently used one, and returns whether such
+    /// an entry exists.
+    fn promote(
+        &mut self,
+        arguments: Option<&[Option<&NonNull<[u8]>>]>,
+        block_size: Option<[u32; 3]>,
+    ) -> bool {
+        let matches = |((key, key_block_size), _): &PtxJITCacheEntry<V>| {
+            *key_block_size == block_size && key_matches(key.as_deref(), arguments)
+        };
+
+        if self.most_recent.as_ref().is_some_and(matches) {
+            return true;
+        }
+
+        let Some(entry) = self
+            .older
+            .iter()
+            .position(matches)
+            .and_then(|index| self.older.remove(index))
+        else {
+            return false;
+        };
+
+        self.older.extend(self.most_recent.replace(entry));
+
+        true
+    }
+
+    /// Inserts the `value` that was specialised for the `key` as the most
+    /// recently used entry, evicting the least recently used entry if the
+    /// cache is full.
+    fn insert(&mut self, key: (Option<PtxJITArguments>, Option<[u32; 3]>), value: V) -> &V {
+        self.older.extend(self.most_recent.take());
+        self.evict_older_to(self.capacity.get() - 1);
+
+        let (_, value) = self.most_recent.insert((key, value));
+
+        value
+    }
+
+    fn evict_older_to(&mut self, len: usize) {
+        while self.older.len() > len {
+            // Drop the least recently used value
+            std::mem::drop(self.older.pop_front());
+            self.stats.evictions += 1;
+        }
+    }
+}
+
+/// Copies the byte representations of the `arguments` into a cache key.
+fn arguments_key(arguments: Option<&[Option<&NonNull<[u8]>>]>) -> Option<PtxJITArguments> {
+    arguments.map(|arguments| {
+        arguments
+            .iter()
+            .map(|arg| arg.map(|bytes| unsafe { bytes.as_ref() }.to_owned().into_boxed_slice()))
+            .collect()
+    })
+}
+
+fn key_matches(
+    key: Option<&[Option<Box<[u8]>>]>,
+    arguments: Option<&[Option<&NonNull<[u8]>>]>,
+) -> bool {
+    match (key, arguments) {
+        (None, None) => true,
+        (Some(key), Some(arguments)) if key.len() == arguments.len() => {
+            key.iter().zip(arguments).all(|(k, a)| match (k, a) {
+                (None, None) => true,
+                (Some(k), Some(a)) => **k == *unsafe { a.as_ref() },
+                _ => false,
+            })
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::{convert::Infallible, num::NonZeroUsize, ptr::NonNull};
+
+    use super::{PtxJITCache, PtxJITCacheLookup, PtxJITCacheStats};
+
+    /// Looks up the value for the `argument` bytes in the `cache`, which is
+    /// specialised as the first argument byte.
+    fn lookup(cache: &mut PtxJITCache<u8>, argument: u8) -> u8 {
+        let bytes = [argument];
+        let bytes = NonNull::from(&bytes[..]);
+
+        *cache
+            .get_or_try_insert_with(Some(&[Some(&bytes), None]), None, |arguments, _| {
+                Ok::<_, Infallible>(arguments.unwrap()[0].as_ref().unwrap()[0])
+            })
+            .unwrap()
+    }
+
+    const fn stats(hits: u64, misses: u64, evictions: u64) -> PtxJITCacheStats {
+        PtxJITCacheStats {
+            hits,
+            misses,
+            evictions,
+        }
+    }
+
+    #[test]
+    fn hit_and_miss() {
+        let mut cache = PtxJITCache::new(PtxJITCache::<u8>::DEFAULT_CAPACITY);
+        assert_eq!(cache.last_lookup(), None);
+
+        assert_eq!(lookup(&mut cache, 1), 1);
+        assert_eq!(cache.last_lookup(), Some(PtxJITCacheLookup::Miss));
+
+        assert_eq!(lookup(&mut cache, 2), 2);
+        assert_eq!(lookup(&mut cache, 1), 1);
+        assert_eq!(cache.last_lookup(), Some(PtxJITCacheLookup::Hit));
+        assert_eq!(cache.most_recent(), Some(&1));
+
+        assert_eq!(cache.stats(), stats(1, 2, 0));
+    }
+
+    #[test]
+    fn block_size_is_part_of_the_key() {
+        let mut cache = PtxJITCache::new(PtxJITCache::<u32>::DEFAULT_CAPACITY);
+
+        for block_size in [None, Some([32, 1, 1]), Some([64, 1, 1]), Some([32, 1, 1])] {
+            cache
+                .get_or_try_insert_with(None, block_size, |_, block_size| {
+                    Ok::<_, Infallible>(block_size.map_or(0, |[x, _, _]| x))
+                })
+                .unwrap();
+        }
+
+        assert_eq!(cache.most_recent(), Some(&32));
+        assert_eq!(cache.stats(), stats(1, 3, 0));
+    }
+
+    #[test]
+    fn least_recently_used_is_evicted() {
+        let mut cache = PtxJITCache::new(NonZeroUsize::new(2).unwrap());
+
+        lookup(&mut cache, 1);
+        lookup(&mut cache, 2);
+        // 1 becomes the most recently used entry
+        lookup(&mut cache, 1);
+        // 2 is evicted to make space for 3
+        lookup(&mut cache, 3);
+        assert_eq!(cache.stats(), stats(1, 3, 1));
+
+        lookup(&mut cache, 1);
+        assert_eq!(cache.last_lookup(), Some(PtxJITCacheLookup::Hit));
+
+        lookup(&mut cache, 2);
+        assert_eq!(cache.last_lookup(), Some(PtxJITCacheLookup::Miss));
+        assert_eq!(cache.stats(), stats(2, 4, 2));
+    }
+
+    #[test]
+    fn shrinking_capacity_evicts() {
+        let mut cache = PtxJITCache::new(NonZeroUsize::new(4).unwrap());
+
+        for argument in 1..=4 {
+            lookup(&mut cache, argument);
+        }
+
+        cache.set_capacity(NonZeroUsize::MIN);
+        assert_eq!(cache.stats(), stats(0, 4, 3));
+        assert_eq!(cache.most_recent(), Some(&4));
+
+        lookup(&mut cache, 4);
+        assert_eq!(cache.last_lookup(), Some(PtxJITCacheLookup::Hit));
+
+        cache.clear();
+        assert_eq!(cache.most_recent(), None);
+        assert_eq!(cache.stats(), stats(1, 4, 3));
+    }
+
+    #[test]
+    fn failed_specialisation_is_not_cached() {
+        let mut cache = PtxJITCache::<u8>::new(PtxJITCache::<u8>::DEFAULT_CAPACITY);
+
+        assert_eq!(
+            cache.most_recent_or_try_insert_with(|_, _| Err("failed")),
+            Err("failed")
+        );
+        assert_eq!(cache.most_recent(), None);
+
+        assert_eq!(
+            cache.most_recent_or_try_insert_with(|_, _| Ok::<_, &str>(42)),
+            Ok(&42)
+        );
+        assert_eq!(cache.stats(), stats(0, 2, 0));
+    }
+}
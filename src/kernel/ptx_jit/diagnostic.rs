@@ -0,0 +1,85 @@
+use std::fmt;
+
+/// A pattern in the PTX source of a kernel that the PTX JIT does not
+/// recognise, and which therefore prevents it from specialising some loads
+/// from a [`PtxJit`](crate::kernel::param::PtxJit) kernel parameter.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PtxJITDiagnostic {
+    /// The marker of a parameter names a `register` that is not loaded from
+    /// a pointer to the parameter, so none of its loads can be found.
+    UnmatchedMarker {
+        /// The index of the kernel parameter
+        parameter_index: usize,
+        /// The register that is named by the marker
+        register: Box<str>,
+    },
+    /// A load from a parameter cannot be replaced with constants.
+    UnsupportedLoad {
+        /// The index of the kernel parameter
+        parameter_index: usize,
+        /// The PTX source of the load instruction
+        instruction: Box<str>,
+        /// Why the load cannot be replaced
+        reason: &'static str,
+    },
+    /// A pointer into a parameter is used by an instruction that is neither
+    /// a load with a constant offset nor a copy of the pointer, e.g. for
+    /// dynamic indexing.
+    UnsupportedUse {
+        /// The index of the kernel parameter
+        parameter_index: usize,
+        /// The PTX source of the instruction
+        instruction: Box<str>,
+    },
+}
+
+impl PtxJITDiagnostic {
+    /// Returns the index of the kernel parameter that is affected.
+    #[must_use]
+    pub const fn parameter_index(&self) -> usize {
+        match self {
+            Self::UnmatchedMarker {
+                parameter_index, ..
+            }
+            | Self::UnsupportedLoad {
+                parameter_index, ..
+            }
+            | Self::UnsupportedUse {
+                parameter_index, ..
+            } => *parameter_index,
+        }
+    }
+}
+
+impl fmt::Display for PtxJITDiagnostic {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnmatchedMarker {
+                parameter_index,
+                register,
+            } => write!(
+                fmt,
+                "the PTX JIT marker of parameter {parameter_index} names the register {register}, \
+                 which is not loaded from the parameter"
+            ),
+            Self::UnsupportedLoad {
+                parameter_index,
+                instruction,
+                reason,
+            } => write!(
+                fmt,
+                "the load `{instruction}` from parameter {parameter_index} cannot be specialised: \
+                 {reason}"
+            ),
+            Self::UnsupportedUse {
+                parameter_index,
+                instruction,
+            } => write!(
+                fmt,
+                "the pointer to parameter {parameter_index} is used by the unsupported \
+                 instruction `{instruction}`"
+            ),
+        }
+    }
+}
@@ -0,0 +1,309 @@
+use std::{
+    borrow::Cow,
+    ffi::{CStr, CString},
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::kernel::current_compute_capability;
+
+use super::PtxJITCompiler;
+
+/// Opt-in persistent cache of PTX JIT specialised kernels, which is stored
+/// in a `directory` on disk and can be shared between several processes.
+///
+/// The cache stores the specialised PTX source of a kernel, keyed by its
+/// original PTX, its entry point, the bytes of the arguments and the block
+/// size that it was specialised for, and the compute capability of the
+/// device. Every entry is named after the hash of its key, but also stores
+/// the full key, which is compared when the entry is read, such that hash
+/// collisions can never return the wrong PTX. The driver's own JIT cache
+/// then avoids recompiling the identical PTX.
+///
+/// Entries are written atomically, so concurrent processes never observe
+/// partially written entries. Once the total size of all entries exceeds the
+/// `max_size` (in bytes), the least recently used entries are evicted.
+///
+/// The cache is best-effort: any I/O error is ignored and only makes the
+/// kernel be specialised in memory instead.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PtxJITDiskCache {
+    directory: PathBuf,
+    max_size: u64,
+}
+
+impl PtxJITDiskCache {
+    const EXTENSION: &'static str = "ptx";
+
+    /// Creates a new disk cache inside the `directory`, which is created on
+    /// first use, that holds up to `max_size` bytes of cached entries.
+    #[must_use]
+    pub fn new(directory: impl Into<PathBuf>, max_size: u64) -> Self {
+        Self {
+            directory: directory.into(),
+            max_size,
+        }
+    }
+
+    /// Returns the directory in which the cache is stored.
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns the maximum total size (in bytes) of all cache entries.
+    #[must_use]
+    pub const fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// Specialises the PTX source of the `compiler` for the byte
+    /// representations of the kernel `arguments` and the `block_size`,
+    /// using the cached PTX if it has already been specialised.
+    pub(in crate::kernel) fn specialise<'a>(
+        &self,
+        compiler: &'a PtxJITCompiler,
+        entry_point: &CStr,
+        arguments: Option<&[Option<Box<[u8]>>]>,
+        block_size: Option<[u32; 3]>,
+    ) -> Cow<'a, CStr> {
+        // unspecialised PTX does not need to be cached
+        if arguments.is_none() && block_size.is_none() {
+            return compiler.specialise(None, None);
+        }
+
+        let Some(compute_capability) = current_compute_capability() else {
+            return compiler.specialise(arguments, block_size);
+        };
+
+        let key = cache_key(
+            compiler,
+            entry_point,
+            arguments,
+            block_size,
+            compute_capability,
+        );
+        let path = self.entry_path(&key);
+
+        if let Some(ptx) = Self::read_entry(&path, &key) {
+            return Cow::Owned(ptx);
+        }
+
+        let ptx = compiler.specialise(arguments, block_size);
+
+        // the entry is only an optimisation, so any failure is ignored
+        if self.write_entry(&path, &key, &ptx).is_ok() {
+            let _ = self.evict();
+        }
+
+        ptx
+    }
+
+    fn entry_path(&self, key: &[u8]) -> PathBuf {
+        self.directory
+            .join(format!("{:016x}.{}", seahash::hash(key), Self::EXTENSION))
+    }
+
+    fn read_entry(path: &Path, key: &[u8]) -> Option<CString> {
+        let entry = fs::read(path).ok()?;
+
+        // an entry consists of the length of its key, the key, and the PTX
+        let (key_len, entry) = entry.split_first_chunk::<8>()?;
+        let key_len = usize::try_from(u64::from_le_bytes(*key_len)).ok()?;
+
+        if entry.get(..key_len)? != key {
+            return None;
+        }
+
+        // mark the entry as recently used for the eviction
+        if let Ok(file) = File::options().write(true).open(path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+
+        CString::new(&entry[key_len..]).ok()
+    }
+
+    fn write_entry(&self, path: &Path, key: &[u8], ptx: &CStr) -> std::io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+
+        // write to a unique temporary file first and then atomically move it
+        //  into place, such that concurrent readers only see complete entries
+        let temporary = path.with_extension(format!(
+            "{}.{}.tmp",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |now| now.as_nanos())
+        ));
+
+        let result = File::create(&temporary)
+            .and_then(|mut file| {
+                file.write_all(&(key.len() as u64).to_le_bytes())?;
+                file.write_all(key)?;
+                file.write_all(ptx.to_bytes())
+            })
+            .and_then(|()| fs::rename(&temporary, path));
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+
+        result
+    }
+
+    fn evict(&self) -> std::io::Result<()> {
+        let mut entries = Vec::new();
+        let mut total_size = 0_u64;
+
+        for entry in fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+
+            if path.extension().and_then(|extension| extension.to_str()) != Some(Self::EXTENSION) {
+                continue;
+            }
+
+            // entries may be concurrently evicted by another process
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+
+            total_size += metadata.len();
+            entries.push((
+                metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                metadata.len(),
+                path,
+            ));
+        }
+
+        // evict the least recently used entries first
+        entries.sort_unstable();
+
+        for (_, size, path) in entries {
+            if total_size <= self.max_size {
+                break;
+            }
+
+            if fs::remove_file(path).is_ok() {
+                total_size -= size;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the key of the PTX that is specialised for the `arguments` and the
+/// `block_size`, which uniquely identifies the cache entry.
+fn cache_key(
+    compiler: &PtxJITCompiler,
+    entry_point: &CStr,
+    arguments: Option<&[Option<Box<[u8]>>]>,
+    block_size: Option<[u32; 3]>,
+    (major, minor): (u32, u32),
+) -> Vec<u8> {
+    fn write_len(key: &mut Vec<u8>, len: usize) {
+        key.extend_from_slice(&(len as u64).to_le_bytes());
+    }
+
+    let mut key = Vec::new();
+
+    write_len(&mut key, compiler.ptx.to_bytes().len());
+    key.extend_from_slice(compiler.ptx.to_bytes());
+    write_len(&mut key, entry_point.to_bytes().len());
+    key.extend_from_slice(entry_point.to_bytes());
+
+    match arguments {
+        None => key.push(0),
+        Some(arguments) => {
+            key.push(1);
+            write_len(&mut key, arguments.len());
+            for argument in arguments {
+                match argument {
+                    None => key.push(0),
+                    Some(bytes) => {
+                        key.push(1);
+                        write_len(&mut key, bytes.len());
+                        key.extend_from_slice(bytes);
+                    },
+                }
+            }
+        },
+    }
+
+    match block_size {
+        None => key.push(0),
+        Some(block_size) => {
+            key.push(1);
+            for dimension in block_size {
+                key.extend_from_slice(&dimension.to_le_bytes());
+            }
+        },
+    }
+
+    key.extend_from_slice(&major.to_le_bytes());
+    key.extend_from_slice(&minor.to_le_bytes());
+
+    key
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        time::{Duration, SystemTime},
+    };
+
+    use super::PtxJITDiskCache;
+
+    #[test]
+    fn write_read_evict() {
+        let directory = std::env::temp_dir().join(format!(
+            "rust-cuda-ptx-jit-disk-cache-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&directory);
+
+        // each entry is 8 bytes of key length, 3 bytes of key, and 6 of PTX
+        let cache = PtxJITDiskCache::new(&directory, 2 * 17);
+
+        let old = cache.entry_path(b"old");
+        cache.write_entry(&old, b"old", c"ptx[0]").unwrap();
+        assert_eq!(
+            PtxJITDiskCache::read_entry(&old, b"old").as_deref(),
+            Some(c"ptx[0]")
+        );
+
+        // an entry is only returned if its full key matches
+        assert_eq!(PtxJITDiskCache::read_entry(&old, b"new"), None);
+
+        File::options()
+            .write(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+
+        let new = cache.entry_path(b"new");
+        cache.write_entry(&new, b"new", c"ptx[1]").unwrap();
+        let newer = cache.entry_path(b"nwr");
+        cache.write_entry(&newer, b"nwr", c"ptx[2]").unwrap();
+
+        cache.evict().unwrap();
+
+        // only the least recently used entry is evicted
+        assert!(!old.exists());
+        assert_eq!(
+            PtxJITDiskCache::read_entry(&new, b"new").as_deref(),
+            Some(c"ptx[1]")
+        );
+        assert_eq!(
+            PtxJITDiskCache::read_entry(&newer, b"nwr").as_deref(),
+            Some(c"ptx[2]")
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,195 @@
+use std::ops::Range;
+
+/// A lexical token of PTX source code, which refers to its `span` of bytes
+/// in the source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A sequence of whitespace characters
+    Whitespace,
+    /// A `// ...` comment until the end of the line
+    LineComment,
+    /// A `/* ... */` comment
+    BlockComment,
+    /// An identifier, e.g. an opcode `ld`, a register `%rd1`, or a label
+    Identifier,
+    /// A directive or instruction modifier, e.g. `.reg`, `.global`, `.u32`,
+    /// or `.L1::evict_last`
+    Directive,
+    /// An integer or floating point literal, e.g. `42`, `0x2AU`, or
+    /// `0f3F800000`
+    Number,
+    /// A `"..."` string literal
+    String,
+    /// Any other single byte, e.g. `;`, `,`, `[`, or `+`
+    Punct(u8),
+}
+
+impl TokenKind {
+    /// Returns `true` iff the token carries no meaning for the PTX parser.
+    #[must_use]
+    pub const fn is_trivia(self) -> bool {
+        matches!(
+            self,
+            Self::Whitespace | Self::LineComment | Self::BlockComment
+        )
+    }
+}
+
+/// Splits the `ptx` source code into a lossless sequence of [`Token`]s, i.e.
+/// the spans of all tokens cover the entire source.
+pub fn tokenize(ptx: &[u8]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+
+    while let Some(&byte) = ptx.get(start) {
+        let rest = ptx.get(start..).unwrap_or_default();
+
+        let (kind, len) = match (byte, rest.get(1).copied()) {
+            (b'/', Some(b'/')) => (
+                TokenKind::LineComment,
+                rest.iter().position(|b| *b == b'\n').unwrap_or(rest.len()),
+            ),
+            (b'/', Some(b'*')) => (
+                TokenKind::BlockComment,
+                rest.windows(2)
+                    .skip(2)
+                    .position(|w| w == b"*/")
+                    .map_or(rest.len(), |end| end + 4),
+            ),
+            (b'"', _) => (TokenKind::String, string_len(rest)),
+            (b'.', Some(next)) if is_identifier_start(next) => (
+                TokenKind::Directive,
+                1 + rest
+                    .iter()
+                    .skip(1)
+                    .take_while(|b| is_identifier_continue(**b) || **b == b':')
+                    .count(),
+            ),
+            (b'0'..=b'9', _) => (
+                TokenKind::Number,
+                rest.iter()
+                    .take_while(|b| b.is_ascii_alphanumeric() || **b == b'.')
+                    .count(),
+            ),
+            (byte, _) if is_identifier_start(byte) || byte == b'%' => (
+                TokenKind::Identifier,
+                1 + rest
+                    .iter()
+                    .skip(1)
+                    .take_while(|b| is_identifier_continue(**b))
+                    .count(),
+            ),
+            (byte, _) if byte.is_ascii_whitespace() => (
+                TokenKind::Whitespace,
+                rest.iter().take_while(|b| b.is_ascii_whitespace()).count(),
+            ),
+            (byte, _) => (TokenKind::Punct(byte), 1),
+        };
+
+        tokens.push(Token {
+            kind,
+            span: start..(start + len),
+        });
+
+        start += len;
+    }
+
+    tokens
+}
+
+const fn is_identifier_start(byte: u8) -> bool {
+    byte.is_ascii_alphabetic() || matches!(byte, b'_' | b'$')
+}
+
+const fn is_identifier_continue(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'$')
+}
+
+fn string_len(rest: &[u8]) -> usize {
+    let mut escaped = false;
+
+    for (i, byte) in rest.iter().enumerate().skip(1) {
+        match byte {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => return i + 1,
+            _ => (),
+        }
+    }
+
+    rest.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tokenize, TokenKind};
+
+    #[test]
+    fn tokens_cover_the_source() {
+        let ptx = b"@!%p1 ld.global.nc.L1::evict_last.u32 \t%r1, [%rd2+0x4U]; // <marker>\n";
+
+        let tokens = tokenize(ptx);
+
+        assert_eq!(tokens.first().map(|token| token.span.start), Some(0));
+        assert_eq!(tokens.last().map(|token| token.span.end), Some(ptx.len()));
+        assert!(tokens
+            .windows(2)
+            .all(|tokens| tokens[0].span.end == tokens[1].span.start));
+
+        let tokens = tokens
+            .iter()
+            .filter(|token| !token.kind.is_trivia())
+            .map(|token| (token.kind, &ptx[token.span.clone()]))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens,
+            [
+                (TokenKind::Punct(b'@'), &b"@"[..]),
+                (TokenKind::Punct(b'!'), b"!"),
+                (TokenKind::Identifier, b"%p1"),
+                (TokenKind::Identifier, b"ld"),
+                (TokenKind::Directive, b".global"),
+                (TokenKind::Directive, b".nc"),
+                (TokenKind::Directive, b".L1::evict_last"),
+                (TokenKind::Directive, b".u32"),
+                (TokenKind::Identifier, b"%r1"),
+                (TokenKind::Punct(b','), b","),
+                (TokenKind::Punct(b'['), b"["),
+                (TokenKind::Identifier, b"%rd2"),
+                (TokenKind::Punct(b'+'), b"+"),
+                (TokenKind::Number, b"0x4U"),
+                (TokenKind::Punct(b']'), b"]"),
+                (TokenKind::Punct(b';'), b";"),
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_and_strings() {
+        let ptx = b"/* a; */ .file 1 \"a // b.rs\" // c\n";
+
+        let kinds = tokenize(ptx)
+            .iter()
+            .map(|token| token.kind)
+            .filter(|kind| *kind != TokenKind::Whitespace)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::BlockComment,
+                TokenKind::Directive,
+                TokenKind::Number,
+                TokenKind::String,
+                TokenKind::LineComment,
+            ]
+        );
+    }
+}
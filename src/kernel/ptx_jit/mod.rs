@@ -1,20 +1,29 @@
-use std::ffi::{CStr, CString};
+use std::ffi::CString;
 
+mod cache;
+mod diagnostic;
+mod disk;
+mod lexer;
+mod parser;
 mod preprocess;
-mod regex;
 mod replace;
+mod report;
 
-type ByteSliceOptionalArguments = Option<Box<[Option<Box<[u8]>>]>>;
+pub use cache::{PtxJITCache, PtxJITCacheLookup, PtxJITCacheStats};
+pub use diagnostic::PtxJITDiagnostic;
+pub use disk::PtxJITDiskCache;
+pub use report::{PtxJITConstLoad, PtxJITReport};
+
+use parser::ScalarType;
+
+/// The byte representations of the kernel arguments that a PTX kernel has
+/// been specialised for, where [`None`] arguments are not specialised.
+type PtxJITArguments = Box<[Option<Box<[u8]>>]>;
 
 pub struct PtxJITCompiler {
     ptx_slices: Box<[PtxElement]>,
-    last_arguments: ByteSliceOptionalArguments,
-    last_ptx: CString,
-}
-
-pub enum PtxJITResult<'s> {
-    Cached(&'s CStr),
-    Recomputed(&'s CStr),
+    ptx: CString,
+    diagnostics: Box<[PtxJITDiagnostic]>,
 }
 
 enum PtxLoadWidth {
@@ -24,15 +33,48 @@ enum PtxLoadWidth {
     B8,
 }
 
+impl PtxLoadWidth {
+    const fn bytes(&self) -> usize {
+        match self {
+            Self::B1 => 1,
+            Self::B2 => 2,
+            Self::B4 => 4,
+            Self::B8 => 8,
+        }
+    }
+}
+
 enum PtxElement {
     CopiedSource {
         ptx: Box<[u8]>,
     },
     ConstLoad {
         ptx: Box<[u8]>,
+        guard: Option<Box<[u8]>>,
         parameter_index: usize,
         byte_offset: usize,
         load_width: PtxLoadWidth,
-        registers: Box<[Box<[u8]>]>,
+        sign_extend: bool,
+        registers: Box<[Option<PtxRegister>]>,
     },
+    /// An existing `.maxntid` or `.reqntid` directive of a kernel entry
+    /// point, which is removed when specialising for a block size
+    BlockSizeDirective {
+        ptx: Box<[u8]>,
+    },
+    /// The position before the body of a kernel entry point, where a
+    /// `.reqntid` directive is inserted when specialising for a block size
+    RequiredBlockSize,
+    /// A `%ntid.{x,y,z}` operand of a `mov`, which is replaced with the
+    /// block size in the `dimension` when specialising for a block size
+    BlockDimension {
+        ptx: Box<[u8]>,
+        dimension: usize,
+    },
+}
+
+/// A destination register of a constant load, which has a declared `ty`pe.
+struct PtxRegister {
+    name: Box<[u8]>,
+    ty: ScalarType,
 }
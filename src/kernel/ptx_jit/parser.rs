@@ -0,0 +1,688 @@
+use std::{collections::HashMap, ops::Range};
+
+use super::lexer::{tokenize, Token, TokenKind};
+
+/// The prefix of the PTX JIT marker comments that are emitted by
+/// [`PtxJit`](crate::kernel::param::PtxJit) kernel parameters.
+const MARKER_PREFIX: &[u8] = b"<rust-cuda-ptx-jit-const-load-";
+
+/// A lightweight model of the instructions inside the function bodies of a
+/// PTX module, which is sufficient for the PTX JIT.
+///
+/// Register names are only unique within the function that declares them,
+/// so all registers are qualified by the `scope` of their function.
+#[derive(Default)]
+pub struct PtxModule<'p> {
+    pub functions: Vec<FunctionHeader<'p>>,
+    pub statements: Vec<Statement<'p>>,
+    pub markers: Vec<Marker<'p>>,
+    register_names: HashMap<(usize, &'p [u8]), ScalarType>,
+    register_prefixes: HashMap<(usize, &'p [u8]), ScalarType>,
+}
+
+/// The header of a function with a body, e.g.
+/// `.visible .entry kernel(.param .u64 kernel_param_0) .maxntid 256, 1, 1`.
+pub struct FunctionHeader<'p> {
+    /// The name of the function iff it is a kernel `.entry` point
+    pub entry_point: Option<&'p [u8]>,
+    /// The byte index of the `{` that opens the function's body
+    pub body: usize,
+    /// The byte spans of all `.maxntid` and `.reqntid` directives
+    pub block_size_directives: Vec<Range<usize>>,
+}
+
+/// An instruction or directive statement that ends with a `;`.
+pub struct Statement<'p> {
+    /// The function scope that the statement belongs to
+    pub scope: usize,
+    /// The byte span of the statement, including its guard and the `;`
+    pub span: Range<usize>,
+    /// The optional `@%p` or `@!%p` guard predicate
+    pub guard: Option<&'p [u8]>,
+    /// The opcode, e.g. `ld`, or a directive, e.g. `.reg`
+    pub opcode: &'p [u8],
+    /// The `.`-prefixed modifiers of the opcode, e.g. `.global` and `.u32`
+    pub modifiers: Vec<&'p [u8]>,
+    pub operands: Vec<Operand<'p>>,
+    /// The byte spans of the `operands`
+    pub operand_spans: Vec<Range<usize>>,
+}
+
+pub enum Operand<'p> {
+    /// A (special) register, e.g. `%rd1` or `%ntid.x`
+    Register(&'p [u8]),
+    /// A vector of registers, e.g. `{%r1, %r2}`, which may contain `_` sinks
+    Vector(Vec<&'p [u8]>),
+    /// A memory address `[base+offset]`, where the base is a register or a
+    /// variable
+    Address { base: &'p [u8], offset: i64 },
+    /// An immediate value, e.g. `42`
+    Immediate(i64),
+    /// Any other operand, e.g. a label, a variable, or a parameter list
+    Other(&'p [u8]),
+}
+
+/// A `// <rust-cuda-ptx-jit-const-load-{register}-{parameter_index}> //`
+/// marker comment, which states that the `register` holds the first four
+/// bytes of the kernel parameter with the `parameter_index`.
+pub struct Marker<'p> {
+    pub scope: usize,
+    pub register: &'p [u8],
+    pub parameter_index: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScalarKind {
+    Bits,
+    Unsigned,
+    Signed,
+    Float,
+    Predicate,
+}
+
+/// A fundamental PTX type, e.g. `.u32`, `.f64`, or `.pred`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScalarType {
+    pub kind: ScalarKind,
+    pub bits: u16,
+}
+
+impl ScalarType {
+    /// Parses a type modifier, e.g. `.s16`.
+    #[must_use]
+    pub fn parse(modifier: &[u8]) -> Option<Self> {
+        if modifier == b".pred" {
+            return Some(Self {
+                kind: ScalarKind::Predicate,
+                bits: 1,
+            });
+        }
+
+        let (kind, bits) = match modifier {
+            [b'.', b'b', bits @ ..] => (ScalarKind::Bits, bits),
+            [b'.', b'u', bits @ ..] => (ScalarKind::Unsigned, bits),
+            [b'.', b's', bits @ ..] => (ScalarKind::Signed, bits),
+            [b'.', b'f', bits @ ..] => (ScalarKind::Float, bits),
+            _ => return None,
+        };
+
+        let bits = std::str::from_utf8(bits).ok()?.parse().ok()?;
+
+        matches!(bits, 8 | 16 | 32 | 64).then_some(Self { kind, bits })
+    }
+}
+
+impl<'p> PtxModule<'p> {
+    /// Returns the declared type of the `register` in the function `scope`.
+    #[must_use]
+    pub fn register_type(&self, scope: usize, register: &[u8]) -> Option<ScalarType> {
+        if let Some(ty) = self.register_names.get(&(scope, register)) {
+            return Some(*ty);
+        }
+
+        // parameterised register declarations, e.g. `.reg .b32 %r<5>;`,
+        //  declare registers with a common prefix and a numeric suffix
+        let prefix = register.trim_ascii_end_matches_digits();
+
+        if prefix.len() == register.len() {
+            return None;
+        }
+
+        self.register_prefixes.get(&(scope, prefix)).copied()
+    }
+
+    fn push_statement(&mut self, ptx: &'p [u8], scope: usize, tokens: &[&Token]) {
+        let Some(statement) = Statement::parse(ptx, scope, tokens) else {
+            return;
+        };
+
+        if statement.opcode != b".reg" {
+            self.statements.push(statement);
+            return;
+        }
+
+        let Some(ty) = statement
+            .modifiers
+            .last()
+            .and_then(|modifier| ScalarType::parse(modifier))
+        else {
+            return;
+        };
+
+        // vector registers are not modelled
+        if statement.modifiers.len() != 1 {
+            return;
+        }
+
+        for operand in statement.operands {
+            match operand {
+                Operand::Register(name) => {
+                    self.register_names.insert((scope, name), ty);
+                },
+                Operand::Other(declaration) => {
+                    if let Some((prefix, _)) = declaration.split_once_byte(b'<') {
+                        self.register_prefixes
+                            .insert((scope, prefix.trim_ascii()), ty);
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Parses the `ptx` source code into a [`PtxModule`].
+///
+/// Only the statements inside function bodies and the headers of functions
+/// are modelled, while all other module level directives, e.g. `.version`
+/// and global variable declarations, are skipped.
+#[must_use]
+pub fn parse(ptx: &[u8]) -> PtxModule<'_> {
+    let mut module = PtxModule::default();
+
+    let mut depth = 0_usize;
+    let mut scope = 0_usize;
+    let mut nesting = 0_usize;
+    let mut statement: Vec<&Token> = Vec::new();
+
+    let tokens = tokenize(ptx);
+
+    for token in &tokens {
+        let text = ptx.get(token.span.clone()).unwrap_or_default();
+
+        match token.kind {
+            TokenKind::LineComment | TokenKind::BlockComment => {
+                if depth > 0 {
+                    if let Some((register, parameter_index)) = parse_marker(text) {
+                        module.markers.push(Marker {
+                            scope,
+                            register,
+                            parameter_index,
+                        });
+                    }
+                }
+            },
+            TokenKind::Whitespace => (),
+            TokenKind::Punct(b';') if nesting == 0 => {
+                if depth > 0 {
+                    statement.push(token);
+                    module.push_statement(ptx, scope, &statement);
+                }
+                statement.clear();
+            },
+            // a `label:` inside a function body
+            TokenKind::Punct(b':')
+                if depth > 0
+                    && nesting == 0
+                    && matches!(
+                        statement.as_slice(),
+                        [Token {
+                            kind: TokenKind::Identifier,
+                            ..
+                        }]
+                    ) =>
+            {
+                statement.clear();
+            },
+            // a `{` inside a statement, e.g. a vector operand or a variable
+            //  initialiser, rather than a function body or block
+            TokenKind::Punct(b'{')
+                if !statement.is_empty()
+                    && (depth > 0
+                        || nesting > 0
+                        || statement
+                            .iter()
+                            .any(|token| token.kind == TokenKind::Punct(b'='))) =>
+            {
+                nesting += 1;
+                statement.push(token);
+            },
+            TokenKind::Punct(b'}') if nesting > 0 => {
+                nesting -= 1;
+                statement.push(token);
+            },
+            TokenKind::Punct(b'{') => {
+                if depth == 0 {
+                    scope += 1;
+                    module
+                        .functions
+                        .push(FunctionHeader::parse(ptx, &statement, token.span.start));
+                }
+                depth += 1;
+                statement.clear();
+            },
+            TokenKind::Punct(b'}') => {
+                depth = depth.saturating_sub(1);
+                statement.clear();
+            },
+            _ => statement.push(token),
+        }
+    }
+
+    module
+}
+
+impl<'p> Statement<'p> {
+    fn parse(ptx: &'p [u8], scope: usize, tokens: &[&Token]) -> Option<Self> {
+        let start = tokens.first()?.span.start;
+        let end = tokens.last()?.span.end;
+
+        let text = |tokens: &[&Token]| -> &'p [u8] {
+            match (tokens.first(), tokens.last()) {
+                (Some(first), Some(last)) => ptx.get(first.span.start..last.span.end),
+                _ => None,
+            }
+            .unwrap_or_default()
+        };
+
+        let mut rest = tokens;
+
+        let guard = match rest {
+            [at, negation, predicate, tail @ ..]
+                if at.kind == TokenKind::Punct(b'@') && negation.kind == TokenKind::Punct(b'!') =>
+            {
+                rest = tail;
+                ptx.get(at.span.start..predicate.span.end)
+            },
+            [at, predicate, tail @ ..] if at.kind == TokenKind::Punct(b'@') => {
+                rest = tail;
+                ptx.get(at.span.start..predicate.span.end)
+            },
+            _ => None,
+        };
+
+        let (opcode, tail) = rest.split_first()?;
+        if !matches!(opcode.kind, TokenKind::Identifier | TokenKind::Directive) {
+            return None;
+        }
+        rest = tail;
+
+        let mut modifiers = Vec::new();
+        while let [modifier, tail @ ..] = rest {
+            if modifier.kind != TokenKind::Directive {
+                break;
+            }
+            modifiers.push(ptx.get(modifier.span.clone()).unwrap_or_default());
+            rest = tail;
+        }
+
+        // the operands are separated by top-level commas, and the trailing
+        //  `;` is not part of any operand
+        let rest = match rest.split_last() {
+            Some((last, init)) if last.kind == TokenKind::Punct(b';') => init,
+            _ => rest,
+        };
+
+        let (operands, operand_spans) = split_top_level(rest, b',')
+            .into_iter()
+            .filter_map(|operand| {
+                let span = operand.first()?.span.start..operand.last()?.span.end;
+                Some((Operand::parse(ptx, operand, text(operand)), span))
+            })
+            .unzip();
+
+        Some(Self {
+            scope,
+            span: start..end,
+            guard,
+            opcode: ptx.get(opcode.span.clone()).unwrap_or_default(),
+            modifiers,
+            operands,
+            operand_spans,
+        })
+    }
+
+    /// Returns `true` iff the statement has the `modifier`, e.g. `.global`.
+    #[must_use]
+    pub fn has_modifier(&self, modifier: &[u8]) -> bool {
+        self.modifiers.contains(&modifier)
+    }
+}
+
+impl<'p> FunctionHeader<'p> {
+    fn parse(ptx: &'p [u8], tokens: &[&Token], body: usize) -> Self {
+        let token_text = |token: &Token| ptx.get(token.span.clone()).unwrap_or_default();
+
+        let entry_point = tokens
+            .iter()
+            .position(|token| token_text(token) == b".entry")
+            .and_then(|entry| tokens.get(entry + 1))
+            .filter(|name| name.kind == TokenKind::Identifier)
+            .map(|name| token_text(name));
+
+        let mut block_size_directives = Vec::new();
+
+        for (i, token) in tokens.iter().enumerate() {
+            if !matches!(token_text(token), b".maxntid" | b".reqntid") {
+                continue;
+            }
+
+            // the directive is followed by one to three comma-separated
+            //  dimensions
+            let end = tokens
+                .iter()
+                .skip(i + 1)
+                .take_while(|token| {
+                    matches!(token.kind, TokenKind::Number | TokenKind::Punct(b','))
+                })
+                .last()
+                .map_or(token.span.end, |last| last.span.end);
+
+            block_size_directives.push(token.span.start..end);
+        }
+
+        Self {
+            entry_point,
+            body,
+            block_size_directives,
+        }
+    }
+}
+
+impl<'p> Operand<'p> {
+    fn parse(ptx: &'p [u8], tokens: &[&Token], text: &'p [u8]) -> Self {
+        let token_text = |token: &Token| ptx.get(token.span.clone()).unwrap_or_default();
+
+        match tokens {
+            [register, components @ ..]
+                if register.kind == TokenKind::Identifier
+                    && token_text(register).starts_with(b"%")
+                    && components
+                        .iter()
+                        .all(|component| component.kind == TokenKind::Directive) =>
+            {
+                Self::Register(text)
+            },
+            [Token {
+                kind: TokenKind::Punct(b'{'),
+                ..
+            }, inner @ .., Token {
+                kind: TokenKind::Punct(b'}'),
+                ..
+            }] => {
+                let mut registers = Vec::new();
+
+                for element in split_top_level(inner, b',') {
+                    match element {
+                        [register] if register.kind == TokenKind::Identifier => {
+                            registers.push(token_text(register));
+                        },
+                        _ => return Self::Other(text),
+                    }
+                }
+
+                Self::Vector(registers)
+            },
+            [Token {
+                kind: TokenKind::Punct(b'['),
+                ..
+            }, base, offset @ .., Token {
+                kind: TokenKind::Punct(b']'),
+                ..
+            }] if base.kind == TokenKind::Identifier => {
+                let offset = match offset {
+                    [] => Some(0),
+                    [Token {
+                        kind: TokenKind::Punct(b'+'),
+                        ..
+                    }, number] => parse_integer(token_text(number)),
+                    [Token {
+                        kind: TokenKind::Punct(b'+'),
+                        ..
+                    }, Token {
+                        kind: TokenKind::Punct(b'-'),
+                        ..
+                    }, number]
+                    | [Token {
+                        kind: TokenKind::Punct(b'-'),
+                        ..
+                    }, number] => parse_integer(token_text(number)).map(i64::wrapping_neg),
+                    _ => None,
+                };
+
+                offset.map_or(Self::Other(text), |offset| Self::Address {
+                    base: token_text(base),
+                    offset,
+                })
+            },
+            [number] if number.kind == TokenKind::Number => {
+                parse_integer(token_text(number)).map_or(Self::Other(text), Self::Immediate)
+            },
+            [Token {
+                kind: TokenKind::Punct(b'-'),
+                ..
+            }, number]
+                if number.kind == TokenKind::Number =>
+            {
+                parse_integer(token_text(number)).map_or(Self::Other(text), |number| {
+                    Self::Immediate(number.wrapping_neg())
+                })
+            },
+            _ => Self::Other(text),
+        }
+    }
+}
+
+/// Splits the `tokens` at every `separator` that is not nested inside any
+/// brackets.
+fn split_top_level<'t, 'a>(tokens: &'a [&'t Token], separator: u8) -> Vec<&'a [&'t Token]> {
+    let mut parts = Vec::new();
+    let mut nesting = 0_usize;
+    let mut start = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Punct(b'[' | b'{' | b'(') => nesting += 1,
+            TokenKind::Punct(b']' | b'}' | b')') => nesting = nesting.saturating_sub(1),
+            TokenKind::Punct(byte) if byte == separator && nesting == 0 => {
+                parts.push(tokens.get(start..i).unwrap_or_default());
+                start = i + 1;
+            },
+            _ => (),
+        }
+    }
+
+    parts.push(tokens.get(start..).unwrap_or_default());
+
+    parts
+}
+
+/// Parses a decimal, hexadecimal, octal, or binary PTX integer literal with
+/// an optional `U` suffix.
+fn parse_integer(literal: &[u8]) -> Option<i64> {
+    let literal = std::str::from_utf8(literal).ok()?;
+    let literal = literal.strip_suffix('U').unwrap_or(literal);
+
+    let (digits, radix) = if let Some(hex) = literal
+        .strip_prefix("0x")
+        .or_else(|| literal.strip_prefix("0X"))
+    {
+        (hex, 16)
+    } else if let Some(binary) = literal
+        .strip_prefix("0b")
+        .or_else(|| literal.strip_prefix("0B"))
+    {
+        (binary, 2)
+    } else if literal.len() > 1 && literal.starts_with('0') {
+        (literal.get(1..)?, 8)
+    } else {
+        (literal, 10)
+    };
+
+    // unsigned literals may use all 64 bits
+    u64::from_str_radix(digits, radix)
+        .ok()
+        .map(|integer| i64::from_ne_bytes(integer.to_ne_bytes()))
+}
+
+/// Parses the `register` and `parameter_index` of a PTX JIT marker comment.
+fn parse_marker(comment: &[u8]) -> Option<(&[u8], usize)> {
+    let start = comment
+        .windows(MARKER_PREFIX.len())
+        .position(|window| window == MARKER_PREFIX)?
+        + MARKER_PREFIX.len();
+    let marker = comment.get(start..)?;
+    let marker = marker.get(..marker.iter().position(|b| *b == b'>')?)?;
+
+    let (register, parameter_index) = marker.rsplit_once_byte(b'-')?;
+    let parameter_index = std::str::from_utf8(parameter_index).ok()?.parse().ok()?;
+
+    Some((register, parameter_index))
+}
+
+trait ByteSliceExt {
+    fn split_once_byte(&self, byte: u8) -> Option<(&[u8], &[u8])>;
+    fn rsplit_once_byte(&self, byte: u8) -> Option<(&[u8], &[u8])>;
+    fn trim_ascii_end_matches_digits(&self) -> &[u8];
+}
+
+impl ByteSliceExt for [u8] {
+    fn split_once_byte(&self, byte: u8) -> Option<(&[u8], &[u8])> {
+        let i = self.iter().position(|b| *b == byte)?;
+        Some((self.get(..i)?, self.get((i + 1)..)?))
+    }
+
+    fn rsplit_once_byte(&self, byte: u8) -> Option<(&[u8], &[u8])> {
+        let i = self.iter().rposition(|b| *b == byte)?;
+        Some((self.get(..i)?, self.get((i + 1)..)?))
+    }
+
+    fn trim_ascii_end_matches_digits(&self) -> &[u8] {
+        let len = self
+            .iter()
+            .rposition(|b| !b.is_ascii_digit())
+            .map_or(0, |i| i + 1);
+        self.get(..len).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::{parse, Operand, ScalarKind, ScalarType};
+
+    const PTX: &[u8] = b"
+.version 8.0
+.global .align 4 .u32 counter[2] = {0, 1};
+
+.visible .entry kernel(
+\t.param .u64 kernel_param_0
+)
+.maxntid 256, 1, 1
+{
+\t.reg .pred \t%p<2>;
+\t.reg .b16 \t%rs<3>;
+\t.reg .b64 \t%rd<5>, %ptr;
+
+\tld.param.u64 \t%rd1, [kernel_param_0];
+\t// <rust-cuda-ptx-jit-const-load-%rd1-0> //
+$L__BB0_1:
+\t@!%p1 ld.global.nc.v2.u16 \t{%rs1, _}, [%rd1+-8];
+\tret;
+}
+
+.func helper()
+{
+\t.reg .b32 \t%rd<2>;
+\tret;
+}
+";
+
+    #[test]
+    fn functions() {
+        let module = parse(PTX);
+
+        assert_eq!(module.functions.len(), 2);
+        assert_eq!(module.functions[0].entry_point, Some(&b"kernel"[..]));
+        assert_eq!(module.functions[1].entry_point, None);
+
+        let directives = &module.functions[0].block_size_directives;
+        assert_eq!(directives.len(), 1);
+        assert_eq!(&PTX[directives[0].clone()], b".maxntid 256, 1, 1");
+        assert_eq!(PTX[module.functions[0].body], b'{');
+    }
+
+    #[test]
+    fn statements() {
+        let module = parse(PTX);
+
+        // the global variable initialiser and the label are not statements
+        let opcodes = module
+            .statements
+            .iter()
+            .map(|statement| statement.opcode)
+            .collect::<Vec<_>>();
+        assert_eq!(opcodes, [&b"ld"[..], b"ld", b"ret", b"ret"]);
+
+        let load = &module.statements[1];
+        assert_eq!(load.scope, 1);
+        assert_eq!(load.guard, Some(&b"@!%p1"[..]));
+        assert_eq!(load.modifiers, [&b".global"[..], b".nc", b".v2", b".u16"]);
+        assert!(matches!(
+            load.operands.as_slice(),
+            [Operand::Vector(registers), Operand::Address { base: b"%rd1", offset: -8 }]
+                if registers == &[&b"%rs1"[..], b"_"]
+        ));
+        assert_eq!(
+            &PTX[load.span.clone()],
+            b"@!%p1 ld.global.nc.v2.u16 \t{%rs1, _}, [%rd1+-8];"
+        );
+    }
+
+    #[test]
+    fn register_types_are_scoped() {
+        let module = parse(PTX);
+
+        let b16 = ScalarType {
+            kind: ScalarKind::Bits,
+            bits: 16,
+        };
+        let b32 = ScalarType {
+            kind: ScalarKind::Bits,
+            bits: 32,
+        };
+        let b64 = ScalarType {
+            kind: ScalarKind::Bits,
+            bits: 64,
+        };
+
+        assert_eq!(module.register_type(1, b"%rs2"), Some(b16));
+        assert_eq!(module.register_type(1, b"%rd1"), Some(b64));
+        assert_eq!(module.register_type(1, b"%ptr"), Some(b64));
+        assert_eq!(module.register_type(2, b"%rd1"), Some(b32));
+        assert_eq!(module.register_type(1, b"%rs"), None);
+        assert_eq!(module.register_type(1, b"%r1"), None);
+    }
+
+    #[test]
+    fn markers() {
+        let module = parse(PTX);
+
+        assert_eq!(module.markers.len(), 1);
+
+        let marker = &module.markers[0];
+        assert_eq!(marker.scope, 1);
+        assert_eq!(marker.register, b"%rd1");
+        assert_eq!(marker.parameter_index, 0);
+    }
+
+    #[test]
+    fn scalar_types() {
+        assert_eq!(
+            ScalarType::parse(b".s8"),
+            Some(ScalarType {
+                kind: ScalarKind::Signed,
+                bits: 8
+            })
+        );
+        assert_eq!(
+            ScalarType::parse(b".pred"),
+            Some(ScalarType {
+                kind: ScalarKind::Predicate,
+                bits: 1
+            })
+        );
+        assert_eq!(ScalarType::parse(b".u128"), None);
+        assert_eq!(ScalarType::parse(b".global"), None);
+    }
+}
@@ -1,136 +1,648 @@
 use std::{
     collections::HashMap,
     ffi::{CStr, CString},
+    ops::Range,
 };
 
 use super::{
-    regex::{
-        const_base_register_regex, const_load_instruction_regex, const_marker_regex, register_regex,
-    },
-    PtxElement, PtxJITCompiler, PtxLoadWidth,
+    parser::{self, Operand, PtxModule, ScalarKind, ScalarType, Statement},
+    PtxElement, PtxJITCompiler, PtxJITDiagnostic, PtxLoadWidth, PtxRegister,
 };
 
+/// A register, which is qualified by the scope of the function in which it
+/// is declared.
+type RegisterKey<'p> = (usize, &'p [u8]);
+
+/// A register which holds a pointer into a kernel parameter, i.e. the
+/// address of the parameter's first byte plus the `byte_offset`.
+#[derive(Clone, Copy)]
+struct ParameterPointer {
+    parameter_index: usize,
+    byte_offset: i64,
+}
+
 impl PtxJITCompiler {
     #[must_use]
     pub fn new(ptx: &CStr) -> Self {
         let ptx = ptx.to_bytes();
 
-        let mut const_markers: HashMap<&[u8], usize> = HashMap::new();
+        let module = parser::parse(ptx);
+        let definitions = count_definitions(&module);
 
-        // Find injected rust-cuda-const-markers which identify dummy register rxx
-        for const_marker in const_marker_regex().captures_iter(ptx) {
-            if let Some(tmpreg) = const_marker.name("tmpreg").map(|s| s.as_bytes()) {
-                if let Some(param) = const_marker
-                    .name("param")
-                    .map(|s| s.as_bytes())
-                    .and_then(|b| std::str::from_utf8(b).ok())
-                    .and_then(|s| s.parse().ok())
-                {
-                    const_markers.insert(tmpreg, param);
-                }
+        let mut diagnostics = Vec::new();
+
+        // Find all registers that point into a kernel parameter, starting
+        //  from the pointers that the rust-cuda-ptx-jit-const-load markers
+        //  were loaded from
+        let pointers = find_parameter_pointers(&module, &definitions, &mut diagnostics);
+
+        // Spans of the PTX source that may be replaced during specialisation
+        let mut replacements: Vec<(Range<usize>, PtxElement)> = Vec::new();
+
+        // Block size directives of kernel entry points are replaced with a
+        //  `.reqntid` directive that is inserted before the body
+        for function in &module.functions {
+            if function.entry_point.is_none() {
+                continue;
+            }
+
+            for directive in &function.block_size_directives {
+                replacements.push((
+                    directive.clone(),
+                    PtxElement::BlockSizeDirective {
+                        ptx: ptx.get(directive.clone()).unwrap_or_default().into(),
+                    },
+                ));
             }
+
+            replacements.push((function.body..function.body, PtxElement::RequiredBlockSize));
         }
-        // const_markers now contains a mapping rxx => param index
-
-        let mut const_base_registers: HashMap<&[u8], usize> = HashMap::new();
-
-        // Find base register ryy which was used in `ld.global.u32 rxx, [ryy];`
-        for const_base_register in const_base_register_regex().captures_iter(ptx) {
-            if let Some(tmpreg) = const_base_register.name("tmpreg").map(|s| s.as_bytes()) {
-                if let Some(param) = const_markers.get(tmpreg) {
-                    if let Some(basereg) = const_base_register.name("basereg").map(|s| s.as_bytes())
-                    {
-                        const_base_registers.insert(basereg, *param);
-                    }
+
+        for statement in &module.statements {
+            replacements.extend(block_dimension_read(ptx, statement));
+
+            let Some(pointer) = statement_uses(statement)
+                .find_map(|register| pointers.get(&(statement.scope, register)).copied())
+            else {
+                continue;
+            };
+
+            let instruction = ptx.get(statement.span.clone()).unwrap_or_default();
+
+            // Copies of pointers have already been followed
+            if let Some((destination, source, _)) = pointer_copy(statement) {
+                if pointers.contains_key(&(statement.scope, destination))
+                    && pointers.contains_key(&(statement.scope, source))
+                {
+                    continue;
                 }
             }
+
+            let const_load = match statement.operands.get(1) {
+                Some(Operand::Address { base, offset }) if is_memory_load(statement) => pointers
+                    .get(&(statement.scope, *base))
+                    .map(|base| const_load(&module, statement, instruction, *base, *offset)),
+                _ => None,
+            };
+
+            match const_load {
+                // Store the load instruction with extracted parameters to
+                //  generate a constant load if requested
+                Some(Ok(const_load)) => replacements.push((statement.span.clone(), const_load)),
+                Some(Err(reason)) => diagnostics.push(PtxJITDiagnostic::UnsupportedLoad {
+                    parameter_index: pointer.parameter_index,
+                    instruction: String::from_utf8_lossy(instruction).into(),
+                    reason,
+                }),
+                None => diagnostics.push(PtxJITDiagnostic::UnsupportedUse {
+                    parameter_index: pointer.parameter_index,
+                    instruction: String::from_utf8_lossy(instruction).into(),
+                }),
+            }
         }
-        // const_base_registers now contains a mapping ryy => param index
 
-        let mut from_index = 0_usize;
-        let mut last_slice = Vec::new();
+        replacements.sort_by_key(|(span, _)| span.start);
 
+        let mut from_index = 0_usize;
         let mut ptx_slices: Vec<PtxElement> = Vec::new();
 
-        // Iterate over all load from base register with offset instructions
-        for const_load_instruction in const_load_instruction_regex().captures_iter(ptx) {
-            // Only consider instructions where the base register is ryy
-            if let Some(basereg) = const_load_instruction.name("basereg").map(|s| s.as_bytes()) {
-                if let Some(param) = const_base_registers.get(basereg) {
-                    if let Some(loadwidth) = match const_load_instruction
-                        .name("loadwidth")
-                        .map(|s| s.as_bytes())
-                    {
-                        Some(b"8") => Some(PtxLoadWidth::B1),
-                        Some(b"16") => Some(PtxLoadWidth::B2),
-                        Some(b"32") => Some(PtxLoadWidth::B4),
-                        Some(b"64") => Some(PtxLoadWidth::B8),
-                        _ => None,
-                    } {
-                        if let Some(constreg) = const_load_instruction
-                            .name("constreg")
-                            .map(|s| s.as_bytes())
-                        {
-                            if let Some(loadoffset) = std::str::from_utf8(
-                                const_load_instruction
-                                    .name("loadoffset")
-                                    .map_or(b"0", |s| s.as_bytes()),
-                            )
-                            .ok()
-                            .and_then(|s| s.parse().ok())
-                            {
-                                if let Some((range, instruction)) = const_load_instruction
-                                    .name("instruction")
-                                    .map(|s| (s.range(), s.as_bytes()))
-                                {
-                                    // Store the PTX source code before the load instruction
-                                    last_slice.extend_from_slice(&ptx[from_index..range.start]);
-
-                                    ptx_slices.push(PtxElement::CopiedSource {
-                                        ptx: std::mem::take(&mut last_slice).into_boxed_slice(),
-                                    });
-
-                                    from_index = range.end;
-
-                                    // Store the load instruction with extracted parameters to
-                                    //  generate a constant load if requested
-                                    ptx_slices.push(PtxElement::ConstLoad {
-                                        ptx: instruction.to_owned().into_boxed_slice(),
-                                        parameter_index: *param,
-                                        byte_offset: loadoffset,
-                                        load_width: loadwidth,
-                                        registers: register_regex()
-                                            .captures_iter(constreg)
-                                            .filter_map(|m| {
-                                                m.name("register").map(|s| {
-                                                    s.as_bytes().to_owned().into_boxed_slice()
-                                                })
-                                            })
-                                            .collect::<Vec<_>>()
-                                            .into_boxed_slice(),
-                                    });
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        for (span, replacement) in replacements {
+            // Store the PTX source code before the replaced span
+            ptx_slices.push(PtxElement::CopiedSource {
+                ptx: ptx.get(from_index..span.start).unwrap_or_default().into(),
+            });
+            ptx_slices.push(replacement);
+
+            from_index = span.end;
         }
 
         // Store the remainder of the PTX source code
-        last_slice.extend_from_slice(&ptx[from_index..ptx.len()]);
-
-        if !last_slice.is_empty() {
+        if let Some(remainder) = ptx
+            .get(from_index..)
+            .filter(|remainder| !remainder.is_empty())
+        {
             ptx_slices.push(PtxElement::CopiedSource {
-                ptx: last_slice.into_boxed_slice(),
+                ptx: remainder.into(),
             });
         }
 
-        // Create the `PtxJITCompiler` which also caches the last PTX version
+        // Create the `PtxJITCompiler` which also keeps the unspecialised PTX
         Self {
             ptx_slices: ptx_slices.into_boxed_slice(),
-            last_arguments: None,
-            last_ptx: unsafe { CString::from_vec_unchecked(ptx.to_owned()) },
+            ptx: unsafe { CString::from_vec_unchecked(ptx.to_owned()) },
+            diagnostics: diagnostics.into_boxed_slice(),
+        }
+    }
+
+    /// Returns the patterns in the PTX source that were not recognised, and
+    /// which prevent some loads from kernel parameters from being
+    /// specialised.
+    #[must_use]
+    pub const fn diagnostics(&self) -> &[PtxJITDiagnostic] {
+        &self.diagnostics
+    }
+}
+
+/// Counts how often every register is assigned to.
+fn count_definitions<'p>(module: &PtxModule<'p>) -> HashMap<RegisterKey<'p>, usize> {
+    let mut definitions = HashMap::new();
+
+    for statement in &module.statements {
+        if !is_defining(statement) {
+            continue;
         }
+
+        let destinations = match statement.operands.first() {
+            Some(Operand::Register(register)) => std::slice::from_ref(register),
+            Some(Operand::Vector(registers)) => registers.as_slice(),
+            _ => &[],
+        };
+
+        for register in destinations {
+            *definitions.entry((statement.scope, *register)).or_default() += 1;
+        }
+    }
+
+    definitions
+}
+
+fn find_parameter_pointers<'p>(
+    module: &PtxModule<'p>,
+    definitions: &HashMap<RegisterKey<'p>, usize>,
+    diagnostics: &mut Vec<PtxJITDiagnostic>,
+) -> HashMap<RegisterKey<'p>, ParameterPointer> {
+    let is_defined_once = |register: RegisterKey<'p>| definitions.get(&register) == Some(&1);
+
+    let mut pointers = HashMap::new();
+
+    // The marker register holds the first bytes of the parameter, which were
+    //  loaded from the pointer `[base+offset]`, i.e. `base` points `offset`
+    //  bytes before the start of the parameter
+    for marker in &module.markers {
+        let base = module
+            .statements
+            .iter()
+            .filter(|statement| statement.scope == marker.scope && is_memory_load(statement))
+            .find_map(|statement| {
+                let Some(Operand::Address { base, offset }) = statement.operands.get(1) else {
+                    return None;
+                };
+
+                let element = match statement.operands.first()? {
+                    Operand::Register(register) if *register == marker.register => 0,
+                    Operand::Vector(registers) => registers
+                        .iter()
+                        .position(|register| *register == marker.register)?,
+                    _ => return None,
+                };
+                let width = statement
+                    .modifiers
+                    .last()
+                    .and_then(|modifier| ScalarType::parse(modifier))?
+                    .bits
+                    / 8;
+
+                Some((
+                    *base,
+                    offset + i64::try_from(element).ok()? * i64::from(width),
+                ))
+            });
+
+        match base {
+            Some((base, offset)) if is_defined_once((marker.scope, base)) => {
+                pointers.insert(
+                    (marker.scope, base),
+                    ParameterPointer {
+                        parameter_index: marker.parameter_index,
+                        byte_offset: -offset,
+                    },
+                );
+            },
+            _ => diagnostics.push(PtxJITDiagnostic::UnmatchedMarker {
+                parameter_index: marker.parameter_index,
+                register: String::from_utf8_lossy(marker.register).into(),
+            }),
+        }
+    }
+
+    // Follow copies of pointers, e.g. through `cvta` or `add` with a constant,
+    //  in both directions until a fixpoint is reached. Only registers that
+    //  are assigned to exactly once are followed, since they otherwise may
+    //  not always point into the parameter.
+    loop {
+        let mut changed = false;
+
+        for statement in &module.statements {
+            let Some((destination, source, offset)) = pointer_copy(statement) else {
+                continue;
+            };
+
+            let destination = (statement.scope, destination);
+            let source = (statement.scope, source);
+
+            match (
+                pointers.get(&destination).copied(),
+                pointers.get(&source).copied(),
+            ) {
+                (None, Some(pointer)) if is_defined_once(destination) => {
+                    let pointer = ParameterPointer {
+                        byte_offset: pointer.byte_offset + offset,
+                        ..pointer
+                    };
+                    pointers.insert(destination, pointer);
+                    changed = true;
+                },
+                (Some(pointer), None) if is_defined_once(source) => {
+                    let pointer = ParameterPointer {
+                        byte_offset: pointer.byte_offset - offset,
+                        ..pointer
+                    };
+                    pointers.insert(source, pointer);
+                    changed = true;
+                },
+                _ => (),
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    pointers
+}
+
+fn const_load(
+    module: &PtxModule,
+    statement: &Statement,
+    instruction: &[u8],
+    pointer: ParameterPointer,
+    offset: i64,
+) -> Result<PtxElement, &'static str> {
+    if statement.modifiers.iter().any(|modifier| {
+        matches!(
+            *modifier,
+            b".volatile" | b".relaxed" | b".acquire" | b".mmio"
+        )
+    }) {
+        return Err("volatile or memory-ordered loads may observe changes");
+    }
+
+    let Some(load_type) = statement
+        .modifiers
+        .last()
+        .and_then(|modifier| ScalarType::parse(modifier))
+    else {
+        return Err("unsupported load type");
+    };
+
+    let load_width = match load_type.bits {
+        8 => PtxLoadWidth::B1,
+        16 => PtxLoadWidth::B2,
+        32 => PtxLoadWidth::B4,
+        64 => PtxLoadWidth::B8,
+        _ => return Err("unsupported load type"),
+    };
+
+    let vector_width = if statement.has_modifier(b".v2") {
+        2
+    } else if statement.has_modifier(b".v4") {
+        4
+    } else {
+        1
+    };
+
+    let destinations = match statement.operands.first() {
+        Some(Operand::Register(register)) => std::slice::from_ref(register),
+        Some(Operand::Vector(registers)) => registers.as_slice(),
+        _ => return Err("unsupported destination operand"),
+    };
+
+    if destinations.len() != vector_width {
+        return Err("mismatched vector width");
+    }
+
+    let Ok(byte_offset) = usize::try_from(pointer.byte_offset + offset) else {
+        return Err("the load starts before the parameter");
+    };
+
+    let registers = destinations
+        .iter()
+        .map(|register| {
+            if *register == b"_" {
+                return Ok(None);
+            }
+
+            let Some(ty) = module.register_type(statement.scope, register) else {
+                return Err("undeclared destination register");
+            };
+
+            if ty.kind == ScalarKind::Predicate {
+                return Err("unsupported predicate destination register");
+            }
+
+            if ty.bits < load_type.bits
+                || ((ty.kind == ScalarKind::Float || load_type.kind == ScalarKind::Float)
+                    && ty.bits != load_type.bits)
+            {
+                return Err("the destination register does not match the load type");
+            }
+
+            Ok(Some(PtxRegister {
+                name: (*register).into(),
+                ty,
+            }))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(PtxElement::ConstLoad {
+        ptx: instruction.into(),
+        guard: statement.guard.map(Into::into),
+        parameter_index: pointer.parameter_index,
+        byte_offset,
+        load_width,
+        sign_extend: load_type.kind == ScalarKind::Signed,
+        registers: registers.into_boxed_slice(),
+    })
+}
+
+/// Returns the span of the block dimension operand iff the `statement` reads
+/// `%ntid.x`, `%ntid.y`, or `%ntid.z` with a `mov`, which also accepts an
+/// immediate operand instead.
+fn block_dimension_read(ptx: &[u8], statement: &Statement) -> Option<(Range<usize>, PtxElement)> {
+    if statement.opcode != b"mov" {
+        return None;
+    }
+
+    let Some(Operand::Register(register)) = statement.operands.get(1) else {
+        return None;
+    };
+
+    let dimension = match *register {
+        b"%ntid.x" => 0,
+        b"%ntid.y" => 1,
+        b"%ntid.z" => 2,
+        _ => return None,
+    };
+
+    let span = statement.operand_spans.get(1)?.clone();
+
+    Some((
+        span.clone(),
+        PtxElement::BlockDimension {
+            ptx: ptx.get(span).unwrap_or_default().into(),
+            dimension,
+        },
+    ))
+}
+
+/// Returns `true` iff the `statement` is a load from global or generic
+/// memory.
+fn is_memory_load(statement: &Statement) -> bool {
+    statement.opcode == b"ld"
+        && !statement.modifiers.iter().any(|modifier| {
+            [&b".param"[..], b".shared", b".local", b".const"]
+                .iter()
+                .any(|space| modifier.starts_with(space))
+        })
+}
+
+/// Returns `true` iff the first operand of the `statement` is a destination.
+fn is_defining(statement: &Statement) -> bool {
+    !statement.opcode.starts_with(b".")
+        && !matches!(
+            statement.opcode,
+            b"st"
+                | b"red"
+                | b"bra"
+                | b"call"
+                | b"ret"
+                | b"exit"
+                | b"bar"
+                | b"barrier"
+                | b"membar"
+                | b"fence"
+                | b"prefetch"
+                | b"prefetchu"
+                | b"trap"
+                | b"brkpt"
+                | b"pmevent"
+        )
+}
+
+/// Returns the registers that the `statement` reads from.
+fn statement_uses<'s, 'p>(statement: &'s Statement<'p>) -> impl Iterator<Item = &'p [u8]> + 's {
+    let skip = usize::from(is_defining(statement));
+
+    statement
+        .operands
+        .iter()
+        .enumerate()
+        .filter_map(move |(i, operand)| match operand {
+            // the base register of an address is always read from
+            Operand::Address { base, .. } => Some(*base),
+            Operand::Register(register) if i >= skip => Some(*register),
+            _ => None,
+        })
+}
+
+/// Returns the `(destination, source, offset)` iff the `statement`
+/// unconditionally copies the `source` register plus a constant `offset`
+/// into the `destination`.
+fn pointer_copy<'p>(statement: &Statement<'p>) -> Option<(&'p [u8], &'p [u8], i64)> {
+    if statement.guard.is_some() {
+        return None;
+    }
+
+    match (statement.opcode, statement.operands.as_slice()) {
+        (b"mov" | b"cvta", [Operand::Register(destination), Operand::Register(source)]) => {
+            Some((*destination, *source, 0))
+        },
+        (
+            b"add",
+            [Operand::Register(destination), Operand::Register(source), Operand::Immediate(offset)]
+            | [Operand::Register(destination), Operand::Immediate(offset), Operand::Register(source)],
+        ) => Some((*destination, *source, *offset)),
+        (
+            b"sub",
+            [Operand::Register(destination), Operand::Register(source), Operand::Immediate(offset)],
+        ) => Some((*destination, *source, offset.checked_neg()?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::{PtxJITCompiler, PtxJITDiagnostic};
+
+    fn compile(body: &str) -> PtxJITCompiler {
+        let ptx = format!(
+            ".visible .entry kernel(
+\t.param .u64 kernel_param_0,
+\t.param .u64 kernel_param_1
+)
+{{
+\t.reg .pred \t%p<2>;
+\t.reg .b16 \t%rs<3>;
+\t.reg .b32 \t%r<10>;
+\t.reg .b64 \t%rd<5>;
+
+\tld.param.u64 \t%rd1, [kernel_param_0];
+\tcvta.to.global.u64 \t%rd2, %rd1;
+\tld.global.nc.u32 \t%r1, [%rd2];
+\t// <rust-cuda-ptx-jit-const-load-%r1-0> //
+{body}
+\tret;
+}}
+"
+        );
+
+        PtxJITCompiler::new(&CString::new(ptx).unwrap())
+    }
+
+    fn specialise(compiler: &PtxJITCompiler) -> String {
+        let argument = [
+            0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x04, 0x00,
+            0x00, 0x00, 0x85, 0xFF, 0x00, 0x00,
+        ];
+
+        compiler
+            .specialise(Some(&[Some(Box::new(argument)), None]), None)
+            .to_str()
+            .unwrap()
+            .to_owned()
+    }
+
+    #[test]
+    fn global_nc_loads_through_cvta() {
+        let compiler = compile(
+            "\tld.global.nc.u32 \t%r2, [%rd2+4];
+\tadd.s64 \t%rd3, %rd2, 8;
+\tld.global.nc.u32 \t%r3, [%rd3];",
+        );
+        assert_eq!(compiler.diagnostics(), []);
+
+        let ptx = specialise(&compiler);
+
+        assert!(ptx.contains("mov.b32 \t%r1, 0x00000001;"));
+        assert!(ptx.contains("mov.b32 \t%r2, 0x00000002;"));
+        assert!(ptx.contains("mov.b32 \t%r3, 0x00000003;"));
+        assert!(!ptx.contains("ld.global"));
+
+        // the pointer itself is still loaded from the parameter
+        assert!(ptx.contains("ld.param.u64 \t%rd1, [kernel_param_0];"));
+    }
+
+    #[test]
+    fn narrow_and_wide_registers() {
+        let compiler = compile(
+            "\tld.global.nc.u8 \t%rs1, [%rd2+12];
+\tld.global.nc.u64 \t%rd4, [%rd2+8];
+\tld.global.nc.u16 \t%r9, [%rd2+16];",
+        );
+        assert_eq!(compiler.diagnostics(), []);
+
+        let ptx = specialise(&compiler);
+
+        assert!(ptx.contains("mov.b16 \t%rs1, 0x0004;"));
+        assert!(ptx.contains("mov.b64 \t%rd4, 0x0000000400000003;"));
+        assert!(ptx.contains("mov.b32 \t%r9, 0x0000FF85;"));
+    }
+
+    #[test]
+    fn sign_extended_narrow_loads() {
+        let compiler = compile(
+            "\tld.global.nc.s8 \t%rs2, [%rd2+16];
+\tld.global.nc.s16 \t%r8, [%rd2+16];
+\tld.global.nc.s8 \t%r7, [%rd2+12];",
+        );
+        assert_eq!(compiler.diagnostics(), []);
+
+        let ptx = specialise(&compiler);
+
+        assert!(ptx.contains("mov.b16 \t%rs2, 0xFF85;"));
+        assert!(ptx.contains("mov.b32 \t%r8, 0xFFFFFF85;"));
+        assert!(ptx.contains("mov.b32 \t%r7, 0x00000004;"));
+    }
+
+    #[test]
+    fn predicate_guards() {
+        let compiler = compile(
+            "\tsetp.eq.s32 \t%p1, %r1, 0;
+\t@%p1 ld.global.nc.u32 \t%r4, [%rd2+4];
+\t@!%p1 ld.global.nc.u32 \t%r5, [%rd2+8];",
+        );
+        assert_eq!(compiler.diagnostics(), []);
+
+        let ptx = specialise(&compiler);
+
+        assert!(ptx.contains("@%p1 mov.b32 \t%r4, 0x00000002;"));
+        assert!(ptx.contains("@!%p1 mov.b32 \t%r5, 0x00000003;"));
+    }
+
+    #[test]
+    fn vector_loads_with_sinks() {
+        let compiler = compile(
+            "\tld.global.nc.v2.u32 \t{%r5, _}, [%rd2+8];
+\tld.global.nc.v4.u32 \t{_, %r6, _, %r7}, [%rd2];",
+        );
+        assert_eq!(compiler.diagnostics(), []);
+
+        let ptx = specialise(&compiler);
+
+        assert!(ptx.contains("mov.b32 \t%r5, 0x00000003;"));
+        assert!(ptx.contains("mov.b32 \t%r6, 0x00000002;"));
+        assert!(ptx.contains("mov.b32 \t%r7, 0x00000004;"));
+        assert!(!ptx.contains("ld.global"));
+        assert!(!ptx.contains("mov.b32 \t_"));
+    }
+
+    #[test]
+    fn short_arguments_keep_the_load() {
+        let compiler = compile("\tld.global.nc.u32 \t%r2, [%rd2+20];");
+        assert_eq!(compiler.diagnostics(), []);
+
+        let ptx = specialise(&compiler);
+
+        assert!(ptx.contains("mov.b32 \t%r1, 0x00000001;"));
+        assert!(ptx.contains("ld.global.nc.u32 \t%r2, [%rd2+20];"));
+    }
+
+    #[test]
+    fn diagnostics() {
+        let compiler = compile(
+            "\tld.volatile.global.u32 \t%r2, [%rd2+4];
+\tmul.wide.u32 \t%rd3, %r1, 4;
+\tadd.s64 \t%rd4, %rd2, %rd3;
+\t// <rust-cuda-ptx-jit-const-load-%r9-1> //",
+        );
+
+        assert_eq!(
+            compiler.diagnostics(),
+            [
+                PtxJITDiagnostic::UnmatchedMarker {
+                    parameter_index: 1,
+                    register: "%r9".into(),
+                },
+                PtxJITDiagnostic::UnsupportedLoad {
+                    parameter_index: 0,
+                    instruction: "ld.volatile.global.u32 \t%r2, [%rd2+4];".into(),
+                    reason: "volatile or memory-ordered loads may observe changes",
+                },
+                PtxJITDiagnostic::UnsupportedUse {
+                    parameter_index: 0,
+                    instruction: "add.s64 \t%rd4, %rd2, %rd3;".into(),
+                },
+            ]
+        );
+
+        // unsupported loads and uses are kept as they are
+        let ptx = specialise(&compiler);
+
+        assert!(ptx.contains("ld.volatile.global.u32 \t%r2, [%rd2+4];"));
+        assert!(ptx.contains("add.s64 \t%rd4, %rd2, %rd3;"));
     }
 }
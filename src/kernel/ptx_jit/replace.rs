@@ -1,169 +1,195 @@
-use std::{ffi::CString, ops::Deref, ptr::NonNull};
+use std::{
+    borrow::Cow,
+    ffi::{CStr, CString},
+};
 
-use super::{PtxElement, PtxJITCompiler, PtxJITResult, PtxLoadWidth};
+use super::{parser::ScalarKind, PtxElement, PtxJITCompiler, PtxJITConstLoad, PtxRegister};
 
 impl PtxJITCompiler {
-    #[expect(clippy::too_many_lines)]
-    pub fn with_arguments(&mut self, arguments: Option<&[Option<&NonNull<[u8]>>]>) -> PtxJITResult {
-        // Check if the arguments, cast as byte slices, are the same as the last cached
-        //  ones
-        #[expect(clippy::explicit_deref_methods)]
-        let needs_recomputation = match (arguments, &self.last_arguments) {
-            (None, None) => false,
-            (Some(arguments), Some(last_arguments)) if arguments.len() == last_arguments.len() => {
-                arguments
-                    .iter()
-                    .zip(last_arguments.iter())
-                    .all(|(a, b)| match (a, b) {
-                        (None, None) => false,
-                        (Some(a), Some(b)) => (unsafe { a.as_ref() }) != b.deref(),
-                        _ => true,
-                    })
-            },
-            _ => true,
-        };
-
-        // Recompute the PTX string, optionally with constant loads, with the new
-        //  arguments
-        if needs_recomputation {
-            // Cache the new arguments
-            self.last_arguments = arguments.map(|arguments| {
-                arguments
-                    .iter()
-                    .map(|arg| {
-                        arg.map(|bytes| unsafe { bytes.as_ref() }.to_owned().into_boxed_slice())
-                    })
-                    .collect::<Vec<Option<Box<[u8]>>>>()
-                    .into_boxed_slice()
-            });
-
-            let mut output_ptx = Vec::new();
-            let mut buffer_ptx = Vec::new();
-
-            if let Some(args) = &self.last_arguments {
-                // Some constant loads are required, rebuild PTX string from source and newly
-                //  generated constant load instructions
-                for element in &self.ptx_slices {
-                    match element {
-                        PtxElement::CopiedSource { ptx } => output_ptx.extend_from_slice(ptx),
-                        PtxElement::ConstLoad {
-                            ptx,
-                            parameter_index,
-                            byte_offset,
-                            load_width,
-                            registers,
-                        } => {
-                            let mut byte_offset = *byte_offset;
-                            let mut registers_iter = registers.iter();
-
-                            let Some(mut register) = registers_iter.next() else {
-                                continue;
-                            };
-
-                            loop {
-                                // Only generate a constant load instructions if the arguments
-                                // contain  the byte range requested
-                                // by the load instruction
-                                if let Some(Some(arg)) = args.get(*parameter_index) {
-                                    let load_width_bytes = match load_width {
-                                        PtxLoadWidth::B1 => 1,
-                                        PtxLoadWidth::B2 => 2,
-                                        PtxLoadWidth::B4 => 4,
-                                        PtxLoadWidth::B8 => 8,
-                                    };
-
-                                    if let Some(bytes) =
-                                        arg.get(byte_offset..(byte_offset + load_width_bytes))
-                                    {
-                                        byte_offset += load_width_bytes;
-
-                                        // Generate the mov instruction with the correct data type
-                                        buffer_ptx.extend_from_slice(b"mov.");
-                                        buffer_ptx.push(if register.contains(&b'r') {
-                                            b'u'
-                                        } else {
-                                            b'f'
-                                        });
-                                        buffer_ptx.extend_from_slice(if register.contains(&b's') {
-                                            b"16"
-                                        } else if register.contains(&b'd') {
-                                            b"64"
-                                        } else {
-                                            b"32"
-                                        });
-
-                                        buffer_ptx.extend_from_slice(b" \t");
+    /// Specialises the PTX source for the byte representations of the kernel
+    /// `arguments` by replacing loads from them with constants, and for the
+    /// `block_size` by requiring it with a `.reqntid` directive and replacing
+    /// reads of `%ntid` with its dimensions.
+    ///
+    /// Without any `arguments` or `block_size`, the unspecialised PTX source
+    /// is returned.
+    #[must_use]
+    pub fn specialise(
+        &self,
+        arguments: Option<&[Option<Box<[u8]>>]>,
+        block_size: Option<[u32; 3]>,
+    ) -> Cow<CStr> {
+        if arguments.is_none() && block_size.is_none() {
+            return Cow::Borrowed(&self.ptx);
+        }
 
-                                        // Append the destination register from the load
-                                        //  instruction
-                                        buffer_ptx.extend_from_slice(register);
+        let mut output_ptx = Vec::new();
+
+        // Rebuild the PTX string from source and newly generated constant load
+        //  instructions and block size directives
+        for element in &self.ptx_slices {
+            match element {
+                PtxElement::CopiedSource { ptx } => output_ptx.extend_from_slice(ptx),
+                PtxElement::BlockSizeDirective { ptx } => {
+                    // the directive is superseded by the inserted `.reqntid`
+                    if block_size.is_none() {
+                        output_ptx.extend_from_slice(ptx);
+                    }
+                },
+                PtxElement::RequiredBlockSize => {
+                    if let Some([x, y, z]) = block_size {
+                        output_ptx
+                            .extend_from_slice(format!(".reqntid {x}, {y}, {z}\n").as_bytes());
+                    }
+                },
+                PtxElement::BlockDimension { ptx, dimension } => {
+                    match block_size
+                        .as_ref()
+                        .and_then(|block_size| block_size.get(*dimension))
+                    {
+                        Some(dimension) => {
+                            output_ptx.extend_from_slice(dimension.to_string().as_bytes());
+                        },
+                        None => output_ptx.extend_from_slice(ptx),
+                    }
+                },
+                PtxElement::ConstLoad {
+                    ptx,
+                    guard,
+                    parameter_index,
+                    byte_offset,
+                    load_width,
+                    sign_extend,
+                    registers,
+                } => {
+                    // Only generate constant load instructions if the arguments
+                    //  contain the byte range requested by the load instruction
+                    let Some(bytes) = arguments.and_then(|arguments| {
+                        const_load_bytes(
+                            arguments,
+                            *parameter_index,
+                            *byte_offset,
+                            load_width.bytes() * registers.len(),
+                        )
+                    }) else {
+                        // else: fall back to the original PTX source
+                        output_ptx.extend_from_slice(ptx);
+                        continue;
+                    };
+
+                    for (register, bytes) in registers.iter().zip(bytes.chunks(load_width.bytes()))
+                    {
+                        // `_` sinks do not need to be assigned to
+                        if let Some(register) = register {
+                            write_const_mov(
+                                &mut output_ptx,
+                                guard.as_deref(),
+                                register,
+                                bytes,
+                                *sign_extend,
+                            );
+                        }
+                    }
+                },
+            }
+        }
 
-                                        // Generate the hexadecimal constant in little-endian
-                                        //  order
-                                        buffer_ptx.extend_from_slice(b", 0");
-                                        buffer_ptx.push(if register.contains(&b'r') {
-                                            b'x'
-                                        } else if register.contains(&b'd') {
-                                            b'd'
-                                        } else {
-                                            b'f'
-                                        });
-                                        for byte in bytes.iter().rev() {
-                                            buffer_ptx
-                                                .push(b"0123456789ABCDEF"[usize::from(*byte >> 4)]);
-                                            buffer_ptx.push(
-                                                b"0123456789ABCDEF"[usize::from(*byte & 0x0F_u8)],
-                                            );
-                                        }
-                                        if register.contains(&b'r') {
-                                            buffer_ptx.push(b'U');
-                                        }
+        Cow::Owned(unsafe { CString::from_vec_unchecked(output_ptx) })
+    }
 
-                                        buffer_ptx.push(b';');
+    /// Reports all constant loads from kernel parameters in the PTX source,
+    /// and whether they are replaced with constants when the PTX source is
+    /// specialised for the byte representations of the kernel `arguments`.
+    #[must_use]
+    pub fn const_loads(&self, arguments: Option<&[Option<Box<[u8]>>]>) -> Box<[PtxJITConstLoad]> {
+        self.ptx_slices
+            .iter()
+            .filter_map(|element| match element {
+                PtxElement::CopiedSource { .. }
+                | PtxElement::BlockSizeDirective { .. }
+                | PtxElement::RequiredBlockSize
+                | PtxElement::BlockDimension { .. } => None,
+                PtxElement::ConstLoad {
+                    parameter_index,
+                    byte_offset,
+                    load_width,
+                    registers,
+                    ..
+                } => Some(PtxJITConstLoad {
+                    parameter_index: *parameter_index,
+                    byte_offset: *byte_offset,
+                    load_width: load_width.bytes(),
+                    vector_width: registers.len(),
+                    substituted: arguments.is_some_and(|arguments| {
+                        const_load_bytes(
+                            arguments,
+                            *parameter_index,
+                            *byte_offset,
+                            load_width.bytes() * registers.len(),
+                        )
+                        .is_some()
+                    }),
+                }),
+            })
+            .collect()
+    }
+}
 
-                                        if let Some(next_register) = registers_iter.next() {
-                                            // Early continue to the next `PtxElement` to avoid
-                                            //  else branch
-                                            register = next_register;
-                                            buffer_ptx.push(b' ');
-                                            continue;
-                                        }
+/// Returns the `len` bytes at the `byte_offset` of the argument with the
+/// `parameter_index`, if it is specialised and long enough.
+fn const_load_bytes(
+    arguments: &[Option<Box<[u8]>>],
+    parameter_index: usize,
+    byte_offset: usize,
+    len: usize,
+) -> Option<&[u8]> {
+    arguments
+        .get(parameter_index)?
+        .as_deref()?
+        .get(byte_offset..(byte_offset + len))
+}
 
-                                        // const load generation finished successfully
-                                        // flush the generated instruction(s)
-                                        output_ptx.append(&mut buffer_ptx);
-                                        break;
-                                    }
-                                }
+/// Generates a `mov` instruction which assigns the little-endian constant
+/// `bytes` to the `register`, extending them to the width of the register.
+fn write_const_mov(
+    output_ptx: &mut Vec<u8>,
+    guard: Option<&[u8]>,
+    register: &PtxRegister,
+    bytes: &[u8],
+    sign_extend: bool,
+) {
+    let mut value = if sign_extend && bytes.last().is_some_and(|byte| byte & 0x80 != 0) {
+        [0xFF_u8; 8]
+    } else {
+        [0_u8; 8]
+    };
+
+    for (value, byte) in value.iter_mut().zip(bytes) {
+        *value = *byte;
+    }
 
-                                // else: const load generation failed
-                                //       fall back to original PTX source
-                                output_ptx.extend_from_slice(ptx);
-                                buffer_ptx.clear();
-                                break;
-                            }
-                        },
-                    }
-                }
-            } else {
-                // No constant loads are requires, just rebuild the PTX string from its slices
-                for element in &self.ptx_slices {
-                    match element {
-                        PtxElement::CopiedSource { ptx } | PtxElement::ConstLoad { ptx, .. } => {
-                            output_ptx.extend_from_slice(ptx);
-                        },
-                    }
-                }
-            }
+    let value = u64::from_le_bytes(value);
+    let bits = usize::from(register.ty.bits);
 
-            // Cache the newly built PTX string
-            self.last_ptx = unsafe { CString::from_vec_unchecked(output_ptx) };
-        }
+    // Keep only the bits that fit into the register
+    let value = value & (u64::MAX >> (64 - bits.clamp(1, 64)));
 
-        if needs_recomputation {
-            PtxJITResult::Recomputed(&self.last_ptx)
-        } else {
-            PtxJITResult::Cached(&self.last_ptx)
-        }
+    if let Some(guard) = guard {
+        output_ptx.extend_from_slice(guard);
+        output_ptx.push(b' ');
     }
+
+    // Generate the mov instruction with the correct data type, using the
+    //  hexadecimal floating point literal syntax for floating point registers
+    let (mov_type, literal_prefix) = match (register.ty.kind, bits) {
+        (ScalarKind::Float, 32) => (Cow::Borrowed("f32"), "0f"),
+        (ScalarKind::Float, 64) => (Cow::Borrowed("f64"), "0d"),
+        (_, bits) => (Cow::Owned(format!("b{bits}")), "0x"),
+    };
+
+    output_ptx.extend_from_slice(format!("mov.{mov_type} \t").as_bytes());
+    output_ptx.extend_from_slice(&register.name);
+    output_ptx.extend_from_slice(
+        format!(", {literal_prefix}{value:0digits$X}; ", digits = bits / 4).as_bytes(),
+    );
 }
@@ -0,0 +1,47 @@
+use std::ffi::CStr;
+
+use super::PtxJITCacheLookup;
+
+/// A constant load from a [`PtxJit`](crate::kernel::param::PtxJit) kernel
+/// parameter that the PTX JIT found in the PTX source of a kernel.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PtxJITConstLoad {
+    /// The index of the kernel parameter that is loaded from
+    pub parameter_index: usize,
+    /// The offset (in bytes) of the load from the start of the parameter
+    pub byte_offset: usize,
+    /// The width (in bytes) of every element that is loaded
+    pub load_width: usize,
+    /// The number of elements that are loaded, which is larger than one for
+    /// vector loads
+    pub vector_width: usize,
+    /// `true` iff the load was replaced with constants, or `false` iff the
+    /// original load was kept, e.g. because the parameter was not
+    /// specialised
+    pub substituted: bool,
+}
+
+/// Report of the PTX JIT specialisation of a kernel that was most recently
+/// launched or compiled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PtxJITReport<'a> {
+    /// The (specialised) PTX source that was compiled
+    pub ptx: &'a CStr,
+    /// All constant loads from kernel parameters in the PTX source, and
+    /// whether they were specialised
+    pub const_loads: &'a [PtxJITConstLoad],
+    /// Whether the specialisation was found in the cache or compiled
+    pub cache_lookup: PtxJITCacheLookup,
+}
+
+impl PtxJITReport<'_> {
+    /// Returns the number of constant loads that were replaced with
+    /// constants.
+    #[must_use]
+    pub fn substituted_loads(&self) -> usize {
+        self.const_loads
+            .iter()
+            .filter(|load| load.substituted)
+            .count()
+    }
+}
@@ -0,0 +1,63 @@
+use super::occupancy::KernelResourceUsage;
+
+/// Resources that are used by a compiled kernel, as reported by the PTX
+/// compiler at compile time for the kernel's target architecture, which is
+/// set with `#[kernel(target(arch = "<arch>"))]` and otherwise defaults to
+/// `sm_52`.
+///
+/// The resources are recorded separately for every kernel specialisation
+/// that is linked with the `link!` macro that is generated by the
+/// `#[kernel]` attribute, and are available as
+/// [`CompiledKernelPtx::RESOURCES`](super::CompiledKernelPtx::RESOURCES).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KernelResources {
+    /// Number of registers that are used by every thread
+    pub registers_per_thread: u32,
+    /// Bytes of statically allocated shared memory per block
+    pub static_shared_memory: u32,
+    /// Bytes of local memory per thread
+    pub local_memory: u32,
+    /// Bytes of the stack frame per thread
+    pub stack_frame: u32,
+    /// Bytes of registers that are spilled to local memory per thread
+    pub spill_stores: u32,
+    /// Bytes of registers that are loaded back from local memory per thread
+    pub spill_loads: u32,
+    /// `(bank, bytes)` of every constant memory bank that is used
+    pub constant_banks: &'static [(u32, u32)],
+    /// Maximum number of threads per block that the kernel can be launched
+    /// with
+    pub max_threads_per_block: u32,
+    /// `true` iff the resources were reported by the PTX compiler, or
+    /// `false` iff they are a conservative estimate, e.g. because the kernel
+    /// was checked without the PTX compiler
+    pub reported: bool,
+}
+
+impl KernelResources {
+    /// Returns the subset of the resources that determines the
+    /// [`occupancy`](super::occupancy) of the kernel.
+    #[must_use]
+    pub const fn usage(&self) -> KernelResourceUsage {
+        KernelResourceUsage {
+            registers_per_thread: self.registers_per_thread,
+            static_shared_memory: self.static_shared_memory,
+            max_threads_per_block: self.max_threads_per_block,
+        }
+    }
+
+    /// Returns the total bytes of constant memory that are used across all
+    /// constant memory banks.
+    #[must_use]
+    pub const fn constant_memory(&self) -> u32 {
+        let mut total = 0;
+
+        let mut i = 0;
+        while i < self.constant_banks.len() {
+            total += self.constant_banks[i].1;
+            i += 1;
+        }
+
+        total
+    }
+}
@@ -0,0 +1,159 @@
+use core::ffi::CStr;
+
+use super::KernelResources;
+
+/// One of the PTX variants of a compiled kernel, each of which is compiled
+/// for a different target architecture.
+///
+/// A kernel is compiled for every architecture that is listed in its
+/// `link!` macro invocation, e.g.
+/// `link! { impl kernel for Kernel in ["sm_70", "sm_80", "sm_90"] }`, and
+/// otherwise only once for its default target architecture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KernelPtxVariant {
+    /// The target architecture, e.g. `sm_80`, that the PTX was compiled for,
+    /// or [`None`] if it was compiled for the default target architecture
+    pub arch: Option<&'static str>,
+    /// The PTX source code
+    pub ptx: &'static CStr,
+    /// The resources that are used by the kernel on the target architecture
+    pub resources: KernelResources,
+}
+
+impl KernelPtxVariant {
+    /// Returns the `(major, minor)` compute capability of the target
+    /// architecture, e.g. `(8, 0)` for `sm_80`, or [`None`] if the PTX was
+    /// compiled for the default target architecture.
+    #[must_use]
+    pub fn compute_capability(&self) -> Option<(u32, u32)> {
+        let version = self.arch?.strip_prefix("sm_")?;
+        let version = version.strip_suffix('a').unwrap_or(version);
+
+        let (major, minor) = version.split_at(version.len().checked_sub(1)?);
+
+        Some((major.parse().ok()?, minor.parse().ok()?))
+    }
+
+    /// Returns `true` iff the PTX uses architecture-specific features, e.g.
+    /// for `sm_90a`, and can only be loaded on a device with exactly its
+    /// compute capability.
+    #[must_use]
+    pub fn is_arch_specific(&self) -> bool {
+        self.arch.map_or(false, |arch| arch.ends_with('a'))
+    }
+
+    /// Selects the variant that best matches a device with the
+    /// `compute_capability` from the `variants`, which are ordered by their
+    /// compute capability.
+    ///
+    /// The best match is the variant with the newest compute capability that
+    /// the device supports, i.e. the highest architecture at or below the
+    /// device's, or the variant for the default target architecture. An
+    /// architecture-specific variant, e.g. for `sm_90a`, only matches a
+    /// device with exactly its compute capability. Returns [`None`] if no
+    /// variant can be loaded on the device.
+    #[must_use]
+    pub fn select(variants: &[Self], compute_capability: (u32, u32)) -> Option<&Self> {
+        variants
+            .iter()
+            .filter(|variant| match variant.compute_capability() {
+                Some(variant_capability) if variant.is_arch_specific() => {
+                    variant_capability == compute_capability
+                },
+                Some(variant_capability) => variant_capability <= compute_capability,
+                None => true,
+            })
+            .max_by_key(|variant| variant.compute_capability())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::kernel::KernelResources;
+
+    use super::KernelPtxVariant;
+
+    const RESOURCES: KernelResources = KernelResources {
+        registers_per_thread: 32,
+        static_shared_memory: 0,
+        local_memory: 0,
+        stack_frame: 0,
+        spill_stores: 0,
+        spill_loads: 0,
+        constant_banks: &[],
+        max_threads_per_block: 1024,
+        reported: true,
+    };
+
+    const fn variant(arch: &'static str) -> KernelPtxVariant {
+        KernelPtxVariant {
+            arch: Some(arch),
+            ptx: c"",
+            resources: RESOURCES,
+        }
+    }
+
+    const VARIANTS: &[KernelPtxVariant] = &[
+        variant("sm_70"),
+        variant("sm_75"),
+        variant("sm_80"),
+        variant("sm_90"),
+        variant("sm_90a"),
+    ];
+
+    fn select(variants: &[KernelPtxVariant], compute_capability: (u32, u32)) -> Option<&str> {
+        KernelPtxVariant::select(variants, compute_capability).map(|variant| variant.arch.unwrap())
+    }
+
+    #[test]
+    fn compute_capabilities() {
+        assert_eq!(variant("sm_70").compute_capability(), Some((7, 0)));
+        assert_eq!(variant("sm_100").compute_capability(), Some((10, 0)));
+        assert_eq!(variant("sm_90a").compute_capability(), Some((9, 0)));
+        assert_eq!(variant("compute_90").compute_capability(), None);
+        assert_eq!(variant("sm_").compute_capability(), None);
+
+        assert!(variant("sm_90a").is_arch_specific());
+        assert!(!variant("sm_90").is_arch_specific());
+    }
+
+    #[test]
+    fn exact_match() {
+        assert_eq!(select(VARIANTS, (7, 5)), Some("sm_75"));
+        assert_eq!(select(VARIANTS, (8, 0)), Some("sm_80"));
+        assert_eq!(select(VARIANTS, (9, 0)), Some("sm_90a"));
+    }
+
+    #[test]
+    fn highest_arch_at_or_below_the_device() {
+        assert_eq!(select(VARIANTS, (7, 2)), Some("sm_70"));
+        assert_eq!(select(VARIANTS, (8, 6)), Some("sm_80"));
+        // the architecture-specific variant cannot be loaded on newer devices
+        assert_eq!(select(VARIANTS, (10, 0)), Some("sm_90"));
+    }
+
+    #[test]
+    fn default_target_architecture() {
+        let variants = [
+            KernelPtxVariant {
+                arch: None,
+                ..variant("sm_52")
+            },
+            variant("sm_80"),
+        ];
+
+        assert_eq!(
+            KernelPtxVariant::select(&variants, (7, 0)).unwrap().arch,
+            None
+        );
+        assert_eq!(select(&variants, (8, 9)), Some("sm_80"));
+    }
+
+    #[test]
+    fn no_fitting_variant() {
+        assert_eq!(select(VARIANTS, (6, 1)), None);
+        assert_eq!(select(&[variant("sm_90a")], (10, 0)), None);
+        assert_eq!(select(&[], (8, 0)), None);
+    }
+}
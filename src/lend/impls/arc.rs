@@ -4,9 +4,6 @@ use std::mem::ManuallyDrop;
 
 use const_type_layout::{TypeGraphLayout, TypeLayout};
 
-#[cfg(feature = "host")]
-use rustacuda::{error::CudaResult, memory::DeviceBox, memory::LockedBox};
-
 use crate::{
     deps::alloc::sync::Arc,
     lend::{CudaAsRust, RustToCuda, RustToCudaAsync},
@@ -20,7 +17,13 @@ use crate::utils::ffi::DeviceAccessible;
 #[cfg(feature = "host")]
 use crate::{
     alloc::{CombinedCudaAlloc, CudaAlloc},
-    host::CudaDropWrapper,
+    host::{
+        driver::{
+            error::CudaResult,
+            memory::{DeviceBox, LockedBox},
+        },
+        CudaDropWrapper,
+    },
     utils::adapter::DeviceCopyWithPortableBitSemantics,
     utils::r#async::Async,
     utils::r#async::CompletionFnMut,
@@ -101,11 +104,11 @@ unsafe impl<T: PortableBitSemantics + TypeGraphLayout> RustToCudaAsync for Arc<T
         &self,
         alloc: A,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         Async<'_, 'stream, DeviceAccessible<Self::CudaRepresentation>>,
         CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
     )> {
-        use rustacuda::memory::AsyncCopyDestination;
+        use crate::host::driver::memory::AsyncCopyDestination;
 
         let locked_box = unsafe {
             let inner = ManuallyDrop::new(_ArcInner {
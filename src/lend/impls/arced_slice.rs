@@ -4,12 +4,6 @@ use std::mem::{ManuallyDrop, MaybeUninit};
 
 use const_type_layout::{TypeGraphLayout, TypeLayout};
 
-#[cfg(feature = "host")]
-use rustacuda::{
-    error::CudaResult,
-    memory::LockedBuffer,
-    memory::{DeviceBox, DeviceBuffer},
-};
 use rustacuda_core::DeviceCopy;
 
 use crate::{
@@ -25,7 +19,13 @@ use crate::utils::ffi::DeviceAccessible;
 #[cfg(feature = "host")]
 use crate::{
     alloc::{CombinedCudaAlloc, CudaAlloc},
-    host::CudaDropWrapper,
+    host::{
+        driver::{
+            error::CudaResult,
+            memory::{DeviceBox, DeviceBuffer, LockedBuffer},
+        },
+        CudaDropWrapper,
+    },
     utils::adapter::DeviceCopyWithPortableBitSemantics,
     utils::r#async::Async,
     utils::r#async::CompletionFnMut,
@@ -74,7 +74,7 @@ unsafe impl<T: PortableBitSemantics + TypeGraphLayout> RustToCuda for Arc<[T]> {
         DeviceAccessible<Self::CudaRepresentation>,
         CombinedCudaAlloc<Self::CudaAllocation, A>,
     )> {
-        use rustacuda::memory::{CopyDestination, DeviceSlice};
+        use crate::host::driver::memory::{CopyDestination, DeviceSlice};
         use rustacuda_core::DevicePointer;
 
         let data_ptr: *const T = std::ptr::from_ref(&**self).as_ptr();
@@ -136,11 +136,11 @@ unsafe impl<T: PortableBitSemantics + TypeGraphLayout> RustToCudaAsync for Arc<[
         &self,
         alloc: A,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         Async<'_, 'stream, DeviceAccessible<Self::CudaRepresentation>>,
         CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
     )> {
-        use rustacuda::memory::AsyncCopyDestination;
+        use crate::host::driver::memory::AsyncCopyDestination;
 
         let data_ptr: *const T = std::ptr::from_ref(&**self).as_ptr();
         let offset = std::mem::offset_of!(_ArcInner<[T; 42]>, data);
@@ -3,9 +3,6 @@ use std::mem::ManuallyDrop;
 
 use const_type_layout::{TypeGraphLayout, TypeLayout};
 
-#[cfg(feature = "host")]
-use rustacuda::{error::CudaResult, memory::DeviceBox, memory::LockedBox};
-
 use crate::{
     deps::alloc::boxed::Box,
     lend::{CudaAsRust, RustToCuda, RustToCudaAsync},
@@ -19,7 +16,13 @@ use crate::utils::ffi::DeviceAccessible;
 #[cfg(feature = "host")]
 use crate::{
     alloc::{CombinedCudaAlloc, CudaAlloc},
-    host::CudaDropWrapper,
+    host::{
+        driver::{
+            error::CudaResult,
+            memory::{DeviceBox, LockedBox},
+        },
+        CudaDropWrapper,
+    },
     utils::adapter::DeviceCopyWithPortableBitSemantics,
     utils::r#async::Async,
     utils::r#async::CompletionFnMut,
@@ -64,7 +67,7 @@ unsafe impl<T: PortableBitSemantics + TypeGraphLayout> RustToCuda for Box<T> {
         &mut self,
         alloc: CombinedCudaAlloc<Self::CudaAllocation, A>,
     ) -> CudaResult<A> {
-        use rustacuda::memory::CopyDestination;
+        use crate::host::driver::memory::CopyDestination;
 
         let (alloc_front, alloc_tail) = alloc.split();
 
@@ -90,11 +93,11 @@ unsafe impl<T: PortableBitSemantics + TypeGraphLayout> RustToCudaAsync for Box<T
         &self,
         alloc: A,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         Async<'_, 'stream, DeviceAccessible<Self::CudaRepresentation>>,
         CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
     )> {
-        use rustacuda::memory::AsyncCopyDestination;
+        use crate::host::driver::memory::AsyncCopyDestination;
 
         let locked_box = unsafe {
             let mut uninit = CudaDropWrapper::from(LockedBox::<
@@ -135,7 +138,7 @@ unsafe impl<T: PortableBitSemantics + TypeGraphLayout> RustToCudaAsync for Box<T
         Async<'a, 'stream, owning_ref::BoxRefMut<'a, O, Self>, CompletionFnMut<'a, Self>>,
         A,
     )> {
-        use rustacuda::memory::AsyncCopyDestination;
+        use crate::host::driver::memory::AsyncCopyDestination;
 
         let (alloc_front, alloc_tail) = alloc.split();
         let (mut locked_box, device_box) = alloc_front.split();
@@ -6,9 +6,6 @@ use crate::{deps::alloc::boxed::Box, lend::RustToCudaAsync, utils::ffi::DeviceOw
 
 use const_type_layout::{TypeGraphLayout, TypeLayout};
 
-#[cfg(feature = "host")]
-use rustacuda::{error::CudaResult, memory::DeviceBuffer, memory::LockedBuffer};
-
 use crate::{
     lend::{CudaAsRust, RustToCuda},
     safety::PortableBitSemantics,
@@ -20,7 +17,13 @@ use crate::utils::ffi::DeviceAccessible;
 #[cfg(feature = "host")]
 use crate::{
     alloc::{CombinedCudaAlloc, CudaAlloc},
-    host::CudaDropWrapper,
+    host::{
+        driver::{
+            error::CudaResult,
+            memory::{DeviceBuffer, LockedBuffer},
+        },
+        CudaDropWrapper,
+    },
     utils::adapter::DeviceCopyWithPortableBitSemantics,
     utils::r#async::{Async, CompletionFnMut, NoCompletion},
 };
@@ -70,7 +73,7 @@ unsafe impl<T: PortableBitSemantics + TypeGraphLayout> RustToCuda for Box<[T]> {
         &mut self,
         alloc: CombinedCudaAlloc<Self::CudaAllocation, A>,
     ) -> CudaResult<A> {
-        use rustacuda::memory::CopyDestination;
+        use crate::host::driver::memory::CopyDestination;
 
         let (alloc_front, alloc_tail) = alloc.split();
 
@@ -96,11 +99,11 @@ unsafe impl<T: PortableBitSemantics + TypeGraphLayout> RustToCudaAsync for Box<[
         &self,
         alloc: A,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         Async<'_, 'stream, DeviceAccessible<Self::CudaRepresentation>>,
         CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
     )> {
-        use rustacuda::memory::AsyncCopyDestination;
+        use crate::host::driver::memory::AsyncCopyDestination;
 
         let locked_buffer = unsafe {
             let mut uninit = CudaDropWrapper::from(LockedBuffer::<
@@ -144,7 +147,7 @@ unsafe impl<T: PortableBitSemantics + TypeGraphLayout> RustToCudaAsync for Box<[
         Async<'a, 'stream, owning_ref::BoxRefMut<'a, O, Self>, CompletionFnMut<'a, Self>>,
         A,
     )> {
-        use rustacuda::memory::AsyncCopyDestination;
+        use crate::host::driver::memory::AsyncCopyDestination;
 
         let (alloc_front, alloc_tail) = alloc.split();
         let (mut locked_buffer, device_buffer) = alloc_front.split();
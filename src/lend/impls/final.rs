@@ -19,7 +19,7 @@ unsafe impl<T: RustToCuda> RustToCuda for Final<T> {
     unsafe fn borrow<A: crate::alloc::CudaAlloc>(
         &self,
         alloc: A,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         DeviceAccessible<Self::CudaRepresentation>,
         crate::alloc::CombinedCudaAlloc<Self::CudaAllocation, A>,
     )> {
@@ -35,7 +35,7 @@ unsafe impl<T: RustToCuda> RustToCuda for Final<T> {
     unsafe fn restore<A: crate::alloc::CudaAlloc>(
         &mut self,
         alloc: crate::alloc::CombinedCudaAlloc<Self::CudaAllocation, A>,
-    ) -> rustacuda::error::CudaResult<A> {
+    ) -> crate::host::driver::error::CudaResult<A> {
         let (_alloc_front, alloc_tail) = alloc.split();
         Ok(alloc_tail)
     }
@@ -49,7 +49,7 @@ unsafe impl<T: RustToCudaAsync> RustToCudaAsync for Final<T> {
         &self,
         alloc: A,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         crate::utils::r#async::Async<'_, 'stream, DeviceAccessible<Self::CudaRepresentation>>,
         crate::alloc::CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
     )> {
@@ -76,7 +76,7 @@ unsafe impl<T: RustToCudaAsync> RustToCudaAsync for Final<T> {
         this: owning_ref::BoxRefMut<'a, O, Self>,
         alloc: crate::alloc::CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         crate::utils::r#async::Async<
             'a,
             'stream,
@@ -2,9 +2,6 @@ use core::mem::MaybeUninit;
 
 use const_type_layout::{TypeGraphLayout, TypeLayout};
 
-#[cfg(feature = "host")]
-use rustacuda::error::CudaResult;
-
 use crate::{
     lend::{CudaAsRust, RustToCuda, RustToCudaAsync, RustToCudaProxy},
     safety::PortableBitSemantics,
@@ -14,6 +11,7 @@ use crate::{
 #[cfg(feature = "host")]
 use crate::{
     alloc::{CombinedCudaAlloc, CudaAlloc},
+    host::driver::error::CudaResult,
     utils::r#async::{Async, CompletionFnMut, NoCompletion},
 };
 
@@ -4,9 +4,6 @@ use std::mem::ManuallyDrop;
 
 use const_type_layout::{TypeGraphLayout, TypeLayout};
 
-#[cfg(feature = "host")]
-use rustacuda::{error::CudaResult, memory::DeviceBox, memory::LockedBox};
-
 use crate::{
     lend::{CudaAsRust, RustToCuda, RustToCudaAsync},
     safety::PortableBitSemantics,
@@ -19,7 +16,13 @@ use crate::utils::ffi::DeviceAccessible;
 #[cfg(feature = "host")]
 use crate::{
     alloc::{CombinedCudaAlloc, CudaAlloc},
-    host::CudaDropWrapper,
+    host::{
+        driver::{
+            error::CudaResult,
+            memory::{DeviceBox, LockedBox},
+        },
+        CudaDropWrapper,
+    },
     utils::adapter::DeviceCopyWithPortableBitSemantics,
     utils::r#async::{Async, CompletionFnMut, NoCompletion},
 };
@@ -85,11 +88,11 @@ unsafe impl<'a, T: PortableBitSemantics + TypeGraphLayout> RustToCudaAsync for &
         &self,
         alloc: A,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         Async<'_, 'stream, DeviceAccessible<Self::CudaRepresentation>>,
         CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
     )> {
-        use rustacuda::memory::AsyncCopyDestination;
+        use crate::host::driver::memory::AsyncCopyDestination;
 
         let locked_box = unsafe {
             let mut uninit = CudaDropWrapper::from(LockedBox::<
@@ -2,9 +2,6 @@ use core::marker::PhantomData;
 
 use const_type_layout::{TypeGraphLayout, TypeLayout};
 
-#[cfg(feature = "host")]
-use rustacuda::{error::CudaResult, memory::DeviceBox};
-
 use crate::{
     lend::{CudaAsRust, RustToCuda},
     safety::PortableBitSemantics,
@@ -17,7 +14,10 @@ use crate::utils::ffi::DeviceAccessible;
 #[cfg(feature = "host")]
 use crate::{
     alloc::{CombinedCudaAlloc, CudaAlloc},
-    host::CudaDropWrapper,
+    host::{
+        driver::{error::CudaResult, memory::DeviceBox},
+        CudaDropWrapper,
+    },
     utils::adapter::DeviceCopyWithPortableBitSemantics,
 };
 
@@ -63,7 +63,7 @@ unsafe impl<'a, T: PortableBitSemantics + TypeGraphLayout> RustToCuda for &'a mu
         &mut self,
         alloc: CombinedCudaAlloc<Self::CudaAllocation, A>,
     ) -> CudaResult<A> {
-        use rustacuda::memory::CopyDestination;
+        use crate::host::driver::memory::CopyDestination;
 
         let (alloc_front, alloc_tail) = alloc.split();
 
@@ -1,6 +1,4 @@
 use const_type_layout::TypeGraphLayout;
-#[cfg(feature = "host")]
-use rustacuda::error::CudaError;
 
 #[cfg(feature = "derive")]
 #[expect(clippy::module_name_repetitions)]
@@ -16,7 +14,9 @@ use crate::{alloc::EmptyCudaAlloc, utils::ffi::DeviceAccessible};
 #[cfg(feature = "host")]
 use crate::{
     alloc::{CombinedCudaAlloc, NoCudaAlloc},
-    host::{HostAndDeviceConstRef, HostAndDeviceMutRef, HostAndDeviceOwned},
+    host::{
+        driver::error::CudaError, HostAndDeviceConstRef, HostAndDeviceMutRef, HostAndDeviceOwned,
+    },
     utils::r#async::{Async, CompletionFnMut, NoCompletion},
 };
 
@@ -34,8 +34,8 @@ pub unsafe trait RustToCuda {
     #[cfg(feature = "host")]
     /// # Errors
     ///
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA
     ///
     /// # Safety
     ///
@@ -46,7 +46,7 @@ pub unsafe trait RustToCuda {
     unsafe fn borrow<A: CudaAlloc>(
         &self,
         alloc: A,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         DeviceAccessible<Self::CudaRepresentation>,
         CombinedCudaAlloc<Self::CudaAllocation, A>,
     )>;
@@ -55,8 +55,8 @@ pub unsafe trait RustToCuda {
     #[cfg(feature = "host")]
     /// # Errors
     ///
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA
     ///
     /// # Safety
     ///
@@ -64,7 +64,7 @@ pub unsafe trait RustToCuda {
     unsafe fn restore<A: CudaAlloc>(
         &mut self,
         alloc: CombinedCudaAlloc<Self::CudaAllocation, A>,
-    ) -> rustacuda::error::CudaResult<A>;
+    ) -> crate::host::driver::error::CudaResult<A>;
 }
 
 /// # Safety
@@ -78,8 +78,8 @@ pub unsafe trait RustToCudaAsync: RustToCuda {
     #[cfg(feature = "host")]
     /// # Errors
     ///
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA
     ///
     /// # Safety
     ///
@@ -101,7 +101,7 @@ pub unsafe trait RustToCudaAsync: RustToCuda {
         &self,
         alloc: A,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         Async<'_, 'stream, DeviceAccessible<Self::CudaRepresentation>>,
         CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
     )>;
@@ -110,8 +110,8 @@ pub unsafe trait RustToCudaAsync: RustToCuda {
     #[cfg(feature = "host")]
     /// # Errors
     ///
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA
     ///
     /// # Safety
     ///
@@ -127,7 +127,7 @@ pub unsafe trait RustToCudaAsync: RustToCuda {
         this: owning_ref::BoxRefMut<'a, O, Self>,
         alloc: CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         Async<'a, 'stream, owning_ref::BoxRefMut<'a, O, Self>, CompletionFnMut<'a, Self>>,
         A,
     )>;
@@ -187,7 +187,7 @@ pub trait LendToCuda: RustToCuda {
     ///
     /// # Errors
     ///
-    /// Returns a `rustacuda::errors::CudaError` iff an error occurs inside CUDA
+    /// Returns a [`CudaError`] iff an error occurs inside CUDA
     fn lend_to_cuda_mut<
         O,
         E: From<CudaError>,
@@ -339,7 +339,7 @@ pub trait LendToCudaAsync: RustToCudaAsync {
     ///
     /// # Errors
     ///
-    /// Returns a `rustacuda::errors::CudaError` iff an error occurs inside CUDA
+    /// Returns a [`CudaError`] iff an error occurs inside CUDA
     fn lend_to_cuda_mut_async<
         'a,
         'stream,
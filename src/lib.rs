@@ -25,6 +25,9 @@
 #![allow(clippy::multiple_unsafe_ops_per_block)] // FIXME
 #![allow(clippy::indexing_slicing)] // FIXME
 #![cfg_attr(all(any(feature = "device", target_os = "cuda"), not(doc)), no_std)]
+// The mock driver does not use rustacuda or cuda-driver-sys, which remain
+//  dependencies of the host feature since cargo features are additive
+#![cfg_attr(feature = "mock", allow(unused_crate_dependencies))]
 #![feature(auto_traits)]
 #![feature(negative_impls)]
 #![cfg_attr(all(feature = "device", not(doc)), feature(stdarch_nvptx))]
@@ -70,5 +73,10 @@ pub mod utils;
 #[cfg(feature = "host")]
 pub mod host;
 
-#[cfg(feature = "device")]
+#[cfg(feature = "host")]
+mod error;
+#[cfg(feature = "host")]
+pub use error::Error;
+
+#[cfg(any(feature = "device", feature = "emulate"))]
 pub mod device;
@@ -1,3 +1,5 @@
+use crate::kernel::KernelPtxVariant;
+
 #[derive(PartialEq, Eq, core::marker::ConstParamTy)]
 pub enum HostAndDeviceKernelEntryPoint {
     Match,
@@ -33,6 +35,27 @@ pub const fn check(ptx: &[u8], entry_point: &[u8]) -> HostAndDeviceKernelEntryPo
     HostAndDeviceKernelEntryPoint::Mismatch
 }
 
+#[must_use]
+pub const fn check_variants(
+    variants: &[KernelPtxVariant],
+    entry_point: &[u8],
+) -> HostAndDeviceKernelEntryPoint {
+    let mut i = 0;
+
+    while i < variants.len() {
+        if matches!(
+            check(variants[i].ptx.to_bytes(), entry_point),
+            HostAndDeviceKernelEntryPoint::Mismatch
+        ) {
+            return HostAndDeviceKernelEntryPoint::Mismatch;
+        }
+
+        i += 1;
+    }
+
+    HostAndDeviceKernelEntryPoint::Match
+}
+
 const fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
     let mut i = 0;
     let mut j = from;
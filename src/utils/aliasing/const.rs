@@ -23,7 +23,7 @@ impl<T, const STRIDE: usize> SplitSliceOverCudaThreadsConstStride<T, STRIDE> {
     }
 }
 
-#[cfg(feature = "device")]
+#[cfg(any(feature = "device", feature = "emulate"))]
 fn split_slice_const_stride<E, const STRIDE: usize>(slice: &[E]) -> &[E] {
     let offset: usize = crate::device::thread::Thread::this().index() * STRIDE;
     let len = slice.len().min(offset + STRIDE).saturating_sub(offset);
@@ -33,7 +33,7 @@ fn split_slice_const_stride<E, const STRIDE: usize>(slice: &[E]) -> &[E] {
     unsafe { core::slice::from_raw_parts(data, len) }
 }
 
-#[cfg(feature = "device")]
+#[cfg(any(feature = "device", feature = "emulate"))]
 fn split_slice_const_stride_mut<E, const STRIDE: usize>(slice: &mut [E]) -> &mut [E] {
     let offset: usize = crate::device::thread::Thread::this().index() * STRIDE;
     let len = slice.len().min(offset + STRIDE).saturating_sub(offset);
@@ -43,7 +43,7 @@ fn split_slice_const_stride_mut<E, const STRIDE: usize>(slice: &mut [E]) -> &mut
     unsafe { core::slice::from_raw_parts_mut(data, len) }
 }
 
-#[cfg(feature = "device")]
+#[cfg(any(feature = "device", feature = "emulate"))]
 impl<T, const STRIDE: usize> SplitSliceOverCudaThreadsConstStride<T, STRIDE> {
     /// # Safety
     ///
@@ -133,6 +133,11 @@ impl<E, T: Deref<Target = [E]>, const STRIDE: usize> Deref
     type Target = [E];
 
     fn deref(&self) -> &Self::Target {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            return split_slice_const_stride::<E, STRIDE>(&self.0);
+        }
+
         &self.0
     }
 }
@@ -142,6 +147,11 @@ impl<E, T: DerefMut<Target = [E]>, const STRIDE: usize> DerefMut
     for SplitSliceOverCudaThreadsConstStride<T, STRIDE>
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            return split_slice_const_stride_mut::<E, STRIDE>(&mut self.0);
+        }
+
         &mut self.0
     }
 }
@@ -151,6 +161,11 @@ impl<E, T: AsRef<[E]>, const STRIDE: usize> AsRef<[E]>
     for SplitSliceOverCudaThreadsConstStride<T, STRIDE>
 {
     fn as_ref(&self) -> &[E] {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            return split_slice_const_stride::<E, STRIDE>(self.0.as_ref());
+        }
+
         self.0.as_ref()
     }
 }
@@ -160,6 +175,11 @@ impl<E, T: AsMut<[E]>, const STRIDE: usize> AsMut<[E]>
     for SplitSliceOverCudaThreadsConstStride<T, STRIDE>
 {
     fn as_mut(&mut self) -> &mut [E] {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            return split_slice_const_stride_mut::<E, STRIDE>(self.0.as_mut());
+        }
+
         self.0.as_mut()
     }
 }
@@ -169,6 +189,11 @@ impl<E, T: Borrow<[E]>, const STRIDE: usize> Borrow<[E]>
     for SplitSliceOverCudaThreadsConstStride<T, STRIDE>
 {
     fn borrow(&self) -> &[E] {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            return split_slice_const_stride::<E, STRIDE>(self.0.borrow());
+        }
+
         self.0.borrow()
     }
 }
@@ -178,6 +203,11 @@ impl<E, T: BorrowMut<[E]>, const STRIDE: usize> BorrowMut<[E]>
     for SplitSliceOverCudaThreadsConstStride<T, STRIDE>
 {
     fn borrow_mut(&mut self) -> &mut [E] {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            return split_slice_const_stride_mut::<E, STRIDE>(self.0.borrow_mut());
+        }
+
         self.0.borrow_mut()
     }
 }
@@ -193,7 +223,7 @@ unsafe impl<T: RustToCuda, const STRIDE: usize> RustToCuda
     unsafe fn borrow<A: crate::alloc::CudaAlloc>(
         &self,
         alloc: A,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         DeviceAccessible<Self::CudaRepresentation>,
         crate::alloc::CombinedCudaAlloc<Self::CudaAllocation, A>,
     )> {
@@ -209,7 +239,7 @@ unsafe impl<T: RustToCuda, const STRIDE: usize> RustToCuda
     unsafe fn restore<A: crate::alloc::CudaAlloc>(
         &mut self,
         alloc: crate::alloc::CombinedCudaAlloc<Self::CudaAllocation, A>,
-    ) -> rustacuda::error::CudaResult<A> {
+    ) -> crate::host::driver::error::CudaResult<A> {
         self.0.restore(alloc)
     }
 }
@@ -224,7 +254,7 @@ unsafe impl<T: RustToCudaAsync, const STRIDE: usize> RustToCudaAsync
         &self,
         alloc: A,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         crate::utils::r#async::Async<'_, 'stream, DeviceAccessible<Self::CudaRepresentation>>,
         crate::alloc::CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
     )> {
@@ -252,7 +282,7 @@ unsafe impl<T: RustToCudaAsync, const STRIDE: usize> RustToCudaAsync
         this: owning_ref::BoxRefMut<'a, O, Self>,
         alloc: crate::alloc::CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         crate::utils::r#async::Async<
             'a,
             'stream,
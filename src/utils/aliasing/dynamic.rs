@@ -26,7 +26,7 @@ impl<T> SplitSliceOverCudaThreadsDynamicStride<T> {
     }
 }
 
-#[cfg(feature = "device")]
+#[cfg(any(feature = "device", feature = "emulate"))]
 fn split_slice_dynamic_stride<E>(slice: &[E], stride: usize) -> &[E] {
     let offset: usize = crate::device::thread::Thread::this().index() * stride;
     let len = slice.len().min(offset + stride).saturating_sub(offset);
@@ -36,7 +36,7 @@ fn split_slice_dynamic_stride<E>(slice: &[E], stride: usize) -> &[E] {
     unsafe { core::slice::from_raw_parts(data, len) }
 }
 
-#[cfg(feature = "device")]
+#[cfg(any(feature = "device", feature = "emulate"))]
 fn split_slice_dynamic_stride_mut<E>(slice: &mut [E], stride: usize) -> &mut [E] {
     let offset: usize = crate::device::thread::Thread::this().index() * stride;
     let len = slice.len().min(offset + stride).saturating_sub(offset);
@@ -46,7 +46,7 @@ fn split_slice_dynamic_stride_mut<E>(slice: &mut [E], stride: usize) -> &mut [E]
     unsafe { core::slice::from_raw_parts_mut(data, len) }
 }
 
-#[cfg(feature = "device")]
+#[cfg(any(feature = "device", feature = "emulate"))]
 impl<T> SplitSliceOverCudaThreadsDynamicStride<T> {
     /// # Safety
     ///
@@ -122,6 +122,11 @@ impl<E, T: Deref<Target = [E]>> Deref for SplitSliceOverCudaThreadsDynamicStride
     type Target = [E];
 
     fn deref(&self) -> &Self::Target {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            return split_slice_dynamic_stride(&self.inner, self.stride);
+        }
+
         &self.inner
     }
 }
@@ -129,6 +134,11 @@ impl<E, T: Deref<Target = [E]>> Deref for SplitSliceOverCudaThreadsDynamicStride
 #[cfg(all(feature = "host", not(doc)))]
 impl<E, T: DerefMut<Target = [E]>> DerefMut for SplitSliceOverCudaThreadsDynamicStride<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            return split_slice_dynamic_stride_mut(&mut self.inner, self.stride);
+        }
+
         &mut self.inner
     }
 }
@@ -136,6 +146,11 @@ impl<E, T: DerefMut<Target = [E]>> DerefMut for SplitSliceOverCudaThreadsDynamic
 #[cfg(all(feature = "host", not(doc)))]
 impl<E, T: AsRef<[E]>> AsRef<[E]> for SplitSliceOverCudaThreadsDynamicStride<T> {
     fn as_ref(&self) -> &[E] {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            return split_slice_dynamic_stride(self.inner.as_ref(), self.stride);
+        }
+
         self.inner.as_ref()
     }
 }
@@ -143,6 +158,11 @@ impl<E, T: AsRef<[E]>> AsRef<[E]> for SplitSliceOverCudaThreadsDynamicStride<T>
 #[cfg(all(feature = "host", not(doc)))]
 impl<E, T: AsMut<[E]>> AsMut<[E]> for SplitSliceOverCudaThreadsDynamicStride<T> {
     fn as_mut(&mut self) -> &mut [E] {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            return split_slice_dynamic_stride_mut(self.inner.as_mut(), self.stride);
+        }
+
         self.inner.as_mut()
     }
 }
@@ -150,6 +170,11 @@ impl<E, T: AsMut<[E]>> AsMut<[E]> for SplitSliceOverCudaThreadsDynamicStride<T>
 #[cfg(all(feature = "host", not(doc)))]
 impl<E, T: Borrow<[E]>> Borrow<[E]> for SplitSliceOverCudaThreadsDynamicStride<T> {
     fn borrow(&self) -> &[E] {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            return split_slice_dynamic_stride(self.inner.borrow(), self.stride);
+        }
+
         self.inner.borrow()
     }
 }
@@ -157,6 +182,11 @@ impl<E, T: Borrow<[E]>> Borrow<[E]> for SplitSliceOverCudaThreadsDynamicStride<T
 #[cfg(all(feature = "host", not(doc)))]
 impl<E, T: BorrowMut<[E]>> BorrowMut<[E]> for SplitSliceOverCudaThreadsDynamicStride<T> {
     fn borrow_mut(&mut self) -> &mut [E] {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            return split_slice_dynamic_stride_mut(self.inner.borrow_mut(), self.stride);
+        }
+
         self.inner.borrow_mut()
     }
 }
@@ -170,7 +200,7 @@ unsafe impl<T: RustToCuda> RustToCuda for SplitSliceOverCudaThreadsDynamicStride
     unsafe fn borrow<A: crate::alloc::CudaAlloc>(
         &self,
         alloc: A,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         DeviceAccessible<Self::CudaRepresentation>,
         crate::alloc::CombinedCudaAlloc<Self::CudaAllocation, A>,
     )> {
@@ -189,7 +219,7 @@ unsafe impl<T: RustToCuda> RustToCuda for SplitSliceOverCudaThreadsDynamicStride
     unsafe fn restore<A: crate::alloc::CudaAlloc>(
         &mut self,
         alloc: crate::alloc::CombinedCudaAlloc<Self::CudaAllocation, A>,
-    ) -> rustacuda::error::CudaResult<A> {
+    ) -> crate::host::driver::error::CudaResult<A> {
         self.inner.restore(alloc)
     }
 }
@@ -202,7 +232,7 @@ unsafe impl<T: RustToCudaAsync> RustToCudaAsync for SplitSliceOverCudaThreadsDyn
         &self,
         alloc: A,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         crate::utils::r#async::Async<'_, 'stream, DeviceAccessible<Self::CudaRepresentation>>,
         crate::alloc::CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
     )> {
@@ -232,7 +262,7 @@ unsafe impl<T: RustToCudaAsync> RustToCudaAsync for SplitSliceOverCudaThreadsDyn
         this: owning_ref::BoxRefMut<'a, O, Self>,
         alloc: crate::alloc::CombinedCudaAlloc<Self::CudaAllocationAsync, A>,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         crate::utils::r#async::Async<
             'a,
             'stream,
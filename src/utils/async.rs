@@ -2,14 +2,19 @@
 use std::{borrow::BorrowMut, future::Future, future::IntoFuture, marker::PhantomData, task::Poll};
 
 #[cfg(feature = "host")]
-use rustacuda::{
-    error::CudaError, error::CudaResult, event::Event, event::EventFlags,
-    stream::StreamWaitEventFlags,
+use crate::{
+    host::{
+        driver::{
+            error::{CudaError, CudaResult},
+            event::{Event, EventFlags},
+            ext::StreamExt,
+            stream::StreamWaitEventFlags,
+        },
+        CudaDropWrapper, Stream,
+    },
+    Error,
 };
 
-#[cfg(feature = "host")]
-use crate::host::{CudaDropWrapper, Stream};
-
 #[cfg(feature = "host")]
 pub struct NoCompletion;
 #[cfg(feature = "host")]
@@ -98,6 +103,37 @@ impl<T: ?Sized + BorrowMut<C::Completed>, C: Completion<T>> Completion<T> for Op
 #[cfg(feature = "host")]
 impl<C> sealed::Sealed for Option<C> {}
 
+/// Sends the result of all work that has been submitted to the `stream` so
+/// far through the `sender` once that work has completed.
+///
+/// If the `stream` is being captured into a [`Graph`], the `sender` is only
+/// sent once the [`Graph`] has been dropped and all of its replays have
+/// completed.
+///
+/// [`Graph`]: crate::host::graph::Graph
+#[cfg(feature = "host")]
+fn send_on_completion(stream: Stream, sender: oneshot::Sender<CudaResult<()>>) -> CudaResult<()> {
+    match crate::host::graph::defer_completion(&stream, sender) {
+        Ok(()) => Ok(()),
+        // A stream that has joined a capture on another stream, e.g. by
+        //  waiting on one of its events, cannot signal the completion
+        Err(_sender) if stream.is_capturing()? => Err(CudaError::NotPermitted),
+        Err(sender) => stream.add_callback(Box::new(|result| std::mem::drop(sender.send(result)))),
+    }
+}
+
+/// Executes the work that is queued on a held mock `stream`, since waiting
+/// for its completion would otherwise deadlock. With the real driver, the
+/// work completes without any intervention.
+#[cfg(feature = "host")]
+fn flush_mock_stream(stream: Stream) -> CudaResult<()> {
+    if cfg!(feature = "mock") {
+        stream.synchronize()
+    } else {
+        Ok(())
+    }
+}
+
 #[cfg(feature = "host")]
 pub struct Async<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T> = NoCompletion> {
     stream: Stream<'stream>,
@@ -136,11 +172,11 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Async<'a, 'strea
     /// such that its computation can be synchronised on.
     ///
     /// # Errors
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA.
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA.
     pub fn pending(value: T, stream: Stream<'stream>, completion: C) -> CudaResult<Self> {
         let (sender, receiver) = oneshot::channel();
-        stream.add_callback(Box::new(|result| std::mem::drop(sender.send(result))))?;
+        send_on_completion(stream, sender)?;
 
         Ok(Self {
             stream,
@@ -160,17 +196,19 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Async<'a, 'strea
     /// operations.
     ///
     /// Calling `synchronize` after the computation has completed, e.g. after
-    /// calling [`rustacuda::stream::Stream::synchronize`], should be very
-    /// cheap.
+    /// calling [`crate::host::driver::stream::Stream::synchronize`], should be
+    /// very cheap.
     ///
     /// # Errors
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA.
-    pub fn synchronize(self) -> CudaResult<T> {
-        let (_stream, mut value, status) = self.destructure_into_parts();
+    /// Returns an [`Error::Driver`] iff an error occurs inside CUDA, or an
+    /// [`Error::CompletionLost`] iff the completion signal was lost.
+    pub fn synchronize(self) -> Result<T, Error> {
+        let (stream, mut value, status) = self.destructure_into_parts();
 
         let (receiver, completion) = match status {
-            AsyncStatus::Completed { result } => return result.map(|()| value),
+            AsyncStatus::Completed { result } => {
+                return result.map(|()| value).map_err(Error::from)
+            },
             AsyncStatus::Processing {
                 receiver,
                 completion,
@@ -179,10 +217,17 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Async<'a, 'strea
             } => (receiver, completion),
         };
 
+        // The captured work only completes once its graph has been dropped
+        if crate::host::graph::is_captured(&stream) {
+            return Err(Error::StreamCaptureUnsupported);
+        }
+
+        flush_mock_stream(stream)?;
+
         match receiver.recv() {
             Ok(Ok(())) => (),
-            Ok(Err(err)) => return Err(err),
-            Err(oneshot::RecvError) => return Err(CudaError::AlreadyAcquired),
+            Ok(Err(err)) => return Err(err.into()),
+            Err(oneshot::RecvError) => return Err(Error::CompletionLost),
         }
 
         completion.complete(value.borrow_mut())?;
@@ -198,12 +243,12 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Async<'a, 'strea
     /// used on the new one.
     ///
     /// # Errors
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA.
+    /// Returns an [`Error::Driver`] iff an error occurs inside CUDA, or an
+    /// [`Error::CompletionLost`] iff the completion signal was lost.
     pub fn move_to_stream<'stream_new>(
         self,
         stream: Stream<'stream_new>,
-    ) -> CudaResult<Async<'a, 'stream_new, T, C>> {
+    ) -> Result<Async<'a, 'stream_new, T, C>, Error> {
         let (old_stream, mut value, status) = self.destructure_into_parts();
 
         let completion = match status {
@@ -221,9 +266,9 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Async<'a, 'strea
                     completion.complete(value.borrow_mut())?;
                     C::no_op()
                 },
-                Ok(Err(err)) => return Err(err),
+                Ok(Err(err)) => return Err(err.into()),
                 Err(oneshot::TryRecvError::Empty) => completion,
-                Err(oneshot::TryRecvError::Disconnected) => return Err(CudaError::AlreadyAcquired),
+                Err(oneshot::TryRecvError::Disconnected) => return Err(Error::CompletionLost),
             },
         };
 
@@ -232,7 +277,7 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Async<'a, 'strea
         stream.wait_event(&event, StreamWaitEventFlags::DEFAULT)?;
 
         let (sender, receiver) = oneshot::channel();
-        stream.add_callback(Box::new(|result| std::mem::drop(sender.send(result))))?;
+        send_on_completion(stream, sender)?;
 
         Ok(Async {
             stream,
@@ -299,8 +344,7 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Async<'a, 'strea
 
                     let (sender, receiver) = oneshot::channel();
 
-                    self.stream
-                        .add_callback(Box::new(|result| std::mem::drop(sender.send(result))))?;
+                    send_on_completion(self.stream, sender)?;
                     event.record(&self.stream)?;
 
                     self.status = AsyncStatus::Processing {
@@ -388,8 +432,7 @@ where
 
                     let (sender, receiver) = oneshot::channel();
 
-                    self.stream
-                        .add_callback(Box::new(|result| std::mem::drop(sender.send(result))))?;
+                    send_on_completion(self.stream, sender)?;
                     event.record(&self.stream)?;
 
                     self.status = AsyncStatus::Processing {
@@ -419,7 +462,17 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Drop for Async<'
             return;
         };
 
-        if completion.synchronize_on_drop() && receiver.recv() == Ok(Ok(())) {
+        if !completion.synchronize_on_drop() {
+            return;
+        }
+
+        // The borrow ends before the captured work could have completed
+        if crate::host::graph::is_captured(&self.stream) {
+            crate::host::graph::invalidate_captures();
+            return;
+        }
+
+        if flush_mock_stream(self.stream).is_ok() && receiver.recv() == Ok(Ok(())) {
             let _ = completion.complete(self.value.borrow_mut());
         }
     }
@@ -427,7 +480,7 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Drop for Async<'
 
 #[cfg(feature = "host")]
 struct AsyncFuture<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> {
-    _stream: PhantomData<Stream<'stream>>,
+    stream: Stream<'stream>,
     value: Option<T>,
     completion: Option<C>,
     status: AsyncStatus<'a, T, NoCompletion>,
@@ -437,7 +490,7 @@ struct AsyncFuture<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> {
 impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Future
     for AsyncFuture<'a, 'stream, T, C>
 {
-    type Output = CudaResult<T>;
+    type Output = Result<T, Error>;
 
     fn poll(
         self: core::pin::Pin<&mut Self>,
@@ -447,6 +500,9 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Future
         let this = unsafe { self.get_unchecked_mut() };
 
         match &mut this.status {
+            AsyncStatus::Processing { .. } if crate::host::graph::is_captured(&this.stream) => {
+                return Poll::Ready(Err(Error::StreamCaptureUnsupported));
+            },
             AsyncStatus::Processing {
                 receiver,
                 completion: _,
@@ -454,18 +510,18 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Future
                 _capture,
             } => match std::pin::Pin::new(receiver).poll(cx) {
                 Poll::Ready(Ok(Ok(()))) => (),
-                Poll::Ready(Ok(Err(err))) => return Poll::Ready(Err(err)),
+                Poll::Ready(Ok(Err(err))) => return Poll::Ready(Err(err.into())),
                 Poll::Ready(Err(oneshot::RecvError)) => {
-                    return Poll::Ready(Err(CudaError::AlreadyAcquired))
+                    return Poll::Ready(Err(Error::CompletionLost))
                 },
                 Poll::Pending => return Poll::Pending,
             },
             AsyncStatus::Completed { result: Ok(()) } => (),
-            AsyncStatus::Completed { result: Err(err) } => return Poll::Ready(Err(*err)),
+            AsyncStatus::Completed { result: Err(err) } => return Poll::Ready(Err((*err).into())),
         }
 
         let Some(mut value) = this.value.take() else {
-            return Poll::Ready(Err(CudaError::AlreadyAcquired));
+            return Poll::Ready(Err(Error::CompletionLost));
         };
 
         if let Some(completion) = this.completion.take() {
@@ -480,12 +536,12 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Future
 impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> IntoFuture
     for Async<'a, 'stream, T, C>
 {
-    type Output = CudaResult<T>;
+    type Output = Result<T, Error>;
 
     type IntoFuture = impl Future<Output = Self::Output>;
 
     fn into_future(self) -> Self::IntoFuture {
-        let (_stream, value, status) = self.destructure_into_parts();
+        let (stream, value, status) = self.destructure_into_parts();
 
         let (completion, status): (Option<C>, AsyncStatus<'a, T, NoCompletion>) = match status {
             AsyncStatus::Completed { result } => {
@@ -508,7 +564,7 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> IntoFuture
         };
 
         AsyncFuture {
-            _stream: PhantomData::<Stream<'stream>>,
+            stream,
             value: Some(value),
             completion,
             status,
@@ -539,7 +595,17 @@ impl<'a, 'stream, T: BorrowMut<C::Completed>, C: Completion<T>> Drop
             return;
         };
 
-        if completion.synchronize_on_drop() && receiver.recv() == Ok(Ok(())) {
+        if !completion.synchronize_on_drop() {
+            return;
+        }
+
+        // The borrow ends before the captured work could have completed
+        if crate::host::graph::is_captured(&self.stream) {
+            crate::host::graph::invalidate_captures();
+            return;
+        }
+
+        if flush_mock_stream(self.stream).is_ok() && receiver.recv() == Ok(Ok(())) {
             let _ = completion.complete(value.borrow_mut());
         }
     }
@@ -730,3 +796,79 @@ impl<'a, 'stream, T: 'a> AsyncProj<'a, 'stream, &'a mut T> {
         self.value
     }
 }
+
+#[cfg(all(test, feature = "mock"))]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::host::{
+        driver::stream::{Stream as DriverStream, StreamFlags},
+        Stream,
+    };
+
+    use super::{Async, CompletionFnMut, NoCompletion};
+
+    fn with_two_streams(inner: impl for<'a, 'b> FnOnce(Stream<'a>, Stream<'b>)) {
+        let mut stream_a = DriverStream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+        let mut stream_b = DriverStream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+
+        Stream::with(&mut stream_a, |stream_a| {
+            Stream::with(&mut stream_b, |stream_b| inner(stream_a, stream_b));
+        });
+    }
+
+    #[test]
+    fn move_to_stream_waits_for_old_stream() {
+        with_two_streams(|stream_a, stream_b| {
+            stream_a.hold();
+
+            let r#async = Async::<_, NoCompletion>::pending(42, stream_a, NoCompletion).unwrap();
+            assert_eq!(stream_a.pending_operations(), 1);
+
+            // The new stream waits for an event recorded on the old stream,
+            //  which forces all previously queued work to complete
+            let r#async = r#async.move_to_stream(stream_b).unwrap();
+            assert_eq!(stream_a.pending_operations(), 0);
+            assert_eq!(stream_a.completed_operations(), 2);
+            assert_eq!(stream_b.completed_operations(), 2);
+
+            assert_eq!(r#async.synchronize(), Ok(42));
+        });
+    }
+
+    #[test]
+    fn move_to_stream_completes_exactly_once() {
+        with_two_streams(|stream_a, stream_b| {
+            stream_a.hold();
+
+            let r#async = Async::<_, CompletionFnMut<u32>>::pending(
+                0_u32,
+                stream_a,
+                Box::new(|value: &mut u32| {
+                    *value += 1;
+                    Ok(())
+                }),
+            )
+            .unwrap();
+
+            let r#async = r#async.move_to_stream(stream_b).unwrap();
+
+            assert_eq!(r#async.synchronize(), Ok(1));
+        });
+    }
+
+    #[test]
+    fn move_to_stream_of_ready_value() {
+        with_two_streams(|stream_a, stream_b| {
+            let r#async = Async::<_, NoCompletion>::ready(42, stream_a);
+
+            stream_b.hold();
+
+            let r#async = r#async.move_to_stream(stream_b).unwrap();
+            assert_eq!(stream_a.completed_operations(), 1);
+            assert_eq!(stream_b.pending_operations(), 2);
+
+            assert_eq!(r#async.synchronize(), Ok(42));
+            assert_eq!(stream_b.pending_operations(), 0);
+        });
+    }
+}
@@ -4,14 +4,16 @@ use std::{
 };
 
 use const_type_layout::TypeGraphLayout;
-use rustacuda::{
-    error::CudaResult,
-    memory::{DeviceBuffer, LockedBuffer},
-};
 
 use crate::{
     alloc::{CombinedCudaAlloc, CudaAlloc, NoCudaAlloc},
-    host::CudaDropWrapper,
+    host::{
+        driver::{
+            error::CudaResult,
+            memory::{DeviceBuffer, LockedBuffer},
+        },
+        CudaDropWrapper,
+    },
     safety::{PortableBitSemantics, StackOnly},
     utils::{
         adapter::DeviceCopyWithPortableBitSemantics,
@@ -45,8 +47,8 @@ impl<
     > CudaExchangeBufferHost<T, M2D, M2H>
 {
     /// # Errors
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA
     pub fn new(elem: &T, capacity: usize) -> CudaResult<Self> {
         // Safety: CudaExchangeItem is a `repr(transparent)` wrapper around T
         let elem: &CudaExchangeItem<T, M2D, M2H> = unsafe { &*std::ptr::from_ref(elem).cast() };
@@ -70,8 +72,8 @@ impl<T: StackOnly + PortableBitSemantics + TypeGraphLayout, const M2D: bool, con
     CudaExchangeBufferHost<T, M2D, M2H>
 {
     /// # Errors
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA
     pub fn from_vec(vec: Vec<T>) -> CudaResult<Self> {
         let host_buffer = unsafe {
             let mut uninit: CudaDropWrapper<LockedBuffer<DeviceCopyWithPortableBitSemantics<_>>> =
@@ -127,7 +129,7 @@ impl<T: StackOnly + PortableBitSemantics + TypeGraphLayout, const M2D: bool, con
     pub unsafe fn borrow<A: CudaAlloc>(
         &self,
         alloc: A,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         DeviceAccessible<CudaExchangeBufferCudaRepresentation<T, M2D, M2H>>,
         CombinedCudaAlloc<NoCudaAlloc, A>,
     )> {
@@ -138,7 +140,7 @@ impl<T: StackOnly + PortableBitSemantics + TypeGraphLayout, const M2D: bool, con
         if M2D {
             // Only move the buffer contents to the device if needed
 
-            rustacuda::memory::CopyDestination::copy_from(
+            crate::host::driver::memory::CopyDestination::copy_from(
                 &mut ***device_buffer,
                 self.host_buffer.as_slice(),
             )?;
@@ -156,13 +158,13 @@ impl<T: StackOnly + PortableBitSemantics + TypeGraphLayout, const M2D: bool, con
     pub unsafe fn restore<A: CudaAlloc>(
         &mut self,
         alloc: CombinedCudaAlloc<NoCudaAlloc, A>,
-    ) -> rustacuda::error::CudaResult<A> {
+    ) -> crate::host::driver::error::CudaResult<A> {
         let (_alloc_front, alloc_tail) = alloc.split();
 
         if M2H {
             // Only move the buffer contents back to the host if needed
 
-            rustacuda::memory::CopyDestination::copy_to(
+            crate::host::driver::memory::CopyDestination::copy_to(
                 &***self.device_buffer.get_mut(),
                 self.host_buffer.as_mut_slice(),
             )?;
@@ -180,7 +182,7 @@ impl<T: StackOnly + PortableBitSemantics + TypeGraphLayout, const M2D: bool, con
         &self,
         alloc: A,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         Async<'_, 'stream, DeviceAccessible<CudaExchangeBufferCudaRepresentation<T, M2D, M2H>>>,
         CombinedCudaAlloc<NoCudaAlloc, A>,
     )> {
@@ -191,7 +193,7 @@ impl<T: StackOnly + PortableBitSemantics + TypeGraphLayout, const M2D: bool, con
         if M2D {
             // Only move the buffer contents to the device if needed
 
-            rustacuda::memory::AsyncCopyDestination::async_copy_from(
+            crate::host::driver::memory::AsyncCopyDestination::async_copy_from(
                 &mut ***device_buffer,
                 self.host_buffer.as_slice(),
                 &stream,
@@ -217,7 +219,7 @@ impl<T: StackOnly + PortableBitSemantics + TypeGraphLayout, const M2D: bool, con
         mut this: owning_ref::BoxRefMut<'a, O, Self>,
         alloc: CombinedCudaAlloc<NoCudaAlloc, A>,
         stream: crate::host::Stream<'stream>,
-    ) -> rustacuda::error::CudaResult<(
+    ) -> crate::host::driver::error::CudaResult<(
         Async<'a, 'stream, owning_ref::BoxRefMut<'a, O, Self>, CompletionFnMut<'a, Self>>,
         A,
     )> {
@@ -228,7 +230,7 @@ impl<T: StackOnly + PortableBitSemantics + TypeGraphLayout, const M2D: bool, con
 
             let this: &mut Self = &mut this;
 
-            rustacuda::memory::AsyncCopyDestination::async_copy_to(
+            crate::host::driver::memory::AsyncCopyDestination::async_copy_to(
                 &***this.device_buffer.get_mut(),
                 this.host_buffer.as_mut_slice(),
                 &stream,
@@ -244,3 +246,141 @@ impl<T: StackOnly + PortableBitSemantics + TypeGraphLayout, const M2D: bool, con
         Ok((r#async, alloc_tail))
     }
 }
+
+#[cfg(all(test, feature = "mock"))]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::{
+        alloc::NoCudaAlloc,
+        host::{
+            driver::{
+                memory::CopyDestination,
+                stream::{Stream, StreamFlags},
+            },
+            Stream as BrandedStream,
+        },
+        utils::adapter::DeviceCopyWithPortableBitSemantics,
+    };
+
+    use super::{CudaExchangeBufferHost, CudaExchangeItem};
+
+    fn host_values<const M2D: bool, const M2H: bool>(
+        buffer: &CudaExchangeBufferHost<u32, M2D, M2H>,
+    ) -> Vec<u32> {
+        buffer.iter().map(|item| item.0).collect()
+    }
+
+    fn device_values<const M2D: bool, const M2H: bool>(
+        buffer: &CudaExchangeBufferHost<u32, M2D, M2H>,
+    ) -> Vec<u32> {
+        let mut items = vec![CudaExchangeItem(0); buffer.len()];
+
+        // Safety: the device buffer is not mutably borrowed in these tests
+        unsafe { &*buffer.device_buffer.get() }
+            .copy_to(DeviceCopyWithPortableBitSemantics::from_mut_slice(
+                &mut items,
+            ))
+            .unwrap();
+
+        items.into_iter().map(|item| item.0).collect()
+    }
+
+    fn write_device<const M2D: bool, const M2H: bool>(
+        buffer: &mut CudaExchangeBufferHost<u32, M2D, M2H>,
+        values: &[u32],
+    ) {
+        let mut items = values
+            .iter()
+            .copied()
+            .map(CudaExchangeItem)
+            .collect::<Vec<_>>();
+
+        buffer
+            .device_buffer
+            .get_mut()
+            .copy_from(DeviceCopyWithPortableBitSemantics::from_mut_slice(
+                &mut items,
+            ))
+            .unwrap();
+    }
+
+    fn write_host<const M2D: bool, const M2H: bool>(
+        buffer: &mut CudaExchangeBufferHost<u32, M2D, M2H>,
+        values: &[u32],
+    ) {
+        for (item, value) in buffer.iter_mut().zip(values) {
+            item.0 = *value;
+        }
+    }
+
+    #[test]
+    fn exchange_in_both_directions() {
+        let mut buffer =
+            CudaExchangeBufferHost::<u32, true, true>::from_vec(vec![1, 2, 3]).unwrap();
+        write_host(&mut buffer, &[4, 5, 6]);
+
+        let (_repr, alloc) = unsafe { buffer.borrow(NoCudaAlloc) }.unwrap();
+        assert_eq!(device_values(&buffer), [4, 5, 6]);
+
+        // Simulate a kernel that writes to the device buffer
+        write_device(&mut buffer, &[7, 8, 9]);
+
+        unsafe { buffer.restore(alloc) }.unwrap();
+        assert_eq!(host_values(&buffer), [7, 8, 9]);
+    }
+
+    #[test]
+    fn exchange_only_to_host() {
+        let mut buffer =
+            CudaExchangeBufferHost::<u32, false, true>::from_vec(vec![1, 2, 3]).unwrap();
+        write_host(&mut buffer, &[4, 5, 6]);
+
+        let (_repr, alloc) = unsafe { buffer.borrow(NoCudaAlloc) }.unwrap();
+        assert_eq!(device_values(&buffer), [1, 2, 3]);
+
+        write_device(&mut buffer, &[7, 8, 9]);
+
+        unsafe { buffer.restore(alloc) }.unwrap();
+        assert_eq!(host_values(&buffer), [7, 8, 9]);
+    }
+
+    #[test]
+    fn exchange_only_to_device() {
+        let mut buffer =
+            CudaExchangeBufferHost::<u32, true, false>::from_vec(vec![1, 2, 3]).unwrap();
+        write_host(&mut buffer, &[4, 5, 6]);
+
+        let (_repr, alloc) = unsafe { buffer.borrow(NoCudaAlloc) }.unwrap();
+        assert_eq!(device_values(&buffer), [4, 5, 6]);
+
+        write_device(&mut buffer, &[7, 8, 9]);
+
+        unsafe { buffer.restore(alloc) }.unwrap();
+        assert_eq!(host_values(&buffer), [4, 5, 6]);
+    }
+
+    #[test]
+    fn async_exchange_waits_for_held_stream() {
+        let mut stream = Stream::new(StreamFlags::NON_BLOCKING, None).unwrap();
+
+        BrandedStream::with(&mut stream, |stream| {
+            let mut buffer =
+                CudaExchangeBufferHost::<u32, true, true>::from_vec(vec![1, 2, 3]).unwrap();
+            write_host(&mut buffer, &[4, 5, 6]);
+
+            stream.hold();
+
+            let (repr, _alloc) = unsafe { buffer.borrow_async(NoCudaAlloc, stream) }.unwrap();
+
+            // The copy to the device and its completion are still queued
+            assert_eq!(stream.pending_operations(), 2);
+            assert_eq!(device_values(&buffer), [1, 2, 3]);
+
+            repr.synchronize().unwrap();
+
+            assert_eq!(stream.pending_operations(), 0);
+            assert_eq!(stream.completed_operations(), 2);
+            assert_eq!(device_values(&buffer), [4, 5, 6]);
+        });
+    }
+}
@@ -1,13 +1,14 @@
 use std::ops::{Deref, DerefMut};
 
-use rustacuda::{
-    error::CudaResult,
-    memory::{AsyncCopyDestination, CopyDestination, DeviceBox, LockedBox},
-};
-
 use crate::{
     alloc::{EmptyCudaAlloc, NoCudaAlloc},
-    host::{CudaDropWrapper, HostAndDeviceConstRef, HostAndDeviceMutRef, Stream},
+    host::{
+        driver::{
+            error::CudaResult,
+            memory::{AsyncCopyDestination, CopyDestination, DeviceBox, LockedBox},
+        },
+        CudaDropWrapper, HostAndDeviceConstRef, HostAndDeviceMutRef, Stream,
+    },
     lend::{RustToCuda, RustToCudaAsync},
     safety::SafeMutableAliasing,
     utils::{
@@ -55,8 +56,8 @@ pub struct ExchangeWrapperOnDevice<T: RustToCuda<CudaAllocation: EmptyCudaAlloc>
 
 impl<T: RustToCuda<CudaAllocation: EmptyCudaAlloc>> ExchangeWrapperOnHost<T> {
     /// # Errors
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA
     pub fn new(value: T) -> CudaResult<Self> {
         // Safety: The uninitialised memory is never exposed
         //         To access the device memory, [`Self::move_to_device`] has to
@@ -88,8 +89,8 @@ impl<T: RustToCuda<CudaAllocation: EmptyCudaAlloc>> ExchangeWrapperOnHost<T> {
     /// via [`ExchangeWrapperOnDevice::as_mut_async`](Async::as_mut_async).
     ///
     /// # Errors
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA
     pub fn move_to_device(mut self) -> CudaResult<ExchangeWrapperOnDevice<T>> {
         let (cuda_repr, null_alloc) = unsafe { self.value.borrow(NoCudaAlloc) }?;
         **self.locked_cuda_repr = DeviceCopyWithPortableBitSemantics::from(cuda_repr);
@@ -113,8 +114,8 @@ impl<T: RustToCudaAsync<CudaAllocationAsync: EmptyCudaAlloc, CudaAllocation: Emp
     /// Moves the data asynchronously to the CUDA device.
     ///
     /// # Errors
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA
     pub fn move_to_device_async<'stream>(
         mut self,
         stream: Stream<'stream>,
@@ -163,8 +164,8 @@ impl<T: RustToCuda<CudaAllocation: EmptyCudaAlloc>> ExchangeWrapperOnDevice<T> {
     /// Moves the data synchronously back to the host CPU device.
     ///
     /// # Errors
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA
     pub fn move_to_host(mut self) -> CudaResult<ExchangeWrapperOnHost<T>> {
         let null_alloc = NoCudaAlloc.into();
 
@@ -201,8 +202,8 @@ impl<T: RustToCudaAsync<CudaAllocationAsync: EmptyCudaAlloc, CudaAllocation: Emp
     /// Moves the data asynchronously back to the host CPU device.
     ///
     /// # Errors
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA
     pub fn move_to_host_async<'stream>(
         self,
         stream: Stream<'stream>,
@@ -259,8 +260,8 @@ impl<
     /// Moves the data asynchronously back to the host CPU device.
     ///
     /// # Errors
-    /// Returns a [`rustacuda::error::CudaError`] iff an error occurs inside
-    /// CUDA
+    /// Returns a [`crate::host::driver::error::CudaError`] iff an error occurs
+    /// inside CUDA
     pub fn move_to_host_async(
         self,
         stream: Stream<'stream>,
@@ -52,19 +52,19 @@ impl<T: 'static + TypeGraphLayout> ThreadBlockSharedSlice<T> {
         unsafe { Layout::for_value_raw(self.shared) }
     }
 
-    #[cfg(feature = "device")]
+    #[cfg(any(feature = "device", feature = "emulate"))]
     #[must_use]
     pub const fn as_mut_ptr(&self) -> *mut T {
         self.shared.cast()
     }
 
-    #[cfg(feature = "device")]
+    #[cfg(any(feature = "device", feature = "emulate"))]
     #[must_use]
     pub const fn as_mut_slice_ptr(&self) -> *mut [T] {
         self.shared
     }
 
-    #[cfg(feature = "device")]
+    #[cfg(any(feature = "device", feature = "emulate"))]
     /// # Safety
     ///
     /// The provided `index` must not be out of bounds.
@@ -116,6 +116,36 @@ impl<T: 'static + TypeGraphLayout> ThreadBlockSharedSlice<T> {
     }
 }
 
+#[cfg(feature = "emulate")]
+impl<T: 'static + TypeGraphLayout> ThreadBlockSharedSlice<T> {
+    /// Exposes the per-block shared memory that backs the `PARAM`th kernel
+    /// parameter inside an emulated kernel launch.
+    ///
+    /// # Panics
+    ///
+    /// Unwinds with an
+    /// [`Error::SharedMemoryTooLarge`](crate::Error::SharedMemoryTooLarge)
+    /// payload iff the `len` elements do not fit into a single allocation,
+    /// which fails the emulated kernel launch.
+    pub(crate) fn with_uninit_for_param<const PARAM: usize, F: FnOnce(&mut Self) -> Q, Q>(
+        len: usize,
+        inner: F,
+    ) -> Q {
+        let Ok(layout) = Layout::array::<T>(len) else {
+            std::panic::resume_unwind(Box::new(crate::Error::SharedMemoryTooLarge {
+                requested: len.saturating_mul(core::mem::size_of::<T>()),
+                available: usize::try_from(isize::MAX).unwrap_or(usize::MAX),
+            }))
+        };
+
+        let data: *mut T = crate::device::emulate::param_shared(PARAM, layout).cast();
+
+        let shared = core::ptr::slice_from_raw_parts_mut(data, len);
+
+        inner(&mut Self { shared })
+    }
+}
+
 #[cfg(feature = "device")]
 /// # Safety
 ///
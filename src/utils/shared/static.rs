@@ -1,6 +1,6 @@
 #[repr(transparent)]
 pub struct ThreadBlockShared<T: 'static> {
-    #[cfg_attr(not(feature = "device"), allow(dead_code))]
+    #[cfg_attr(not(any(feature = "device", feature = "emulate")), allow(dead_code))]
     shared: *mut T,
 }
 
@@ -8,9 +8,22 @@ impl<T: 'static> ThreadBlockShared<T> {
     #[cfg(any(feature = "host", feature = "device"))]
     #[must_use]
     #[expect(clippy::inline_always)]
-    #[cfg_attr(feature = "host", expect(clippy::missing_const_for_fn))]
+    #[cfg_attr(
+        all(feature = "host", not(feature = "emulate")),
+        expect(clippy::missing_const_for_fn)
+    )]
+    #[cfg_attr(feature = "emulate", track_caller)]
     #[inline(always)]
     pub fn new_uninit() -> Self {
+        #[cfg(feature = "emulate")]
+        if crate::device::emulate::is_emulated_thread() {
+            // Each declaration site gets its own per-block shared memory,
+            //  just like the `.shared` declaration on the device
+            return Self {
+                shared: crate::device::emulate::static_shared(core::panic::Location::caller()),
+            };
+        }
+
         #[cfg(feature = "host")]
         {
             Self {
@@ -36,7 +49,7 @@ impl<T: 'static> ThreadBlockShared<T> {
         }
     }
 
-    #[cfg(feature = "device")]
+    #[cfg(any(feature = "device", feature = "emulate"))]
     #[must_use]
     pub const fn as_mut_ptr(&self) -> *mut T {
         self.shared
@@ -44,7 +57,7 @@ impl<T: 'static> ThreadBlockShared<T> {
 }
 
 impl<T: 'static, const N: usize> ThreadBlockShared<[T; N]> {
-    #[cfg(feature = "device")]
+    #[cfg(any(feature = "device", feature = "emulate"))]
     /// # Safety
     ///
     /// The provided `index` must not be out of bounds.
@@ -57,3 +70,15 @@ impl<T: 'static, const N: usize> ThreadBlockShared<[T; N]> {
         core::ptr::slice_from_raw_parts_mut(self.shared.cast::<T>(), N).get_unchecked_mut(index)
     }
 }
+
+#[cfg(feature = "emulate")]
+impl<T: 'static> ThreadBlockShared<T> {
+    /// Returns the per-block shared memory that backs the `PARAM`th kernel
+    /// parameter inside an emulated kernel launch.
+    pub(crate) fn new_uninit_for_param<const PARAM: usize>() -> Self {
+        Self {
+            shared: crate::device::emulate::param_shared(PARAM, core::alloc::Layout::new::<T>())
+                .cast(),
+        }
+    }
+}